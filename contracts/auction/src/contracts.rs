@@ -5,6 +5,7 @@ use cosmwasm_std::{
 };
 use cw2::set_contract_version;
 
+use membrane::pause::{assert_not_paused, execute_pause, PauseActions};
 use membrane::auction::{ExecuteMsg, InstantiateMsg, QueryMsg, Config, UpdateConfig, MigrateMsg};
 use membrane::math::{decimal_division, decimal_multiplication, decimal_subtraction};
 use membrane::oracle::{PriceResponse, QueryMsg as OracleQueryMsg};
@@ -21,6 +22,13 @@ use crate::state::{CONFIG, DEBT_AUCTION, FEE_AUCTIONS, OWNERSHIP_TRANSFER};
 const CONTRACT_NAME: &str = "auctions";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+//Execute msgs handled by the pause guard
+//Auctions are started by bad debt checks in liquidations so they're never paused
+const PAUSE_ACTIONS: PauseActions = PauseActions {
+    pausable: &["swap_for_t_e_m_a", "swap_for_fee", "remove_auction", "update_config"],
+    unpausable: &["start_auction"],
+};
+
 //Constants
 const MAX_LIMIT: u64 = 31u64;
 
@@ -77,6 +85,9 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
+    //Check the pause guard
+    assert_not_paused(deps.storage, &env, &msg, &PAUSE_ACTIONS)?;
+
     match msg {
         ExecuteMsg::StartAuction {
             repayment_position_info,
@@ -87,6 +98,10 @@ pub fn execute(
         ExecuteMsg::SwapForFee { auction_asset } => swap_with_the_contracts_desired_asset(deps, info, env, auction_asset),
         ExecuteMsg::RemoveAuction { } => remove_auction(deps, info),
         ExecuteMsg::UpdateConfig ( update)  => update_config( deps, info, update),
+        ExecuteMsg::Pause(pause_msg) => {
+            let config = CONFIG.load(deps.storage)?;
+            Ok(execute_pause(deps.storage, deps.api, &env, &info, info.sender == config.owner, &PAUSE_ACTIONS, pause_msg)?)
+        }
     }
 }
 
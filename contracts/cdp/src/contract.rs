@@ -7,6 +7,7 @@ use cosmwasm_std::{
     MessageInfo, Reply, Response, StdError, StdResult, Uint128, WasmMsg,
 };
 
+use membrane::pause::{assert_not_paused, execute_pause, PauseActions};
 use membrane::auction::ExecuteMsg as AuctionExecuteMsg;
 use membrane::helpers::assert_sent_native_token_balance;
 use membrane::liq_queue::ExecuteMsg as LQ_ExecuteMsg;
@@ -23,7 +24,7 @@ use crate::positions::{
     deposit,
     edit_basket, increase_debt,
     liq_repay, repay, redeem_for_collateral, edit_redemption_info,
-    withdraw, BAD_DEBT_REPLY_ID, WITHDRAW_REPLY_ID,
+    withdraw, BAD_DEBT_REPLY_ID, WITHDRAW_REPLY_ID, LQ_FALLBACK_REPLY_ID,
     LIQ_QUEUE_REPLY_ID, USER_SP_REPAY_REPLY_ID, create_basket,
 };
 use crate::query::{
@@ -37,6 +38,13 @@ use crate::state::{ get_target_position, update_position, CollateralVolatility,
 const CONTRACT_NAME: &str = "crates.io:cdp";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+//Execute msgs handled by the pause guard
+//Repayments & liquidations are never paused
+const PAUSE_ACTIONS: PauseActions = PauseActions {
+    pausable: &["update_config", "deposit", "increase_debt", "withdraw", "redeem_collateral", "edit_redeemability", "accrue", "edit_basket", "editc_asset"],
+    unpausable: &["repay", "liq_repay", "liquidate", "callback"],
+};
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
@@ -127,6 +135,9 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
+    //Check the pause guard
+    assert_not_paused(deps.storage, &env, &msg, &PAUSE_ACTIONS)?;
+
     match msg {
         ExecuteMsg::UpdateConfig (update) => update_config(deps, info, update),
         ExecuteMsg::Deposit { position_owner, position_id} => {
@@ -254,6 +265,10 @@ pub fn execute(
                 Err(ContractError::Unauthorized { owner: env.contract.address.to_string() })
             }
         }
+        ExecuteMsg::Pause(pause_msg) => {
+            let config = CONFIG.load(deps.storage)?;
+            Ok(execute_pause(deps.storage, deps.api, &env, &info, info.sender == config.owner, &PAUSE_ACTIONS, pause_msg)?)
+        }
     }
 }
 
@@ -513,6 +528,7 @@ pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> StdResult<Response> {
         LIQ_QUEUE_REPLY_ID => handle_liq_queue_reply(deps, msg, env),
         WITHDRAW_REPLY_ID => handle_withdraw_reply(deps, env, msg),
         BAD_DEBT_REPLY_ID => Ok(Response::new()),
+        LQ_FALLBACK_REPLY_ID => Ok(Response::new().add_attribute("fallback_error", msg.result.into_result().err().unwrap_or_default())),
         id => Err(StdError::generic_err(format!("invalid reply id: {}", id))),
     }
 }
@@ -10,12 +10,12 @@ use membrane::cdp::{Config, ExecuteMsg, CallbackMsg};
 use membrane::oracle::PriceResponse;
 use membrane::osmosis_proxy::QueryMsg as OsmoQueryMsg;
 use membrane::stability_pool::{LiquidatibleResponse as SP_LiquidatibleResponse, ExecuteMsg as SP_ExecuteMsg, QueryMsg as SP_QueryMsg};
-use membrane::liq_queue::{ExecuteMsg as LQ_ExecuteMsg, QueryMsg as LQ_QueryMsg, LiquidatibleResponse as LQ_LiquidatibleResponse};
+use membrane::liq_queue::{ExecuteMsg as LQ_ExecuteMsg, QueryMsg as LQ_QueryMsg, LiquidatibleResponse as LQ_LiquidatibleResponse, FallbackCapitalResponse as LQ_FallbackCapitalResponse};
 use membrane::staking::ExecuteMsg as StakingExecuteMsg;
 use membrane::types::{Basket, Position, AssetInfo, UserInfo, Asset, cAsset, PoolStateResponse, AssetPool};
 
 use crate::error::ContractError; 
-use crate::positions::{BAD_DEBT_REPLY_ID, USER_SP_REPAY_REPLY_ID, LIQ_QUEUE_REPLY_ID};
+use crate::positions::{BAD_DEBT_REPLY_ID, USER_SP_REPAY_REPLY_ID, LIQ_QUEUE_REPLY_ID, LQ_FALLBACK_REPLY_ID};
 use crate::query::{insolvency_check, get_cAsset_ratios};
 use crate::risk_engine::update_basket_tally;
 use crate::state::{get_target_position, update_position, LiquidationPropagation, Timer, BASKET, CONFIG, FREEZE_TIMER, LIQUIDATION};
@@ -654,10 +654,18 @@ pub fn build_sp_submsgs(
             leftover_repayment = Decimal::from_ratio(basket.credit_price.get_amount(leftover_position_value)?, Uint128::one());            
         }        
 
-        //If SP AssetPool is 0, repay nothing
-        if sp_pool.credit_asset.amount.is_zero(){
-            leftover_repayment = Decimal::zero();
-        }
+        //Split the leftovers between the SP & the LQ's sp_fallback bids.
+        //Fallback bids only repay what the SP can't.
+        let sp_capital = Decimal::from_ratio(sp_pool.credit_asset.amount, Uint128::one());
+        let (sp_repayment, fallback_repayment) = if leftover_repayment > sp_capital {
+            let fallback_capital = query_fallback_capital(querier, basket.clone());
+
+            (sp_capital, (leftover_repayment - sp_capital).min(fallback_capital))
+        } else {
+            (leftover_repayment, Decimal::zero())
+        };
+        //If neither has capital, repay nothing
+        leftover_repayment = sp_repayment + fallback_repayment;
         
         // Set repay values for reply msg
         let liquidation_propagation = LiquidationPropagation {
@@ -681,11 +689,11 @@ pub fn build_sp_submsgs(
         LIQUIDATION.save(storage, &liquidation_propagation)?;
 
         //We use 1 as our 0 to account for LQ rounding errors
-        if leftover_repayment > Decimal::one() {
+        if sp_repayment > Decimal::one() {
 
             //Stability Pool message builder
             let liq_msg = SP_ExecuteMsg::Liquidate {
-                liq_amount: leftover_repayment
+                liq_amount: sp_repayment
             };
 
             let msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
@@ -704,6 +712,20 @@ pub fn build_sp_submsgs(
             //( https://github.com/CosmWasm/cosmwasm/blob/main/SEMANTICS.md#submessages )
         }
 
+        //LQ fallback runs after the SP's repay & distribution, same flow as the SP
+        if fallback_repayment > Decimal::one() {
+            let msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: basket.clone().liq_queue.unwrap_or_else(|| Addr::unchecked("")).to_string(),
+                msg: to_binary(&LQ_ExecuteMsg::FallbackLiquidate {
+                    liq_amount: fallback_repayment
+                })?,
+                funds: vec![],
+            });
+
+            //Reply on error so a failed fallback leaves the debt to the bad debt check instead of reverting the liquidation
+            submessages.push(SubMsg::reply_on_error(msg, LQ_FALLBACK_REPLY_ID));
+        }
+
         //Collateral distributions get handled in the reply        
     } else {
         //In case SP isn't used, we need to set LiquidationPropagation
@@ -732,6 +754,25 @@ pub fn build_sp_submsgs(
     Ok((leftover_repayment))
 }
 
+/// Returns the LQ's sp_fallback bid capital, 0 if there is no LQ or the query fails
+fn query_fallback_capital(
+    querier: QuerierWrapper,
+    basket: Basket,
+) -> Decimal {
+    match basket.liq_queue {
+        Some(liq_queue) => {
+            match querier.query_wasm_smart::<LQ_FallbackCapitalResponse>(
+                liq_queue.to_string(),
+                &LQ_QueryMsg::FallbackCapital {},
+            ){
+                Ok(res) => Decimal::from_ratio(Uint128::new(res.capital.into()), Uint128::one()),
+                Err(_) => Decimal::zero(),
+            }
+        },
+        None => Decimal::zero(),
+    }
+}
+
 /// Returns leftover liquidatible amount from the stability pool
 pub fn query_stability_pool_liquidatible(
     querier: QuerierWrapper,
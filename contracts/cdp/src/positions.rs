@@ -39,6 +39,7 @@ pub const SP_REPLY_ID: u64 = 2u64;
 pub const USER_SP_REPAY_REPLY_ID: u64 = 3u64;
 
 pub const WITHDRAW_REPLY_ID: u64 = 4u64;
+pub const LQ_FALLBACK_REPLY_ID: u64 = 5u64;
 pub const BAD_DEBT_REPLY_ID: u64 = 999999u64;
 
 
@@ -704,8 +705,9 @@ pub fn liq_repay(
     let config = liquidation_propagation.clone().config;
     let mut basket = liquidation_propagation.clone().basket;
 
-    //Can only be called by the SP contract
-    if config.stability_pool.is_none() || info.sender != config.clone().stability_pool.unwrap_or_else(|| Addr::unchecked("")){
+    //Can only be called by the SP contract or the LQ for its sp_fallback bids
+    let from_liq_queue = basket.liq_queue.is_some() && info.sender == basket.clone().liq_queue.unwrap_or_else(|| Addr::unchecked(""));
+    if !from_liq_queue && (config.stability_pool.is_none() || info.sender != config.clone().stability_pool.unwrap_or_else(|| Addr::unchecked(""))){
         return Err(ContractError::Unauthorized { owner: config.owner.to_string() });
     }
     //This position has collateral & credit_amount updated in the liquidation process...
@@ -725,7 +727,7 @@ pub fn liq_repay(
             }
         },
         Err(_err) => {
-            //Send the excess repayment back to the SP
+            //Send the excess repayment back to the SP or LQ
             excess_repayment = credit_asset.amount - target_position.credit_amount;
 
             let excess_repayment_msg = withdrawal_msg(
@@ -733,7 +735,7 @@ pub fn liq_repay(
                     amount: excess_repayment,
                     ..basket.clone().credit_asset
                 },
-                config.clone().stability_pool.unwrap_or_else(|| Addr::unchecked("")),
+                info.clone().sender,
             )?;
             //Update credit_asset amount so its correct for the burn
             credit_asset.amount = target_position.credit_amount;
@@ -836,7 +838,7 @@ pub fn liq_repay(
             deps.querier, 
             env.clone(), 
             &mut basket,
-            liquidation_propagation.liquidated_assets,
+            liquidation_propagation.clone().liquidated_assets,
             target_position.clone().collateral_assets,
             false,
             config.clone(),
@@ -845,22 +847,40 @@ pub fn liq_repay(
     }
 
     //Update position
-    update_position(deps.storage, liquidation_propagation.position_owner, target_position)?;
+    update_position(deps.storage, liquidation_propagation.clone().position_owner, target_position.clone())?;
     //Update Basket
     BASKET.save(deps.storage, &basket)?;
 
+    //Save state for a following LQ fallback repayment.
+    //Liquidated assets have been removed from the Supply caps so they are cleared.
+    liquidation_propagation.target_position = target_position;
+    liquidation_propagation.basket = basket;
+    liquidation_propagation.liquidated_assets = vec![];
+    LIQUIDATION.save(deps.storage, &liquidation_propagation)?;
+
     //Adds Native token distribution msg to messages
-    let distribution_msg = SP_ExecuteMsg::Distribute {
-        distribution_assets: distribution_assets.clone(),
-        distribution_asset_ratios: cAsset_ratios, //The distributions are based off cAsset_ratios so they shouldn't change
-        distribute_for: credit_asset.amount,
+    let msg = if from_liq_queue {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: info.sender.to_string(),
+            msg: to_binary(&LQ_ExecuteMsg::DistributeFallback {
+                distribution_assets: distribution_assets.clone(),
+                distribute_for: credit_asset.amount,
+            })?,
+            funds: coins,
+        })
+    } else {
+        let distribution_msg = SP_ExecuteMsg::Distribute {
+            distribution_assets: distribution_assets.clone(),
+            distribution_asset_ratios: cAsset_ratios, //The distributions are based off cAsset_ratios so they shouldn't change
+            distribute_for: credit_asset.amount,
+        };
+        //Build the Execute msg w/ the full list of native tokens
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: config.stability_pool.unwrap_or_else(|| Addr::unchecked("")).to_string(),
+            msg: to_binary(&distribution_msg)?,
+            funds: coins,
+        })
     };
-    //Build the Execute msg w/ the full list of native tokens
-    let msg = CosmosMsg::Wasm(WasmMsg::Execute {
-        contract_addr: config.stability_pool.unwrap_or_else(|| Addr::unchecked("")).to_string(),
-        msg: to_binary(&distribution_msg)?,
-        funds: coins,
-    });
     messages.push(msg);
     
     Ok(Response::new()
@@ -1877,6 +1897,7 @@ pub fn edit_basket(
                         ),                       
                         decimals: 18,
                         pyth_price_feed_id: None,
+                        external_oracle: None,
                     },
                 })?,
                 funds: vec![],
@@ -2004,6 +2025,7 @@ pub fn edit_basket(
                         lp_pool_info: None,
                         decimals: 6,
                         pyth_price_feed_id: None,
+                        external_oracle: None,
                     }),
                     remove: false,
                 })?,
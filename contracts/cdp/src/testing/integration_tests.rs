@@ -950,6 +950,7 @@ mod tests {
                             current_supply: Uint128::new(200_000u128),
                             max_supply: Uint128::new(1_000_000_000_000_000u128),
                             burned_supply: Uint128::zero(),
+                            mint_limits: vec![],
                         })?)
                     },
                     Osmo_MockQueryMsg::GetOwner { owner } => {
@@ -1039,6 +1040,7 @@ mod tests {
                             current_supply: Uint128::new(200_000u128),
                             max_supply: Uint128::new(1_000_000_000_000_000u128),
                             burned_supply: Uint128::zero(),
+                            mint_limits: vec![],
                         })?)
                     },
                     Osmo_MockQueryMsg::GetOwner { owner } => {
@@ -1181,6 +1183,13 @@ mod tests {
                             vesting_contract: None,
                             governance_contract: None,
                             osmosis_proxy: None,
+                            participation_requirements: None,
+                            dex_router: None,
+                            keeper_tip: Decimal::zero(),
+                            instant_unstake_penalty: None,
+                            stability_pool: None,
+                            oracle_contract: None,
+                            max_slippage: Decimal::zero(),
                         })?)
                     }
                 }
@@ -1321,6 +1330,7 @@ mod tests {
                                 lp_pool_info: None,
                                 decimals: 6,
                                 pyth_price_feed_id: None,
+                                external_oracle: None,
                             }],
                         },
                         AssetResponse {
@@ -1338,6 +1348,7 @@ mod tests {
                                 lp_pool_info: None,
                                 decimals: 6,
                                 pyth_price_feed_id: None,
+                                external_oracle: None,
                             }],
                         }
                     ])?),
@@ -1414,6 +1425,7 @@ mod tests {
                                 lp_pool_info: None,
                                 decimals: 6,
                                 pyth_price_feed_id: None,
+                                external_oracle: None,
                             }],
                         },
                         AssetResponse {
@@ -1431,6 +1443,7 @@ mod tests {
                                 lp_pool_info: None,
                                 decimals: 6,
                                 pyth_price_feed_id: None,
+                                external_oracle: None,
                             }],
                         }
                     ])?),
@@ -1857,6 +1870,7 @@ mod tests {
             ExecuteMsg, InsolvencyResponse, PositionResponse, InterestResponse, RedeemabilityResponse
         };
         use membrane::types::{InsolventPosition, LPAssetInfo, PoolInfo, SupplyCap, UserInfo, Basket};
+        use membrane::pause::PauseMsg;
 
         #[test]
         fn freeze(){
@@ -3007,6 +3021,112 @@ mod tests {
 
         }
 
+        #[test]
+        fn pause_guard() {
+            let (mut app, cdp_contract, lq_contract) =
+                proper_instantiate(false, false, false, false);
+
+            //Edit Basket
+            let msg = ExecuteMsg::EditBasket(EditBasket {
+                take_revenue: None,
+                added_cAsset: None,
+                liq_queue: Some(lq_contract.addr().to_string()),
+                credit_pool_infos: Some(vec![PoolType::Balancer { pool_id: 1u64 }]),
+                collateral_supply_caps: Some(vec![SupplyCap {
+                    asset_info: AssetInfo::NativeToken {
+                        denom: "debit".to_string(),
+                    },
+                    current_supply: Uint128::zero(),
+                    debt_total: Uint128::zero(),
+                    supply_cap_ratio: Decimal::percent(100),
+                    lp: false,
+                        stability_pool_ratio_for_debt_cap: None,
+                }]),
+                base_interest_rate: None,
+                credit_asset_twap_price_source: None,
+                negative_rates: None,
+                cpc_margin_of_error: None,
+                frozen: None,
+                rev_to_stakers: None,
+                multi_asset_supply_caps: None,
+            });
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            //Initial Deposit & Increase
+            let msg = ExecuteMsg::Deposit {
+                position_owner: Some("test".to_string()),
+                position_id: None,
+            };
+            let cosmos_msg = cdp_contract
+                .call(msg, vec![coin(100_000_000_000, "debit")])
+                .unwrap();
+            app.execute(Addr::unchecked("test"), cosmos_msg).unwrap();
+
+            let msg = ExecuteMsg::IncreaseDebt {
+                position_id: Uint128::from(1u128),
+                amount: Some(Uint128::from(50_000_000_000u128)),
+                LTV: None,
+                mint_to_addr: None,
+            };
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked("test"), cosmos_msg).unwrap();
+
+            //Error: Repayments & liquidations can't be paused
+            for action in ["repay", "liquidate"] {
+                let msg = ExecuteMsg::Pause(PauseMsg::Pause { actions: vec![String::from(action)], duration: 86_400 });
+                let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+                let err = app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap_err();
+                assert_eq!(err.root_cause().to_string(), format!("Generic error: {} isn't a pausable action", action));
+            }
+
+            //Pause all actions
+            let msg = ExecuteMsg::Pause(PauseMsg::Pause { actions: vec![String::from("all")], duration: 86_400 });
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            let deposit_msg = ExecuteMsg::Deposit {
+                position_owner: None,
+                position_id: None,
+            };
+            let cosmos_msg = cdp_contract
+                .call(deposit_msg.clone(), vec![coin(10_000_000, "debit")])
+                .unwrap();
+            let err = app.execute(Addr::unchecked("big_bank"), cosmos_msg).unwrap_err();
+            assert_eq!(
+                err.root_cause().to_string(),
+                format!("Generic error: deposit is paused until {}", app.block_info().time.seconds() + 86_400)
+            );
+
+            //Repayments still go through
+            app.send_tokens(
+                Addr::unchecked("sender"),
+                Addr::unchecked("test"),
+                &[coin(50_000_000_000, "credit_fulldenom")],
+            )
+            .unwrap();
+
+            let msg = ExecuteMsg::Repay {
+                position_id: Uint128::from(1u128),
+                position_owner: None,
+                send_excess_to: None,
+            };
+            let cosmos_msg = cdp_contract
+                .call(msg, vec![coin(50_000_000_000, "credit_fulldenom")])
+                .unwrap();
+            app.execute(Addr::unchecked("test"), cosmos_msg).unwrap();
+
+            //Unpause
+            let msg = ExecuteMsg::Pause(PauseMsg::Unpause { actions: vec![String::from("all")] });
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            let cosmos_msg = cdp_contract
+                .call(deposit_msg, vec![coin(10_000_000, "debit")])
+                .unwrap();
+            app.execute(Addr::unchecked("big_bank"), cosmos_msg).unwrap();
+        }
+
         #[test]
         fn accrue_debt() {
             // panic!("{}", PriceResponse {
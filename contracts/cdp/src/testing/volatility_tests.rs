@@ -524,6 +524,7 @@ mod tests {
                             current_supply: Uint128::new(200_000u128),
                             max_supply: Uint128::new(1_000_000_000_000_000u128),
                             burned_supply: Uint128::zero(),
+                            mint_limits: vec![],
                         })?)
                     },
                     Osmo_MockQueryMsg::GetOwner { owner } => {
@@ -666,6 +667,13 @@ mod tests {
                             vesting_contract: None,
                             governance_contract: None,
                             osmosis_proxy: None,
+                            participation_requirements: None,
+                            dex_router: None,
+                            keeper_tip: Decimal::zero(),
+                            instant_unstake_penalty: None,
+                            stability_pool: None,
+                            oracle_contract: None,
+                            max_slippage: Decimal::zero(),
                         })?)
                     }
                 }
@@ -792,6 +800,7 @@ mod tests {
                                 lp_pool_info: None,
                                 decimals: 6,
                                 pyth_price_feed_id: None,
+                                external_oracle: None,
                             }],
                         },
                         AssetResponse {
@@ -809,6 +818,7 @@ mod tests {
                                 lp_pool_info: None,
                                 decimals: 6,
                                 pyth_price_feed_id: None,
+                                external_oracle: None,
                             }],
                         }
                     ])?),
@@ -869,6 +879,7 @@ mod tests {
                                 lp_pool_info: None,
                                 decimals: 6,
                                 pyth_price_feed_id: None,
+                                external_oracle: None,
                             }],
                         },
                         AssetResponse {
@@ -886,6 +897,7 @@ mod tests {
                                 lp_pool_info: None,
                                 decimals: 6,
                                 pyth_price_feed_id: None,
+                                external_oracle: None,
                             }],
                         }
                     ])?),
@@ -947,6 +959,7 @@ mod tests {
                                 lp_pool_info: None,
                                 decimals: 6,
                                 pyth_price_feed_id: None,
+                                external_oracle: None,
                             }],
                         },
                         AssetResponse {
@@ -964,6 +977,7 @@ mod tests {
                                 lp_pool_info: None,
                                 decimals: 6,
                                 pyth_price_feed_id: None,
+                                external_oracle: None,
                             }],
                         }
                     ])?),
@@ -1025,6 +1039,7 @@ mod tests {
                                 lp_pool_info: None,
                                 decimals: 6,
                                 pyth_price_feed_id: None,
+                                external_oracle: None,
                             }],
                         },
                         AssetResponse {
@@ -1042,6 +1057,7 @@ mod tests {
                                 lp_pool_info: None,
                                 decimals: 6,
                                 pyth_price_feed_id: None,
+                                external_oracle: None,
                             }],
                         }
                     ])?),
@@ -1102,6 +1118,7 @@ mod tests {
                                 lp_pool_info: None,
                                 decimals: 6,
                                 pyth_price_feed_id: None,
+                                external_oracle: None,
                             }],
                         },
                         AssetResponse {
@@ -1119,6 +1136,7 @@ mod tests {
                                 lp_pool_info: None,
                                 decimals: 6,
                                 pyth_price_feed_id: None,
+                                external_oracle: None,
                             }],
                         }
                     ])?),
@@ -1180,6 +1198,7 @@ mod tests {
                                 lp_pool_info: None,
                                 decimals: 6,
                                 pyth_price_feed_id: None,
+                                external_oracle: None,
                             }],
                         },
                         AssetResponse {
@@ -1197,6 +1216,7 @@ mod tests {
                                 lp_pool_info: None,
                                 decimals: 6,
                                 pyth_price_feed_id: None,
+                                external_oracle: None,
                             }],
                         }
                     ])?),
@@ -10,6 +10,7 @@ use cosmwasm_std::{
 };
 use cw2::set_contract_version;
 
+use membrane::pause::{assert_not_paused, execute_pause, PauseActions};
 use membrane::cdp::QueryMsg as CDPQueryMsg;
 use membrane::helpers::{multi_native_withdrawal_msg, get_pool_state_response, accrue_user_positions};
 use membrane::discount_vault::{Config, ExecuteMsg, InstantiateMsg, QueryMsg, UserResponse};
@@ -22,6 +23,12 @@ use crate::state::{CONFIG, USERS, OWNERSHIP_TRANSFER};
 const CONTRACT_NAME: &str = "crates.io:discount_vault";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+//Execute msgs handled by the pause guard
+const PAUSE_ACTIONS: PauseActions = PauseActions {
+    pausable: &["deposit", "withdraw", "change_owner", "edit_accepted_l_ps", "toggle_deposits"],
+    unpausable: &[],
+};
+
 //Constants
 pub const SECONDS_PER_DAY: u64 = 86_400u64;
 
@@ -114,12 +121,19 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
+    //Check the pause guard
+    assert_not_paused(deps.storage, &env, &msg, &PAUSE_ACTIONS)?;
+
     match msg {
         ExecuteMsg::Deposit {  } => deposit(deps, env, info),
         ExecuteMsg::Withdraw { withdrawal_assets } => withdraw(deps, info, withdrawal_assets),
         ExecuteMsg::ChangeOwner { owner } => change_owner(deps, info, owner),
         ExecuteMsg::EditAcceptedLPs { pool_ids, remove } => edit_LPs(deps, info, pool_ids, remove),
         ExecuteMsg::ToggleDeposits { enable } => toggle_deposits(deps, info, enable),
+        ExecuteMsg::Pause(pause_msg) => {
+            let config = CONFIG.load(deps.storage)?;
+            Ok(execute_pause(deps.storage, deps.api, &env, &info, info.sender == config.owner, &PAUSE_ACTIONS, pause_msg)?)
+        }
     }
 }
 
@@ -2,36 +2,36 @@
 //Proposal Msg tutorial: https://blog.astroport.fi/post/tutorial-structuring-executable-messages-for-assembly-proposals-part-2-adding-proxy-contracts
 
 use cosmwasm_std::{
-    attr, entry_point, to_binary, Addr, Binary, CosmosMsg, Decimal, Deps, DepsMut, Env,
-    MessageInfo, Order, QueryRequest, Response, StdResult, Uint128, Uint64, WasmMsg, WasmQuery, Storage, QuerierWrapper,
+    attr, entry_point, to_binary, to_vec, Addr, BankMsg, Binary, ContractResult, CosmosMsg, Decimal, Deps, DepsMut, Empty, Env,
+    MessageInfo, Order, QueryRequest, Reply, Response, StdError, StdResult, SubMsg, SystemResult, Uint128, Uint64, WasmMsg, WasmQuery, Storage, QuerierWrapper,
 };
 use osmosis_std::types::osmosis::incentives::{MsgCreateGauge, MsgAddToGauge};
 use cw2::set_contract_version;
 use cw_storage_plus::Bound;
 
-use membrane::helpers::{query_staking_totals, asset_to_coin, query_basket};
+use membrane::helpers::query_staking_totals;
 use membrane::math::decimal_multiplication;
-use membrane::types::{SupplyCap, AssetInfo, Asset, Basket, BidInput};
+use membrane::types::{SupplyCap, AssetInfo, Delegate};
 use membrane::vesting::{AllocationResponse, QueryMsg as VestingQueryMsg, RecipientsResponse};
-use membrane::governance::helpers::validate_links;
+use membrane::governance::helpers::{get_json_decimal, get_json_msg_name, validate_links};
 use membrane::governance::{
-    Config, ExecuteMsg, InstantiateMsg, Proposal, ProposalListResponse, ProposalMessage,
-    ProposalResponse, ProposalStatus, ProposalVoteOption, ProposalVotesResponse, QueryMsg,
-    UpdateConfig, BLOCKS_PER_DAY, MigrateMsg
+    Config, DepositStatus, ExecuteMsg, FieldGuardrail, Guardrail, HealthCheck, HealthCheckCondition, InstantiateMsg, OldProposal, OptionVote, OptionVoterResponse, Proposal, ProposalOption, QueuedProposal, TimelockDelay, ProposalListResponse, ProposalMessage,
+    ProposalDeposit, ProposalResponse, ProposalStatus, ProposalVoteOption, ProposalVoterResponse, ProposalVotesResponse, QueryMsg, Vote, VoterPower,
+    UpdateConfig, BLOCKS_PER_DAY, MAX_TIMELOCK_DELAY, MigrateMsg
 };
-use membrane::cdp::{ExecuteMsg as CDP_ExecuteMsg, EditBasket, QueryMsg as CDP_QueryMsg, Config as CDP_Config};
+use membrane::cdp::{ExecuteMsg as CDP_ExecuteMsg, EditBasket};
 use membrane::staking::{
     Config as StakingConfig, DelegationResponse, ExecuteMsg as Staking_ExecuteMsg, QueryMsg as StakingQueryMsg, StakedResponse, StakerResponse, TotalStakedResponse
 };
-use membrane::liq_queue::{Config as LQ_Config, ExecuteMsg as LQ_ExecuteMsg, QueryMsg as LQ_QueryMsg, QueueResponse};
-use membrane::osmosis_proxy::ExecuteMsg as OP_ExecuteMsg;
 
 use core::panic;
-use std::cmp::min;
+use std::cmp::{max, min};
+use std::collections::BTreeSet;
 use std::str::FromStr;
 
 use crate::error::ContractError;
-use crate::state::{CONFIG, PROPOSALS, PROPOSAL_COUNT, PENDING_PROPOSALS};
+use crate::treasury::{cancel_stream, claim_stream, create_stream, edit_budgets, query_budgets, query_streams};
+use crate::state::{CONFIG, DELEGATE_OVERRIDES, DEPOSITS, ESCROWED_DEPOSITS, GUARDRAILS, HEALTH_CHECKS, OLD_PENDING_PROPOSALS, OLD_PROPOSALS, PENDING_HEALTH_CHECKS, STAKER_DEPOSIT, VOTES, PROPOSALS, PROPOSAL_COUNT, PENDING_PROPOSALS, TIMELOCK_DELAYS, TIMELOCK_QUEUE};
 
 // Contract name and version used for migration.
 const CONTRACT_NAME: &str = "tema-governance";
@@ -46,6 +46,10 @@ const MAX_VOTERS_LIMIT: u32 = 250;
 //External query limit
 const STAKE_QUERY_LIMIT: u32 = 1024;
 
+//Reply IDs
+const REPORT_PARTICIPATION_REPLY_ID: u64 = 1u64;
+const STAKER_DEPOSIT_REPLY_ID: u64 = 2u64;
+
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -82,6 +86,10 @@ pub fn instantiate(
         proposal_required_threshold: Decimal::from_str(&msg.proposal_required_threshold)?,
         whitelisted_links: msg.whitelisted_links,
         quadratic_voting: true,
+        guardian: None,
+        timelock_delay: 0,
+        proposal_deposit: None,
+        removed_deposit_to_stakers: false,
     };
 
     config.validate()?;
@@ -109,6 +117,7 @@ pub fn execute(
             description,
             link,
             messages,
+            options,
             recipient,
             expedited,
         } => submit_proposal(
@@ -119,6 +128,7 @@ pub fn execute(
             description,
             link,
             messages,
+            options,
             recipient,
             expedited,
         ),
@@ -127,13 +137,39 @@ pub fn execute(
             vote,
             recipient,
         } => cast_vote(deps, env, info, proposal_id, vote, recipient),
+        ExecuteMsg::CastOptionVote {
+            proposal_id,
+            weights,
+            recipient,
+        } => cast_option_vote(deps, env, info, proposal_id, Some(weights), None, recipient),
+        ExecuteMsg::CastRankedVote {
+            proposal_id,
+            ranking,
+            recipient,
+        } => cast_option_vote(deps, env, info, proposal_id, None, Some(ranking), recipient),
         ExecuteMsg::EndProposal { proposal_id } => end_proposal(deps, env, proposal_id),
+        ExecuteMsg::QueueProposal { proposal_id } => queue_proposal(deps, env, proposal_id),
         ExecuteMsg::ExecuteProposal { proposal_id } => execute_proposal(deps, env, proposal_id),
-        ExecuteMsg::CheckMessages { messages, msg_switch } => check_messages(deps, env, messages, msg_switch),
+        ExecuteMsg::CancelProposal { proposal_id } => cancel_proposal(deps, env, info, proposal_id),
+        ExecuteMsg::CheckMessages { messages, checks } => check_messages(deps, env, messages, checks),
         ExecuteMsg::CheckMessagesPassed { error } => passed_messages(deps, env, error),
         ExecuteMsg::RemoveCompletedProposal { proposal_id } => {
             remove_completed_proposal(deps, env, info, proposal_id)
         }
+        ExecuteMsg::EditHealthChecks { add, remove } => edit_health_checks(deps, env, info, add, remove),
+        ExecuteMsg::EditGuardrails { guardrails } => edit_guardrails(deps, env, info, guardrails),
+        ExecuteMsg::EditBudgets { budgets } => edit_budgets(deps, env, info, budgets),
+        ExecuteMsg::CreateStream {
+            category,
+            recipient,
+            denom,
+            amount,
+            start_time,
+            cliff_time,
+            end_time,
+        } => create_stream(deps, env, info, category, recipient, denom, amount, start_time, cliff_time, end_time),
+        ExecuteMsg::CancelStream { stream_id } => cancel_stream(deps, env, info, stream_id),
+        ExecuteMsg::ClaimStream { stream_id } => claim_stream(deps, env, info, stream_id),
         ExecuteMsg::UpdateConfig(config) => update_config(deps, env, info, config),
         ExecuteMsg::CreateOsmosisGauge { gauge_msg } => create_gauge(info, env, gauge_msg),
         ExecuteMsg::AddToOsmosisGauge { gauge_msg } => add_to_gauge(info, env, gauge_msg),
@@ -152,6 +188,7 @@ pub fn submit_proposal(
     description: String,
     link: Option<String>,
     messages: Option<Vec<ProposalMessage>>,
+    options: Option<Vec<ProposalOption>>,
     recipient: Option<String>,
     mut expedited: bool,
 ) -> Result<Response, ContractError> {
@@ -173,6 +210,13 @@ pub fn submit_proposal(
     if non_vested_total < config.minimum_total_stake {
         return Err(ContractError::InsufficientTotalStake { minimum: config.minimum_total_stake.into() });
     }   
+
+    //Assert the proposal deposit was sent
+    if let Some(deposit) = config.proposal_deposit.clone() {
+        if info.funds != vec![deposit.clone()] {
+            return Err(ContractError::InvalidDeposit { deposit: deposit.to_string() });
+        }
+    }
    
     //Validate voting power
     let voting_power = calc_voting_power(
@@ -199,12 +243,15 @@ pub fn submit_proposal(
         submitter = Some(deps.api.addr_validate(&recipient)?);
     }
 
+    //Multiple-choice proposals are executable if any option has messages
+    let executable = messages.is_some() || options.clone().unwrap_or_default().iter().any(|option| option.messages.is_some());
+
     //Set end_block 
     let end_block: u64 = {
         //Config is all in blocks, the 'times 6' converts it to 6 seconds per block
         if expedited {
             env.block.time.seconds() + (config.expedited_proposal_voting_period * 6) 
-        } else if messages.is_some() && config.proposal_voting_period <  (7 * BLOCKS_PER_DAY){ //Proposals with executables have to be at least 7 days
+        } else if executable && config.proposal_voting_period <  (7 * BLOCKS_PER_DAY){ //Proposals with executables have to be at least 7 days
             env.block.time.seconds() + (7 * BLOCKS_PER_DAY * 6)
         } else {
             env.block.time.seconds() + (config.proposal_voting_period * 6) 
@@ -212,7 +259,6 @@ pub fn submit_proposal(
     };
 
     let mut proposal = Proposal {
-        voting_power: vec![],
         proposal_id: count,
         submitter: submitter.unwrap_or_else(|| info.sender.clone()),
         status: ProposalStatus::Active,
@@ -221,11 +267,6 @@ pub fn submit_proposal(
         against_power: Uint128::zero(),
         amendment_power: Uint128::zero(),
         removal_power: Uint128::zero(),
-        aligned_voters: vec![info.sender.clone()],
-        for_voters: Vec::new(),
-        against_voters: Vec::new(),
-        amendment_voters: Vec::new(),
-        removal_voters: Vec::new(),
         start_block: env.block.height,
         start_time: env.block.time.seconds(),
         end_block,
@@ -238,10 +279,40 @@ pub fn submit_proposal(
         description,
         link,
         messages,
+        option_power: vec![Uint128::zero(); options.clone().unwrap_or_default().len()],
+        options,
+        winning_option: None,
     };
 
     proposal.validate(config.whitelisted_links)?;
 
+    //Check messages against the parameter guardrails
+    check_guardrails(deps.storage, deps.querier, &proposal.messages.clone().unwrap_or_default())?;
+    for option in proposal.options.clone().unwrap_or_default() {
+        check_guardrails(deps.storage, deps.querier, &option.messages.unwrap_or_default())?;
+    }
+
+    //Escrow the deposit, refunded to the submitter
+    if let Some(deposit) = config.proposal_deposit.clone() {
+        DEPOSITS.save(deps.storage, count.u64(), &ProposalDeposit {
+            depositor: proposal.submitter.clone(),
+            amount: deposit.clone(),
+            status: DepositStatus::Escrowed,
+        })?;
+        ESCROWED_DEPOSITS.update(deps.storage, deposit.denom, |escrowed| -> StdResult<Uint128> {
+            Ok(escrowed.unwrap_or_default() + deposit.amount)
+        })?;
+    }
+
+    //Submitter aligns with their voting power
+    VOTES.save(deps.storage, (count, info.sender.clone()), &Vote {
+        option: Some(ProposalVoteOption::Align),
+        weights: None,
+        ranking: None,
+        power: voting_power,
+        voter_power: None,
+    })?;
+
     if proposal.aligned_power >= config.proposal_required_stake && config.quadratic_voting {
         //Calc difference
         let mut difference = proposal.aligned_power.checked_sub(config.proposal_required_stake)?;
@@ -323,39 +394,28 @@ pub fn cast_vote(
     }
 
     //Get voting power from Proposal struct
-    let mut voting_power: Uint128 = calc_voting_power(
+    let (voter_power, reclaimed_delegations) = calc_proposal_voter_power(
         deps.storage, 
         deps.querier,
-        None,
+        proposal_id,
         info.sender.to_string(), 
         proposal.start_time,
-        &mut false, 
         recipient.clone(), 
         config.quadratic_voting,
     )?;
+    let mut voting_power = voter_power.total();
     if voting_power.is_zero() {
         return Err(ContractError::NoVotingPower {});
     }
 
     //Remove previous vote
-    if let Some((vote, _)) = proposal.for_voters.clone().into_iter().enumerate().find(|(_, voter)| voter == &info.sender) {
-        proposal.for_voters.remove(vote);
-        proposal.for_power = proposal.for_power.checked_sub(voting_power)?;
-
-    } else if let Some((vote, _)) = proposal.against_voters.clone().into_iter().enumerate().find(|(_, voter)| voter == &info.sender) {
-        proposal.against_voters.remove(vote);
-        proposal.against_power = proposal.against_power.checked_sub(voting_power)?;
-
-    } else if let Some((vote, _)) = proposal.amendment_voters.clone().into_iter().enumerate().find(|(_, voter)| voter == &info.sender) {
-        proposal.amendment_voters.remove(vote);
-        proposal.amendment_power = proposal.amendment_power.checked_sub(voting_power)?;
-
-    } else if let Some((vote, _)) = proposal.removal_voters.clone().into_iter().enumerate().find(|(_, voter)| voter == &info.sender) {
-        proposal.removal_voters.remove(vote);
-        proposal.removal_power = proposal.removal_power.checked_sub(voting_power)?;
-    } else if let Some((vote, _)) = proposal.aligned_voters.clone().into_iter().enumerate().find(|(_, voter)| voter == &info.sender) {
-        proposal.aligned_voters.remove(vote);
-        proposal.aligned_power = proposal.aligned_power.checked_sub(voting_power)?;
+    let previous_vote = VOTES.may_load(deps.storage, (proposal_id, info.sender.clone()))?;
+    if let Some(previous_vote) = previous_vote.clone() {
+        remove_vote_power(&mut proposal, &previous_vote, previous_vote.power)?;
+    }
+    //Voting directly overrides the voter's delegates, aligning doesn't
+    if vote_option != ProposalVoteOption::Align && !has_overridden_delegates(&previous_vote) {
+        override_delegates(deps.storage, deps.querier, proposal_id, &mut proposal, reclaimed_delegations)?;
     }
 
     match vote_option {
@@ -363,29 +423,28 @@ pub fn cast_vote(
             if pending {
                 return Err(ContractError::ProposalNotActive {});
             }
+            if proposal.options.is_some() {
+                return Err(ContractError::OptionVoteRequired {});
+            }
             proposal.for_power = proposal.for_power.checked_add(voting_power)?;
-            proposal.for_voters.push(info.sender.clone());
         }
         ProposalVoteOption::Against => {
             if pending {
                 return Err(ContractError::ProposalNotActive {});
             }
             proposal.against_power = proposal.against_power.checked_add(voting_power)?;
-            proposal.against_voters.push(info.sender.clone());
         }
         ProposalVoteOption::Amend => {
             if pending {
                 return Err(ContractError::ProposalNotActive {});
             }
             proposal.amendment_power = proposal.amendment_power.checked_add(voting_power)?;
-            proposal.amendment_voters.push(info.sender.clone());
         }
         ProposalVoteOption::Remove => {
             if pending {
                 return Err(ContractError::ProposalNotActive {});
             }
             proposal.removal_power = proposal.removal_power.checked_add(voting_power)?;
-            proposal.removal_voters.push(info.sender.clone());
         }
         ProposalVoteOption::Align => {
             //Remove quadratic voting for alignment if not reached yet
@@ -398,9 +457,7 @@ pub fn cast_vote(
                 )?.to_uint_ceil();                
 
                 //Adding voting power to proposal
-                proposal.aligned_power = proposal.aligned_power.checked_add(voting_power)?;            
-                //Add voter to aligned voters
-                proposal.aligned_voters.push(info.sender.clone());
+                proposal.aligned_power = proposal.aligned_power.checked_add(voting_power)?;
 
                 //If this addition pushes the proposal over the threshold, square root the difference & add to aligned_power.
                 ///
@@ -420,9 +477,7 @@ pub fn cast_vote(
             //If quadratic voting is disabled or the threshold has been reached, add voting power to proposal
             {
                 //Adding voting power to proposal
-                proposal.aligned_power = proposal.aligned_power.checked_add(voting_power)?;            
-                //Add voter to aligned voters
-                proposal.aligned_voters.push(info.sender.clone());
+                proposal.aligned_power = proposal.aligned_power.checked_add(voting_power)?;
             }
             //If alignment is reached, move to active proposal state
             if proposal.aligned_power >= config.proposal_required_stake {
@@ -436,6 +491,15 @@ pub fn cast_vote(
         }
     };
 
+    //Save vote
+    VOTES.save(deps.storage, (proposal_id, info.sender.clone()), &Vote {
+        option: Some(vote_option.clone()),
+        weights: None,
+        ranking: None,
+        power: voting_power,
+        voter_power: Some(voter_power),
+    })?;
+
     //Save proposal
     if !saved {
         if !pending {
@@ -454,6 +518,220 @@ pub fn cast_vote(
     ]))
 }
 
+/// Remove voting power from the tally of a vote
+fn remove_vote_power(
+    proposal: &mut Proposal,
+    vote: &Vote,
+    power: Uint128,
+) -> StdResult<()> {
+    match vote.option {
+        Some(ProposalVoteOption::For) => proposal.for_power = proposal.for_power.checked_sub(power)?,
+        Some(ProposalVoteOption::Against) => proposal.against_power = proposal.against_power.checked_sub(power)?,
+        Some(ProposalVoteOption::Amend) => proposal.amendment_power = proposal.amendment_power.checked_sub(power)?,
+        Some(ProposalVoteOption::Remove) => proposal.removal_power = proposal.removal_power.checked_sub(power)?,
+        //Aligned power is square rooted past the threshold, so it can be less than the sum of alignments
+        Some(ProposalVoteOption::Align) => proposal.aligned_power = proposal.aligned_power.saturating_sub(power),
+        None => {
+            for (option_power, weight) in proposal.option_power.iter_mut().zip(vote.weights.clone().unwrap_or_default().into_iter()) {
+                *option_power = option_power.checked_sub(power * weight)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether a previous vote already reclaimed the voter's delegated power.
+/// Alignments & votes cast before voting power breakdowns were saved never overrode delegates.
+fn has_overridden_delegates(previous_vote: &Option<Vote>) -> bool {
+    match previous_vote {
+        Some(vote) => vote.option != Some(ProposalVoteOption::Align) && vote.voter_power.is_some(),
+        None => false,
+    }
+}
+
+/// Reclaim delegated power from delegates for a delegator who voted directly.
+/// Delegates who already voted have their vote reduced, alignments aren't reduced.
+fn override_delegates(
+    storage: &mut dyn Storage,
+    querier: QuerierWrapper,
+    proposal_id: u64,
+    proposal: &mut Proposal,
+    reclaimed_delegations: Vec<(Addr, Uint128)>,
+) -> StdResult<()> {
+    let config = CONFIG.load(storage)?;
+
+    for (delegate, reclaimed_power) in reclaimed_delegations {
+        let mut vote = VOTES.may_load(storage, (proposal_id, delegate.clone()))?;
+
+        //Votes cast before breakdowns were saved get their breakdown from before this override
+        if let Some(vote) = vote.as_mut() {
+            if vote.voter_power.is_none() {
+                let (voter_power, _) = calc_proposal_voter_power(
+                    storage,
+                    querier,
+                    proposal_id,
+                    delegate.to_string(),
+                    proposal.start_time,
+                    None,
+                    config.quadratic_voting,
+                )?;
+                vote.voter_power = Some(voter_power);
+            }
+        }
+
+        let overridden_power = DELEGATE_OVERRIDES.may_load(storage, (proposal_id, delegate.clone()))?.unwrap_or_default();
+        DELEGATE_OVERRIDES.save(storage, (proposal_id, delegate.clone()), &(overridden_power + reclaimed_power))?;
+
+        if let Some(mut vote) = vote {
+            if vote.option == Some(ProposalVoteOption::Align) {
+                continue;
+            }
+
+            let removed_power = match vote.voter_power.as_mut() {
+                Some(voter_power) => {
+                    let previous_power = voter_power.total();
+                    voter_power.overridden_power += reclaimed_power;
+                    min(previous_power - voter_power.total(), vote.power)
+                },
+                None => min(reclaimed_power, vote.power),
+            };
+            remove_vote_power(proposal, &vote, removed_power)?;
+
+            vote.power -= removed_power;
+            VOTES.save(storage, (proposal_id, delegate.clone()), &vote)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Split voting power across the options of an active multiple-choice proposal
+#[allow(clippy::too_many_arguments)]
+pub fn cast_option_vote(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    proposal_id: u64,
+    weights: Option<Vec<Decimal>>,
+    ranking: Option<Vec<u64>>,
+    recipient: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    //Pending proposals can only be aligned
+    let mut proposal = match PROPOSALS.load(deps.storage, proposal_id.to_string()){
+        Ok(proposal) => proposal,
+        Err(_) => return Err(ContractError::ProposalNotActive {}),
+    };
+
+    //Validate weights, rankings are converted to weights
+    let option_count = match proposal.options.clone() {
+        Some(options) => options.len(),
+        None => return Err(ContractError::NotMultipleChoice {}),
+    };
+    let weights = match (weights, ranking.clone()) {
+        (_, Some(ranking)) => get_ranking_weights(&ranking, option_count)?,
+        (Some(weights), None) => weights,
+        (None, None) => return Err(ContractError::InvalidOptionWeights { options: option_count as u64 }),
+    };
+    if ranking.is_none() && (weights.len() != option_count || weights.iter().fold(Decimal::zero(), |sum, weight| sum + *weight) != Decimal::one()) {
+        return Err(ContractError::InvalidOptionWeights { options: option_count as u64 });
+    }
+
+    //Can't vote on your own proposal
+    if proposal.submitter == info.sender {
+        return Err(ContractError::Unauthorized {});
+    } else if let Some(recipient) = recipient.clone() {
+        //validate recipient
+        deps.api.addr_validate(&recipient)?;
+
+        if proposal.submitter == recipient {
+            return Err(ContractError::Unauthorized {});
+        }
+    }
+
+    //Checking as if end_block is in seconds
+    if env.block.time.seconds() > proposal.end_block {
+        return Err(ContractError::VotingPeriodEnded {});
+    }
+
+    let (voter_power, reclaimed_delegations) = calc_proposal_voter_power(
+        deps.storage, 
+        deps.querier,
+        proposal_id,
+        info.sender.to_string(), 
+        proposal.start_time,
+        recipient.clone(), 
+        config.quadratic_voting,
+    )?;
+    let voting_power = voter_power.total();
+    if voting_power.is_zero() {
+        return Err(ContractError::NoVotingPower {});
+    }
+
+    //Remove previous vote
+    let previous_vote = VOTES.may_load(deps.storage, (proposal_id, info.sender.clone()))?;
+    if let Some(previous_vote) = previous_vote.clone() {
+        remove_vote_power(&mut proposal, &previous_vote, previous_vote.power)?;
+    }
+    //Voting directly overrides the voter's delegates
+    if !has_overridden_delegates(&previous_vote) {
+        override_delegates(deps.storage, deps.querier, proposal_id, &mut proposal, reclaimed_delegations)?;
+    }
+
+    //Split power across options
+    for (option_power, weight) in proposal.option_power.iter_mut().zip(weights.clone().into_iter()) {
+        *option_power = option_power.checked_add(voting_power * weight)?;
+    }
+    VOTES.save(deps.storage, (proposal_id, info.sender.clone()), &Vote {
+        option: None,
+        weights: Some(weights.clone()),
+        ranking: ranking.clone(),
+        power: voting_power,
+        voter_power: Some(voter_power),
+    })?;
+    PROPOSALS.save(deps.storage, proposal_id.to_string(), &proposal)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "cast_option_vote"),
+        attr("proposal_id", proposal_id.to_string()),
+        attr("voter", recipient.unwrap_or_else(|| info.sender.to_string())),
+        attr("weights", format!("{:?}", weights)),
+        attr("ranking", format!("{:?}", ranking)),
+        attr("voting_power", voting_power),
+    ]))
+}
+
+/// Convert a ranking of options into Borda weights.
+/// The k ranked options get k, k-1, ..., 1 points out of k(k+1)/2, unranked options get none.
+fn get_ranking_weights(ranking: &[u64], option_count: usize) -> Result<Vec<Decimal>, ContractError> {
+    let unique_options = ranking.iter().collect::<BTreeSet<&u64>>();
+    if ranking.is_empty() || unique_options.len() != ranking.len() || ranking.iter().any(|option| *option as usize >= option_count) {
+        return Err(ContractError::InvalidOptionRanking { options: option_count as u64 });
+    }
+
+    let ranked = ranking.len() as u128;
+    let total_points = ranked * (ranked + 1) / 2;
+    let mut weights = vec![Decimal::zero(); option_count];
+    for (rank, option) in ranking.iter().enumerate() {
+        weights[*option as usize] = Decimal::from_ratio(ranked - rank as u128, total_points);
+    }
+
+    Ok(weights)
+}
+
+/// Return the option with strictly the most power.
+/// Ties for the most power have no winner, so the proposal can't pass with an arbitrary option.
+pub fn get_winning_option(option_power: &[Uint128]) -> Option<(usize, Uint128)> {
+    let (index, power) = option_power.iter().enumerate().max_by_key(|(_, power)| **power)?;
+    if option_power.iter().filter(|option| *option == power).count() > 1 {
+        return None;
+    }
+
+    Some((index, *power))
+}
+
 /// End a proposal and determine the result.
 pub fn end_proposal(deps: DepsMut, env: Env, proposal_id: u64) -> Result<Response, ContractError> {
     let mut proposal = PROPOSALS.load(deps.storage, proposal_id.to_string())?;
@@ -469,23 +747,26 @@ pub fn end_proposal(deps: DepsMut, env: Env, proposal_id: u64) -> Result<Respons
 
     let mut config = CONFIG.load(deps.storage)?;
 
-    let for_votes = proposal.for_power;
+    //Multiple-choice proposals use the option with the most power as their For votes, ties have no For votes
+    let winning_option = get_winning_option(&proposal.option_power);
+
+    let for_votes = match winning_option {
+        Some((_, power)) => power,
+        None if proposal.options.is_some() => Uint128::zero(),
+        None => proposal.for_power,
+    };
     let against_votes = proposal.against_power;
     let amend_votes = proposal.amendment_power;
     let removal_votes = proposal.removal_power;
+    let option_votes = proposal.option_power.iter().fold(Uint128::zero(), |sum, power| sum + *power);
 
-    let total_votes = for_votes + against_votes + amend_votes + removal_votes;
+    let total_votes = proposal.for_power + option_votes + against_votes + amend_votes + removal_votes;
 
-    let total_voting_power =
-        match calc_total_voting_power_at(
-            deps.as_ref(), 
-            config.quadratic_voting,
-            proposal.start_time,
-        ){
-            Ok(voting_power) => voting_power,
-            Err(_) => Uint128::one(), //if cast_vote works but this doesn't, assume 1 so we can still end the proposal
-        
-        };
+    let total_voting_power = calc_total_voting_power_at(
+        deps.as_ref(),
+        config.quadratic_voting,
+        proposal.start_time,
+    )?;
 
     let mut proposal_quorum: Decimal = Decimal::zero();
     let mut for_threshold: Decimal = Decimal::zero();
@@ -509,6 +790,11 @@ pub fn end_proposal(deps: DepsMut, env: Env, proposal_id: u64) -> Result<Respons
         amend_threshold = Decimal::from_ratio(for_votes + amend_votes, total_votes);
         removal_threshold = Decimal::from_ratio(removal_votes, total_votes);
 
+        //Get the winning option's executables
+        if let (Some((index, _)), Some(options)) = (winning_option, proposal.options.clone()) {
+            proposal.messages = options[index].messages.clone();
+        }
+
         //Set config.proposal_required_threshold to 50 if the proposal has no executables
         if proposal.messages.is_none() || proposal.messages.clone().unwrap().is_empty() {
             config.proposal_required_threshold = Decimal::percent(50);
@@ -520,6 +806,8 @@ pub fn end_proposal(deps: DepsMut, env: Env, proposal_id: u64) -> Result<Respons
     proposal.status = if proposal_quorum >= config.proposal_required_quorum {
         //For check
         if for_threshold >= config.proposal_required_threshold {
+            proposal.winning_option = winning_option.map(|(index, _)| index as u64);
+            
             ProposalStatus::Passed
         } //Amend check
         else if amend_threshold >= config.proposal_required_threshold {
@@ -552,26 +840,138 @@ pub fn end_proposal(deps: DepsMut, env: Env, proposal_id: u64) -> Result<Respons
     };
 
 
+    //Only the winning option's executables are kept
+    if proposal.status != ProposalStatus::Passed && proposal.options.is_some() {
+        proposal.messages = None;
+    }
+
     //Update proposal if still in state
     if !removed {
         PROPOSALS.save(deps.storage, proposal_id.to_string(), &proposal)?;    
     }
 
-    let response = Response::new().add_attributes(vec![
+    //Removed proposals & proposals that don't reach quorum lose their deposit, proposals that reach quorum get it back
+    let deposit_status = if removed {
+        if config.removed_deposit_to_stakers {
+            Some(DepositStatus::SentToStakers)
+        } else {
+            Some(DepositStatus::Burned)
+        }
+    } else if proposal_quorum >= config.proposal_required_quorum {
+        Some(DepositStatus::Refunded)
+    } else if proposal.status == ProposalStatus::Rejected {
+        Some(DepositStatus::Burned)
+    } else {
+        None
+    };
+    let deposit_msgs = match deposit_status {
+        Some(status) => settle_deposit(deps.storage, &config, proposal_id, status)?,
+        None => vec![],
+    };
+
+    let mut response = Response::new().add_submessages(deposit_msgs).add_attributes(vec![
         attr("action", "end_proposal"),
         attr("proposal_id", proposal_id.to_string()),
         attr("proposal_result", proposal.status.to_string()),
     ]);
 
+    //Report voters to staking so delegates are held to participation requirements
+    if proposal.status != ProposalStatus::Active {
+        //Only declared delegates are held to participation requirements, so only their votes are reported
+        let delegates: Vec<Delegate> = deps.querier.query_wasm_smart(
+            config.staking_contract_addr.to_string(),
+            &StakingQueryMsg::DeclaredDelegates {
+                limit: Some(u32::MAX),
+                start_after: None,
+                end_before: None,
+                user: None,
+            },
+        ).unwrap_or_default();
+
+        if !delegates.is_empty() {
+            let mut voters: Vec<String> = vec![];
+            for delegate in delegates {
+                //Alignment isn't participation
+                if let Some(vote) = VOTES.may_load(deps.storage, (proposal_id, delegate.delegate.clone()))? {
+                    if vote.option != Some(ProposalVoteOption::Align) {
+                        voters.push(delegate.delegate.to_string());
+                    }
+                }
+            }
+
+            //A failed report shouldn't block the proposal from ending
+            response = response.add_submessage(SubMsg::reply_on_error(
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: config.staking_contract_addr.to_string(),
+                    msg: to_binary(&Staking_ExecuteMsg::ReportParticipation {
+                        proposal_id,
+                        voters,
+                    })?,
+                    funds: vec![],
+                }),
+                REPORT_PARTICIPATION_REPLY_ID,
+            ));
+        }
+    }
+
     Ok(response)
 }
 
-/// Execute Proposal Msgs
-pub fn execute_proposal(
+/// Settle a proposal's escrowed deposit & return the msgs that pay it out
+fn settle_deposit(
+    storage: &mut dyn Storage,
+    config: &Config,
+    proposal_id: u64,
+    status: DepositStatus,
+) -> StdResult<Vec<SubMsg>> {
+    let mut deposit = match DEPOSITS.may_load(storage, proposal_id)? {
+        Some(deposit) if deposit.status == DepositStatus::Escrowed => deposit,
+        _ => return Ok(vec![]),
+    };
+
+    let messages: Vec<SubMsg> = match status {
+        DepositStatus::Refunded => vec![SubMsg::new(BankMsg::Send {
+            to_address: deposit.depositor.to_string(),
+            amount: vec![deposit.amount.clone()],
+        })],
+        DepositStatus::Burned => vec![SubMsg::new(BankMsg::Burn {
+            amount: vec![deposit.amount.clone()],
+        })],
+        //A failed payment to stakers burns the deposit instead, see reply
+        DepositStatus::SentToStakers => {
+            STAKER_DEPOSIT.save(storage, &proposal_id)?;
+
+            vec![SubMsg::reply_always(
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: config.staking_contract_addr.to_string(),
+                    msg: to_binary(&Staking_ExecuteMsg::DepositFee {})?,
+                    funds: vec![deposit.amount.clone()],
+                }),
+                STAKER_DEPOSIT_REPLY_ID,
+            )]
+        },
+        DepositStatus::Escrowed => vec![],
+    };
+
+    //Settled deposits are no longer escrowed
+    ESCROWED_DEPOSITS.update(storage, deposit.amount.denom.clone(), |escrowed| -> StdResult<Uint128> {
+        Ok(escrowed.unwrap_or_default().saturating_sub(deposit.amount.amount))
+    })?;
+
+    deposit.status = status;
+    DEPOSITS.save(storage, proposal_id, &deposit)?;
+
+    Ok(messages)
+}
+
+/// Queue a passed Proposal in the timelock.
+/// It waits for the longest delay of the default & its messages' targets.
+pub fn queue_proposal(
     deps: DepsMut,
     env: Env,
     proposal_id: u64,
 ) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
     let mut proposal = PROPOSALS.load(deps.storage, proposal_id.to_string())?;
 
     if proposal.status != ProposalStatus::Passed {
@@ -586,6 +986,120 @@ pub fn execute_proposal(
         return Err(ContractError::ExecuteProposalExpired {});
     }
 
+    //Get the longest delay of the proposal's messages
+    let mut delay = config.timelock_delay;
+    for message in proposal.clone().messages.unwrap_or_default() {
+        delay = max(delay, get_timelock_delay(deps.storage, &config, &message.msg)?);
+    }
+
+    let queued_proposal = QueuedProposal {
+        proposal_id,
+        queued_time: env.block.time.seconds(),
+        eta: env.block.time.seconds() + delay,
+    };
+    TIMELOCK_QUEUE.save(deps.storage, proposal_id, &queued_proposal)?;
+
+    //Expiration starts at the end of the timelock
+    proposal.status = ProposalStatus::Queued;
+    proposal.expiration_block = queued_proposal.eta + (config.proposal_expiration_period * 6);
+    PROPOSALS.save(deps.storage, proposal_id.to_string(), &proposal)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "queue_proposal"),
+        attr("proposal_id", proposal_id.to_string()),
+        attr("eta", queued_proposal.eta.to_string()),
+    ]))
+}
+
+/// Get the timelock delay of a proposal message.
+/// Execute msgs use their target's delays for all msgs & their msg name.
+/// Migrate & admin msgs use their target's longest delay.
+fn get_timelock_delay(
+    storage: &dyn Storage,
+    config: &Config,
+    msg: &CosmosMsg,
+) -> StdResult<u64> {
+    let (target, execute_msg) = match msg {
+        CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, msg, .. }) => (contract_addr, Some(msg)),
+        CosmosMsg::Wasm(WasmMsg::Migrate { contract_addr, .. }) => (contract_addr, None),
+        CosmosMsg::Wasm(WasmMsg::UpdateAdmin { contract_addr, .. }) => (contract_addr, None),
+        CosmosMsg::Wasm(WasmMsg::ClearAdmin { contract_addr }) => (contract_addr, None),
+        _ => return Ok(config.timelock_delay),
+    };
+
+    let target_delays = TIMELOCK_DELAYS
+        .prefix(Addr::unchecked(target))
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(String, u64)>>>()?;
+
+    //Msgs are JSON objects keyed by their name, every delay applies to msgs that can't be parsed
+    let execute_msg_name = execute_msg.and_then(|execute_msg| get_json_msg_name(execute_msg.as_slice()));
+
+    Ok(target_delays
+        .into_iter()
+        .filter(|(msg_name, _)| match (execute_msg, &execute_msg_name) {
+            (Some(_), Some(execute_msg_name)) => msg_name.is_empty() || msg_name == execute_msg_name,
+            _ => true,
+        })
+        .map(|(_, delay)| delay)
+        .fold(config.timelock_delay, max))
+}
+
+/// Cancel a passed or queued Proposal
+pub fn cancel_proposal(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    proposal_id: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    //Only the guardian or the Governance contract can cancel
+    if Some(info.sender.clone()) != config.guardian && info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut proposal = PROPOSALS.load(deps.storage, proposal_id.to_string())?;
+    if proposal.status != ProposalStatus::Passed && proposal.status != ProposalStatus::Queued {
+        return Err(ContractError::ProposalNotPassed {});
+    }
+
+    proposal.status = ProposalStatus::Cancelled;
+    PROPOSALS.save(deps.storage, proposal_id.to_string(), &proposal)?;
+    TIMELOCK_QUEUE.remove(deps.storage, proposal_id);
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "cancel_proposal"),
+        attr("proposal_id", proposal_id.to_string()),
+        attr("canceller", info.sender),
+    ]))
+}
+
+/// Execute Proposal Msgs once its timelock ends
+pub fn execute_proposal(
+    deps: DepsMut,
+    env: Env,
+    proposal_id: u64,
+) -> Result<Response, ContractError> {
+    let mut proposal = PROPOSALS.load(deps.storage, proposal_id.to_string())?;
+
+    if proposal.status != ProposalStatus::Queued {
+        return Err(ContractError::ProposalNotQueued {});
+    }
+
+    let queued_proposal = TIMELOCK_QUEUE.load(deps.storage, proposal_id)?;
+    if env.block.time.seconds() < queued_proposal.eta {
+        return Err(ContractError::TimelockNotEnded { eta: queued_proposal.eta });
+    }
+
+    if env.block.time.seconds() > proposal.expiration_block { //Checking as if block is in seconds
+        return Err(ContractError::ExecuteProposalExpired {});
+    }
+
+    //Guardrails or the current values may have changed since submission
+    check_guardrails(deps.storage, deps.querier, &proposal.messages.clone().unwrap_or_default())?;
+
+    TIMELOCK_QUEUE.remove(deps.storage, proposal_id);
     proposal.status = ProposalStatus::Executed;
 
     PROPOSALS.save(deps.storage, proposal_id.to_string(), &proposal)?;
@@ -612,235 +1126,43 @@ pub fn execute_proposal(
 }
 
 /// Checks that proposal messages are correct.
+/// Registered health checks are queried before & after the messages.
 /// Returns [`ContractError`] on failure, otherwise returns a [`Response`] with the specified
 /// attributes. The last message will always fail to prevent committing into blockchain.
 pub fn check_messages(
     deps: DepsMut,
     env: Env,
     mut messages: Vec<ProposalMessage>,
-    // Additional contract check messages
-    msg_switch: Option<u64>,
+    // Health checks to run, None runs all
+    checks: Option<Vec<String>>,
 ) -> Result<Response, ContractError> {
 
     messages.sort_by(|a, b| a.order.cmp(&b.order));
 
     let mut messages: Vec<_> = messages.into_iter().map(|message| message.msg).collect();
 
-    //Create additional check messages for system contracts\
-    //Set contracts
-    let cdp_contract = "furya1nes05572upvgkyz9ctsz787qt9r3pzy5nfe0r0n49m2fh002ytmspeuw8u".to_string();
-    let lq_contract = "furya1kppp8ywzdgtmqlup25ws33fdlhwwuu8he2yk3vgkuldzcfr5a2jqp8qcsa".to_string();
-    let op_contract = "furya1qte5mlv06nwu7wqewxg9066xjvlmlyghs9d5udkf8el5vvn50sfscgnylk".to_string();
-    //CDP
-    if msg_switch == Some(0) {
-        //Query CDP basket
-        let basket = match query_basket(deps.querier, cdp_contract.clone()){
-            Ok(basket) => basket,
-            Err(_) => 
-                deps.querier.query_wasm_smart::<Basket>(
-                cdp_contract.clone(),
-                &CDP_QueryMsg::GetBasket {}
-                )?,
-            };    
-        //Check if the contract has ufury && deposit it all if it does
-        let ufury_balance = match deps.querier.query_balance(env.clone().contract.address, "ufury"){
-            Ok(balance) => balance.amount,
-            Err(_) => Uint128::zero(),
-        };
-        if !ufury_balance.is_zero() {
-            //Get the next position id
-            let position_id = basket.current_position_id;
-            //Deposit all ufury
-            messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: cdp_contract.clone(),
-                msg: to_binary(&CDP_ExecuteMsg::Deposit {
-                    position_id: None,
-                    position_owner: None,
-                })?,
-                funds: vec![
-                    asset_to_coin(
-                        Asset {
-                            info: AssetInfo::NativeToken {
-                                denom: String::from("ufury"),
-                            },
-                            amount: ufury_balance,
-                        }
-                    )?
-                ],
-            }));
-            //Query CDP Config
-            let cdp_config = deps.querier.query_wasm_smart::<CDP_Config>(
-                cdp_contract.clone(),
-                &CDP_QueryMsg::Config {}
-            )?;
-
-
-            //Mint minimum CDT, this may error if there isn't enough FURY in the contract
-            messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: cdp_contract.clone(),
-                msg: to_binary(&CDP_ExecuteMsg::IncreaseDebt { 
-                    position_id, 
-                    amount: Some(cdp_config.debt_minimum * Uint128::new(1_000_000)), 
-                    LTV: None, 
-                    mint_to_addr: None
-                })?,
-                funds: vec![],
-            }));
-
-            //Repay CDT
-            messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: cdp_contract.clone(),
-                msg: to_binary(&CDP_ExecuteMsg::Repay { 
-                    position_id, 
-                    position_owner: None, 
-                    send_excess_to: None
-                })?,
-                funds: vec![
-                    asset_to_coin(
-                        Asset {
-                            info: basket.credit_asset.info,
-                            amount:( cdp_config.debt_minimum * Uint128::new(1_000_000)),
-                        }
-                    )?
-                ],
-            }));
-
-            //Withdraw all FURY
-            messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: cdp_contract.clone(),
-                msg: to_binary(&CDP_ExecuteMsg::Withdraw { 
-                    position_id, 
-                    assets: vec![
-                        Asset {
-                            info: AssetInfo::NativeToken {
-                                denom: String::from("ufury"),
-                            },
-                            amount: ufury_balance,
-                        }
-                    ],
-                    send_to: None
-                })?,
-                funds: vec![],
-            }));
-        }        
-    }
-    //Staking
-    if msg_switch == Some(1) {
-        let config = CONFIG.load(deps.storage)?;
-        //Set minimum stake
-        let minimum_stake = Uint128::new(1_000_000); //1 TEMA
-
-        //Unstake TEMA if the contract already has stake
-        //Unstake the TEMA (claimable)
-        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: config.clone().staking_contract_addr.to_string(),
-            msg: to_binary(&Staking_ExecuteMsg::Unstake { 
-                tema_amount: None,
-            })?,
-            funds: vec![],
-        }));
+    //Load health checks
+    let health_checks: Vec<HealthCheck> = match checks {
+        Some(names) => names
+            .into_iter()
+            .map(|name| HEALTH_CHECKS.load(deps.storage, name))
+            .collect::<StdResult<Vec<HealthCheck>>>()?,
+        None => HEALTH_CHECKS
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(_, check)| check))
+            .collect::<StdResult<Vec<HealthCheck>>>()?,
+    };
 
-        //Stake minimum TEMA from the unstake
-        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: config.clone().staking_contract_addr.to_string(),
-            msg: to_binary(&Staking_ExecuteMsg::Stake { 
-                user: None,
-            })?,
-            funds: vec![
-                asset_to_coin(
-                    Asset {
-                        info: AssetInfo::NativeToken {
-                            denom: config.clone().tema_denom,
-                        },
-                        amount: minimum_stake,
-                    }
-                )?
-            ],
-        }));
-
-        //Start the unstake for the recently staked TEMA
-        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: config.clone().staking_contract_addr.to_string(),
-            msg: to_binary(&Staking_ExecuteMsg::Unstake { 
-                tema_amount: None,
-            })?,
-            funds: vec![],
-        }));
-    }
-    //LQ    
-    if msg_switch == Some(2) {
-        //Query CDP basket
-        let basket = match query_basket(deps.querier, cdp_contract.clone()){
-            Ok(basket) => basket,
-            Err(_) => 
-                deps.querier.query_wasm_smart::<Basket>(
-                cdp_contract.clone(),
-                &CDP_QueryMsg::GetBasket {}
-                )?,
-            };    
-        //Check if the contract has cdt
-        let cdt_balance = match deps.querier.query_balance(env.clone().contract.address, basket.clone().credit_asset.info.to_string()){
-            Ok(balance) => balance.amount,
-            Err(_) => Uint128::zero(),
+    //Save responses from before the messages to compare in CheckMessagesPassed
+    let mut pending_checks: Vec<(HealthCheck, Option<Binary>)> = vec![];
+    for check in health_checks {
+        let response = match check.condition {
+            HealthCheckCondition::Unchanged => Some(query_health_check(deps.querier, &check)?),
+            HealthCheckCondition::Queryable => None,
         };
-        //Query LQ config 
-        let lq_config: LQ_Config = deps.querier.query_wasm_smart::<LQ_Config>(
-            lq_contract.clone(),
-            &LQ_QueryMsg::Config {}
-        )?;
-        //if CDT is less than the minimum, mint minimum CDT
-        if cdt_balance < lq_config.clone().minimum_bid {
-            //Mint minimum CDT from Osmosis Proxy
-            messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: op_contract.clone(),
-                msg: to_binary(&OP_ExecuteMsg::MintTokens { 
-                    denom: basket.clone().credit_asset.info.to_string(), 
-                    amount: lq_config.clone().minimum_bid, 
-                    mint_to_address: env.clone().contract.address.to_string() 
-                })?,
-                funds: vec![],
-            }));
-        }
-
-        //Query Queue to get the bid_id
-        let ufury_queue: QueueResponse = deps.querier.query_wasm_smart::<QueueResponse>(
-            lq_contract.clone(),
-            &LQ_QueryMsg::Queue { bid_for: AssetInfo::NativeToken { denom: String::from("ufury") } 
-            }
-        )?;
-        let bid_id = ufury_queue.current_bid_id;
-
-        //Minimum CDT Bid
-        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: lq_contract.clone(),
-            msg: to_binary(&LQ_ExecuteMsg::SubmitBid { 
-                bid_input: BidInput {
-                    bid_for: AssetInfo::NativeToken { denom: String::from("ufury") },
-                    liq_premium: 0u8
-                },
-                bid_owner: None,
-            })?,
-            funds: vec![
-                asset_to_coin(
-                    Asset {
-                        info: basket.credit_asset.info,
-                        amount: lq_config.clone().minimum_bid,
-                    }
-                )?
-            ],
-        }));
-
-        //Retract bid
-        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: lq_contract.clone(),
-            msg: to_binary(&LQ_ExecuteMsg::RetractBid { 
-                bid_id,
-                bid_for: AssetInfo::NativeToken { denom: String::from("ufury") },
-                amount: None,
-            })?,
-            funds: vec![],
-        }));        
+        pending_checks.push((check, response));
     }
+    PENDING_HEALTH_CHECKS.save(deps.storage, &pending_checks)?;
 
     //Guarantee that the last message will fail
     messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
@@ -851,9 +1173,153 @@ pub fn check_messages(
 
     Ok(Response::new()
         .add_attribute("action", "check_messages")
+        .add_attribute("health_checks", pending_checks.len().to_string())
         .add_messages(messages))
 }
 
+/// Query a health check's contract, returning the raw response
+fn query_health_check(querier: QuerierWrapper, check: &HealthCheck) -> Result<Binary, ContractError> {
+    match query_raw(querier, check.contract.clone(), check.query.clone())? {
+        Some(response) => Ok(response),
+        None => Err(ContractError::HealthCheckFailed { name: check.name.clone() }),
+    }
+}
+
+/// Query a contract with a raw msg, returns None if the query fails
+fn query_raw(querier: QuerierWrapper, contract_addr: String, msg: Binary) -> StdResult<Option<Binary>> {
+    let request: QueryRequest<Empty> = QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg });
+
+    match querier.raw_query(&to_vec(&request)?) {
+        SystemResult::Ok(ContractResult::Ok(response)) => Ok(Some(response)),
+        _ => Ok(None),
+    }
+}
+
+/// Add or remove post-proposal health checks
+fn edit_health_checks(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    add: Option<Vec<HealthCheck>>,
+    remove: Option<Vec<String>>,
+) -> Result<Response, ContractError> {
+    // Only the Governance contract is allowed to edit its health checks
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    //Remove checks
+    for name in remove.clone().unwrap_or_default() {
+        HEALTH_CHECKS.remove(deps.storage, name);
+    }
+
+    //Add checks
+    for mut check in add.clone().unwrap_or_default() {
+        check.contract = deps.api.addr_validate(&check.contract)?.to_string();
+        HEALTH_CHECKS.save(deps.storage, check.name.clone(), &check)?;
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "edit_health_checks"),
+        attr("added", format!("{:?}", add.unwrap_or_default().into_iter().map(|check| check.name).collect::<Vec<String>>())),
+        attr("removed", format!("{:?}", remove.unwrap_or_default())),
+    ]))
+}
+
+/// Set parameter guardrails for proposal messages
+fn edit_guardrails(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    guardrails: Vec<Guardrail>,
+) -> Result<Response, ContractError> {
+    // Only the Governance contract is allowed to edit its guardrails
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    for guardrail in guardrails.clone() {
+        let target = deps.api.addr_validate(&guardrail.target)?;
+
+        //Guardrails without fields are removed
+        if guardrail.fields.is_empty() {
+            GUARDRAILS.remove(deps.storage, (target, guardrail.msg));
+            continue;
+        }
+
+        for field in guardrail.fields.iter() {
+            if let (Some(min), Some(max)) = (field.min, field.max) {
+                if min > max {
+                    return Err(ContractError::Std(cosmwasm_std::StdError::GenericErr { msg: format!("{} {} min is greater than its max", guardrail.msg, field.field) }));
+                }
+            }
+            if field.max_change.is_some() && field.current_value.is_none() {
+                return Err(ContractError::Std(cosmwasm_std::StdError::GenericErr { msg: format!("{} {} max_change requires a current_value query", guardrail.msg, field.field) }));
+            }
+        }
+
+        GUARDRAILS.save(deps.storage, (target, guardrail.msg), &guardrail.fields)?;
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "edit_guardrails"),
+        attr("guardrails", format!("{:?}", guardrails.into_iter().map(|guardrail| format!("{}:{}", guardrail.target, guardrail.msg)).collect::<Vec<String>>())),
+    ]))
+}
+
+/// Check proposal messages against the parameter guardrails of their target contracts
+fn check_guardrails(
+    storage: &dyn Storage,
+    querier: QuerierWrapper,
+    messages: &[ProposalMessage],
+) -> Result<(), ContractError> {
+    for message in messages {
+        if let CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, msg, .. }) = &message.msg {
+            let guardrails = GUARDRAILS
+                .prefix(Addr::unchecked(contract_addr))
+                .range(storage, None, None, Order::Ascending)
+                .collect::<StdResult<Vec<(String, Vec<FieldGuardrail>)>>>()?;
+
+            for (msg_name, fields) in guardrails {
+                for guardrail in fields {
+                    let breached = ContractError::GuardrailBreached { msg: msg_name.clone(), field: guardrail.field.clone() };
+                    let field = guardrail.field.clone();
+                    let unreadable = |error: String| ContractError::GuardrailUnreadable { msg: msg_name.clone(), field: field.clone(), error };
+
+                    //Fields that aren't set are unchanged
+                    let value = match get_json_decimal(msg.as_slice(), &format!("{}.{}", msg_name, guardrail.field)) {
+                        Ok(Some(value)) => value,
+                        Ok(None) => continue,
+                        Err(err) => return Err(unreadable(err.to_string())),
+                    };
+
+                    if guardrail.min.map(|min| value < min).unwrap_or(false) || guardrail.max.map(|max| value > max).unwrap_or(false) {
+                        return Err(breached);
+                    }
+
+                    if let (Some(max_change), Some(current_value)) = (guardrail.max_change, guardrail.current_value) {
+                        let current = match query_raw(querier, contract_addr.clone(), current_value.query)? {
+                            Some(response) => match get_json_decimal(response.as_slice(), &current_value.field) {
+                                Ok(Some(current)) => current,
+                                Ok(None) => return Err(unreadable(format!("current value {} isn't set", current_value.field))),
+                                Err(err) => return Err(unreadable(err.to_string())),
+                            },
+                            None => return Err(unreadable(String::from("current value query failed"))),
+                        };
+
+                        let change = if value > current { value - current } else { current - value };
+                        if change > max_change {
+                            return Err(breached);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
 ///Errors to prevent checked messages from being executed
 /// Tests staking queries necessary for proposal execution
 pub fn passed_messages(deps: DepsMut, env: Env, error: Option<bool>) -> Result<Response, ContractError> {
@@ -891,10 +1357,10 @@ pub fn passed_messages(deps: DepsMut, env: Env, error: Option<bool>) -> Result<R
         }))?
         .stakers;
 
-    //Query a vesting recipient
-    deps.querier.query::<AllocationResponse>(&QueryRequest::Wasm(WasmQuery::Smart {
+    //Query vesting recipients
+    deps.querier.query::<RecipientsResponse>(&QueryRequest::Wasm(WasmQuery::Smart {
         contract_addr: config.vesting_contract_addr.to_string(),
-        msg: to_binary(&VestingQueryMsg::Allocation { recipient: String::from("furya16w6chfrrg930cqcfewdzse6szgjk657764dll7") })?,
+        msg: to_binary(&VestingQueryMsg::Recipients { })?,
     }))?;
 
     //Query the contracts config to ensure we don't migrate to a code_id that is not a Gov contract
@@ -903,17 +1369,31 @@ pub fn passed_messages(deps: DepsMut, env: Env, error: Option<bool>) -> Result<R
         msg: to_binary(&QueryMsg::Config {})?,
     }))?;
 
-    //Query delegations, this is last incase this addr ever runs out of delegations & that causes the error
+    //Query delegations
     deps.querier.query::<Vec<DelegationResponse>>(&QueryRequest::Wasm(WasmQuery::Smart {
         contract_addr: config.staking_contract_addr.to_string(),
         msg: to_binary(&StakingQueryMsg::Delegations {
             limit: Some(1),
             start_after: None,
             end_before: None,
-            user: Some("furya1pg9t4kxgwhtsp72dgd42x42pxzswaze35fxxlq".to_string()),
+            user: None,
         })?,
     }))?;
 
+    //Run the health checks of a CheckMessages simulation
+    if let Some(pending_checks) = PENDING_HEALTH_CHECKS.may_load(deps.storage)? {
+        for (check, prev_response) in pending_checks {
+            let response = query_health_check(deps.querier, &check)?;
+
+            if let Some(prev_response) = prev_response {
+                if response != prev_response {
+                    return Err(ContractError::HealthCheckFailed { name: check.name });
+                }
+            }
+        }
+        PENDING_HEALTH_CHECKS.remove(deps.storage);
+    }
+
     if let Some(true) = error {
         return Ok(Response::new().add_message(CosmosMsg::Wasm(WasmMsg::ClearAdmin { contract_addr: env.contract.address.to_string() })))
     } else {
@@ -944,10 +1424,14 @@ pub fn remove_completed_proposal(
     };
 
     if aligned {
+        //Queued proposals expire after their timelock
+        let expiration = if proposal.status == ProposalStatus::Queued {
+            proposal.expiration_block
+        } else {
+            proposal.end_block + ((config.proposal_effective_delay + config.proposal_expiration_period) * 6)
+        };
         
-        if env.block.time.seconds()
-        > (proposal.end_block + ((config.proposal_effective_delay + config.proposal_expiration_period) * 6))
-        {
+        if env.block.time.seconds() > expiration {
             proposal.status = ProposalStatus::Expired;
         }
     } //If pending, expiration starts at end_block
@@ -961,17 +1445,22 @@ pub fn remove_completed_proposal(
     if proposal.status == ProposalStatus::Expired && !aligned{
         PENDING_PROPOSALS.remove(deps.storage, proposal_id.to_string());    
     }
-    //If proposal is expired or rejected, remove
-    else if proposal.status == ProposalStatus::Expired || proposal.status == ProposalStatus::Rejected {
+    //If proposal is expired, rejected or cancelled, remove
+    else if proposal.status == ProposalStatus::Expired || proposal.status == ProposalStatus::Rejected || proposal.status == ProposalStatus::Cancelled {
         PROPOSALS.remove(deps.storage, proposal_id.to_string());
+        TIMELOCK_QUEUE.remove(deps.storage, proposal_id);
     } else if info.sender == proposal.submitter {
         PROPOSALS.remove(deps.storage, proposal_id.to_string());
+        TIMELOCK_QUEUE.remove(deps.storage, proposal_id);
     } else {
         return Err(ContractError::CantRemove {});
     }
-    
+
+    //Deposits still escrowed never reached quorum
+    let deposit_msgs = settle_deposit(deps.storage, &config, proposal_id, DepositStatus::Burned)?;
 
     Ok(Response::new()
+        .add_submessages(deposit_msgs)
         .add_attribute("action", "remove_completed_proposal")
         .add_attribute("proposal_id", proposal_id.to_string()))
 }
@@ -1024,6 +1513,45 @@ pub fn update_config(
         config.proposal_required_threshold = Decimal::from_str(&proposal_required_threshold)?;
     }
 
+    if let Some(guardian) = updated_config.guardian {
+        if guardian.is_empty() {
+            config.guardian = None;
+        } else {
+            config.guardian = Some(deps.api.addr_validate(&guardian)?);
+        }
+    }
+    if let Some(timelock_delay) = updated_config.timelock_delay {
+        config.timelock_delay = timelock_delay;
+    }
+    if let Some(proposal_deposit) = updated_config.proposal_deposit {
+        if proposal_deposit.amount.is_zero() {
+            config.proposal_deposit = None;
+        } else {
+            config.proposal_deposit = Some(proposal_deposit);
+        }
+    }
+    if let Some(removed_deposit_to_stakers) = updated_config.removed_deposit_to_stakers {
+        config.removed_deposit_to_stakers = removed_deposit_to_stakers;
+    }
+    if let Some(timelock_target_delays) = updated_config.timelock_target_delays {
+        for target_delay in timelock_target_delays {
+            let key = (
+                deps.api.addr_validate(&target_delay.target)?,
+                target_delay.msg.unwrap_or_default(),
+            );
+
+            if target_delay.delay == 0 {
+                TIMELOCK_DELAYS.remove(deps.storage, key);
+            } else if target_delay.delay > MAX_TIMELOCK_DELAY {
+                return Err(ContractError::Std(cosmwasm_std::StdError::GenericErr {
+                    msg: format!("The timelock delay cannot be higher than {} seconds", MAX_TIMELOCK_DELAY),
+                }));
+            } else {
+                TIMELOCK_DELAYS.save(deps.storage, key, &target_delay.delay)?;
+            }
+        }
+    }
+
     if let Some(whitelist_add) = updated_config.whitelist_add {
         validate_links(&whitelist_add)?;
 
@@ -1061,55 +1589,17 @@ pub fn calc_total_voting_power_at(
     proposal_start_time: u64,
 ) -> StdResult<Uint128> {
     let config = CONFIG.load(deps.storage)?;
-    //Initialize total voting power
-    let mut total: Uint128 = Uint128::zero();
-
-    //Query stake from before Proposal's start_time
-    let mut staked_tema = match deps.querier
-        .query::<StakedResponse>(&QueryRequest::Wasm(WasmQuery::Smart {
-            contract_addr: config.staking_contract_addr.to_string(),
-            msg: to_binary(&StakingQueryMsg::Staked {
-                limit: Some(STAKE_QUERY_LIMIT),
-                start_after: None,
-                end_before: Some(proposal_start_time),
-                unstaking: false,
-            })?,
-        })){
-            Ok(staked) => staked.stakers,
-            Err(_) => vec![],
-        };
-        
-    //This will provide the lowest total voting power if quadratic voting is enabled
-    //bc the stake can be split into delegations which are individually square rooted
-    for staker in staked_tema.clone() {
-        let mut staker_total = Uint128::zero();
-        let mut remove_list = vec![];
-
-        for (i, stake) in staked_tema.clone().into_iter().enumerate(){
-            if stake.staker == staker.staker {
-                //Remove to shorten subsequent iterations
-                remove_list.push(i);
-
-                if stake.stake_time < proposal_start_time && stake.unstake_start_time.is_none() {
-                    staker_total += stake.amount;
-                }
-            }
-        };
-        //Reverse remove_list so we don't break the index ordering
-        remove_list.reverse();
-        //Remove staker from list
-        for i in remove_list {
-            staked_tema.remove(i);
-        }
-        //Transform w/ quadratics if enabled
-        if quadratic_voting {
-            staker_total = Decimal::from_ratio(staker_total, Uint128::one()).sqrt().to_uint_ceil();
-        }
-        //Add to total
-        total += staker_total;
-
-    }
 
+    //Query total checkpointed voting power from before Proposal's start_time.
+    //Staking checkpoints each staker's lock boosted power & the sum of their square roots,
+    //the quadratic total is the lowest total voting power bc stake can be split into delegations which are individually square rooted
+    let mut total: Uint128 = deps.querier.query_wasm_smart(
+        config.staking_contract_addr.to_string(),
+        &StakingQueryMsg::TotalStakeAt {
+            time: Some(proposal_start_time),
+            quadratic: Some(quadratic_voting),
+        },
+    )?;
 
     /////Get vested vp/////   
     //Set staked non-vested total
@@ -1156,6 +1646,56 @@ pub fn calc_total_voting_power_at(
 
 /// Calc voting power for sender at a Proposal's start_time
 pub fn calc_voting_power(
+    storage: &dyn Storage,
+    querier: QuerierWrapper,
+    non_vested_total: Option<Uint128>,
+    sender: String,
+    start_time: u64,
+    expedited: &mut bool,
+    recipient: Option<String>,
+    quadratic_voting: bool,
+) -> StdResult<Uint128> {
+    let config = CONFIG.load(storage)?;
+    let total = calc_own_voting_power(storage, querier, non_vested_total, sender.clone(), start_time, expedited, recipient, quadratic_voting)?;
+    let (delegated_to_user, delegated_from_user) = query_voting_delegations(querier, &config, sender, start_time, quadratic_voting)?;
+
+    //Add delegated to user and subtract delegated from user
+    let total = total + delegated_to_user.into_iter().map(|(_, power)| power).sum::<Uint128>();
+    
+    Ok(total.saturating_sub(delegated_from_user.into_iter().map(|(_, power)| power).sum()))
+}
+
+/// Calc voting power for a proposal voter, split by source.
+/// Delegators voting directly reclaim their delegated power, which is returned with each delegate.
+fn calc_proposal_voter_power(
+    storage: &dyn Storage,
+    querier: QuerierWrapper,
+    proposal_id: u64,
+    sender: String,
+    start_time: u64,
+    recipient: Option<String>,
+    quadratic_voting: bool,
+) -> StdResult<(VoterPower, Vec<(Addr, Uint128)>)> {
+    let config = CONFIG.load(storage)?;
+    let own_power = calc_own_voting_power(storage, querier, None, sender.clone(), start_time, &mut false, recipient, quadratic_voting)?;
+    let (delegated_to_user, delegated_from_user) = query_voting_delegations(querier, &config, sender.clone(), start_time, quadratic_voting)?;
+
+    //Delegations to yourself aren't reclaimed
+    let (self_delegations, reclaimed_delegations): (Vec<(Addr, Uint128)>, Vec<(Addr, Uint128)>) = delegated_from_user
+        .into_iter()
+        .partition(|(delegate, _)| delegate.as_str() == sender);
+
+    let voter_power = VoterPower {
+        own_power: own_power.saturating_sub(self_delegations.into_iter().map(|(_, power)| power).sum()),
+        delegated_power: delegated_to_user.into_iter().map(|(_, power)| power).sum(),
+        overridden_power: DELEGATE_OVERRIDES.may_load(storage, (proposal_id, Addr::unchecked(sender)))?.unwrap_or_default(),
+    };
+
+    Ok((voter_power, reclaimed_delegations))
+}
+
+/// Calc voting power from sender's own stake at a Proposal's start_time
+fn calc_own_voting_power(
     storage: &dyn Storage,
     querier: QuerierWrapper,
     mut non_vested_total: Option<Uint128>,
@@ -1182,28 +1722,19 @@ pub fn calc_voting_power(
         non_vested_total = Some(new_non_vested_total);
     }
       
-    //Query stake from before Proposal's start_time
-    let mut total: Uint128 = match querier
-    .query::<StakerResponse>(&QueryRequest::Wasm(WasmQuery::Smart {
-        contract_addr: config.staking_contract_addr.to_string(),
-        msg: to_binary(&StakingQueryMsg::UserStake { staker: sender.clone() })?,
-    })){
-        Ok(stake) => {
-            //Filter out unstaking stake & stake after proposal start time
-            let voting_stake = stake.clone().deposit_list.into_iter().filter(|stake| {
-                stake.stake_time < start_time && stake.unstake_start_time.is_none()
-            }).map(|stake| stake.amount).sum();
-
-            voting_stake
+    //Query checkpointed voting power from before Proposal's start_time, includes lock boosts & excludes unstaking stake
+    let mut total: Uint128 = querier.query_wasm_smart(
+        config.staking_contract_addr.to_string(),
+        &StakingQueryMsg::StakeAt {
+            user: sender.clone(),
+            time: Some(start_time),
         },
-        Err(_) => Uint128::zero(),
-    };
+    )?;
     
     //If calculating vesting voting power, we take from recipient's allocation
     if recipient.is_none() {
         //Only vesting recipients can submit expedited proposals
         *expedited = false;
-
     } else if recipient.is_some() {
         let recipient = recipient.clone().unwrap();
         //info.sender must equal the recipient contract
@@ -1239,6 +1770,18 @@ pub fn calc_voting_power(
         total = Decimal::from_ratio(total, Uint128::one()).sqrt().to_uint_ceil();
     }
 
+    Ok(total)
+}
+
+/// Query sender's voting power delegations from before a Proposal's start_time.
+/// Returns power delegated to sender per delegator & power delegated from sender per delegate.
+fn query_voting_delegations(
+    querier: QuerierWrapper,
+    config: &Config,
+    sender: String,
+    start_time: u64,
+    quadratic_voting: bool,
+) -> StdResult<(Vec<(Addr, Uint128)>, Vec<(Addr, Uint128)>)> {
     // Query delegations 
     let delegations: Vec<DelegationResponse> = match querier.query::<Vec<DelegationResponse>>(&QueryRequest::Wasm(WasmQuery::Smart {
         contract_addr: config.staking_contract_addr.to_string(),
@@ -1259,42 +1802,36 @@ pub fn calc_voting_power(
     //Otherwise delegates would get exponentially less voting power than what was delegated to them which makes quorum harder to reach with delegations vs w/o.
     match delegations.into_iter().find(|delegation| delegation.user.to_string() == sender){
         Some(delegation_info) => {
-            //Get total delegated to user from before proposal start time
-            let total_delegated_to_user: Uint128 = delegation_info.delegation_info.clone().delegated
+            //Get delegations to user from before proposal start time
+            let delegated_to_user: Vec<(Addr, Uint128)> = delegation_info.delegation_info.clone().delegated
                 .into_iter()
                 .filter(|delegation| delegation.time_of_delegation <= start_time && delegation.voting_power_delegation)
                 .map(|dele| {
                     if quadratic_voting {
-                        Decimal::from_ratio(dele.amount, Uint128::one()).sqrt().to_uint_ceil()
+                        (dele.delegate, Decimal::from_ratio(dele.amount, Uint128::one()).sqrt().to_uint_ceil())
                     } else {
-                        dele.amount
+                        (dele.delegate, dele.amount)
                     }
                 })
-                .sum();
+                .collect();
 
-            //Get total delegated away from user from before proposal start time
-            let total_delegated_from_user: Uint128 = delegation_info.delegation_info.clone().delegated_to
+            //Get delegations away from user from before proposal start time
+            let delegated_from_user: Vec<(Addr, Uint128)> = delegation_info.delegation_info.clone().delegated_to
                 .into_iter()
                 .filter(|delegation| delegation.time_of_delegation <= start_time && delegation.voting_power_delegation)
                 .map(|dele| {
                     if quadratic_voting {
-                        Decimal::from_ratio(dele.amount, Uint128::one()).sqrt().to_uint_ceil()
+                        (dele.delegate, Decimal::from_ratio(dele.amount, Uint128::one()).sqrt().to_uint_ceil())
                     } else {
-                        dele.amount
+                        (dele.delegate, dele.amount)
                     }
                 })
-                .sum();
-            //Add delegated to user and subtract delegated from user
-            total += total_delegated_to_user;
-            total = match total.checked_sub(total_delegated_from_user){
-                Ok(total) => total,
-                Err(_) => Uint128::zero(),
-            };
+                .collect();
+
+            Ok((delegated_to_user, delegated_from_user))
         },
-        None => {}
+        None => Ok((vec![], vec![])),
     }
-    
-    Ok(total)
 }
 
 /// Create Osmosis Incentive Gauge.
@@ -1401,7 +1938,41 @@ fn freeze_positions(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> StdResult<Response> {
+    match msg.id {
+        REPORT_PARTICIPATION_REPLY_ID => Ok(Response::new().add_attribute("report_participation_error", msg.result.into_result().err().unwrap_or_default())),
+        STAKER_DEPOSIT_REPLY_ID => handle_staker_deposit_reply(deps, msg),
+        id => Err(StdError::generic_err(format!("invalid reply id: {}", id))),
+    }
+}
+
+/// Burn a deposit that failed to be sent to stakers
+fn handle_staker_deposit_reply(deps: DepsMut, msg: Reply) -> StdResult<Response> {
+    let proposal_id = STAKER_DEPOSIT.load(deps.storage)?;
+    STAKER_DEPOSIT.remove(deps.storage);
+
+    let error = match msg.result.into_result() {
+        Ok(_) => return Ok(Response::new()),
+        Err(error) => error,
+    };
+
+    let mut deposit = DEPOSITS.load(deps.storage, proposal_id)?;
+    deposit.status = DepositStatus::Burned;
+    DEPOSITS.save(deps.storage, proposal_id, &deposit)?;
+
+    Ok(Response::new()
+        .add_message(BankMsg::Burn {
+            amount: vec![deposit.amount],
+        })
+        .add_attributes(vec![
+            attr("action", "burn_staker_deposit"),
+            attr("proposal_id", proposal_id.to_string()),
+            attr("deposit_fee_error", error),
+        ]))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&CONFIG.load(deps.storage)?),
         QueryMsg::ActiveProposals { start, limit } => to_binary(&query_proposals(deps, start, limit)?),
@@ -1410,6 +1981,7 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::ProposalVotes { proposal_id } => {
             to_binary(&query_proposal_votes(deps, proposal_id)?)
         }
+        QueryMsg::ProposalDeposit { proposal_id } => to_binary(&DEPOSITS.may_load(deps.storage, proposal_id)?),
         QueryMsg::UserVotingPower {
             user,
             proposal_id,
@@ -1435,6 +2007,35 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
                 CONFIG.load(deps.storage)?.quadratic_voting,
             )?)
         }
+        QueryMsg::Guardrails {} => to_binary(&GUARDRAILS
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|item| {
+                let ((target, msg), fields) = item?;
+                Ok(Guardrail {
+                    target: target.to_string(),
+                    msg,
+                    fields,
+                })
+            })
+            .collect::<StdResult<Vec<Guardrail>>>()?),
+        QueryMsg::Budgets {} => to_binary(&query_budgets(deps)?),
+        QueryMsg::Streams { recipient, start_after, limit } => to_binary(&query_streams(deps, env, recipient, start_after, limit)?),
+        QueryMsg::HealthChecks {} => to_binary(&HEALTH_CHECKS
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(_, check)| check))
+            .collect::<StdResult<Vec<HealthCheck>>>()?),
+        QueryMsg::TimelockQueue { start, limit } => to_binary(&query_timelock_queue(deps, start, limit)?),
+        QueryMsg::TimelockDelays {} => to_binary(&TIMELOCK_DELAYS
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|item| {
+                let ((target, msg), delay) = item?;
+                Ok(TimelockDelay {
+                    target: target.to_string(),
+                    msg: if msg.is_empty() { None } else { Some(msg) },
+                    delay,
+                })
+            })
+            .collect::<StdResult<Vec<TimelockDelay>>>()?),
         QueryMsg::TotalVotingPower { proposal_id } => {
             let proposal = PROPOSALS.load(deps.storage, proposal_id.to_string())?;
             to_binary(&calc_total_voting_power_at(
@@ -1446,17 +2047,22 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::ProposalVoters {
             proposal_id,
             vote_option,
-            start,
+            start_after,
             limit,
             specific_user
         } => to_binary(&query_proposal_voters(
             deps,
             proposal_id,
             vote_option,
-            start,
+            start_after,
             limit,
             specific_user
         )?),
+        QueryMsg::OptionVoters {
+            proposal_id,
+            start_after,
+            limit,
+        } => to_binary(&query_option_voters(deps, proposal_id, start_after, limit)?),
     }
 }
 
@@ -1477,7 +2083,6 @@ pub fn query_proposals(
         .map(|item| {
             let (_, proposal) = item?;
             Ok(ProposalResponse {
-                voting_power: proposal.voting_power,
                 proposal_id: proposal.proposal_id,
                 submitter: proposal.submitter,
                 status: proposal.status,
@@ -1495,6 +2100,9 @@ pub fn query_proposals(
                 description: proposal.description,
                 link: proposal.link,
                 messages: proposal.messages,
+                options: proposal.options,
+                option_power: proposal.option_power,
+                winning_option: proposal.winning_option,
             })
         })
         .collect::<StdResult<Vec<_>>>()?;
@@ -1522,7 +2130,6 @@ pub fn query_pending_proposals(
         .map(|item| {
             let (_, proposal) = item?;
             Ok(ProposalResponse {
-                voting_power: proposal.voting_power,
                 proposal_id: proposal.proposal_id,
                 submitter: proposal.submitter,
                 status: proposal.status,
@@ -1540,6 +2147,9 @@ pub fn query_pending_proposals(
                 description: proposal.description,
                 link: proposal.link,
                 messages: proposal.messages,
+                options: proposal.options,
+                option_power: proposal.option_power,
+                winning_option: proposal.winning_option,
             })
         })
         .collect::<StdResult<Vec<_>>>()?;
@@ -1550,43 +2160,64 @@ pub fn query_pending_proposals(
     })
 }
 
-/// Return a list of voters for a given proposal
+/// Returns proposals queued in the timelock
+pub fn query_timelock_queue(
+    deps: Deps,
+    start: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<QueuedProposal>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start.map(Bound::inclusive);
+
+    TIMELOCK_QUEUE
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, queued_proposal)| queued_proposal))
+        .collect()
+}
+
+/// Return a list of voters for a given proposal & their voting power breakdown
 pub fn query_proposal_voters(
     deps: Deps,
     proposal_id: u64,
     vote_option: ProposalVoteOption,
-    start: Option<u64>,
+    start_after: Option<String>,
     limit: Option<u32>,
     specific_user: Option<String>,
-) -> StdResult<Vec<Addr>> {
-    let limit = limit.unwrap_or(DEFAULT_VOTERS_LIMIT).min(MAX_VOTERS_LIMIT);
-    let start = start.unwrap_or_default();
-
-    let proposal = PROPOSALS.load(deps.storage, proposal_id.to_string())?;
-
-    let voters = match vote_option {
-        ProposalVoteOption::For => proposal.for_voters,
-        ProposalVoteOption::Against => proposal.against_voters,
-        ProposalVoteOption::Amend => proposal.amendment_voters,
-        ProposalVoteOption::Remove => proposal.removal_voters,
-        ProposalVoteOption::Align => proposal.aligned_voters,
-    };
+) -> StdResult<Vec<ProposalVoterResponse>> {
+    let limit = limit.unwrap_or(DEFAULT_VOTERS_LIMIT).min(MAX_VOTERS_LIMIT) as usize;
 
     if let Some(specific_user) = specific_user {
         let specific_user = deps.api.addr_validate(&specific_user)?;
-        if voters.contains(&specific_user) {
-            return Ok(vec![specific_user]);
-        } else {
-            return Err(cosmwasm_std::StdError::GenericErr { msg: format!("User did not vote for this option in proposal {}", proposal_id) })
+        match VOTES.may_load(deps.storage, (proposal_id, specific_user.clone()))? {
+            Some(vote) if vote.option == Some(vote_option.clone()) => {
+                return Ok(vec![ProposalVoterResponse {
+                    voter: specific_user,
+                    power: vote.voter_power,
+                }]);
+            },
+            _ => return Err(cosmwasm_std::StdError::GenericErr { msg: format!("User did not vote for this option in proposal {}", proposal_id) })
         }
     };
 
-    Ok(voters
-        .iter()
-        .skip(start as usize)
-        .take(limit as usize)
-        .cloned()
-        .collect())
+    let start = match start_after {
+        Some(voter) => Some(Bound::exclusive(deps.api.addr_validate(&voter)?)),
+        None => None,
+    };
+
+    VOTES
+        .prefix(proposal_id)
+        .range(deps.storage, start, None, Order::Ascending)
+        .filter(|item| match item {
+            Ok((_, vote)) => vote.option == Some(vote_option.clone()),
+            Err(_) => true,
+        })
+        .take(limit)
+        .map(|item| item.map(|(voter, vote)| ProposalVoterResponse {
+            voter,
+            power: vote.voter_power,
+        }))
+        .collect()
 }
 
 /// Return the voting power per option for a given proposal
@@ -1600,10 +2231,140 @@ pub fn query_proposal_votes(deps: Deps, proposal_id: u64) -> StdResult<ProposalV
         amendment_power: proposal.amendment_power,
         removal_power: proposal.removal_power,
         aligned_power: proposal.aligned_power,
+        option_power: proposal.option_power,
     })
 }
 
+/// Return the option votes for a given multiple-choice proposal
+pub fn query_option_voters(
+    deps: Deps,
+    proposal_id: u64,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<OptionVoterResponse>> {
+    let limit = limit.unwrap_or(DEFAULT_VOTERS_LIMIT).min(MAX_VOTERS_LIMIT) as usize;
+    let start = match start_after {
+        Some(voter) => Some(Bound::exclusive(deps.api.addr_validate(&voter)?)),
+        None => None,
+    };
+
+    VOTES
+        .prefix(proposal_id)
+        .range(deps.storage, start, None, Order::Ascending)
+        .filter_map(|item| match item {
+            Ok((voter, vote)) => vote.weights.map(|weights| Ok(OptionVoterResponse {
+                voter,
+                vote: OptionVote { power: vote.power, weights, ranking: vote.ranking },
+            })),
+            Err(err) => Some(Err(err)),
+        })
+        .take(limit)
+        .collect()
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
-    Ok(Response::default())
+pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    //Move inline proposal voters to VOTES, a page of proposals per migration
+    let limit = msg.limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as u64;
+    let mut migrated = 0u64;
+    for (proposals, old_proposals) in [(PROPOSALS, OLD_PROPOSALS), (PENDING_PROPOSALS, OLD_PENDING_PROPOSALS)] {
+        let keys = proposals
+            .keys(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<String>>>()?;
+
+        for key in keys {
+            if migrated == limit {
+                break;
+            }
+            //Proposals that fail to load as the old format are already migrated
+            if let Ok(old_proposal) = old_proposals.load(deps.storage, key.clone()) {
+                let proposal = migrate_proposal_votes(deps.storage, deps.querier, old_proposal)?;
+                proposals.save(deps.storage, key, &proposal)?;
+                migrated += 1;
+            }
+        }
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "migrate"),
+        attr("migrated_proposals", migrated.to_string()),
+    ]))
+}
+
+/// Save an old proposal's inline voters to VOTES & return it without them.
+/// Only active & pending proposals can still change votes, completed proposals drop their voters.
+/// Vote power is recomputed like it was when removing old votes, vesting power is used if staked power is zero.
+fn migrate_proposal_votes(
+    storage: &mut dyn Storage,
+    querier: QuerierWrapper,
+    old_proposal: OldProposal,
+) -> StdResult<Proposal> {
+    let config = CONFIG.load(storage)?;
+    let proposal_id = old_proposal.proposal_id.u64();
+    let voters = [
+        (old_proposal.for_voters.clone(), ProposalVoteOption::For),
+        (old_proposal.against_voters.clone(), ProposalVoteOption::Against),
+        (old_proposal.amendment_voters.clone(), ProposalVoteOption::Amend),
+        (old_proposal.removal_voters.clone(), ProposalVoteOption::Remove),
+        (old_proposal.aligned_voters.clone(), ProposalVoteOption::Align),
+    ];
+    let mut proposal = Proposal {
+        proposal_id: old_proposal.proposal_id,
+        submitter: old_proposal.submitter,
+        status: old_proposal.status,
+        aligned_power: old_proposal.aligned_power,
+        for_power: old_proposal.for_power,
+        against_power: old_proposal.against_power,
+        amendment_power: old_proposal.amendment_power,
+        removal_power: old_proposal.removal_power,
+        start_block: old_proposal.start_block,
+        start_time: old_proposal.start_time,
+        end_block: old_proposal.end_block,
+        delayed_end_block: old_proposal.delayed_end_block,
+        expiration_block: old_proposal.expiration_block,
+        title: old_proposal.title,
+        description: old_proposal.description,
+        link: old_proposal.link,
+        messages: old_proposal.messages,
+        options: old_proposal.options,
+        option_power: old_proposal.option_power,
+        winning_option: old_proposal.winning_option,
+    };
+    if proposal.status != ProposalStatus::Active {
+        return Ok(proposal);
+    }
+
+    //Tallies are rebuilt from the recomputed vote power so removing a vote can't underflow them.
+    //Aligned power is kept since it's square rooted past the threshold.
+    proposal.for_power = Uint128::zero();
+    proposal.against_power = Uint128::zero();
+    proposal.amendment_power = Uint128::zero();
+    proposal.removal_power = Uint128::zero();
+
+    for (voters, option) in voters {
+        for voter in voters {
+            let mut power = calc_voting_power(storage, querier, None, voter.to_string(), proposal.start_time, &mut false, None, config.quadratic_voting)?;
+            if power.is_zero() {
+                power = calc_voting_power(storage, querier, None, voter.to_string(), proposal.start_time, &mut false, Some(voter.to_string()), config.quadratic_voting)?;
+            }
+
+            match option {
+                ProposalVoteOption::For => proposal.for_power = proposal.for_power.checked_add(power)?,
+                ProposalVoteOption::Against => proposal.against_power = proposal.against_power.checked_add(power)?,
+                ProposalVoteOption::Amend => proposal.amendment_power = proposal.amendment_power.checked_add(power)?,
+                ProposalVoteOption::Remove => proposal.removal_power = proposal.removal_power.checked_add(power)?,
+                ProposalVoteOption::Align => {},
+            }
+
+            VOTES.save(storage, (proposal_id, voter), &Vote {
+                option: Some(option.clone()),
+                weights: None,
+                ranking: None,
+                power,
+                voter_power: None,
+            })?;
+        }
+    }
+
+    Ok(proposal)
 }
\ No newline at end of file
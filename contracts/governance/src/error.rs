@@ -41,6 +41,12 @@ pub enum ContractError {
     #[error("Proposal delay not ended!")]
     ProposalDelayNotEnded {},
 
+    #[error("Proposal not queued!")]
+    ProposalNotQueued {},
+
+    #[error("Timelock ends at {eta}")]
+    TimelockNotEnded { eta: u64 },
+
     #[error("Contract can't be migrated!")]
     MigrationError {},
 
@@ -55,6 +61,42 @@ pub enum ContractError {
     
     #[error("No need for further alignment to activate the proposal")]
     AlignmentReached {},
+
+    #[error("Health check {name} failed")]
+    HealthCheckFailed { name: String },
+
+    #[error("Proposal isn't multiple-choice!")]
+    NotMultipleChoice {},
+
+    #[error("Multiple-choice proposals take option votes!")]
+    OptionVoteRequired {},
+
+    #[error("Option weights must sum to 1 across {options} options")]
+    InvalidOptionWeights { options: u64 },
+
+    #[error("Rankings must list unique options of the {options} options")]
+    InvalidOptionRanking { options: u64 },
+
+    #[error("Proposal breaks the {msg} {field} guardrail")]
+    GuardrailBreached { msg: String, field: String },
+
+    #[error("Proposal's {msg} {field} can't be checked against its guardrail: {error}")]
+    GuardrailUnreadable { msg: String, field: String, error: String },
+
+    #[error("Insufficient {category} {denom} budget")]
+    InsufficientBudget { category: String, denom: String },
+
+    #[error("Insufficient {denom} treasury balance outside of escrowed deposits")]
+    InsufficientTreasury { denom: String },
+
+    #[error("Invalid stream schedule or amount")]
+    InvalidStream {},
+
+    #[error("Nothing to claim")]
+    NothingToClaim {},
+
+    #[error("Proposals require a deposit of exactly {deposit}")]
+    InvalidDeposit { deposit: String },
 }
 
 impl From<OverflowError> for ContractError {
@@ -7,7 +7,7 @@ mod tests {
 
     use ::membrane::vesting::AllocationResponse;
     use membrane::governance::{
-        ExecuteMsg, InstantiateMsg, QueryMsg, STAKE_INTERVAL, VOTING_PERIOD_INTERVAL,
+        ExecuteMsg, InstantiateMsg, MigrateMsg, OldProposal, QueryMsg, STAKE_INTERVAL, VOTING_PERIOD_INTERVAL,
     };
     use membrane::staking::{
         Config as StakingConfig, DelegationResponse, StakedResponse, StakerResponse, TotalStakedResponse
@@ -38,7 +38,27 @@ mod tests {
             crate::contracts::execute,
             crate::contracts::instantiate,
             crate::contracts::query,
-        );
+        ).with_reply(crate::contracts::reply)
+        .with_migrate(crate::contracts::migrate);
+        Box::new(contract)
+    }
+
+    //Mock Gov Contract that saves proposals in the format before per-vote storage
+    pub fn old_gov_contract() -> Box<dyn Contract<Empty>> {
+        let contract = ContractWrapper::new(
+            |deps, _, _, msg: OldProposal| -> StdResult<Response> {
+                crate::state::OLD_PROPOSALS.save(deps.storage, msg.proposal_id.to_string(), &msg)?;
+                Ok(Response::default())
+            },
+            |_, _, _, _: Empty| -> StdResult<Response> {
+                Ok(Response::default())
+            },
+            |_, _, _: Empty| -> StdResult<Binary> {
+                to_binary(&Empty {})
+            },
+        ).with_migrate(|_, _, _: Empty| -> StdResult<Response> {
+            Ok(Response::default())
+        });
         Box::new(contract)
     }
 
@@ -77,7 +97,12 @@ mod tests {
     //Mock Staking Contract
     #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
     #[serde(rename_all = "snake_case")]
-    pub enum Staking_MockExecuteMsg { }
+    pub enum Staking_MockExecuteMsg {
+        ReportParticipation {
+            proposal_id: u64,
+            voters: Vec<String>,
+        },
+    }
 
     #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
     #[serde(rename_all = "snake_case")]
@@ -100,6 +125,14 @@ mod tests {
         },
         Config {},
         TotalStaked {},
+        StakeAt {
+            user: String,
+            time: Option<u64>,
+        },
+        TotalStakeAt {
+            time: Option<u64>,
+            quadratic: Option<bool>,
+        },
     }
 
     pub fn staking_contract() -> Box<dyn Contract<Empty>> {
@@ -328,6 +361,40 @@ mod tests {
                             commission: Decimal::zero(),
                         },
                     },
+                    DelegationResponse {
+                        user: Addr::unchecked("delegate"),
+                        delegation_info: DelegationInfo {
+                            delegated: vec![
+                                Delegation { 
+                                    delegate: Addr::unchecked("delegator"), 
+                                    amount: Uint128::new(40_000_000),
+                                    fluidity: false,
+                                    voting_power_delegation: true,
+                                    time_of_delegation: 0,
+                                    last_accrued: None,
+                                },
+                            ],
+                            delegated_to: vec![],
+                            commission: Decimal::zero(),
+                        },
+                    },
+                    DelegationResponse {
+                        user: Addr::unchecked("delegator"),
+                        delegation_info: DelegationInfo {
+                            delegated: vec![],
+                            delegated_to: vec![
+                                Delegation { 
+                                    delegate: Addr::unchecked("delegate"), 
+                                    amount: Uint128::new(40_000_000),
+                                    fluidity: false,
+                                    voting_power_delegation: true,
+                                    time_of_delegation: 0,
+                                    last_accrued: None,
+                                },
+                            ],
+                            commission: Decimal::zero(),
+                        },
+                    },
                     // DelegationResponse {
                     //     user: Addr::unchecked(ADMIN),
                     //     delegation_info: DelegationInfo {
@@ -393,11 +460,37 @@ mod tests {
                         max_commission_rate: Decimal::zero(),
                         keep_raw_cdt: false,
                         vesting_rev_multiplier: Decimal::zero(),
+                        participation_requirements: None,
+                        dex_router: None,
+                        keeper_tip: Decimal::zero(),
+                        instant_unstake_penalty: None,
+                        stability_pool: None,
+                        oracle_contract: None,
+                        max_slippage: Decimal::zero(),
                     })?),
                     Staking_MockQueryMsg::TotalStaked {  } => Ok(to_binary(&TotalStakedResponse {
                         total_not_including_vested: Uint128::new(1000000_000000u128),
                         vested_total: Uint128::zero(),   
                     })?),
+                    Staking_MockQueryMsg::StakeAt { user, time } => {
+                        let stake = match user.as_str() {
+                            "user" => Uint128::new(1_000_000_000u128),
+                            "admin" => Uint128::new(60_000_000u128),
+                            "alignment" => Uint128::new(980_000_000u128),
+                            "alignment2.0" => Uint128::new(980_000_000_000u128),
+                            "delegator" => Uint128::new(40_000_000u128),
+                            _ => Uint128::zero(),
+                        };
+                        Ok(to_binary(&stake)?)
+                    },
+                    Staking_MockQueryMsg::TotalStakeAt { time, quadratic } => {
+                        //Sum of each staker's checkpointed stake, or of their square roots
+                        if quadratic.unwrap_or(false) {
+                            Ok(to_binary(&Uint128::new(1_060_624u128))?)
+                        } else {
+                            Ok(to_binary(&Uint128::new(982_040_000_002u128))?)
+                        }
+                    },
                 }
             },
         );
@@ -550,7 +643,7 @@ mod tests {
         };
 
         let gov_contract_addr = app
-            .instantiate_contract(gov_id, Addr::unchecked(ADMIN), &msg, &[], "test", None)
+            .instantiate_contract(gov_id, Addr::unchecked(ADMIN), &msg, &[], "test", Some(ADMIN.to_string()))
             .unwrap();
 
         let gov_contract = GovContract(gov_contract_addr);
@@ -563,8 +656,10 @@ mod tests {
         use super::*;
         use cosmwasm_std::{Uint64, WasmMsg};
         use membrane::governance::{
-            Config, ProposalListResponse, ProposalMessage, ProposalStatus,
-            ProposalVoteOption, ProposalVotesResponse, UpdateConfig, Proposal
+            Config, HealthCheck, HealthCheckCondition, ProposalListResponse, ProposalMessage, ProposalStatus,
+            ProposalVoteOption, ProposalVotesResponse, UpdateConfig, Proposal, QueuedProposal, TimelockDelay,
+            ProposalOption, OptionVoterResponse, ProposalVoterResponse, Guardrail, FieldGuardrail, CurrentValueQuery,
+            Budget, BudgetUpdate, StreamResponse, ProposalDeposit, DepositStatus,
         };
 
         #[test]
@@ -577,6 +672,7 @@ mod tests {
                 description: "Test description!".to_string(),
                 link: None,
                 messages: None,
+                options: None,
                 recipient: None,
                 expedited: false,
             };
@@ -624,6 +720,7 @@ mod tests {
                 description: "Test description!".to_string(),
                 link: None,
                 messages: None,
+                options: None,
                 recipient: Some(String::from("recipient")),
                 expedited: false,
             };
@@ -636,6 +733,7 @@ mod tests {
                 description: "Test description!".to_string(),
                 link: None,
                 messages: None,
+                options: None,
                 recipient: None,
                 expedited: false,
             };
@@ -653,6 +751,7 @@ mod tests {
                 description: "Test description!".to_string(),
                 link: None,
                 messages: None,
+                options: None,
                 recipient: Some(String::from("recipient")),
                 expedited: false,
             };
@@ -669,6 +768,7 @@ mod tests {
                 description: "Test description!".to_string(),
                 link: None,
                 messages: None,
+                options: None,
                 recipient: Some(String::from("recipient")),
                 expedited: false,
             };
@@ -687,6 +787,7 @@ mod tests {
                 description: "X".to_string(),
                 link: None,
                 messages: None,
+                options: None,
                 recipient: Some(String::from("recipient")),
                 expedited: false,
             };
@@ -705,6 +806,7 @@ mod tests {
                 description: String::from_utf8(vec![b'X'; 1025]).unwrap(),
                 link: None,
                 messages: None,
+                options: None,
                 recipient: Some(String::from("recipient")),
                 expedited: false,
             };
@@ -723,6 +825,7 @@ mod tests {
                 description: "Test description!".to_string(),
                 link: Some(String::from("X")),
                 messages: None,
+                options: None,
                 recipient: Some(String::from("recipient")),
                 expedited: false,
             };
@@ -741,6 +844,7 @@ mod tests {
                 description: "Test description!".to_string(),
                 link: Some(String::from_utf8(vec![b'X'; 129]).unwrap()),
                 messages: None,
+                options: None,
                 recipient: Some(String::from("recipient")),
                 expedited: false,
             };
@@ -759,6 +863,7 @@ mod tests {
                 description: "Test description!".to_string(),
                 link: Some(String::from("https://some1.link")),
                 messages: None,
+                options: None,
                 recipient: Some(String::from("recipient")),
                 expedited: false,
             };
@@ -779,6 +884,7 @@ mod tests {
                     "https://some.link/<script>alert('test');</script>",
                 )),
                 messages: None,
+                options: None,
                 recipient: Some(String::from("recipient")),
                 expedited: false,
             };
@@ -806,6 +912,7 @@ mod tests {
                         funds: vec![],
                     }),
                 }]),
+                options: None,
                 recipient: Some(String::from("recipient")),
                 expedited: false,
             };
@@ -896,11 +1003,17 @@ mod tests {
                             ]),
                             whitelist_remove: Some(vec!["https://some.link/".to_string()]),
                             quadratic_voting: None,
+                            guardian: None,
+                            timelock_delay: None,
+                            timelock_target_delays: None,
+                            proposal_deposit: None,
+                            removed_deposit_to_stakers: None,
                         }))
                         .unwrap(),
                         funds: vec![],
                     }),
                 }]),
+                options: None,
                 recipient: Some(String::from("recipient")),
                 expedited: false,
             };
@@ -971,28 +1084,28 @@ mod tests {
                 )
                 .unwrap();
 
-            let proposal_for_voters: Vec<Addr> = app
+            let proposal_for_voters: Vec<ProposalVoterResponse> = app
                 .wrap()
                 .query_wasm_smart(
                     gov_contract.addr(),
                     &QueryMsg::ProposalVoters {
                         proposal_id: 1,
                         vote_option: ProposalVoteOption::For,
-                        start: None,
+                        start_after: None,
                         limit: None,
                         specific_user: None,
                     },
                 )
                 .unwrap();
 
-            let proposal_against_voters: Vec<Addr> = app
+            let proposal_against_voters: Vec<ProposalVoterResponse> = app
                 .wrap()
                 .query_wasm_smart(
                     gov_contract.addr(),
                     &QueryMsg::ProposalVoters {
                         proposal_id: 1,
                         vote_option: ProposalVoteOption::Against,
-                        start: None,
+                        start_after: None,
                         limit: None,
                         specific_user: None,
                     },
@@ -1006,8 +1119,8 @@ mod tests {
             assert_eq!(proposal_votes.for_power, Uint128::from(32112u128));
             assert_eq!(proposal_votes.against_power, Uint128::from(7746u128));
 
-            assert_eq!(proposal_for_voters, vec![Addr::unchecked("user")]);
-            assert_eq!(proposal_against_voters, vec![Addr::unchecked("admin")]);
+            assert_eq!(proposal_for_voters.iter().map(|voter| voter.voter.clone()).collect::<Vec<Addr>>(), vec![Addr::unchecked("user")]);
+            assert_eq!(proposal_against_voters.iter().map(|voter| voter.voter.clone()).collect::<Vec<Addr>>(), vec![Addr::unchecked("admin")]);
 
             //Query voting power
             let voting_power_1: Uint128 = app
@@ -1149,28 +1262,28 @@ mod tests {
                 )
                 .unwrap();
 
-            let proposal_for_voters: Vec<Addr> = app
+            let proposal_for_voters: Vec<ProposalVoterResponse> = app
                 .wrap()
                 .query_wasm_smart(
                     gov_contract.addr(),
                     &QueryMsg::ProposalVoters {
                         proposal_id: 1,
                         vote_option: ProposalVoteOption::For,
-                        start: None,
+                        start_after: None,
                         limit: None,
                         specific_user: None,
                     },
                 )
                 .unwrap();
 
-            let proposal_against_voters: Vec<Addr> = app
+            let proposal_against_voters: Vec<ProposalVoterResponse> = app
                 .wrap()
                 .query_wasm_smart(
                     gov_contract.addr(),
                     &QueryMsg::ProposalVoters {
                         proposal_id: 1,
                         vote_option: ProposalVoteOption::Against,
-                        start: None,
+                        start_after: None,
                         limit: None,
                         specific_user: None,
                     },
@@ -1184,9 +1297,25 @@ mod tests {
             assert_eq!(proposal_votes.for_power, Uint128::new(39858));
             assert_eq!(proposal_votes.against_power, Uint128::zero());
 
-            assert_eq!(proposal_for_voters, vec![Addr::unchecked("user"), Addr::unchecked("admin")]);
+            assert_eq!(proposal_for_voters.iter().map(|voter| voter.voter.clone()).collect::<Vec<Addr>>(), vec![Addr::unchecked("admin"), Addr::unchecked("user")]);
             assert_eq!(proposal_against_voters.len(), 0 as usize);
 
+            //Paginate voters
+            let proposal_for_voters: Vec<ProposalVoterResponse> = app
+                .wrap()
+                .query_wasm_smart(
+                    gov_contract.addr(),
+                    &QueryMsg::ProposalVoters {
+                        proposal_id: 1,
+                        vote_option: ProposalVoteOption::For,
+                        start_after: Some(String::from("admin")),
+                        limit: Some(1),
+                        specific_user: None,
+                    },
+                )
+                .unwrap();
+            assert_eq!(proposal_for_voters.iter().map(|voter| voter.voter.clone()).collect::<Vec<Addr>>(), vec![Addr::unchecked("user")]);
+
             // Skip voting period
             app.update_block(|bi| {
                 bi.height += 7 * PROPOSAL_VOTING_PERIOD + 1;
@@ -1206,8 +1335,8 @@ mod tests {
                 String::from("Voting period ended!")
             );
 
-            //Try to execute the proposal before ending it
-            let msg = ExecuteMsg::ExecuteProposal { proposal_id: 1u64 };
+            //Try to queue the proposal before ending it
+            let msg = ExecuteMsg::QueueProposal { proposal_id: 1u64 };
             let cosmos_msg = gov_contract.call(msg, vec![]).unwrap();
             let err = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
             assert_eq!(
@@ -1230,8 +1359,8 @@ mod tests {
 
             assert_eq!(proposal.status, ProposalStatus::Passed);
 
-            //Try to Execute before the delay
-            let msg = ExecuteMsg::ExecuteProposal { proposal_id: 1u64 };
+            //Try to Queue before the delay
+            let msg = ExecuteMsg::QueueProposal { proposal_id: 1u64 };
             let cosmos_msg = gov_contract.call(msg, vec![]).unwrap();
             let err = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
             assert_eq!(
@@ -1245,11 +1374,98 @@ mod tests {
                 bi.time = bi.time.plus_seconds(6 * (PROPOSAL_EFFECTIVE_DELAY + 1));
             });
 
-            //Try to Execute after the delay
+            //Set a guardian & a timelock delay for config updates
+            let msg = ExecuteMsg::UpdateConfig(UpdateConfig {
+                tema_denom: None,
+                staking_contract: None,
+                vesting_contract_addr: None,
+                vesting_voting_power_multiplier: None,
+                minimum_total_stake: None,
+                proposal_voting_period: None,
+                expedited_proposal_voting_period: None,
+                proposal_effective_delay: None,
+                proposal_expiration_period: None,
+                proposal_required_stake: None,
+                proposal_required_quorum: None,
+                proposal_required_threshold: None,
+                whitelist_add: None,
+                whitelist_remove: None,
+                quadratic_voting: None,
+                guardian: Some(String::from("guardian")),
+                timelock_delay: None,
+                timelock_target_delays: Some(vec![TimelockDelay {
+                    target: gov_contract.addr().to_string(),
+                    msg: Some(String::from("update_config")),
+                    delay: 86_400,
+                }]),
+                proposal_deposit: None,
+                removed_deposit_to_stakers: None,
+            });
+            let cosmos_msg = gov_contract.call(msg, vec![]).unwrap();
+            app.execute(gov_contract.addr(), cosmos_msg).unwrap();
+
+            //Try to Execute before queueing
+            let msg = ExecuteMsg::ExecuteProposal { proposal_id: 1u64 };
+            let cosmos_msg = gov_contract.call(msg, vec![]).unwrap();
+            let err = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
+            assert_eq!(
+                err.root_cause().to_string(),
+                String::from("Proposal not queued!")
+            );
+
+            //Queue after the delay
+            let msg = ExecuteMsg::QueueProposal { proposal_id: 1u64 };
+            let cosmos_msg = gov_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            //The proposal's message waits for the config update delay
+            let eta = app.block_info().time.seconds() + 86_400;
+            let queue: Vec<QueuedProposal> = app
+                .wrap()
+                .query_wasm_smart(
+                    gov_contract.addr().to_string(),
+                    &QueryMsg::TimelockQueue { start: None, limit: None },
+                )
+                .unwrap();
+            assert_eq!(queue, vec![QueuedProposal {
+                proposal_id: 1,
+                queued_time: app.block_info().time.seconds(),
+                eta,
+            }]);
+
+            //Try to Execute before the timelock ends
+            let msg = ExecuteMsg::ExecuteProposal { proposal_id: 1u64 };
+            let cosmos_msg = gov_contract.call(msg, vec![]).unwrap();
+            let err = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
+            assert_eq!(
+                err.root_cause().to_string(),
+                format!("Timelock ends at {}", eta)
+            );
+
+            //Error: Only the guardian can cancel
+            let msg = ExecuteMsg::CancelProposal { proposal_id: 1u64 };
+            let cosmos_msg = gov_contract.call(msg, vec![]).unwrap();
+            let err = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
+            assert_eq!(err.root_cause().to_string(), String::from("Unauthorized"));
+
+            app.update_block(|bi| {
+                bi.time = bi.time.plus_seconds(86_400);
+            });
+
+            //Execute after the timelock
             let msg = ExecuteMsg::ExecuteProposal { proposal_id: 1u64 };
             let cosmos_msg = gov_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
 
+            //Error: Executed proposals can't be cancelled
+            let msg = ExecuteMsg::CancelProposal { proposal_id: 1u64 };
+            let cosmos_msg = gov_contract.call(msg, vec![]).unwrap();
+            let err = app.execute(Addr::unchecked("guardian"), cosmos_msg).unwrap_err();
+            assert_eq!(
+                err.root_cause().to_string(),
+                String::from("Proposal not passed!")
+            );
+
             // Check execution result
             let config: Config = app
                 .wrap()
@@ -1275,6 +1491,41 @@ mod tests {
                 ]
             );
             assert_eq!(proposal.status, ProposalStatus::Executed);
+            assert_eq!(config.guardian, Some(Addr::unchecked("guardian")));
+
+            //Remove the guardian
+            let msg = ExecuteMsg::UpdateConfig(UpdateConfig {
+                tema_denom: None,
+                staking_contract: None,
+                vesting_contract_addr: None,
+                vesting_voting_power_multiplier: None,
+                minimum_total_stake: None,
+                proposal_voting_period: None,
+                expedited_proposal_voting_period: None,
+                proposal_effective_delay: None,
+                proposal_expiration_period: None,
+                proposal_required_stake: None,
+                proposal_required_quorum: None,
+                proposal_required_threshold: None,
+                whitelist_add: None,
+                whitelist_remove: None,
+                quadratic_voting: None,
+                guardian: Some(String::new()),
+                timelock_delay: None,
+                timelock_target_delays: None,
+                proposal_deposit: None,
+                removed_deposit_to_stakers: None,
+            });
+            let cosmos_msg = gov_contract.call(msg, vec![]).unwrap();
+            app.execute(gov_contract.addr(), cosmos_msg).unwrap();
+            let config: Config = app
+                .wrap()
+                .query_wasm_smart(
+                    gov_contract.addr().to_string(),
+                    &QueryMsg::Config {},
+                )
+                .unwrap();
+            assert_eq!(config.guardian, None);
 
             //Try to remove proposal before expiration period
             let msg = ExecuteMsg::RemoveCompletedProposal { proposal_id: 1u64 };
@@ -1363,11 +1614,17 @@ mod tests {
                             ]),
                             whitelist_remove: Some(vec!["https://some.link/".to_string()]),
                             quadratic_voting: None,
+                            guardian: None,
+                            timelock_delay: None,
+                            timelock_target_delays: None,
+                            proposal_deposit: None,
+                            removed_deposit_to_stakers: None,
                         }))
                         .unwrap(),
                         funds: vec![],
                     }),
                 }]),
+                options: None,
                 recipient: Some(String::from("recipient")),
                 expedited: true,
             };
@@ -1443,11 +1700,17 @@ mod tests {
                             ]),
                             whitelist_remove: Some(vec!["https://some.link/".to_string()]),
                             quadratic_voting: None,
+                            guardian: None,
+                            timelock_delay: None,
+                            timelock_target_delays: None,
+                            proposal_deposit: None,
+                            removed_deposit_to_stakers: None,
                         }))
                         .unwrap(),
                         funds: vec![],
                     }),
                 }]),
+                options: None,
                 recipient: Some(String::from("recipient")),
                 expedited: false,
             };
@@ -1505,28 +1768,28 @@ mod tests {
                 )
                 .unwrap();
 
-            let proposal_for_voters: Vec<Addr> = app
+            let proposal_for_voters: Vec<ProposalVoterResponse> = app
                 .wrap()
                 .query_wasm_smart(
                     gov_contract.addr(),
                     &QueryMsg::ProposalVoters {
                         proposal_id: 1,
                         vote_option: ProposalVoteOption::For,
-                        start: None,
+                        start_after: None,
                         limit: None,
                         specific_user: None,
                     },
                 )
                 .unwrap();
 
-            let proposal_amend_voters: Vec<Addr> = app
+            let proposal_amend_voters: Vec<ProposalVoterResponse> = app
                 .wrap()
                 .query_wasm_smart(
                     gov_contract.addr(),
                     &QueryMsg::ProposalVoters {
                         proposal_id: 1,
                         vote_option: ProposalVoteOption::Amend,
-                        start: None,
+                        start_after: None,
                         limit: None,
                         specific_user: None,
                     },
@@ -1540,8 +1803,8 @@ mod tests {
             assert_eq!(proposal_votes.amendment_power, Uint128::from(32112u128));
             assert_eq!(proposal_votes.for_power, Uint128::from(7746u128));
 
-            assert_eq!(proposal_for_voters, vec![Addr::unchecked("admin")]);
-            assert_eq!(proposal_amend_voters, vec![Addr::unchecked("user")]);
+            assert_eq!(proposal_for_voters.iter().map(|voter| voter.voter.clone()).collect::<Vec<Addr>>(), vec![Addr::unchecked("admin")]);
+            assert_eq!(proposal_amend_voters.iter().map(|voter| voter.voter.clone()).collect::<Vec<Addr>>(), vec![Addr::unchecked("user")]);
 
             
             // Skip voting period
@@ -1594,11 +1857,17 @@ mod tests {
                             ]),
                             whitelist_remove: Some(vec!["https://some.link/".to_string()]),
                             quadratic_voting: None,
+                            guardian: None,
+                            timelock_delay: None,
+                            timelock_target_delays: None,
+                            proposal_deposit: None,
+                            removed_deposit_to_stakers: None,
                         }))
                         .unwrap(),
                         funds: vec![],
                     }),
                 }]),
+                options: None,
                 recipient: Some(String::from("recipient")),
                 expedited: false,
             };
@@ -1656,14 +1925,14 @@ mod tests {
                 )
                 .unwrap();
 
-            let proposal_removal_voters: Vec<Addr> = app
+            let proposal_removal_voters: Vec<ProposalVoterResponse> = app
                 .wrap()
                 .query_wasm_smart(
                     gov_contract.addr(),
                     &QueryMsg::ProposalVoters {
                         proposal_id: 2,
                         vote_option: ProposalVoteOption::Remove,
-                        start: None,
+                        start_after: None,
                         limit: None,
                         specific_user: None,
                     },
@@ -1674,7 +1943,7 @@ mod tests {
             assert_eq!(proposal.removal_power, Uint128::from(32112u128 + 7746u128)); 
             assert_eq!(proposal_votes.removal_power, Uint128::from(32112u128 + 7746u128));
 
-            assert_eq!(proposal_removal_voters, vec![Addr::unchecked("admin"), Addr::unchecked("user")]);
+            assert_eq!(proposal_removal_voters.iter().map(|voter| voter.voter.clone()).collect::<Vec<Addr>>(), vec![Addr::unchecked("admin"), Addr::unchecked("user")]);
             
             // Skip voting period
             app.update_block(|bi| {
@@ -1736,11 +2005,17 @@ mod tests {
                             ]),
                             whitelist_remove: Some(vec!["https://some.link/".to_string()]),
                             quadratic_voting: None,
+                            guardian: None,
+                            timelock_delay: None,
+                            timelock_target_delays: None,
+                            proposal_deposit: None,
+                            removed_deposit_to_stakers: None,
                         }))
                         .unwrap(),
                         funds: vec![],
                     }),
                 }]),
+                options: None,
                 recipient: None,
                 expedited: false,
             };
@@ -1807,11 +2082,17 @@ mod tests {
                             ]),
                             whitelist_remove: Some(vec!["https://some.link/".to_string()]),
                             quadratic_voting: None,
+                            guardian: None,
+                            timelock_delay: None,
+                            timelock_target_delays: None,
+                            proposal_deposit: None,
+                            removed_deposit_to_stakers: None,
                         }))
                         .unwrap(),
                         funds: vec![],
                     }),
                 }]),
+                options: None,
                 recipient: None,
                 expedited: false,
             };
@@ -1879,11 +2160,17 @@ mod tests {
                             ]),
                             whitelist_remove: Some(vec!["https://some.link/".to_string()]),
                             quadratic_voting: None,
+                            guardian: None,
+                            timelock_delay: None,
+                            timelock_target_delays: None,
+                            proposal_deposit: None,
+                            removed_deposit_to_stakers: None,
                         }))
                         .unwrap(),
                         funds: vec![],
                     }),
                 }]),
+                options: None,
                 recipient: Some(String::from("recipient")),
                 expedited: false,
             };
@@ -2004,12 +2291,17 @@ mod tests {
                             ]),
                             whitelist_remove: Some(vec!["https://some.link/".to_string()]),
                             quadratic_voting: None,
+                            guardian: None,
+                            timelock_delay: None,
+                            timelock_target_delays: None,
+                            proposal_deposit: None,
+                            removed_deposit_to_stakers: None,
                         }))
                         .unwrap(),
                         funds: vec![],
                     }),
                 }],
-                msg_switch: None,
+                checks: None,
             };
             let cosmos_msg = gov_contract.call(msg, vec![]).unwrap();
             let err = app
@@ -2029,5 +2321,1095 @@ mod tests {
                 .unwrap();
             assert_eq!(config_before, config_after);
         }
+
+        #[test]
+        fn health_checks() {
+            let (mut app, gov_contract, bv_contract_addr) = proper_instantiate();
+
+            let health_check = HealthCheck {
+                name: String::from("gov_config"),
+                contract: gov_contract.addr().to_string(),
+                query: to_binary(&QueryMsg::Config {}).unwrap(),
+                condition: HealthCheckCondition::Unchanged,
+            };
+
+            //Error: Only the contract can edit health checks
+            let msg = ExecuteMsg::EditHealthChecks {
+                add: Some(vec![health_check.clone()]),
+                remove: None,
+            };
+            let cosmos_msg = gov_contract.call(msg.clone(), vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap_err();
+
+            //Add health check
+            let cosmos_msg = gov_contract.call(msg, vec![]).unwrap();
+            app.execute(gov_contract.addr(), cosmos_msg).unwrap();
+
+            let health_checks: Vec<HealthCheck> = app
+                .wrap()
+                .query_wasm_smart(
+                    gov_contract.addr().to_string(),
+                    &QueryMsg::HealthChecks {},
+                )
+                .unwrap();
+            assert_eq!(health_checks, vec![health_check]);
+
+            //Config changes so the check fails
+            let msg = ExecuteMsg::CheckMessages {
+                messages: vec![ProposalMessage {
+                    order: Uint64::new(1u64),
+                    msg: cosmwasm_std::CosmosMsg::Wasm(WasmMsg::Execute {
+                        contract_addr: gov_contract.addr().to_string(),
+                        msg: to_binary(&ExecuteMsg::UpdateConfig(UpdateConfig {
+                            tema_denom: None,
+                            staking_contract: None,
+                            vesting_contract_addr: None,
+                            vesting_voting_power_multiplier: None,
+                            minimum_total_stake: None,
+                            proposal_voting_period: Some(PROPOSAL_VOTING_PERIOD + 1000),
+                            expedited_proposal_voting_period: None,
+                            proposal_effective_delay: None,
+                            proposal_expiration_period: None,
+                            proposal_required_stake: None,
+                            proposal_required_quorum: None,
+                            proposal_required_threshold: None,
+                            whitelist_add: None,
+                            whitelist_remove: None,
+                            quadratic_voting: None,
+                            guardian: None,
+                            timelock_delay: None,
+                            timelock_target_delays: None,
+                            proposal_deposit: None,
+                            removed_deposit_to_stakers: None,
+                        }))
+                        .unwrap(),
+                        funds: vec![],
+                    }),
+                }],
+                checks: None,
+            };
+            let cosmos_msg = gov_contract.call(msg, vec![]).unwrap();
+            let err = app
+                .execute(bv_contract_addr.clone(), cosmos_msg)
+                .unwrap_err();
+            assert_eq!(
+                &err.root_cause().to_string(),
+                "Health check gov_config failed"
+            );
+
+            //Error: Unknown check
+            let msg = ExecuteMsg::CheckMessages {
+                messages: vec![],
+                checks: Some(vec![String::from("unknown")]),
+            };
+            let cosmos_msg = gov_contract.call(msg, vec![]).unwrap();
+            app.execute(bv_contract_addr, cosmos_msg).unwrap_err();
+
+            //Remove health check
+            let msg = ExecuteMsg::EditHealthChecks {
+                add: None,
+                remove: Some(vec![String::from("gov_config")]),
+            };
+            let cosmos_msg = gov_contract.call(msg, vec![]).unwrap();
+            app.execute(gov_contract.addr(), cosmos_msg).unwrap();
+
+            let health_checks: Vec<HealthCheck> = app
+                .wrap()
+                .query_wasm_smart(
+                    gov_contract.addr().to_string(),
+                    &QueryMsg::HealthChecks {},
+                )
+                .unwrap();
+            assert_eq!(health_checks, vec![]);
+        }
+
+        #[test]
+        fn multiple_choice_proposal() {
+            let (mut app, gov_contract, bv_contract_addr) = proper_instantiate();
+
+            //Submit Proposal: Error, options can't be combined with messages
+            let msg = ExecuteMsg::SubmitProposal {
+                title: "Test title!".to_string(),
+                description: "Test description!".to_string(),
+                link: None,
+                messages: Some(vec![]),
+                options: Some(vec![
+                    ProposalOption { title: String::from("Option A"), messages: None },
+                    ProposalOption { title: String::from("Option B"), messages: None },
+                ]),
+                recipient: Some(String::from("recipient")),
+                expedited: false,
+            };
+            let cosmos_msg = gov_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked("recipient"), cosmos_msg).unwrap_err();
+
+            //Submit Proposal
+            let msg = ExecuteMsg::SubmitProposal {
+                title: "Test title!".to_string(),
+                description: "Test description!".to_string(),
+                link: None,
+                messages: None,
+                options: Some(vec![
+                    ProposalOption { title: String::from("Option A"), messages: None },
+                    ProposalOption { title: String::from("Option B"), messages: None },
+                ]),
+                recipient: Some(String::from("recipient")),
+                expedited: false,
+            };
+            let cosmos_msg = gov_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked("recipient"), cosmos_msg).unwrap();
+
+            //Error: Weights don't sum to 1
+            let msg = ExecuteMsg::CastOptionVote {
+                proposal_id: 1u64,
+                weights: vec![Decimal::percent(50), Decimal::percent(40)],
+                recipient: None,
+            };
+            let cosmos_msg = gov_contract.call(msg, vec![]).unwrap();
+            let err = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
+            assert_eq!(
+                err.root_cause().to_string(),
+                "Option weights must sum to 1 across 2 options"
+            );
+
+            //Error: For votes aren't accepted on multiple-choice proposals
+            let msg = ExecuteMsg::CastVote {
+                proposal_id: 1u64,
+                vote: ProposalVoteOption::For,
+                recipient: None,
+            };
+            let cosmos_msg = gov_contract.call(msg, vec![]).unwrap();
+            let err = app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap_err();
+            assert_eq!(
+                err.root_cause().to_string(),
+                "Multiple-choice proposals take option votes!"
+            );
+
+            //Split votes
+            let msg = ExecuteMsg::CastOptionVote {
+                proposal_id: 1u64,
+                weights: vec![Decimal::one(), Decimal::zero()],
+                recipient: None,
+            };
+            let cosmos_msg = gov_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+            let msg = ExecuteMsg::CastOptionVote {
+                proposal_id: 1u64,
+                weights: vec![Decimal::one(), Decimal::zero()],
+                recipient: None,
+            };
+            let cosmos_msg = gov_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+            //Recast replaces the previous option vote
+            let msg = ExecuteMsg::CastOptionVote {
+                proposal_id: 1u64,
+                weights: vec![Decimal::percent(25), Decimal::percent(75)],
+                recipient: None,
+            };
+            let cosmos_msg = gov_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            //Error: Rankings must be unique options
+            let msg = ExecuteMsg::CastRankedVote {
+                proposal_id: 1u64,
+                ranking: vec![1, 1],
+                recipient: None,
+            };
+            let cosmos_msg = gov_contract.call(msg, vec![]).unwrap();
+            let err = app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap_err();
+            assert_eq!(
+                err.root_cause().to_string(),
+                "Rankings must list unique options of the 2 options"
+            );
+            //Error: Ranked option doesn't exist
+            let msg = ExecuteMsg::CastRankedVote {
+                proposal_id: 1u64,
+                ranking: vec![2],
+                recipient: None,
+            };
+            let cosmos_msg = gov_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap_err();
+
+            //Ranked recast splits 2/3 to the first choice & 1/3 to the second
+            let msg = ExecuteMsg::CastRankedVote {
+                proposal_id: 1u64,
+                ranking: vec![1, 0],
+                recipient: None,
+            };
+            let cosmos_msg = gov_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+            //Align to pass Quorum
+            let msg = ExecuteMsg::CastVote {
+                proposal_id: 1u64,
+                vote: ProposalVoteOption::Align,
+                recipient: None,
+            };
+            let cosmos_msg = gov_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked("alignment2.0"), cosmos_msg).unwrap();
+
+            let option_voters: Vec<OptionVoterResponse> = app
+                .wrap()
+                .query_wasm_smart(
+                    gov_contract.addr(),
+                    &QueryMsg::OptionVoters { proposal_id: 1, start_after: None, limit: None },
+                )
+                .unwrap();
+            assert_eq!(option_voters.len(), 2);
+            assert_eq!(option_voters[0].voter, Addr::unchecked(ADMIN));
+            assert_eq!(option_voters[0].vote.weights, vec![Decimal::from_ratio(1u128, 3u128), Decimal::from_ratio(2u128, 3u128)]);
+            assert_eq!(option_voters[0].vote.ranking, Some(vec![1, 0]));
+            assert_eq!(option_voters[1].voter, Addr::unchecked(USER));
+            assert_eq!(option_voters[1].vote.weights, vec![Decimal::percent(25), Decimal::percent(75)]);
+            assert_eq!(option_voters[1].vote.ranking, None);
+
+            //Paginate
+            let option_voters: Vec<OptionVoterResponse> = app
+                .wrap()
+                .query_wasm_smart(
+                    gov_contract.addr(),
+                    &QueryMsg::OptionVoters { proposal_id: 1, start_after: Some(String::from(ADMIN)), limit: None },
+                )
+                .unwrap();
+            assert_eq!(option_voters.len(), 1);
+
+            let proposal_votes: ProposalVotesResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    gov_contract.addr(),
+                    &QueryMsg::ProposalVotes { proposal_id: 1 },
+                )
+                .unwrap();
+            assert_eq!(proposal_votes.for_power, Uint128::zero());
+            assert!(proposal_votes.option_power[1] > proposal_votes.option_power[0]);
+
+            // Skip voting period
+            app.update_block(|bi| {
+                bi.height += 7 * PROPOSAL_VOTING_PERIOD + 1;
+                bi.time = bi.time.plus_seconds(6 * (7 * PROPOSAL_VOTING_PERIOD + 1));
+            });
+
+            //Error: Voting period ended
+            let msg = ExecuteMsg::CastOptionVote {
+                proposal_id: 1u64,
+                weights: vec![Decimal::one(), Decimal::zero()],
+                recipient: None,
+            };
+            let cosmos_msg = gov_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
+
+            //Successful End
+            let msg = ExecuteMsg::EndProposal { proposal_id: 1u64 };
+            let cosmos_msg = gov_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            let proposal: Proposal = app
+                .wrap()
+                .query_wasm_smart(
+                    gov_contract.addr(),
+                    &QueryMsg::Proposal { proposal_id: 1 },
+                )
+                .unwrap();
+            assert_eq!(proposal.status, ProposalStatus::Passed);
+            assert_eq!(proposal.winning_option, Some(1));
+            assert_eq!(proposal.messages, None);
+        }
+
+        #[test]
+        fn migrate_proposal_votes() {
+            let (mut app, gov_contract, bv_contract_addr) = proper_instantiate();
+
+            //Submit Proposal
+            let msg = ExecuteMsg::SubmitProposal {
+                title: "Test title!".to_string(),
+                description: "Test description!".to_string(),
+                link: None,
+                messages: None,
+                options: None,
+                recipient: Some(String::from("recipient")),
+                expedited: false,
+            };
+            let cosmos_msg = gov_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked("recipient"), cosmos_msg).unwrap();
+
+            let msg = ExecuteMsg::CastVote {
+                proposal_id: 1u64,
+                vote: ProposalVoteOption::For,
+                recipient: None,
+            };
+            let cosmos_msg = gov_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            let proposal: Proposal = app
+                .wrap()
+                .query_wasm_smart(
+                    gov_contract.addr(),
+                    &QueryMsg::Proposal { proposal_id: 1 },
+                )
+                .unwrap();
+
+            //Save an active & a completed proposal in the old format
+            let old_gov_id = app.store_code(old_gov_contract());
+            let gov_id = app.store_code(gov_contract());
+            app.execute(Addr::unchecked(ADMIN), WasmMsg::Migrate {
+                contract_addr: gov_contract.addr().to_string(),
+                new_code_id: old_gov_id,
+                msg: to_binary(&Empty {}).unwrap(),
+            }.into()).unwrap();
+
+            for (proposal_id, status) in [(2u64, ProposalStatus::Active), (3u64, ProposalStatus::Passed)] {
+                let old_proposal = OldProposal {
+                    voting_power: vec![],
+                    proposal_id: Uint64::new(proposal_id),
+                    submitter: proposal.submitter.clone(),
+                    status,
+                    aligned_power: proposal.aligned_power,
+                    for_power: Uint128::one(),
+                    against_power: Uint128::new(1_000),
+                    amendment_power: Uint128::zero(),
+                    removal_power: Uint128::zero(),
+                    aligned_voters: vec![],
+                    for_voters: vec![Addr::unchecked(USER)],
+                    against_voters: vec![],
+                    amendment_voters: vec![],
+                    removal_voters: vec![],
+                    start_block: proposal.start_block,
+                    start_time: proposal.start_time,
+                    end_block: proposal.end_block,
+                    delayed_end_block: proposal.delayed_end_block,
+                    expiration_block: proposal.expiration_block,
+                    title: proposal.title.clone(),
+                    description: proposal.description.clone(),
+                    link: None,
+                    messages: None,
+                    options: None,
+                    option_power: vec![],
+                    winning_option: None,
+                };
+                app.execute_contract(Addr::unchecked(ADMIN), gov_contract.addr(), &old_proposal, &[]).unwrap();
+            }
+
+            //Migrate a page of 1 proposal
+            app.execute(Addr::unchecked(ADMIN), WasmMsg::Migrate {
+                contract_addr: gov_contract.addr().to_string(),
+                new_code_id: gov_id,
+                msg: to_binary(&MigrateMsg { limit: Some(1) }).unwrap(),
+            }.into()).unwrap();
+
+            //Active proposal tallies are rebuilt from the recomputed votes
+            let migrated_proposal: Proposal = app
+                .wrap()
+                .query_wasm_smart(
+                    gov_contract.addr(),
+                    &QueryMsg::Proposal { proposal_id: 2 },
+                )
+                .unwrap();
+            assert_eq!(migrated_proposal.for_power, proposal.for_power);
+            assert_eq!(migrated_proposal.against_power, Uint128::zero());
+
+            let proposal_for_voters: Vec<ProposalVoterResponse> = app
+                .wrap()
+                .query_wasm_smart(
+                    gov_contract.addr(),
+                    &QueryMsg::ProposalVoters {
+                        proposal_id: 2,
+                        vote_option: ProposalVoteOption::For,
+                        start_after: None,
+                        limit: None,
+                        specific_user: None,
+                    },
+                )
+                .unwrap();
+            assert_eq!(proposal_for_voters.len(), 1);
+            assert_eq!(proposal_for_voters[0].voter, Addr::unchecked(USER));
+            assert_eq!(proposal_for_voters[0].power, None);
+
+            //Error: Proposal past the page isn't migrated yet
+            app
+                .wrap()
+                .query_wasm_smart::<Proposal>(
+                    gov_contract.addr(),
+                    &QueryMsg::Proposal { proposal_id: 3 },
+                )
+                .unwrap_err();
+
+            //Migrate the next page
+            app.execute(Addr::unchecked(ADMIN), WasmMsg::Migrate {
+                contract_addr: gov_contract.addr().to_string(),
+                new_code_id: gov_id,
+                msg: to_binary(&MigrateMsg { limit: None }).unwrap(),
+            }.into()).unwrap();
+
+            //Completed proposals keep their tallies & drop their voters
+            let migrated_proposal: Proposal = app
+                .wrap()
+                .query_wasm_smart(
+                    gov_contract.addr(),
+                    &QueryMsg::Proposal { proposal_id: 3 },
+                )
+                .unwrap();
+            assert_eq!(migrated_proposal.for_power, Uint128::one());
+            assert_eq!(migrated_proposal.against_power, Uint128::new(1_000));
+
+            let proposal_for_voters: Vec<ProposalVoterResponse> = app
+                .wrap()
+                .query_wasm_smart(
+                    gov_contract.addr(),
+                    &QueryMsg::ProposalVoters {
+                        proposal_id: 3,
+                        vote_option: ProposalVoteOption::For,
+                        start_after: None,
+                        limit: None,
+                        specific_user: None,
+                    },
+                )
+                .unwrap();
+            assert!(proposal_for_voters.is_empty());
+        }
+
+        #[test]
+        fn guardrail_json_paths() {
+            use membrane::governance::helpers::get_json_decimal;
+
+            let json = br#"{"edit_basket":{"ltv":"0.5","assets":[{"max_LTV":"0.7"},{"max_LTV":0.8}],"oracle":null,"name":"basket"}}"#;
+
+            assert_eq!(get_json_decimal(json, "edit_basket.ltv").unwrap(), Some(Decimal::percent(50)));
+            assert_eq!(get_json_decimal(json, "edit_basket.assets.1.max_LTV").unwrap(), Some(Decimal::percent(80)));
+            //Unset fields & elements
+            assert_eq!(get_json_decimal(json, "edit_basket.assets.2.max_LTV").unwrap(), None);
+            assert_eq!(get_json_decimal(json, "edit_basket.oracle.price").unwrap(), None);
+            assert_eq!(get_json_decimal(json, "edit_basket.cap").unwrap(), None);
+            //Unresolvable paths & non-numbers
+            get_json_decimal(json, "edit_basket.assets.first.max_LTV").unwrap_err();
+            get_json_decimal(json, "edit_basket.ltv.value").unwrap_err();
+            get_json_decimal(json, "edit_basket.name").unwrap_err();
+        }
+
+        #[test]
+        fn tied_options_have_no_winner() {
+            use crate::contracts::get_winning_option;
+
+            assert_eq!(get_winning_option(&[Uint128::new(5), Uint128::new(7), Uint128::new(3)]), Some((1, Uint128::new(7))));
+            assert_eq!(get_winning_option(&[Uint128::new(7), Uint128::new(7), Uint128::new(3)]), None);
+            assert_eq!(get_winning_option(&[]), None);
+        }
+
+        #[test]
+        fn delegator_overrides_delegate() {
+            let (mut app, gov_contract, bv_contract_addr) = proper_instantiate();
+
+            //Submit Proposal
+            let msg = ExecuteMsg::SubmitProposal {
+                title: "Test title!".to_string(),
+                description: "Test description!".to_string(),
+                link: None,
+                messages: None,
+                options: None,
+                recipient: Some(String::from("recipient")),
+                expedited: false,
+            };
+            let cosmos_msg = gov_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked("recipient"), cosmos_msg).unwrap();
+
+            //Delegate votes with its delegators' power
+            let msg = ExecuteMsg::CastVote {
+                proposal_id: 1u64,
+                vote: ProposalVoteOption::For,
+                recipient: None,
+            };
+            let cosmos_msg = gov_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked("delegate"), cosmos_msg).unwrap();
+
+            let proposal_for_voters: Vec<ProposalVoterResponse> = app
+                .wrap()
+                .query_wasm_smart(
+                    gov_contract.addr(),
+                    &QueryMsg::ProposalVoters {
+                        proposal_id: 1,
+                        vote_option: ProposalVoteOption::For,
+                        start_after: None,
+                        limit: None,
+                        specific_user: None,
+                    },
+                )
+                .unwrap();
+            let delegate_power = proposal_for_voters[0].power.clone().unwrap();
+            assert_eq!(proposal_for_voters[0].voter, Addr::unchecked("delegate"));
+            assert_eq!(delegate_power.own_power, Uint128::zero());
+            assert_eq!(delegate_power.overridden_power, Uint128::zero());
+
+            let proposal: Proposal = app
+                .wrap()
+                .query_wasm_smart(
+                    gov_contract.addr(),
+                    &QueryMsg::Proposal { proposal_id: 1 },
+                )
+                .unwrap();
+            assert_eq!(proposal.for_power, delegate_power.delegated_power);
+
+            //Aligning doesn't override the delegate
+            let msg = ExecuteMsg::CastVote {
+                proposal_id: 1u64,
+                vote: ProposalVoteOption::Align,
+                recipient: None,
+            };
+            let cosmos_msg = gov_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked("delegator"), cosmos_msg).unwrap();
+
+            let proposal: Proposal = app
+                .wrap()
+                .query_wasm_smart(
+                    gov_contract.addr(),
+                    &QueryMsg::Proposal { proposal_id: 1 },
+                )
+                .unwrap();
+            assert_eq!(proposal.for_power, delegate_power.delegated_power);
+
+            //Delegator overrides its delegate after aligning
+            let msg = ExecuteMsg::CastVote {
+                proposal_id: 1u64,
+                vote: ProposalVoteOption::Against,
+                recipient: None,
+            };
+            let cosmos_msg = gov_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked("delegator"), cosmos_msg).unwrap();
+
+            let proposal_for_voters: Vec<ProposalVoterResponse> = app
+                .wrap()
+                .query_wasm_smart(
+                    gov_contract.addr(),
+                    &QueryMsg::ProposalVoters {
+                        proposal_id: 1,
+                        vote_option: ProposalVoteOption::For,
+                        start_after: None,
+                        limit: None,
+                        specific_user: Some(String::from("delegate")),
+                    },
+                )
+                .unwrap();
+            let delegate_power = proposal_for_voters[0].power.clone().unwrap();
+            assert_eq!(delegate_power.overridden_power, delegate_power.delegated_power);
+
+            let proposal_against_voters: Vec<ProposalVoterResponse> = app
+                .wrap()
+                .query_wasm_smart(
+                    gov_contract.addr(),
+                    &QueryMsg::ProposalVoters {
+                        proposal_id: 1,
+                        vote_option: ProposalVoteOption::Against,
+                        start_after: None,
+                        limit: None,
+                        specific_user: None,
+                    },
+                )
+                .unwrap();
+            let delegator_power = proposal_against_voters[0].power.clone().unwrap();
+            assert_eq!(proposal_against_voters[0].voter, Addr::unchecked("delegator"));
+            assert_eq!(delegator_power.delegated_power, Uint128::zero());
+
+            let proposal: Proposal = app
+                .wrap()
+                .query_wasm_smart(
+                    gov_contract.addr(),
+                    &QueryMsg::Proposal { proposal_id: 1 },
+                )
+                .unwrap();
+            assert_eq!(proposal.for_power, Uint128::zero());
+            assert_eq!(proposal.against_power, delegator_power.own_power);
+
+            //Error: Delegate has no voting power left
+            let msg = ExecuteMsg::CastVote {
+                proposal_id: 1u64,
+                vote: ProposalVoteOption::For,
+                recipient: None,
+            };
+            let cosmos_msg = gov_contract.call(msg, vec![]).unwrap();
+            let err = app.execute(Addr::unchecked("delegate"), cosmos_msg).unwrap_err();
+            assert_eq!(
+                err.root_cause().to_string(),
+                "You don't have any voting power!"
+            );
+        }
+
+        #[test]
+        fn guardrails() {
+            let (mut app, gov_contract, bv_contract_addr) = proper_instantiate();
+
+            let guardrail = Guardrail {
+                target: gov_contract.addr().to_string(),
+                msg: String::from("update_config"),
+                fields: vec![FieldGuardrail {
+                    field: String::from("proposal_voting_period"),
+                    min: Some(Decimal::from_ratio(PROPOSAL_VOTING_PERIOD, 1u128)),
+                    max: None,
+                    max_change: Some(Decimal::from_ratio(1000u128, 1u128)),
+                    current_value: Some(CurrentValueQuery {
+                        query: to_binary(&QueryMsg::Config {}).unwrap(),
+                        field: String::from("proposal_voting_period"),
+                    }),
+                }],
+            };
+
+            //Error: Only the contract can edit guardrails
+            let msg = ExecuteMsg::EditGuardrails { guardrails: vec![guardrail.clone()] };
+            let cosmos_msg = gov_contract.call(msg.clone(), vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap_err();
+
+            //Error: max_change needs a current value query
+            let mut invalid_guardrail = guardrail.clone();
+            invalid_guardrail.fields[0].current_value = None;
+            let cosmos_msg = gov_contract.call(ExecuteMsg::EditGuardrails { guardrails: vec![invalid_guardrail] }, vec![]).unwrap();
+            app.execute(gov_contract.addr(), cosmos_msg).unwrap_err();
+
+            //Add guardrail
+            let cosmos_msg = gov_contract.call(msg, vec![]).unwrap();
+            app.execute(gov_contract.addr(), cosmos_msg).unwrap();
+
+            let guardrails: Vec<Guardrail> = app
+                .wrap()
+                .query_wasm_smart(
+                    gov_contract.addr().to_string(),
+                    &QueryMsg::Guardrails {},
+                )
+                .unwrap();
+            assert_eq!(guardrails, vec![guardrail.clone()]);
+
+            let submit_voting_period = |proposal_voting_period: u64| ExecuteMsg::SubmitProposal {
+                title: "Test title!".to_string(),
+                description: "Test description!".to_string(),
+                link: None,
+                messages: Some(vec![ProposalMessage {
+                    order: Uint64::new(1u64),
+                    msg: cosmwasm_std::CosmosMsg::Wasm(WasmMsg::Execute {
+                        contract_addr: gov_contract.addr().to_string(),
+                        msg: to_binary(&ExecuteMsg::UpdateConfig(UpdateConfig {
+                            tema_denom: None,
+                            staking_contract: None,
+                            vesting_contract_addr: None,
+                            vesting_voting_power_multiplier: None,
+                            minimum_total_stake: None,
+                            proposal_voting_period: Some(proposal_voting_period),
+                            expedited_proposal_voting_period: None,
+                            proposal_effective_delay: None,
+                            proposal_expiration_period: None,
+                            proposal_required_stake: None,
+                            proposal_required_quorum: None,
+                            proposal_required_threshold: None,
+                            whitelist_add: None,
+                            whitelist_remove: None,
+                            quadratic_voting: None,
+                            guardian: None,
+                            timelock_delay: None,
+                            timelock_target_delays: None,
+                            proposal_deposit: None,
+                            removed_deposit_to_stakers: None,
+                        }))
+                        .unwrap(),
+                        funds: vec![],
+                    }),
+                }]),
+                options: None,
+                recipient: Some(String::from("recipient")),
+                expedited: false,
+            };
+
+            //Error: Under the minimum
+            let cosmos_msg = gov_contract.call(submit_voting_period(PROPOSAL_VOTING_PERIOD - 1), vec![]).unwrap();
+            let err = app.execute(Addr::unchecked("recipient"), cosmos_msg).unwrap_err();
+            assert_eq!(
+                err.root_cause().to_string(),
+                "Proposal breaks the update_config proposal_voting_period guardrail"
+            );
+
+            //Error: Changes more than the max change
+            let cosmos_msg = gov_contract.call(submit_voting_period(PROPOSAL_VOTING_PERIOD + 1002), vec![]).unwrap();
+            let err = app.execute(Addr::unchecked("recipient"), cosmos_msg).unwrap_err();
+            assert_eq!(
+                err.root_cause().to_string(),
+                "Proposal breaks the update_config proposal_voting_period guardrail"
+            );
+
+            //Within the guardrail
+            let cosmos_msg = gov_contract.call(submit_voting_period(PROPOSAL_VOTING_PERIOD + 1001), vec![]).unwrap();
+            app.execute(Addr::unchecked("recipient"), cosmos_msg).unwrap();
+
+            //Remove guardrail
+            let cosmos_msg = gov_contract.call(ExecuteMsg::EditGuardrails {
+                guardrails: vec![Guardrail { fields: vec![], ..guardrail }],
+            }, vec![]).unwrap();
+            app.execute(gov_contract.addr(), cosmos_msg).unwrap();
+
+            let guardrails: Vec<Guardrail> = app
+                .wrap()
+                .query_wasm_smart(
+                    gov_contract.addr().to_string(),
+                    &QueryMsg::Guardrails {},
+                )
+                .unwrap();
+            assert_eq!(guardrails, vec![]);
+        }
+
+        #[test]
+        fn treasury_streams() {
+            let (mut app, gov_contract, bv_contract_addr) = proper_instantiate();
+
+            //Fund the treasury
+            app.send_tokens(
+                Addr::unchecked("coin_God"),
+                gov_contract.addr(),
+                &[coin(1_000, "debit")],
+            )
+            .unwrap();
+
+            //Error: Only the contract can edit budgets
+            let msg = ExecuteMsg::EditBudgets {
+                budgets: vec![BudgetUpdate {
+                    category: String::from("contributors"),
+                    denom: String::from("debit"),
+                    amount: Uint128::new(1_000),
+                }],
+            };
+            let cosmos_msg = gov_contract.call(msg.clone(), vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap_err();
+
+            //Error: Budget is over the treasury balance
+            let cosmos_msg = gov_contract.call(ExecuteMsg::EditBudgets {
+                budgets: vec![BudgetUpdate {
+                    category: String::from("contributors"),
+                    denom: String::from("debit"),
+                    amount: Uint128::new(1_001),
+                }],
+            }, vec![]).unwrap();
+            let err = app.execute(gov_contract.addr(), cosmos_msg).unwrap_err();
+            assert_eq!(
+                err.root_cause().to_string(),
+                "Insufficient debit treasury balance outside of escrowed deposits"
+            );
+
+            let cosmos_msg = gov_contract.call(msg, vec![]).unwrap();
+            app.execute(gov_contract.addr(), cosmos_msg).unwrap();
+
+            let start_time = app.block_info().time.seconds();
+            let stream_msg = |amount: u128| ExecuteMsg::CreateStream {
+                category: String::from("contributors"),
+                recipient: String::from("contributor"),
+                denom: String::from("debit"),
+                amount: Uint128::new(amount),
+                start_time,
+                cliff_time: start_time + 100,
+                end_time: start_time + 1_000,
+            };
+
+            //Error: Over budget
+            let cosmos_msg = gov_contract.call(stream_msg(1_001), vec![]).unwrap();
+            app.execute(gov_contract.addr(), cosmos_msg).unwrap_err();
+
+            //Create stream
+            let cosmos_msg = gov_contract.call(stream_msg(600), vec![]).unwrap();
+            app.execute(gov_contract.addr(), cosmos_msg).unwrap();
+
+            let budgets: Vec<Budget> = app
+                .wrap()
+                .query_wasm_smart(gov_contract.addr().to_string(), &QueryMsg::Budgets {})
+                .unwrap();
+            assert_eq!(budgets[0].remaining, Uint128::new(400));
+            assert_eq!(budgets[0].allocated, Uint128::new(600));
+
+            //Error: Nothing to claim before the cliff
+            let cosmos_msg = gov_contract.call(ExecuteMsg::ClaimStream { stream_id: 1 }, vec![]).unwrap();
+            app.execute(Addr::unchecked("contributor"), cosmos_msg).unwrap_err();
+
+            app.update_block(|bi| {
+                bi.time = bi.time.plus_seconds(500);
+            });
+
+            //Error: Only the recipient can claim
+            let cosmos_msg = gov_contract.call(ExecuteMsg::ClaimStream { stream_id: 1 }, vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
+
+            //Claim half of the stream
+            let cosmos_msg = gov_contract.call(ExecuteMsg::ClaimStream { stream_id: 1 }, vec![]).unwrap();
+            app.execute(Addr::unchecked("contributor"), cosmos_msg).unwrap();
+            assert_eq!(
+                app.wrap().query_balance("contributor", "debit").unwrap().amount,
+                Uint128::new(300)
+            );
+
+            //Cancel the stream, returning the unvested half to the budget
+            app.update_block(|bi| {
+                bi.time = bi.time.plus_seconds(100);
+            });
+            let cosmos_msg = gov_contract.call(ExecuteMsg::CancelStream { stream_id: 1 }, vec![]).unwrap();
+            app.execute(gov_contract.addr(), cosmos_msg).unwrap();
+
+            let budgets: Vec<Budget> = app
+                .wrap()
+                .query_wasm_smart(gov_contract.addr().to_string(), &QueryMsg::Budgets {})
+                .unwrap();
+            assert_eq!(budgets[0].remaining, Uint128::new(640));
+            assert_eq!(budgets[0].allocated, Uint128::new(360));
+
+            //The vested amount is still claimable after cancelation
+            let streams: Vec<StreamResponse> = app
+                .wrap()
+                .query_wasm_smart(
+                    gov_contract.addr().to_string(),
+                    &QueryMsg::Streams { recipient: Some(String::from("contributor")), start_after: None, limit: None },
+                )
+                .unwrap();
+            assert_eq!(streams[0].claimable, Uint128::new(60));
+
+            app.update_block(|bi| {
+                bi.time = bi.time.plus_seconds(1_000);
+            });
+            let cosmos_msg = gov_contract.call(ExecuteMsg::ClaimStream { stream_id: 1 }, vec![]).unwrap();
+            app.execute(Addr::unchecked("contributor"), cosmos_msg).unwrap();
+            assert_eq!(
+                app.wrap().query_balance("contributor", "debit").unwrap().amount,
+                Uint128::new(360)
+            );
+
+            //Fully claimed streams are removed
+            let streams: Vec<StreamResponse> = app
+                .wrap()
+                .query_wasm_smart(
+                    gov_contract.addr().to_string(),
+                    &QueryMsg::Streams { recipient: None, start_after: None, limit: None },
+                )
+                .unwrap();
+            assert!(streams.is_empty());
+        }
+
+        #[test]
+        fn proposal_deposits() {
+            let (mut app, gov_contract, bv_contract_addr) = proper_instantiate();
+
+            app.send_tokens(
+                Addr::unchecked("coin_God"),
+                Addr::unchecked("recipient"),
+                &[coin(200, "debit")],
+            )
+            .unwrap();
+
+            //Require a deposit
+            let msg = ExecuteMsg::UpdateConfig(UpdateConfig {
+                tema_denom: None,
+                staking_contract: None,
+                vesting_contract_addr: None,
+                vesting_voting_power_multiplier: None,
+                minimum_total_stake: None,
+                proposal_voting_period: None,
+                expedited_proposal_voting_period: None,
+                proposal_effective_delay: None,
+                proposal_expiration_period: None,
+                proposal_required_stake: None,
+                proposal_required_quorum: None,
+                proposal_required_threshold: None,
+                whitelist_add: None,
+                whitelist_remove: None,
+                quadratic_voting: None,
+                guardian: None,
+                timelock_delay: None,
+                timelock_target_delays: None,
+                proposal_deposit: Some(coin(100, "debit")),
+                removed_deposit_to_stakers: None,
+            });
+            let cosmos_msg = gov_contract.call(msg, vec![]).unwrap();
+            app.execute(gov_contract.addr(), cosmos_msg).unwrap();
+
+            let msg = ExecuteMsg::SubmitProposal {
+                title: "Test title!".to_string(),
+                description: "Test description!".to_string(),
+                link: None,
+                messages: None,
+                options: None,
+                recipient: Some(String::from("recipient")),
+                expedited: false,
+            };
+
+            //Error: No deposit
+            let cosmos_msg = gov_contract.call(msg.clone(), vec![]).unwrap();
+            let err = app.execute(Addr::unchecked("recipient"), cosmos_msg).unwrap_err();
+            assert_eq!(
+                err.root_cause().to_string(),
+                "Proposals require a deposit of exactly 100debit"
+            );
+
+            //Submit proposals 1 & 2
+            for _ in 0..2 {
+                let cosmos_msg = gov_contract.call(msg.clone(), vec![coin(100, "debit")]).unwrap();
+                app.execute(Addr::unchecked("recipient"), cosmos_msg).unwrap();
+            }
+
+            let deposit: Option<ProposalDeposit> = app
+                .wrap()
+                .query_wasm_smart(
+                    gov_contract.addr(),
+                    &QueryMsg::ProposalDeposit { proposal_id: 1 },
+                )
+                .unwrap();
+            assert_eq!(deposit, Some(ProposalDeposit {
+                depositor: Addr::unchecked("recipient"),
+                amount: coin(100, "debit"),
+                status: DepositStatus::Escrowed,
+            }));
+            assert_eq!(
+                app.wrap().query_balance("recipient", "debit").unwrap().amount,
+                Uint128::zero()
+            );
+
+            //Error: Escrowed deposits can't be budgeted
+            let cosmos_msg = gov_contract.call(ExecuteMsg::EditBudgets {
+                budgets: vec![BudgetUpdate {
+                    category: String::from("contributors"),
+                    denom: String::from("debit"),
+                    amount: Uint128::new(1),
+                }],
+            }, vec![]).unwrap();
+            let err = app.execute(gov_contract.addr(), cosmos_msg).unwrap_err();
+            assert_eq!(
+                err.root_cause().to_string(),
+                "Insufficient debit treasury balance outside of escrowed deposits"
+            );
+
+            //Vote For proposal 1 & Remove proposal 2
+            for (proposal_id, vote) in [(1u64, ProposalVoteOption::For), (2u64, ProposalVoteOption::Remove)] {
+                for voter in [ADMIN, USER] {
+                    let msg = ExecuteMsg::CastVote {
+                        proposal_id,
+                        vote: vote.clone(),
+                        recipient: None,
+                    };
+                    let cosmos_msg = gov_contract.call(msg, vec![]).unwrap();
+                    app.execute(Addr::unchecked(voter), cosmos_msg).unwrap();
+                }
+                //Align to pass Quorum
+                for voter in ["alignment2.0", "who"] {
+                    let msg = ExecuteMsg::CastVote {
+                        proposal_id,
+                        vote: ProposalVoteOption::Align,
+                        recipient: None,
+                    };
+                    let cosmos_msg = gov_contract.call(msg, vec![]).unwrap();
+                    app.execute(Addr::unchecked(voter), cosmos_msg).unwrap();
+                }
+            }
+
+            // Skip voting period
+            app.update_block(|bi| {
+                bi.height += 7 * PROPOSAL_VOTING_PERIOD + 1;
+                bi.time = bi.time.plus_seconds(6 * (7* PROPOSAL_VOTING_PERIOD + 1));
+            });
+
+            for proposal_id in [1u64, 2u64] {
+                let msg = ExecuteMsg::EndProposal { proposal_id };
+                let cosmos_msg = gov_contract.call(msg, vec![]).unwrap();
+                app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+            }
+
+            //Proposal 1 reached quorum & was refunded
+            let deposit: Option<ProposalDeposit> = app
+                .wrap()
+                .query_wasm_smart(
+                    gov_contract.addr(),
+                    &QueryMsg::ProposalDeposit { proposal_id: 1 },
+                )
+                .unwrap();
+            assert_eq!(deposit.unwrap().status, DepositStatus::Refunded);
+            assert_eq!(
+                app.wrap().query_balance("recipient", "debit").unwrap().amount,
+                Uint128::new(100)
+            );
+
+            //Proposal 2 was removed & its deposit burned
+            let deposit: Option<ProposalDeposit> = app
+                .wrap()
+                .query_wasm_smart(
+                    gov_contract.addr(),
+                    &QueryMsg::ProposalDeposit { proposal_id: 2 },
+                )
+                .unwrap();
+            assert_eq!(deposit.unwrap().status, DepositStatus::Burned);
+            assert_eq!(
+                app.wrap().query_balance(gov_contract.addr(), "debit").unwrap().amount,
+                Uint128::zero()
+            );
+
+            //Send removed deposits to stakers
+            let msg = ExecuteMsg::UpdateConfig(UpdateConfig {
+                tema_denom: None,
+                staking_contract: None,
+                vesting_contract_addr: None,
+                vesting_voting_power_multiplier: None,
+                minimum_total_stake: None,
+                proposal_voting_period: None,
+                expedited_proposal_voting_period: None,
+                proposal_effective_delay: None,
+                proposal_expiration_period: None,
+                proposal_required_stake: None,
+                proposal_required_quorum: None,
+                proposal_required_threshold: None,
+                whitelist_add: None,
+                whitelist_remove: None,
+                quadratic_voting: None,
+                guardian: None,
+                timelock_delay: None,
+                timelock_target_delays: None,
+                proposal_deposit: None,
+                removed_deposit_to_stakers: Some(true),
+            });
+            let cosmos_msg = gov_contract.call(msg, vec![]).unwrap();
+            app.execute(gov_contract.addr(), cosmos_msg).unwrap();
+
+            //Submit & Remove proposal 3
+            let cosmos_msg = gov_contract.call(ExecuteMsg::SubmitProposal {
+                title: "Test title!".to_string(),
+                description: "Test description!".to_string(),
+                link: None,
+                messages: None,
+                options: None,
+                recipient: Some(String::from("recipient")),
+                expedited: false,
+            }, vec![coin(100, "debit")]).unwrap();
+            app.execute(Addr::unchecked("recipient"), cosmos_msg).unwrap();
+            for voter in [ADMIN, USER] {
+                let msg = ExecuteMsg::CastVote {
+                    proposal_id: 3u64,
+                    vote: ProposalVoteOption::Remove,
+                    recipient: None,
+                };
+                let cosmos_msg = gov_contract.call(msg, vec![]).unwrap();
+                app.execute(Addr::unchecked(voter), cosmos_msg).unwrap();
+            }
+            for voter in ["alignment2.0", "who"] {
+                let msg = ExecuteMsg::CastVote {
+                    proposal_id: 3u64,
+                    vote: ProposalVoteOption::Align,
+                    recipient: None,
+                };
+                let cosmos_msg = gov_contract.call(msg, vec![]).unwrap();
+                app.execute(Addr::unchecked(voter), cosmos_msg).unwrap();
+            }
+            app.update_block(|bi| {
+                bi.height += 7 * PROPOSAL_VOTING_PERIOD + 1;
+                bi.time = bi.time.plus_seconds(6 * (7* PROPOSAL_VOTING_PERIOD + 1));
+            });
+
+            //The mock staking contract rejects DepositFee so the deposit is burned instead of blocking the end
+            let cosmos_msg = gov_contract.call(ExecuteMsg::EndProposal { proposal_id: 3 }, vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            let deposit: Option<ProposalDeposit> = app
+                .wrap()
+                .query_wasm_smart(
+                    gov_contract.addr(),
+                    &QueryMsg::ProposalDeposit { proposal_id: 3 },
+                )
+                .unwrap();
+            assert_eq!(deposit.unwrap().status, DepositStatus::Burned);
+            assert_eq!(
+                app.wrap().query_balance(gov_contract.addr(), "debit").unwrap().amount,
+                Uint128::zero()
+            );
+        }
     }
 }
@@ -8,3 +8,4 @@ pub mod error;
 pub mod helpers;
 pub mod integration_tests;
 pub mod state;
+pub mod treasury;
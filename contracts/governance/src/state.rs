@@ -1,6 +1,6 @@
-use cosmwasm_std::Uint64;
+use cosmwasm_std::{Addr, Binary, Uint128, Uint64};
 use cw_storage_plus::{Item, Map};
-use membrane::governance::{Config, Proposal};
+use membrane::governance::{Budget, Config, FieldGuardrail, HealthCheck, OldProposal, PaymentStream, Proposal, ProposalDeposit, QueuedProposal, Vote};
 
 /// ## Description
 /// Stores the config for the Governance contract
@@ -17,4 +17,73 @@ pub const PROPOSALS: Map<String, Proposal> = Map::new("proposals");
 
 /// ## Description
 /// This is a map that contains information about pending proposals
-pub const PENDING_PROPOSALS: Map<String, Proposal> = Map::new("pending_proposals");
\ No newline at end of file
+pub const PENDING_PROPOSALS: Map<String, Proposal> = Map::new("pending_proposals");
+
+/// ## Description
+/// Proposals with inline voters, read once to migrate to VOTES
+pub const OLD_PROPOSALS: Map<String, OldProposal> = Map::new("proposals");
+pub const OLD_PENDING_PROPOSALS: Map<String, OldProposal> = Map::new("pending_proposals");
+
+/// ## Description
+/// Votes on proposals, tallied on the proposal
+/// <(Proposal #, Voter), Vote>
+pub const VOTES: Map<(u64, Addr), Vote> = Map::new("votes");
+
+/// ## Description
+/// Deposits escrowed at proposal submission
+/// <Proposal #, ProposalDeposit>
+pub const DEPOSITS: Map<u64, ProposalDeposit> = Map::new("proposal_deposits");
+
+/// ## Description
+/// Total of escrowed proposal deposits, which aren't available to the treasury
+/// <Denom, Amount>
+pub const ESCROWED_DEPOSITS: Map<String, Uint128> = Map::new("escrowed_deposits");
+
+/// ## Description
+/// Proposal whose deposit is being sent to stakers, burned instead if the DepositFee msg fails.
+/// Only exists within the settling transaction.
+pub const STAKER_DEPOSIT: Item<u64> = Item::new("staker_deposit");
+
+/// ## Description
+/// Delegated power reclaimed from each delegate by delegators who voted directly
+/// <(Proposal #, Delegate), Reclaimed power>
+pub const DELEGATE_OVERRIDES: Map<(u64, Addr), Uint128> = Map::new("delegate_overrides");
+
+/// ## Description
+/// Post-proposal health checks run by CheckMessages
+/// <Check name, HealthCheck>
+pub const HEALTH_CHECKS: Map<String, HealthCheck> = Map::new("health_checks");
+
+/// ## Description
+/// Health checks of the CheckMessages simulation in progress & their responses from before the messages.
+/// Only exists within the simulated transaction.
+pub const PENDING_HEALTH_CHECKS: Item<Vec<(HealthCheck, Option<Binary>)>> = Item::new("pending_health_checks");
+
+/// ## Description
+/// Passed proposals waiting in the timelock
+/// <Proposal #, QueuedProposal>
+pub const TIMELOCK_QUEUE: Map<u64, QueuedProposal> = Map::new("timelock_queue");
+
+/// ## Description
+/// Minimum timelock delays for proposal messages to a target contract, in seconds
+/// <(Target, Execute msg name or "" for all msgs), Delay>
+pub const TIMELOCK_DELAYS: Map<(Addr, String), u64> = Map::new("timelock_delays");
+
+/// ## Description
+/// Parameter guardrails for proposal messages to a target contract
+/// <(Target, Execute msg name), Field guardrails>
+pub const GUARDRAILS: Map<(Addr, String), Vec<FieldGuardrail>> = Map::new("guardrails");
+
+/// ## Description
+/// Treasury budgets
+/// <(Category, Denom), Budget>
+pub const BUDGETS: Map<(String, String), Budget> = Map::new("budgets");
+
+/// ## Description
+/// Active payment streams
+/// <Stream #, PaymentStream>
+pub const STREAMS: Map<u64, PaymentStream> = Map::new("streams");
+
+/// ## Description
+/// Stores the last stream id
+pub const STREAM_COUNT: Item<u64> = Item::new("stream_count");
@@ -0,0 +1,271 @@
+//Treasury budgets & contributor payment streams, funded by protocol revenue held by this contract
+
+use std::collections::BTreeSet;
+
+use cosmwasm_std::{
+    attr, coins, BankMsg, Deps, DepsMut, Env, MessageInfo, Order, Response, StdResult, Uint128,
+};
+use cw_storage_plus::Bound;
+
+use membrane::governance::{Budget, BudgetUpdate, PaymentStream, StreamResponse};
+
+use crate::error::ContractError;
+use crate::state::{BUDGETS, ESCROWED_DEPOSITS, STREAMS, STREAM_COUNT};
+
+// Default pagination constants
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+/// Set treasury budgets
+pub fn edit_budgets(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    budgets: Vec<BudgetUpdate>,
+) -> Result<Response, ContractError> {
+    // Only the Governance contract is allowed to edit its budgets
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    for update in budgets.clone() {
+        let key = (update.category.clone(), update.denom.clone());
+        let allocated = match BUDGETS.may_load(deps.storage, key.clone())? {
+            Some(budget) => budget.allocated,
+            None => Uint128::zero(),
+        };
+
+        BUDGETS.save(deps.storage, key, &Budget {
+            category: update.category,
+            denom: update.denom,
+            remaining: update.amount,
+            allocated,
+        })?;
+    }
+
+    //Budgets & unclaimed streams can't exceed the treasury balance outside of escrowed deposits
+    let denoms = budgets.iter().map(|update| update.denom.clone()).collect::<BTreeSet<String>>();
+    for denom in denoms {
+        let budgeted: Uint128 = BUDGETS
+            .range(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<((String, String), Budget)>>>()?
+            .into_iter()
+            .filter(|(_, budget)| budget.denom == denom)
+            .map(|(_, budget)| budget.remaining)
+            .sum();
+        let streamed: Uint128 = STREAMS
+            .range(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<(u64, PaymentStream)>>>()?
+            .into_iter()
+            .filter(|(_, stream)| stream.denom == denom)
+            .map(|(_, stream)| stream.amount.saturating_sub(stream.claimed))
+            .sum();
+
+        if budgeted + streamed > get_available_balance(deps.as_ref(), &env, denom.clone())? {
+            return Err(ContractError::InsufficientTreasury { denom });
+        }
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "edit_budgets"),
+        attr("budgets", format!("{:?}", budgets.into_iter().map(|budget| format!("{}:{}{}", budget.category, budget.amount, budget.denom)).collect::<Vec<String>>())),
+    ]))
+}
+
+/// Stream a payment from a treasury budget
+#[allow(clippy::too_many_arguments)]
+pub fn create_stream(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    category: String,
+    recipient: String,
+    denom: String,
+    amount: Uint128,
+    start_time: u64,
+    cliff_time: u64,
+    end_time: u64,
+) -> Result<Response, ContractError> {
+    // Only the Governance contract is allowed to create streams (through a successful proposal)
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let recipient = deps.api.addr_validate(&recipient)?;
+
+    //Validate schedule
+    if amount.is_zero() || start_time >= end_time || cliff_time < start_time || cliff_time > end_time || end_time <= env.block.time.seconds() {
+        return Err(ContractError::InvalidStream {});
+    }
+
+    //Allocate from the budget
+    let mut budget = BUDGETS
+        .may_load(deps.storage, (category.clone(), denom.clone()))?
+        .ok_or_else(|| ContractError::InsufficientBudget { category: category.clone(), denom: denom.clone() })?;
+    if budget.remaining < amount {
+        return Err(ContractError::InsufficientBudget { category, denom });
+    }
+    budget.remaining -= amount;
+    budget.allocated += amount;
+    BUDGETS.save(deps.storage, (category.clone(), denom.clone()), &budget)?;
+
+    let stream_id = STREAM_COUNT.may_load(deps.storage)?.unwrap_or_default() + 1;
+    STREAM_COUNT.save(deps.storage, &stream_id)?;
+
+    STREAMS.save(deps.storage, stream_id, &PaymentStream {
+        stream_id,
+        category: category.clone(),
+        recipient: recipient.clone(),
+        denom: denom.clone(),
+        amount,
+        claimed: Uint128::zero(),
+        start_time,
+        cliff_time,
+        end_time,
+    })?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "create_stream"),
+        attr("stream_id", stream_id.to_string()),
+        attr("category", category),
+        attr("recipient", recipient),
+        attr("amount", format!("{}{}", amount, denom)),
+    ]))
+}
+
+/// Cancel a stream, returning its unvested amount to its budget
+pub fn cancel_stream(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    stream_id: u64,
+) -> Result<Response, ContractError> {
+    // Only the Governance contract is allowed to cancel streams (through a successful proposal)
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut stream = STREAMS.load(deps.storage, stream_id)?;
+    let current_time = env.block.time.seconds();
+
+    //End the stream at its vested amount
+    let vested = stream.vested(current_time);
+    let returned = stream.amount - vested;
+    stream.amount = vested;
+    stream.end_time = stream.end_time.min(current_time);
+    stream.cliff_time = stream.cliff_time.min(stream.end_time);
+    stream.start_time = stream.start_time.min(stream.end_time);
+
+    //Return the unvested amount to the budget
+    BUDGETS.update(deps.storage, (stream.category.clone(), stream.denom.clone()), |budget| -> StdResult<Budget> {
+        let mut budget = budget.unwrap_or(Budget {
+            category: stream.category.clone(),
+            denom: stream.denom.clone(),
+            remaining: Uint128::zero(),
+            allocated: Uint128::zero(),
+        });
+        budget.remaining += returned;
+        budget.allocated = budget.allocated.saturating_sub(returned);
+        Ok(budget)
+    })?;
+
+    //Remove the stream if nothing is left to claim
+    if stream.claimed >= stream.amount {
+        STREAMS.remove(deps.storage, stream_id);
+    } else {
+        STREAMS.save(deps.storage, stream_id, &stream)?;
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "cancel_stream"),
+        attr("stream_id", stream_id.to_string()),
+        attr("returned", format!("{}{}", returned, stream.denom)),
+    ]))
+}
+
+/// Claim the vested amount of a stream
+pub fn claim_stream(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    stream_id: u64,
+) -> Result<Response, ContractError> {
+    let mut stream = STREAMS.load(deps.storage, stream_id)?;
+    if info.sender != stream.recipient {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let claimable = stream.claimable(env.block.time.seconds());
+    if claimable.is_zero() {
+        return Err(ContractError::NothingToClaim {});
+    }
+    //Escrowed deposits can't be paid out
+    if claimable > get_available_balance(deps.as_ref(), &env, stream.denom.clone())? {
+        return Err(ContractError::InsufficientTreasury { denom: stream.denom });
+    }
+    stream.claimed += claimable;
+
+    //Remove the stream once fully claimed
+    if stream.claimed >= stream.amount {
+        STREAMS.remove(deps.storage, stream_id);
+    } else {
+        STREAMS.save(deps.storage, stream_id, &stream)?;
+    }
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: stream.recipient.to_string(),
+            amount: coins(claimable.u128(), stream.denom.clone()),
+        })
+        .add_attributes(vec![
+            attr("action", "claim_stream"),
+            attr("stream_id", stream_id.to_string()),
+            attr("claimed", format!("{}{}", claimable, stream.denom)),
+        ]))
+}
+
+/// Return the treasury balance of a denom that isn't escrowed for proposal deposits
+pub fn get_available_balance(deps: Deps, env: &Env, denom: String) -> StdResult<Uint128> {
+    let balance = deps.querier.query_balance(env.contract.address.to_string(), denom.clone())?.amount;
+    let escrowed = ESCROWED_DEPOSITS.may_load(deps.storage, denom)?.unwrap_or_default();
+
+    Ok(balance.saturating_sub(escrowed))
+}
+
+/// Return the treasury budgets
+pub fn query_budgets(deps: Deps) -> StdResult<Vec<Budget>> {
+    BUDGETS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, budget)| budget))
+        .collect()
+}
+
+/// Return active streams & their claimable amounts
+pub fn query_streams(
+    deps: Deps,
+    env: Env,
+    recipient: Option<String>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<StreamResponse>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let recipient = recipient.map(|recipient| deps.api.addr_validate(&recipient)).transpose()?;
+    let current_time = env.block.time.seconds();
+
+    STREAMS
+        .range(deps.storage, start, None, Order::Ascending)
+        .filter(|item| match (item, &recipient) {
+            (Ok((_, stream)), Some(recipient)) => stream.recipient == *recipient,
+            _ => true,
+        })
+        .take(limit)
+        .map(|item| {
+            let (_, stream) = item?;
+            Ok(StreamResponse {
+                claimable: stream.claimable(current_time),
+                stream,
+            })
+        })
+        .collect()
+}
@@ -495,7 +495,8 @@ fn claim() {
                 contract_addr: String::from(""),
                 funds: vec![coin(3554, "")],
                 msg: to_binary(&StakingExecuteMsg::Stake {
-                    user: Some(String::from("user1"))
+                    user: Some(String::from("user1")),
+                    lock_duration: None,
                 })
                 .unwrap()
             }))
@@ -532,7 +533,8 @@ fn claim() {
                 contract_addr: String::from(""),
                 funds: vec![coin(2152088471, "")],
                 msg: to_binary(&StakingExecuteMsg::Stake {
-                    user: Some(String::from("user1"))
+                    user: Some(String::from("user1")),
+                    lock_duration: None,
                 })
                 .unwrap()
             }))
@@ -596,7 +598,8 @@ fn claim() {
                 contract_addr: String::from(""),
                 funds: vec![coin(1041332297, "")],
                 msg: to_binary(&StakingExecuteMsg::Stake {
-                    user: Some(String::from("user1"))
+                    user: Some(String::from("user1")),
+                    lock_duration: None,
                 })
                 .unwrap()
             }))
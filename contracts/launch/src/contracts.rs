@@ -20,6 +20,7 @@ use membrane::liq_queue::ExecuteMsg as LQExecuteMsg;
 use membrane::liquidity_check::ExecuteMsg as LCExecuteMsg;
 use membrane::auction::{ExecuteMsg as DAExecuteMsg, UpdateConfig as AuctionUpdateConfig};
 use membrane::discount_vault::ExecuteMsg as DiscountVaultExecuteMsg;
+use membrane::pause::{assert_not_paused, execute_pause, PauseActions};
 
 use osmosis_std::types::cosmos::base::v1beta1::Coin;
 use osmosis_std::types::osmosis::gamm::poolmodels::balancer::v1beta1::MsgCreateBalancerPool;
@@ -35,6 +36,12 @@ use crate::reply::{handle_auction_reply, handle_cdp_reply, handle_create_denom_r
 const CONTRACT_NAME: &str = "launch";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+//Execute msgs handled by the pause guard
+const PAUSE_ACTIONS: PauseActions = PauseActions {
+    pausable: &["lock", "change_lock_duration", "withdraw", "claim", "launch", "update_config", "update_contract_configs"],
+    unpausable: &[],
+};
+
 //Reply ID
 pub const OSMOSIS_PROXY_REPLY_ID: u64 = 1;
 pub const ORACLE_REPLY_ID: u64 = 2;
@@ -151,6 +158,9 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
+    //Check the pause guard
+    assert_not_paused(deps.storage, &env, &msg, &PAUSE_ACTIONS)?;
+
     match msg {
         ExecuteMsg::Lock { lock_up_duration } => lock(deps, env, info, lock_up_duration),
         ExecuteMsg::ChangeLockDuration { ufury_amount, old_lock_up_duration, new_lock_up_duration } => change_lockup_duration(deps, env, info, ufury_amount, old_lock_up_duration, new_lock_up_duration),
@@ -159,6 +169,10 @@ pub fn execute(
         ExecuteMsg::Launch{ } => end_of_launch(deps, env),
         ExecuteMsg::UpdateConfig(update) => update_config(deps, info, update),
         ExecuteMsg::UpdateContractConfigs { new_governance_contract } => update_contract_configs(deps, info, new_governance_contract),
+        ExecuteMsg::Pause(pause_msg) => {
+            let config = CONFIG.load(deps.storage)?;
+            Ok(execute_pause(deps.storage, deps.api, &env, &info, info.sender == config.pre_launch_contributors, &PAUSE_ACTIONS, pause_msg)?)
+        }
     }
 }
 
@@ -474,7 +488,7 @@ fn claim(
 
         let stake_msg = CosmosMsg::Wasm(WasmMsg::Execute { 
             contract_addr: addrs.staking.to_string(), 
-            msg: to_binary(&StakingExecuteMsg::Stake { user: Some(info.clone().sender.to_string()) })?, 
+            msg: to_binary(&StakingExecuteMsg::Stake { user: Some(info.clone().sender.to_string()), lock_duration: None })?, 
             funds: vec![coin(amount_to_mint.into(), config.clone().tema_denom)] 
         });
 
@@ -732,7 +746,8 @@ fn update_contract_configs(
     //             osmosis_proxy_contract:  None,
     //             waiting_period:  None, 
     //             minimum_bid:  None, 
-    //             maximum_waiting_bids: None
+    //             maximum_waiting_bids: None,
+    //             dex_router: None,
     //         })?,
     //         funds: vec![],
     //     })
@@ -399,6 +399,7 @@ pub fn handle_gov_reply(deps: DepsMut, _env: Env, msg: Reply)-> StdResult<Respon
                             lp_pool_info: None,
                             decimals: 6,
                             pyth_price_feed_id: Some(String::from("b00b60f88b03a6a625a8d1c048c3f66653edf217439983d037e7222c4e612819")),
+                            external_oracle: None,
                         },
                     })?, 
                     funds: vec![],
@@ -417,6 +418,7 @@ pub fn handle_gov_reply(deps: DepsMut, _env: Env, msg: Reply)-> StdResult<Respon
                             lp_pool_info: None,
                             decimals: 6,
                             pyth_price_feed_id: Some(String::from("a06a7e17a81f8f33d23152fc69e0433244f239aa0635e7b621f03fe0e51245b0")),
+                            external_oracle: None,
                         },
                     })?, 
                     funds: vec![],
@@ -441,6 +443,7 @@ pub fn handle_gov_reply(deps: DepsMut, _env: Env, msg: Reply)-> StdResult<Respon
                             lp_pool_info: None,
                             decimals: 6,
                             pyth_price_feed_id: None, //We don't set a pyth price feed for axlUSDC bc its a non-IBC bridged asset
+                            external_oracle: None,
                         },
                     })?, 
                     funds: vec![],
@@ -916,7 +919,8 @@ pub fn handle_lq_reply(deps: DepsMut, _env: Env, msg: Reply)-> StdResult<Respons
                 osmosis_proxy_contract: None,
                 waiting_period: None, 
                 minimum_bid: None, 
-                maximum_waiting_bids: None 
+                maximum_waiting_bids: None,
+                dex_router: Some(config.clone().apollo_router.to_string()),
             };
             let msg = CosmosMsg::Wasm(WasmMsg::Execute { 
                 contract_addr: addrs.clone().liq_queue.to_string(), 
@@ -1255,6 +1259,13 @@ pub fn handle_auction_reply(deps: DepsMut, _env: Env, msg: Reply)-> StdResult<Re
                         max_commission_rate: None,
                         keep_raw_cdt: None,
                         vesting_rev_multiplier: None,
+                        participation_requirements: None,
+                        dex_router: Some(config.clone().apollo_router.to_string()),
+                        keeper_tip: None,
+                        instant_unstake_penalty: None,
+                        stability_pool: Some(addrs.clone().stability_pool.to_string()),
+                        oracle_contract: Some(addrs.clone().oracle.to_string()),
+                        max_slippage: None,
                     })?, 
                     funds: vec![],
                 }));
@@ -1426,6 +1437,7 @@ pub fn handle_balancer_reply(deps: DepsMut, env: Env, msg: Reply) -> StdResult<R
                                 ),
                                 decimals: 18,
                                 pyth_price_feed_id: None,
+                                external_oracle: None,
                             },
                         })?, 
                         funds: vec![],
@@ -1449,6 +1461,7 @@ pub fn handle_balancer_reply(deps: DepsMut, env: Env, msg: Reply) -> StdResult<R
                                 lp_pool_info: None,
                                 decimals: 6,
                                 pyth_price_feed_id: None,
+                                external_oracle: None,
                             },
                         })?, 
                         funds: vec![],
@@ -1573,6 +1586,7 @@ pub fn handle_balancer_reply(deps: DepsMut, env: Env, msg: Reply) -> StdResult<R
             //                 lp_pool_info: None,
             //                 decimals: 6,       
             //                 pyth_price_feed_id: None,                     
+            //                 external_oracle: None,
             //             },
             //         })?, 
             //         funds: vec![],
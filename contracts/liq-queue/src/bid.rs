@@ -1,24 +1,27 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::{
-    attr, to_binary, Addr, CosmosMsg, DepsMut, Env,
-    MessageInfo, Response, StdError, StdResult, Storage, Uint128, WasmMsg,
+    attr, to_binary, Addr, Attribute, CosmosMsg, Decimal, DepsMut, Env,
+    MessageInfo, Order, Reply, Response, StdError, StdResult, Storage, SubMsg, Uint128, WasmMsg,
 };
 use cosmwasm_storage::{Bucket, ReadonlyBucket};
+use cw_storage_plus::Bound;
 use membrane::math::{Decimal256, Uint256, U256};
 use membrane::osmosis_proxy::ExecuteMsg as OP_ExecuteMsg;
 use membrane::liq_queue::Config;
 use membrane::oracle::{PriceResponse256, PriceResponse};
 use membrane::types::{Asset, AssetInfo, Bid, BidInput, PremiumSlot, Queue};
-use membrane::helpers::{validate_position_owner, withdrawal_msg};
+use membrane::helpers::{router_native_to_native, validate_position_owner, withdrawal_msg};
 
 use crate::error::ContractError;
-use crate::state::{CONFIG, QUEUES};
+use crate::state::{LiquidationPrices, RecyclePropagation, BIDS, CONFIG, FALLBACK_BIDS, LIQUIDATION_PRICES, QUEUES, RECYCLE, USER_BIDS, WAITING_BIDS};
 
 const MAX_LIMIT: u32 = 32;
 
+pub const RECYCLE_REPLY_ID: u64 = 1u64;
+
 static PREFIX_EPOCH_SCALE_SUM: &[u8] = b"epoch_scale_sum";
 
-/// Create Bid and add to the corresponding Slot
+/// Create Bid(s) and add to the corresponding Slot(s)
 pub fn submit_bid(
     deps: DepsMut,
     info: MessageInfo,
@@ -42,137 +45,197 @@ pub fn submit_bid(
 
     let bid_asset: Asset = assert_bid_asset_from_sent_funds(queue.clone().bid_asset.info, &info, config.minimum_bid)?;
 
-    let mut bid: Bid;
-    //Add bid to selected premium
-    let edited_slot = match queue
-        .clone()
-        .slots
-        .into_iter()
-        //Hard coded 1% per slot
-        .find(|slot| {
-            slot.liq_premium
-                == Decimal256::percent(1)
-                    * Decimal256::from_uint256(Uint256::from(bid_input.liq_premium as u128))
-        }) {
-        Some(mut slot) => {
-            bid = Bid {
-                user: valid_owner_addr.clone(),
-                id: queue.current_bid_id,
-                amount: Uint256::from(bid_asset.amount.u128()),
-                liq_premium: bid_input.liq_premium,
-                product_snapshot: Decimal256::one(),
-                sum_snapshot: Decimal256::zero(),
-                pending_liquidated_collateral: Uint256::zero(),
-                wait_end: None,
-                epoch_snapshot: Uint128::zero(),
-                scale_snapshot: Uint128::zero(),
-            };
-            
-            //Increment bid_id
-            queue.current_bid_id += Uint128::new(1u128);
-
-            //Add to total_queue_amount and total_slot_amount if below bid_threshold
-            if slot.total_bid_amount <= queue.bid_threshold {
-                //If the whole bid + the current bid total is less than the bid threshold + minimum_bid, activate the whole bid
-                //This ensures the amount sent to wait is at least the minimum
-                if slot.total_bid_amount + bid.amount < queue.bid_threshold + config.minimum_bid.into(){
-                    //Add active bid amounts to the queue and slot
-                    queue.bid_asset.amount += bid_asset.amount;
-                    slot.total_bid_amount += bid.amount;
-
-                    process_bid_activation(&mut bid, &mut slot);
-                
-                    //Add bid to active bids
-                    slot.bids.push(bid.clone());    
-
-                    //Set the (remaining) bid to 0 which will skip the waiting queue logic
-                    bid.amount = Uint256::zero();
-
-                    attrs.extend(vec![
-                        attr("bid_id", bid.id.to_string()),
-                        attr("bid", bid_asset.amount.to_string()),
-                    ]);
-
-                } else { //Activate the amount within the bid threshold and send the rest to the waiting queue
-                    let amount_sent_to_wait = slot.total_bid_amount + bid.amount - queue.bid_threshold;
-                    
-                    //Create clone for the active bid
-                    let mut bid_clone = bid.clone();
-                                       
-                    //Set the clone to the remaining active amount
-                    bid_clone.amount = bid.amount - amount_sent_to_wait;
-
-                    //Update bid_id to reflect the clone and increment
-                    bid.id = queue.current_bid_id;
-                    queue.current_bid_id += Uint128::new(1u128);                        
-
-                    //Add active bid amounts to the queue and slot
-                    queue.bid_asset.amount += bid_asset.amount - Uint128::new(u128::from(amount_sent_to_wait));
-                    slot.total_bid_amount += bid_clone.amount;
-
-                    process_bid_activation(&mut bid_clone, &mut slot);
-
-                    attrs.push(attr("bid_id", bid_clone.id.to_string()));
-                    attrs.push(attr("bid", (bid_asset.amount- Uint128::new(u128::from(amount_sent_to_wait))).to_string()));
-                
-                    //Add bid_clone to active bids
-                    slot.bids.push(bid_clone);    
-
-                    //Set the (remaining) bid to the amount to send to the waiting queue
-                    bid.amount = amount_sent_to_wait;
-                }  
-            } 
-            
-            //Set the (remaining) bid to waiting 
-            if !bid.amount.is_zero() {
-                //Set wait time
-                // calculate wait_end from current time
-                bid.wait_end = Some(env.block.time.plus_seconds(config.waiting_period).seconds());
-
-                //Add bid to waiting bids           
-                slot.waiting_bids.push(bid.clone());
-
-                //Enforce maximum number of waiting bids
-                if slot.waiting_bids.len() > config.maximum_waiting_bids as usize {
-                    return Err(ContractError::TooManyWaitingBids {
-                        max_waiting_bids: config.maximum_waiting_bids,
-                    });
-                }
+    //Split the bid across its premium(s)
+    for (premium, amount) in split_bid_amount(&bid_input, bid_asset.amount)? {
+        attrs.extend(place_bid(
+            deps.storage,
+            &env,
+            &config,
+            &mut queue,
+            bid_input.bid_for.to_string(),
+            valid_owner_addr.clone(),
+            premium,
+            amount,
+            bid_input.auto_recycle,
+            bid_input.sp_fallback,
+            bid_input.max_slippage,
+        )?);
+    }
 
-                attrs.extend(vec![
-                    attr("bid_id", bid.id.to_string()),
-                    attr("bid", bid.amount.to_string()),
-                ]);
-            }
+    //Save queue to state
+    QUEUES.save(deps.storage, bid_input.bid_for.to_string(), &queue)?;
+
+    //Response build
+    let response = Response::new();    
+
+    Ok(response.add_attributes(attrs))
+}
 
-            slot
+/// Split a bid amount into (premium, amount) pairs using the BidInput's premium range
+fn split_bid_amount(bid_input: &BidInput, amount: Uint128) -> StdResult<Vec<(u8, Uint128)>> {
+    let range = match bid_input.premium_range.clone() {
+        Some(range) => range,
+        None => return Ok(vec![(bid_input.liq_premium, amount)]),
+    };
+
+    let premiums: Vec<u8> = (range.min_premium..=range.max_premium).collect();
+    let weights = match range.weights {
+        Some(weights) => weights,
+        None => vec![Decimal::from_ratio(1u128, premiums.len() as u128); premiums.len()],
+    };
+
+    let mut splits = vec![];
+    let mut allocated = Uint128::zero();
+    for (i, premium) in premiums.clone().into_iter().enumerate() {
+        //Last premium gets the remainder to account for rounding
+        let split = if i == premiums.len() - 1 {
+            amount - allocated
+        } else {
+            weights[i] * amount
+        };
+        allocated += split;
+
+        //Skip empty slots
+        if !split.is_zero() {
+            splits.push((premium, split));
         }
-        None => return Err(ContractError::InvalidPremium {}), //Shouldn't be reached due to validate_bid_input() above
+    }
+
+    Ok(splits)
+}
+
+/// Add a bid to a Queue's premium slot.
+/// Activates the amount within the bid threshold & sends the rest to the waiting queue.
+#[allow(clippy::too_many_arguments)]
+pub fn place_bid(
+    storage: &mut dyn Storage,
+    env: &Env,
+    config: &Config,
+    queue: &mut Queue,
+    bid_for: String,
+    bid_owner: Addr,
+    liq_premium: u8,
+    amount: Uint128,
+    auto_recycle: bool,
+    sp_fallback: bool,
+    max_slippage: Decimal,
+) -> Result<Vec<Attribute>, ContractError> {
+    let mut attrs = vec![];
+
+    //Each (split) bid must be at least the minimum bid
+    if amount < config.minimum_bid {
+        return Err(ContractError::BidTooSmall { minimum: config.minimum_bid });
+    }
+
+    //Add bid to selected premium
+    let mut slot: PremiumSlot = read_premium_slot(queue.clone(), liq_premium)?;
+
+    //Enforce maximum number of waiting bids before any state is saved
+    let amount_to_wait = if slot.total_bid_amount > queue.bid_threshold {
+        Uint256::from(amount.u128())
+    } else if slot.total_bid_amount + Uint256::from(amount.u128()) < queue.bid_threshold + config.minimum_bid.into() {
+        Uint256::zero()
+    } else {
+        slot.total_bid_amount + Uint256::from(amount.u128()) - queue.bid_threshold
     };
+    if !amount_to_wait.is_zero() && slot.waiting_bid_count >= config.maximum_waiting_bids {
+        return Err(ContractError::TooManyWaitingBids {
+            max_waiting_bids: config.maximum_waiting_bids,
+        });
+    }
 
-    //Filter for unedited slots
-    let mut new_slots: Vec<PremiumSlot> = queue
-        .slots
-        .into_iter()
-        .filter(|slot| {
-            slot.liq_premium
-                != Decimal256::percent(1)
-                    * Decimal256::from_uint256(Uint256::from(bid_input.liq_premium as u128))
-        }) //Hard coded 1% per slot
-        .collect::<Vec<PremiumSlot>>();
-    //Add edited_slot
-    new_slots.push(edited_slot);
+    let mut bid = Bid {
+        user: bid_owner,
+        id: queue.current_bid_id,
+        amount: Uint256::from(amount.u128()),
+        liq_premium,
+        product_snapshot: Decimal256::one(),
+        sum_snapshot: Decimal256::zero(),
+        pending_liquidated_collateral: Uint256::zero(),
+        wait_end: None,
+        epoch_snapshot: Uint128::zero(),
+        scale_snapshot: Uint128::zero(),
+        auto_recycle,
+        sp_fallback,
+        max_slippage,
+    };
+    
+    //Increment bid_id
+    queue.current_bid_id += Uint128::new(1u128);
+
+    //Add to total_queue_amount and total_slot_amount if below bid_threshold
+    if slot.total_bid_amount <= queue.bid_threshold {
+        //If the whole bid + the current bid total is less than the bid threshold + minimum_bid, activate the whole bid
+        //This ensures the amount sent to wait is at least the minimum
+        if slot.total_bid_amount + bid.amount < queue.bid_threshold + config.minimum_bid.into(){
+            //Add active bid amounts to the queue and slot
+            queue.bid_asset.amount += amount;
+            slot.total_bid_amount += bid.amount;
+
+            process_bid_activation(&mut bid, &mut slot);
+        
+            //Add bid to active bids
+            store_bid(storage, bid_for.clone(), bid.clone())?;
+
+            //Set the (remaining) bid to 0 which will skip the waiting queue logic
+            bid.amount = Uint256::zero();
+
+            attrs.extend(vec![
+                attr("bid_id", bid.id.to_string()),
+                attr("bid", amount.to_string()),
+            ]);
+
+        } else { //Activate the amount within the bid threshold and send the rest to the waiting queue
+            let amount_sent_to_wait = slot.total_bid_amount + bid.amount - queue.bid_threshold;
+            
+            //Create clone for the active bid
+            let mut bid_clone = bid.clone();
+                               
+            //Set the clone to the remaining active amount
+            bid_clone.amount = bid.amount - amount_sent_to_wait;
+
+            //Update bid_id to reflect the clone and increment
+            bid.id = queue.current_bid_id;
+            queue.current_bid_id += Uint128::new(1u128);                        
+
+            //Add active bid amounts to the queue and slot
+            queue.bid_asset.amount += amount - Uint128::new(u128::from(amount_sent_to_wait));
+            slot.total_bid_amount += bid_clone.amount;
+
+            process_bid_activation(&mut bid_clone, &mut slot);
+
+            attrs.push(attr("bid_id", bid_clone.id.to_string()));
+            attrs.push(attr("bid", (amount - Uint128::new(u128::from(amount_sent_to_wait))).to_string()));
+        
+            //Add bid_clone to active bids
+            store_bid(storage, bid_for.clone(), bid_clone)?;
+
+            //Set the (remaining) bid to the amount to send to the waiting queue
+            bid.amount = amount_sent_to_wait;
+        }  
+    } 
+    
+    //Set the (remaining) bid to waiting 
+    if !bid.amount.is_zero() {
+        //Set wait time
+        // calculate wait_end from current time
+        bid.wait_end = Some(env.block.time.plus_seconds(config.waiting_period).seconds());
 
-    //Assign new slots to queue
-    queue.slots = new_slots;
+        slot.waiting_bid_count += 1;
 
-    //Save queue to state
-    QUEUES.save(deps.storage, bid_input.bid_for.to_string(), &queue)?;
+        //Add bid to waiting bids           
+        store_bid(storage, bid_for, bid.clone())?;
 
-    //Response build
-    let response = Response::new();    
+        attrs.extend(vec![
+            attr("bid_id", bid.id.to_string()),
+            attr("bid", bid.amount.to_string()),
+        ]);
+    }
 
-    Ok(response.add_attributes(attrs))
+    //Save edited slot
+    store_premium_slot(queue, slot)?;
+
+    Ok(attrs)
 }
 
 /// Activate bid
@@ -236,12 +299,8 @@ pub fn retract_bid(
 ) -> Result<Response, ContractError> {
     let config: Config = CONFIG.load(deps.storage)?;
     let mut queue = QUEUES.load(deps.storage, bid_for.to_string())?;
-    let mut bid = read_bid(deps.storage, bid_id, queue.clone())?;
-
     //Only owner can withdraw
-    if bid.clone().user != info.sender {
-        return Err(ContractError::Unauthorized {});
-    }
+    let mut bid = read_user_bid(deps.storage, bid_for.to_string(), info.clone().sender, bid_id)?;
     
     let mut slot: PremiumSlot =
         read_premium_slot(queue.clone(), bid.clone().liq_premium)?;
@@ -250,12 +309,15 @@ pub fn retract_bid(
         // waiting bid amount can be withdrawn without restriction
         let waiting_withdraw_amount = assert_withdraw_amount(amount, bid.amount, Uint256::from(config.minimum_bid))?;
         if waiting_withdraw_amount == bid.amount {
-            remove_bid(bid.clone(), &mut queue)?;
+            remove_bid(deps.storage, bid_for.to_string(), bid.clone());
+            slot.waiting_bid_count = slot.waiting_bid_count.saturating_sub(1);
         } else {
             bid.amount = bid.amount - waiting_withdraw_amount;
-            store_bid(deps.storage, &mut queue, bid.clone())?;
+            store_bid(deps.storage, bid_for.to_string(), bid.clone())?;
         }
 
+        store_premium_slot(&mut queue, slot)?;
+
         waiting_withdraw_amount
     } else {
         // calculate spent and reward until this moment
@@ -273,19 +335,16 @@ pub fn retract_bid(
         slot.residue_collateral += residue_collateral;
         slot.residue_bid += residue_bid;
 
-        //Store slot so store_bid() is using the correct slot
-        store_premium_slot(&mut queue, slot.clone())?;
-
         //Check requested amount
         let withdraw_amount = assert_withdraw_amount(amount, withdrawable_amount, Uint256::from(config.minimum_bid))?;
 
         //remove or update bid
         if withdraw_amount == bid.amount && bid.pending_liquidated_collateral.is_zero() {
-            remove_bid(bid.clone(), &mut queue)?;
+            remove_bid(deps.storage, bid_for.to_string(), bid.clone());
         } else {
             store_bid(
                 deps.storage,
-                &mut queue,
+                bid_for.to_string(),
                 Bid {
                     amount: withdrawable_amount - withdraw_amount,
                     product_snapshot: slot.product_snapshot,
@@ -296,9 +355,6 @@ pub fn retract_bid(
             )?;
         }
 
-        //Reload slot so that store_slot() below doesn't override the above store_bid()
-        let mut slot: PremiumSlot =
-            read_premium_slot(queue.clone(), bid.clone().liq_premium)?;
         slot.total_bid_amount = slot.total_bid_amount - withdraw_amount;
 
         //User's share
@@ -372,6 +428,12 @@ pub fn execute_liquidation(
 
     let price: PriceResponse256 = collateral_price.to_decimal256()?;
 
+    //Save prices to set the minimum receive of recycle swaps
+    LIQUIDATION_PRICES.save(deps.storage, bid_for.to_string(), &LiquidationPrices {
+        collateral_price: collateral_price.clone(),
+        credit_price: credit_price.clone(),
+    })?;
+
     let mut remaining_collateral_to_liquidate = collateral_amount;
     let mut repay_amount = Uint256::zero();
     let mut filled: bool = false;
@@ -385,7 +447,7 @@ pub fn execute_liquidation(
                 Err(_) => continue,
             };
         //Activates necessary bids for a new total
-        slot = set_slot_total(deps.storage, slot, env.clone(), &mut queue, config.clone())?;
+        slot = set_slot_total(deps.storage, slot, env.clone(), &mut queue, config.clone(), bid_for.to_string(), premium as u8)?;
 
         if slot.total_bid_amount.is_zero() {
             continue;
@@ -484,7 +546,9 @@ pub fn claim_liquidations(
     bid_for: AssetInfo,
     bid_ids: Option<Vec<Uint128>>,
 ) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
     let mut queue = QUEUES.load(deps.storage, bid_for.to_string())?;
+    let liquidation_prices = LIQUIDATION_PRICES.may_load(deps.storage, bid_for.to_string())?;
 
     
     let bids: Vec<Bid> = if let Some(bid_ids) = bid_ids {
@@ -500,12 +564,12 @@ pub fn claim_liquidations(
         //Read bids
         bid_ids
             .into_iter()
-            .map(|id| read_bid(deps.storage, id, queue.clone()))
+            .map(|id| read_user_bid(deps.storage, bid_for.to_string(), info.clone().sender, id))
             .collect::<Result<Vec<Bid>, StdError>>()?
     } else {
         read_bids_by_user(
             deps.storage,
-            queue.clone(),
+            bid_for.to_string(),
             info.clone().sender,
             None,
             None,
@@ -513,13 +577,13 @@ pub fn claim_liquidations(
     };
 
     let mut claim_amount = Uint256::zero();
+    //Collateral to swap & re-bid, per premium
+    let mut recycle_premiums: Vec<(u8, bool, Decimal, Uint256)> = vec![];
+    let mut recycle_amount = Uint256::zero();
+    let mut minimum_receive = Uint128::zero();
 
     for bid in bids.into_iter() {
-        if bid.user != info.clone().sender {
-            return Err(ContractError::Unauthorized {});
-        }
-
-        if bid.wait_end.is_some() && bid.wait_end.unwrap() > env.block.time.seconds() {
+        if bid.wait_end.is_some() {
             // bid not activated
             continue;
         }
@@ -542,20 +606,36 @@ pub fn claim_liquidations(
         slot.residue_bid += residue_bid;
 
         // get claimable amount
-        claim_amount += bid.pending_liquidated_collateral
+        let bid_claim = bid.pending_liquidated_collateral
             + liquidated_collateral
             + claim_col_residue(&mut slot);
 
+        //Recycle if the bid opted in & there is a router to swap with.
+        //The swap's minimum receive is the collateral's value at the last liquidation prices, less the bid's max slippage.
+        if bid.auto_recycle && config.dex_router.is_some() && liquidation_prices.is_some() && !bid_claim.is_zero() {
+            match recycle_premiums
+                .iter_mut()
+                .find(|(premium, sp_fallback, max_slippage, _)| *premium == bid.liq_premium && *sp_fallback == bid.sp_fallback && *max_slippage == bid.max_slippage)
+            {
+                Some((_, _, _, amount)) => *amount += bid_claim,
+                None => recycle_premiums.push((bid.liq_premium, bid.sp_fallback, bid.max_slippage, bid_claim)),
+            }
+            recycle_amount += bid_claim;
+            minimum_receive += get_recycle_minimum(liquidation_prices.as_ref().unwrap(), bid_claim, bid.max_slippage)?;
+        } else {
+            claim_amount += bid_claim;
+        }
+
         // store slot to update residue
         store_premium_slot( &mut queue, slot.clone())?;
 
         // check if bid has been consumed, include 1 for rounding
         if remaining_bid <= Uint256::one() {
-            remove_bid(bid, &mut queue)?;
+            remove_bid(deps.storage, bid_for.to_string(), bid);
         } else {
             store_bid(
                 deps.storage,
-                &mut queue,
+                bid_for.to_string(),
                 Bid {
                     amount: remaining_bid,
                     product_snapshot: slot.product_snapshot,
@@ -580,14 +660,155 @@ pub fn claim_liquidations(
             amount: Uint128::new(c_amount),
         };
 
-        messages.push(withdrawal_msg(withdrawal_asset, info.sender)?);
+        messages.push(withdrawal_msg(withdrawal_asset, info.clone().sender)?);
     }
 
-    Ok(Response::new().add_messages(messages).add_attributes(vec![
+    let mut attrs = vec![
         attr("action", "claim_liquidations"),
         attr("collateral_token", bid_for.to_string()),
         attr("collateral_amount", claim_amount),
-    ]))
+    ];
+
+    //Swap recycled collateral to the bid asset, the reply re-bids it in the same slots
+    let mut sub_msgs: Vec<SubMsg> = vec![];
+    if !recycle_amount.is_zero() {
+        attrs.push(attr("recycled_collateral_amount", recycle_amount));
+
+        if let Some(dex_router) = config.dex_router {
+            let prev_balance = deps.querier
+                .query_balance(env.contract.address, queue.bid_asset.info.to_string())?
+                .amount;
+
+            RECYCLE.save(deps.storage, &RecyclePropagation {
+                user: info.sender,
+                bid_for: bid_for.clone(),
+                premiums: recycle_premiums,
+                prev_balance,
+            })?;
+
+            let swap_msg = router_native_to_native(
+                dex_router.to_string(),
+                bid_for.clone(),
+                queue.bid_asset.info,
+                None,
+                recycle_amount.into(),
+                Some(minimum_receive),
+            )?;
+            //If the swap fails, the reply sends the collateral to the bidder
+            sub_msgs.push(SubMsg::reply_always(swap_msg, RECYCLE_REPLY_ID));
+        }
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_submessages(sub_msgs)
+        .add_attributes(attrs))
+}
+
+/// Return the minimum bid asset to receive for recycled collateral,
+/// valued at the collateral's last liquidation prices less the bid's max slippage
+fn get_recycle_minimum(prices: &LiquidationPrices, collateral: Uint256, max_slippage: Decimal) -> StdResult<Uint128> {
+    let value = prices.collateral_price.get_value(Uint128::new(u128::from(collateral)))?;
+
+    Ok(prices.credit_price.get_amount(value)? * (Decimal::one() - max_slippage))
+}
+
+/// Re-bid the bid asset received from a recycle swap in the same premium slots.
+/// Amounts that can't be re-bid are sent to the bidder, as is the collateral if the swap failed.
+pub fn handle_recycle_reply(deps: DepsMut, env: Env, msg: Reply) -> StdResult<Response> {
+    let recycle: RecyclePropagation = RECYCLE.load(deps.storage)?;
+    RECYCLE.remove(deps.storage);
+
+    //Collateral sold from each premium
+    let total_collateral = recycle.premiums
+        .iter()
+        .fold(Uint256::zero(), |total, (_, _, _, amount)| total + *amount);
+
+    match msg.result.into_result() {
+        Ok(_result) => {
+            let config: Config = CONFIG.load(deps.storage)?;
+            let mut queue: Queue = QUEUES.load(deps.storage, recycle.bid_for.to_string())?;
+
+            //Amount received from the swap
+            let current_balance = deps.querier
+                .query_balance(env.clone().contract.address, queue.bid_asset.info.to_string())?
+                .amount;
+            let received = current_balance.checked_sub(recycle.prev_balance)?;
+
+            let mut attrs = vec![
+                attr("method", "recycle_bids"),
+                attr("bid_owner", recycle.user.to_string()),
+                attr("bid_asset_received", received.to_string()),
+            ];
+            //Split the received amount by the collateral sold from each premium
+            let mut allocated = Uint128::zero();
+            let mut unplaced = Uint128::zero();
+            for (i, (premium, sp_fallback, max_slippage, collateral)) in recycle.premiums.clone().into_iter().enumerate() {
+                //Last premium gets the remainder to account for rounding
+                let amount = if i == recycle.premiums.len() - 1 {
+                    received - allocated
+                } else {
+                    Decimal::from_ratio(
+                        u128::from(collateral),
+                        u128::from(total_collateral),
+                    ) * received
+                };
+                allocated += amount;
+
+                if amount.is_zero() {
+                    continue;
+                }
+
+                //place_bid validates before saving, so a failed bid leaves no state behind
+                match place_bid(
+                    deps.storage,
+                    &env,
+                    &config,
+                    &mut queue,
+                    recycle.bid_for.to_string(),
+                    recycle.user.clone(),
+                    premium,
+                    amount,
+                    true,
+                    sp_fallback,
+                    max_slippage,
+                ){
+                    Ok(bid_attrs) => attrs.extend(bid_attrs),
+                    Err(_) => unplaced += amount,
+                };
+            }
+
+            QUEUES.save(deps.storage, recycle.bid_for.to_string(), &queue)?;
+
+            //Send amounts that couldn't be re-bid to the bidder
+            let mut messages = vec![];
+            if !unplaced.is_zero() {
+                attrs.push(attr("bid_asset_returned", unplaced.to_string()));
+                messages.push(withdrawal_msg(
+                    Asset {
+                        info: queue.bid_asset.info,
+                        amount: unplaced,
+                    },
+                    recycle.user,
+                )?);
+            }
+
+            Ok(Response::new().add_messages(messages).add_attributes(attrs))
+        },
+        Err(err) => {
+            //The swap failed so send the collateral to the bidder
+            Ok(Response::new()
+                .add_message(withdrawal_msg(
+                    Asset {
+                        info: recycle.bid_for,
+                        amount: Uint128::new(u128::from(total_collateral)),
+                    },
+                    recycle.user,
+                )?)
+                .add_attribute("method", "recycle_bids")
+                .add_attribute("error", err))
+        }
+    }
 }
 
 /// On each collateral execution the product_snapshot and sum_snapshot are updated
@@ -687,11 +908,13 @@ fn execute_pool_liquidation(
 
 /// Calculate & update PremiumSlot total bid amount
 pub(crate) fn set_slot_total(    
-    _deps: &mut dyn Storage,
+    storage: &mut dyn Storage,
     mut slot: PremiumSlot,
     env: Env,
     queue: &mut Queue,
     config: Config,
+    bid_for: String,
+    premium: u8,
 ) -> Result<PremiumSlot, ContractError> {
     let block_time = env.block.time.seconds();
 
@@ -703,49 +926,37 @@ pub(crate) fn set_slot_total(
         return (Ok(slot));
     }
 
-    let edited_bids: Vec<Bid> = slot
-        .clone()
-        .waiting_bids
-        .into_iter()
-        .map(|mut bid| {
-            //IF the bid is past the wait time, activate it
-            if bid.wait_end.unwrap() <= block_time {
-                let b_amount: u128 = bid.amount.into();
-                queue.bid_asset.amount += Uint128::new(b_amount);
-
-                slot.total_bid_amount += bid.amount;
-
-                process_bid_activation(&mut bid, &mut slot);
-
-                //Add bid to active bid list
-                slot.bids.push(bid.clone());
+    //Waiting bids are keyed by bid_id, which isn't wait_end order once the waiting period changes,
+    //so every waiting bid is checked. They're capped by the config's maximum_waiting_bids.
+    let mut activated_bids: Vec<Bid> = vec![];
+    let mut slot_total = slot.total_bid_amount;
 
-                //Set bid amount to 0 so we can filter it out at the end
-                bid.amount = Uint256::zero();
-
-            //IF the slot total is less than the threshold, activate the bid
-            } else if slot.total_bid_amount <= queue.bid_threshold {
-                let b_amount: u128 = bid.amount.into();
-                queue.bid_asset.amount += Uint128::new(b_amount);
+    for item in WAITING_BIDS
+        .prefix((bid_for.clone(), premium))
+        .range(storage, None, None, Order::Ascending)
+    {
+        let (_, bid) = item?;
 
-                slot.total_bid_amount += bid.amount;
+        //IF the bid is past the wait time or the slot total is less than the threshold, activate it
+        if bid.wait_end.unwrap() <= block_time || slot_total <= queue.bid_threshold {
+            slot_total += bid.amount;
+            activated_bids.push(bid);
+        }
+    }
 
-                process_bid_activation(&mut bid, &mut slot);
+    for mut bid in activated_bids {
+        let b_amount: u128 = bid.amount.into();
+        queue.bid_asset.amount += Uint128::new(b_amount);
 
-                //Add bid to active bid list
-                slot.bids.push(bid.clone());
+        slot.total_bid_amount += bid.amount;
 
-                //Set bid amount to 0 so we can filter it out at the end
-                bid.amount = Uint256::zero();
-            }
-            bid
-        })
-        .collect::<Vec<Bid>>()
-        .into_iter()
-        .filter(|bid| !bid.amount.is_zero())
-        .collect::<Vec<Bid>>();
+        process_bid_activation(&mut bid, &mut slot);
 
-    slot.waiting_bids = edited_bids;
+        //Move bid from the waiting bids to the active bids
+        WAITING_BIDS.remove(storage, (bid_for.clone(), premium, bid.id.u128()));
+        store_bid(storage, bid_for.clone(), bid)?;
+        slot.waiting_bid_count = slot.waiting_bid_count.saturating_sub(1);
+    }
 
     //Set the last_total time
     slot.last_total = block_time;
@@ -910,7 +1121,7 @@ pub fn read_premium_slot(
 }
 
 /// Store premium slot
-fn store_premium_slot(
+pub fn store_premium_slot(
     queue: &mut Queue,
     slot: PremiumSlot,
 ) -> Result<(), ContractError> {
@@ -930,128 +1141,28 @@ fn store_premium_slot(
     Ok(())
 }
 
-/// Remove bid from premium slot
-fn remove_bid(bid: Bid, queue: &mut Queue) -> Result<(), ContractError> {
-
-    //Get premium_slot to edit
-    let some_slot: Option<PremiumSlot> = queue
-        .clone()
-        .slots
-        .into_iter()
-        .filter(|slot| {
-            slot.liq_premium
-                == Decimal256::percent(1)
-                    * Decimal256::from_uint256(Uint256::from(bid.liq_premium as u128))
-        }) //Hard coded 1% per slot
-        .next();
-    let mut slot = match some_slot {
-        Some(slot) => slot,
-        None => return Err(ContractError::InvalidPremium {}),
-    };
-
-    //Filter bid from said slot if active
-    let new_bids: Vec<Bid> = slot
-        .bids
-        .into_iter()
-        .filter(|temp_bid| temp_bid.id != bid.id)
-        .collect::<Vec<Bid>>();
-
-    //Set
-    slot.bids = new_bids;
-
-    //Filter bid from said slot if waiting
-    let new_bids: Vec<Bid> = slot
-        .waiting_bids
-        .into_iter()
-        .filter(|temp_bid| temp_bid.id != bid.id)
-        .collect::<Vec<Bid>>();
-
-    //Set
-    slot.waiting_bids = new_bids;
-
-    //Filter for all slots except the edited one and then push the new slot
-    let mut slots: Vec<PremiumSlot> = queue.clone()
-        .slots
-        .into_iter()
-        .filter(|slot| {
-            slot.liq_premium
-                != Decimal256::percent(1)
-                    * Decimal256::from_uint256(Uint256::from(bid.liq_premium as u128))
-        }) //Hard coded 1% per slot
-        .collect::<Vec<PremiumSlot>>();
-
-    slots.push(slot);
-
-    //Set
-    queue.slots = slots;
-
-    Ok(())
+/// Remove bid from storage
+pub fn remove_bid(storage: &mut dyn Storage, bid_for: String, bid: Bid) {
+    BIDS.remove(storage, (bid_for.clone(), bid.liq_premium, bid.id.u128()));
+    WAITING_BIDS.remove(storage, (bid_for.clone(), bid.liq_premium, bid.id.u128()));
+    FALLBACK_BIDS.remove(storage, (bid_for.clone(), bid.id.u128()));
+    USER_BIDS.remove(storage, (bid_for, bid.user, bid.id.u128()));
 }
 
-/// Store bid in premium slot
-fn store_bid(_deps: &mut dyn Storage, queue: &mut Queue, bid: Bid) -> Result<(), ContractError> {
-
-    //Get premium_slot to edit
-    let some_slot: Option<PremiumSlot> = queue
-        .clone()
-        .slots
-        .into_iter()
-        .filter(|slot| {
-            slot.liq_premium
-                == Decimal256::percent(1)
-                    * Decimal256::from_uint256(Uint256::from(bid.liq_premium as u128))
-        }) //Hard coded 1% per slot
-        .next();
-    let mut slot = match some_slot {
-        Some(slot) => slot,
-        None => return Err(ContractError::InvalidPremium {}),
-    };
-
-    //Store bid in slot list depending on if it is active or waiting
-    if bid.wait_end.is_some(){
-        //Filter bid from said slot if waiting
-        let mut new_bids: Vec<Bid> = slot
-            .waiting_bids
-            .into_iter()
-            .filter(|temp_bid| temp_bid.id != bid.id)
-            .collect::<Vec<Bid>>();
-        //Push new bid
-        new_bids.push(bid.clone());
-
-        //Set
-        slot.waiting_bids = new_bids;
+/// Store bid as active or waiting depending on its wait_end
+pub fn store_bid(storage: &mut dyn Storage, bid_for: String, bid: Bid) -> StdResult<()> {
+    if bid.wait_end.is_some() {
+        WAITING_BIDS.save(storage, (bid_for.clone(), bid.liq_premium, bid.id.u128()), &bid)?;
     } else {
-        //Filter bid from said slot and push new bid
-        let mut new_bids: Vec<Bid> = slot
-            .bids
-            .into_iter()
-            .filter(|temp_bid| temp_bid.id != bid.id)
-            .collect::<Vec<Bid>>();
-        //Push new bid
-        new_bids.push(bid.clone());
+        BIDS.save(storage, (bid_for.clone(), bid.liq_premium, bid.id.u128()), &bid)?;
 
-        //Set
-        slot.bids = new_bids;
+        //Only active bids are used as fallback capital
+        if bid.sp_fallback {
+            FALLBACK_BIDS.save(storage, (bid_for.clone(), bid.id.u128()), &bid.liq_premium)?;
+        }
     }
 
-
-    //Filter for all slots except the edited one and then push the new slot
-    let mut slots: Vec<PremiumSlot> = queue.clone()
-        .slots
-        .into_iter()
-        .filter(|slot| {
-            slot.liq_premium
-                != Decimal256::percent(1)
-                    * Decimal256::from_uint256(Uint256::from(bid.liq_premium as u128))
-        }) //Hard coded 1% per slot
-        .collect::<Vec<PremiumSlot>>();
-
-    slots.push(slot);
-
-    //Set
-    queue.slots = slots;
-
-    Ok(())
+    USER_BIDS.save(storage, (bid_for, bid.user.clone(), bid.id.u128()), &bid.liq_premium)
 }
 
 /// Validate withdrawal amount
@@ -1077,74 +1188,119 @@ fn assert_withdraw_amount(
     Ok(withdrawal_amount)
 }
 
-/// Return Bid from storage
-pub fn read_bid(_deps: &dyn Storage, bid_id: Uint128, queue: Queue) -> StdResult<Bid> {
-    let mut read_bid: Option<Bid> = None;
+/// Return active or waiting Bid from a premium slot
+pub fn read_slot_bid(storage: &dyn Storage, bid_for: String, premium: u8, bid_id: u128) -> StdResult<Bid> {
+    if let Some(bid) = BIDS.may_load(storage, (bid_for.clone(), premium, bid_id))? {
+        return Ok(bid);
+    }
 
+    match WAITING_BIDS.may_load(storage, (bid_for, premium, bid_id))? {
+        Some(bid) => Ok(bid),
+        None => Err(StdError::GenericErr {
+            msg: "Bid not found".to_string(),
+        }),
+    }
+}
+
+/// Return Bid from storage
+pub fn read_bid(storage: &dyn Storage, bid_for: String, bid_id: Uint128, queue: Queue) -> StdResult<Bid> {
     let premium_range = 0..(queue.max_premium.u128() as u8 + 1u8);
 
     for premium in premium_range {
-        let slot = read_premium_slot(queue.clone(), premium)?;
-
-        match slot.bids.into_iter().find(|bid| bid.id.eq(&bid_id)) {
-            Some(bid) => read_bid = Some(bid),
-            None => {
-                //Check in waiting bids
-                match slot.waiting_bids.into_iter().find(|bid| bid.id.eq(&bid_id)) {
-                    Some(bid) => read_bid = Some(bid),
-                    None => { }
-                }
-            }
-        }
-
-        if read_bid.is_some() {
-            break;
+        if let Ok(bid) = read_slot_bid(storage, bid_for.clone(), premium, bid_id.u128()) {
+            return Ok(bid);
         }
     }
 
-    if read_bid.is_none() {
-        return Err(StdError::GenericErr {
+    Err(StdError::GenericErr {
+        msg: "Bid not found".to_string(),
+    })
+}
+
+/// Return a user's Bid from storage
+pub fn read_user_bid(
+    storage: &dyn Storage,
+    bid_for: String,
+    user: Addr,
+    bid_id: Uint128,
+) -> StdResult<Bid> {
+    match USER_BIDS.may_load(storage, (bid_for.clone(), user, bid_id.u128()))? {
+        Some(premium) => read_slot_bid(storage, bid_for, premium, bid_id.u128()),
+        None => Err(StdError::GenericErr {
             msg: "Bid not found".to_string(),
-        });
+        }),
     }
-
-    Ok(read_bid.unwrap())
 }
 
 /// Return Bids for a user
 pub fn read_bids_by_user(
-    _deps: &dyn Storage,
-    queue: Queue,
+    storage: &dyn Storage,
+    bid_for: String,
     user: Addr,
     limit: Option<u32>,
     start_after: Option<Uint128>, //bid.id
 ) -> StdResult<Vec<Bid>> {
-    
-    let mut read_bids: Vec<Bid> = vec![];
     let limit = limit.unwrap_or(MAX_LIMIT) as usize;
-    let start = start_after.unwrap_or_else(Uint128::zero);
-
-    for slot in queue.slots {
-        read_bids.extend(
-            slot.bids
-                .into_iter()
-                .filter(|bid| bid.id > start)
-                .filter(|bid| bid.user == user)
-                .collect::<Vec<Bid>>(),
-        );
-    }
-
-    read_bids = read_bids.into_iter().take(limit).collect::<Vec<Bid>>();
+    let start = start_after.map(|bid_id| Bound::exclusive(bid_id.u128()));
+
+    USER_BIDS
+        .prefix((bid_for.clone(), user))
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (bid_id, premium) = item?;
+            read_slot_bid(storage, bid_for.clone(), premium, bid_id)
+        })
+        .collect::<StdResult<Vec<Bid>>>()
+}
 
-    Ok(read_bids)
+/// Return a page of a premium slot's active or waiting Bids
+pub fn read_slot_bids(
+    storage: &dyn Storage,
+    bid_for: String,
+    premium: u8,
+    waiting: bool,
+    start_after: Option<Uint128>,
+    limit: Option<u32>,
+) -> StdResult<Vec<Bid>> {
+    let limit = limit.unwrap_or(MAX_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|bid_id| Bound::exclusive(bid_id.u128()));
+    let bids = if waiting { WAITING_BIDS } else { BIDS };
+
+    bids
+        .prefix((bid_for, premium))
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, bid)| bid))
+        .collect::<StdResult<Vec<Bid>>>()
 }
 
 /// Validate bid input
 pub fn validate_bid_input(deps: &dyn Storage, bid_input: BidInput) -> Result<(), ContractError> {
     match QUEUES.load(deps, bid_input.bid_for.to_string()) {
         Ok(queue) => {
-            if bid_input.liq_premium <= queue.max_premium.u128() as u8
-            {
+            let max_premium = queue.max_premium.u128() as u8;
+
+            if bid_input.max_slippage > Decimal::one() {
+                return Err(ContractError::InvalidMaxSlippage {});
+            }
+
+            if let Some(range) = bid_input.premium_range {
+                if range.min_premium > range.max_premium || range.max_premium > max_premium {
+                    return Err(ContractError::InvalidPremium {});
+                }
+                //Weights must cover each premium in the range & sum to 1
+                if let Some(weights) = range.weights {
+                    let range_length = (range.max_premium - range.min_premium) as usize + 1;
+                    let total: Decimal = weights.iter().fold(Decimal::zero(), |total, weight| total + *weight);
+
+                    if weights.len() != range_length || total != Decimal::one() {
+                        return Err(ContractError::InvalidBidWeights {});
+                    }
+                }
+
+                Ok(())
+            } else if bid_input.liq_premium <= max_premium {
                 Ok(())
             } else {
                 Err(ContractError::InvalidPremium {})
@@ -3,22 +3,24 @@ use std::env;
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    attr, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response,
-    StdResult, Uint128, QueryRequest, WasmQuery, 
+    attr, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response,
+    StdError, StdResult, Uint128, QueryRequest, WasmQuery, Order, Decimal,
 };
 use cw2::set_contract_version;
+use membrane::pause::{assert_not_paused, execute_pause, PauseActions};
 use membrane::cdp::QueryMsg as CDP_QueryMsg;
-use membrane::liq_queue::{Config, ExecuteMsg, InstantiateMsg, QueryMsg};
+use membrane::liq_queue::{Config, ExecuteMsg, InstantiateMsg, QueryMsg, MigrateMsg};
 use membrane::math::{Decimal256, Uint256};
-use membrane::types::{Asset, AssetInfo, PremiumSlot, Queue, Basket};
+use membrane::types::{Asset, AssetInfo, Bid, PremiumSlot, Queue, Basket};
 
-use crate::bid::{claim_liquidations, execute_liquidation, retract_bid, submit_bid};
+use crate::bid::{claim_liquidations, execute_liquidation, handle_recycle_reply, retract_bid, store_bid, submit_bid, RECYCLE_REPLY_ID};
 use crate::error::ContractError;
+use crate::fallback::{claim_fallback, distribute_fallback, fallback_liquidate, query_fallback_capital};
 use crate::query::{
     query_bid, query_bids_by_user, query_liquidatible, query_premium_slot,
     query_premium_slots, query_queues, query_user_claims,
 };
-use crate::state::{CONFIG, QUEUES, OWNERSHIP_TRANSFER};
+use crate::state::{CONFIG, QUEUES, OLD_QUEUES, OWNERSHIP_TRANSFER, WAITING_BIDS};
 
 // Modifications from origin
 
@@ -30,11 +32,25 @@ use crate::state::{CONFIG, QUEUES, OWNERSHIP_TRANSFER};
 // - Removed bid_with, instead saving the bid_asset from the Positions contract
 // - Don't error if the full collateral amount isn't liquidated, just update the returning attribute
 // - bid_for is a String in functions that require .as_bytes() to allow LP tokens to work
+// - Bids are stored in their own Maps keyed by (bid_for, premium, bid_id) so PremiumSlots only hold aggregates
+// - Bids can be spread across a premium range & opt in to recycling claimed collateral into new bids through the DEX router
+// - Bids can opt in to being Stability Pool fallback capital, repaying at the SP's premium once the SP is exhausted
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:liq-queue";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+//Bids moved per migration
+const DEFAULT_MIGRATION_LIMIT: u32 = 100;
+const MAX_MIGRATION_LIMIT: u32 = 500;
+
+//Execute msgs handled by the pause guard
+//Liquidations called by the Positions contract are never paused
+const PAUSE_ACTIONS: PauseActions = PauseActions {
+    pausable: &["submit_bid", "retract_bid", "claim_fallback", "claim_liquidations", "add_queue", "update_queue", "update_config"],
+    unpausable: &["liquidate", "fallback_liquidate", "distribute_fallback"],
+};
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
@@ -67,6 +83,7 @@ pub fn instantiate(
             bid_asset,
             minimum_bid: msg.minimum_bid,
             maximum_waiting_bids: msg.maximum_waiting_bids,
+            dex_router: None,
         };
     } else {
         config = Config {
@@ -78,6 +95,7 @@ pub fn instantiate(
             bid_asset,
             minimum_bid: msg.minimum_bid,
             maximum_waiting_bids: msg.maximum_waiting_bids,
+            dex_router: None,
         };
     }
 
@@ -98,6 +116,9 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
+    //Check the pause guard
+    assert_not_paused(deps.storage, &env, &msg, &PAUSE_ACTIONS)?;
+
     match msg {
         ExecuteMsg::SubmitBid {
             bid_input,
@@ -122,6 +143,12 @@ pub fn execute(
             collateral_price,
             credit_price,
         ),
+        ExecuteMsg::FallbackLiquidate { liq_amount } => fallback_liquidate(deps, info, liq_amount),
+        ExecuteMsg::DistributeFallback {
+            distribution_assets,
+            distribute_for,
+        } => distribute_fallback(deps, info, distribution_assets, distribute_for),
+        ExecuteMsg::ClaimFallback {} => claim_fallback(deps, info),
         ExecuteMsg::ClaimLiquidations { bid_for, bid_ids } => {
             claim_liquidations(deps, env, info, bid_for, bid_ids)
         }
@@ -142,6 +169,7 @@ pub fn execute(
             waiting_period,
             minimum_bid,
             maximum_waiting_bids,
+            dex_router,
         } => update_config(
             deps,
             info,
@@ -151,7 +179,12 @@ pub fn execute(
             waiting_period,
             minimum_bid,
             maximum_waiting_bids,
+            dex_router,
         ),
+        ExecuteMsg::Pause(pause_msg) => {
+            let config = CONFIG.load(deps.storage)?;
+            Ok(execute_pause(deps.storage, deps.api, &env, &info, info.sender == config.owner, &PAUSE_ACTIONS, pause_msg)?)
+        }
     }
 } //Functions assume Cw20 asset amounts are taken from Messageinfo
 
@@ -165,6 +198,7 @@ fn update_config(
     waiting_period: Option<u64>,
     minimum_bid: Option<Uint128>,
     maximum_waiting_bids: Option<u64>,
+    dex_router: Option<String>,
 ) -> Result<Response, ContractError> {
     let mut config = CONFIG.load(deps.storage)?;
     let mut attrs = vec![attr("method", "update_config")];
@@ -203,6 +237,9 @@ fn update_config(
     if let Some(maximum_waiting_bids) = maximum_waiting_bids {
         config.maximum_waiting_bids = maximum_waiting_bids;
     }
+    if let Some(dex_router) = dex_router {
+        config.dex_router = Some(deps.api.addr_validate(&dex_router)?);
+    }
 
     CONFIG.save(deps.storage, &config)?;
 
@@ -247,12 +284,11 @@ fn edit_queue(
     
             for premium in premium_floor..=premium_ceiling {
                 queue.slots.push(PremiumSlot {
-                    bids: vec![],
-                    waiting_bids: vec![],
                     liq_premium: Decimal256::percent(premium), //This is a hard coded 1% per slot
                     sum_snapshot: Decimal256::zero(),
                     product_snapshot: Decimal256::one(),
                     total_bid_amount: Uint256::zero(),
+                    waiting_bid_count: 0u64,
                     last_total: 0u64,
                     current_epoch: Uint128::zero(),
                     current_scale: Uint128::zero(),
@@ -307,12 +343,11 @@ fn add_queue(
 
     for premium in 0..max_premium_plus_1 as u64 {
         slots.push(PremiumSlot {
-            bids: vec![],
-            waiting_bids: vec![],
             liq_premium: Decimal256::percent(premium), //This is a hard coded 1% per slot
             sum_snapshot: Decimal256::zero(),
             product_snapshot: Decimal256::one(),
             total_bid_amount: Uint256::zero(),
+            waiting_bid_count: 0u64,
             last_total: 0u64,
             current_epoch: Uint128::zero(),
             current_scale: Uint128::zero(),
@@ -362,6 +397,14 @@ fn add_queue(
 }
 
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> StdResult<Response> {
+    match msg.id {
+        RECYCLE_REPLY_ID => handle_recycle_reply(deps, env, msg),
+        id => Err(StdError::generic_err(format!("invalid reply id: {}", id))),
+    }
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
@@ -380,15 +423,20 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
             credit_info,
             credit_price,
         )?),
-        QueryMsg::PremiumSlot { bid_for, premium } => {
-            to_binary(&query_premium_slot(deps, bid_for, premium)?)
-        }
+        QueryMsg::PremiumSlot {
+            bid_for,
+            premium,
+            start_after,
+            waiting_start_after,
+            limit,
+        } => to_binary(&query_premium_slot(deps, bid_for, premium, start_after, waiting_start_after, limit)?),
         QueryMsg::PremiumSlots {
             bid_for,
             start_after,
             limit,
         } => to_binary(&query_premium_slots(deps, bid_for, start_after, limit)?),
         QueryMsg::UserClaims { user } => to_binary(&query_user_claims(deps, user)?),
+        QueryMsg::FallbackCapital {} => to_binary(&query_fallback_capital(deps)?),
         QueryMsg::Bid { bid_for, bid_id } => to_binary(&query_bid(deps, bid_for, bid_id)?),
         QueryMsg::BidsByUser {
             bid_for,
@@ -407,4 +455,93 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
             to_binary(&query_queues(deps, start_after, limit)?)
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    //Move inline slot bids to their own Maps, a page of bids per migration
+    let limit = msg.limit.unwrap_or(DEFAULT_MIGRATION_LIMIT).min(MAX_MIGRATION_LIMIT) as u64;
+    let keys = QUEUES
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<String>>>()?;
+
+    let mut migrated_bids = 0u64;
+    for bid_for in keys {
+        if migrated_bids == limit {
+            break;
+        }
+        //Queues that fail to load as the old format are already migrated
+        let mut old_queue = match OLD_QUEUES.load(deps.storage, bid_for.clone()) {
+            Ok(old_queue) => old_queue,
+            Err(_) => continue,
+        };
+
+        for old_slot in old_queue.slots.iter_mut() {
+            while migrated_bids < limit {
+                let old_bid = match old_slot.bids.pop().or_else(|| old_slot.waiting_bids.pop()) {
+                    Some(old_bid) => old_bid,
+                    None => break,
+                };
+                store_bid(deps.storage, bid_for.clone(), Bid {
+                    user: old_bid.user,
+                    id: old_bid.id,
+                    amount: old_bid.amount,
+                    liq_premium: old_bid.liq_premium,
+                    product_snapshot: old_bid.product_snapshot,
+                    sum_snapshot: old_bid.sum_snapshot,
+                    pending_liquidated_collateral: old_bid.pending_liquidated_collateral,
+                    wait_end: old_bid.wait_end,
+                    epoch_snapshot: old_bid.epoch_snapshot,
+                    scale_snapshot: old_bid.scale_snapshot,
+                    auto_recycle: false,
+                    sp_fallback: false,
+                    max_slippage: Decimal::zero(),
+                })?;
+                migrated_bids += 1;
+            }
+        }
+
+        //Partially moved queues keep the old layout until the next migration
+        if old_queue.slots.iter().any(|slot| !slot.bids.is_empty() || !slot.waiting_bids.is_empty()) {
+            OLD_QUEUES.save(deps.storage, bid_for, &old_queue)?;
+            break;
+        }
+
+        let mut slots: Vec<PremiumSlot> = vec![];
+        for (premium, old_slot) in old_queue.slots.into_iter().enumerate() {
+            //Hard coded 1% per slot
+            let waiting_bid_count = WAITING_BIDS
+                .prefix((bid_for.clone(), premium as u8))
+                .keys(deps.storage, None, None, Order::Ascending)
+                .count() as u64;
+
+            slots.push(PremiumSlot {
+                liq_premium: old_slot.liq_premium,
+                sum_snapshot: old_slot.sum_snapshot,
+                product_snapshot: old_slot.product_snapshot,
+                total_bid_amount: old_slot.total_bid_amount,
+                waiting_bid_count,
+                last_total: old_slot.last_total,
+                current_epoch: old_slot.current_epoch,
+                current_scale: old_slot.current_scale,
+                residue_collateral: old_slot.residue_collateral,
+                residue_bid: old_slot.residue_bid,
+            });
+        }
+
+        QUEUES.save(deps.storage, bid_for, &Queue {
+            bid_asset: old_queue.bid_asset,
+            max_premium: old_queue.max_premium,
+            slots,
+            current_bid_id: old_queue.current_bid_id,
+            bid_threshold: old_queue.bid_threshold,
+        })?;
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "migrate"),
+        attr("migrated_bids", migrated_bids.to_string()),
+    ]))
+}
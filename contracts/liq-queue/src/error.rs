@@ -1,4 +1,4 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{StdError, Uint128};
 use membrane::math::Uint256;
 use thiserror::Error;
 
@@ -22,6 +22,12 @@ pub enum ContractError {
     #[error("Premium greater than max premium for this asset queue")]
     InvalidPremium {},
 
+    #[error("Premium range weights must match the range length & sum to 1")]
+    InvalidBidWeights {},
+
+    #[error("Max slippage can't be more than 100%")]
+    InvalidMaxSlippage {},
+
     #[error("A queue for this asset already exists")]
     DuplicateQueue {},
 
@@ -40,6 +46,9 @@ pub enum ContractError {
     #[error("Variable overflow due to mismanaged state")]
     MismanagedState {},
 
+    #[error("Bid amount too small, minimum is {minimum}")]
+    BidTooSmall { minimum: Uint128 },
+
     #[error("Waiting bids are full at {max_waiting_bids:?}")]
     TooManyWaitingBids { max_waiting_bids: u64 },
     
@@ -0,0 +1,286 @@
+use cosmwasm_std::{
+    attr, coin, to_binary, Addr, BankMsg, Coin, CosmosMsg, Decimal, Deps, DepsMut, MessageInfo, Order,
+    Response, StdResult, Storage, Uint128, WasmMsg,
+};
+use membrane::cdp::ExecuteMsg as CDP_ExecuteMsg;
+use membrane::liq_queue::{Config, FallbackCapitalResponse};
+use membrane::math::Uint256;
+use membrane::helpers::asset_to_coin;
+use membrane::types::{Asset, Bid, PremiumSlot, Queue};
+
+use crate::bid::{
+    calculate_liquidated_collateral, calculate_remaining_bid, read_premium_slot, read_slot_bid,
+    remove_bid, store_bid, store_premium_slot,
+};
+use crate::error::ContractError;
+use crate::state::{FallbackPropagation, CONFIG, FALLBACK, FALLBACK_BIDS, FALLBACK_CLAIMS, QUEUES};
+
+/// Max fallback bids drawn from per fallback liquidation, keeps the liquidation's gas bounded
+pub const MAX_FALLBACK_BIDS: usize = 30;
+
+/// Repay for the Positions contract using active sp_fallback bids.
+/// Positions contract sends back a DistributeFallback msg.
+pub fn fallback_liquidate(
+    deps: DepsMut,
+    info: MessageInfo,
+    liq_amount: Decimal,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.positions_contract {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut leftover = Uint256::from((liq_amount * Uint128::new(1u128)).u128());
+
+    //Fallback bids in order of (bid_for, bid_id)
+    let fallback_bids: Vec<(String, u128, u8)> = FALLBACK_BIDS
+        .range(deps.storage, None, None, Order::Ascending)
+        .take(MAX_FALLBACK_BIDS)
+        .map(|item| {
+            let ((bid_for, bid_id), premium) = item?;
+            Ok((bid_for, bid_id, premium))
+        })
+        .collect::<StdResult<Vec<(String, u128, u8)>>>()?;
+
+    let mut queues: Vec<(String, Queue)> = vec![];
+    let mut drawn: Vec<(Addr, Uint256)> = vec![];
+    let mut total_drawn = Uint256::zero();
+
+    for (bid_for, bid_id, premium) in fallback_bids {
+        if leftover.is_zero() {
+            break;
+        }
+
+        //Load each queue once
+        let queue_index = match queues.iter().position(|(queue_for, _)| *queue_for == bid_for) {
+            Some(index) => index,
+            None => {
+                queues.push((bid_for.clone(), QUEUES.load(deps.storage, bid_for.clone())?));
+                queues.len() - 1
+            }
+        };
+        let queue = &mut queues[queue_index].1;
+
+        let draw = draw_from_bid(deps.storage, queue, bid_for, premium, bid_id, leftover)?;
+        if draw.1.is_zero() {
+            continue;
+        }
+
+        match drawn.iter_mut().find(|(user, _)| *user == draw.0) {
+            Some((_, amount)) => *amount += draw.1,
+            None => drawn.push(draw.clone()),
+        }
+        total_drawn += draw.1;
+        leftover = leftover - draw.1;
+    }
+
+    //Save edited queues
+    for (bid_for, queue) in queues.clone() {
+        QUEUES.save(deps.storage, bid_for, &queue)?;
+    }
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    if !total_drawn.is_zero() {
+        //Save drawn amounts for the distribution
+        FALLBACK.save(deps.storage, &FallbackPropagation {
+            drawn,
+            total_drawn,
+        })?;
+
+        //Repay for the Positions contract
+        let repay_coin: Coin = asset_to_coin(Asset {
+            info: config.bid_asset,
+            amount: Uint128::new(total_drawn.into()),
+        })?;
+        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: config.positions_contract.to_string(),
+            msg: to_binary(&CDP_ExecuteMsg::LiqRepay {})?,
+            funds: vec![repay_coin],
+        }));
+    }
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        attr("method", "fallback_liquidate"),
+        attr("repay_amount", total_drawn.to_string()),
+        attr("leftover_repayment", leftover.to_string()),
+    ]))
+}
+
+/// Withdraw up to the requested amount from an active bid, keeping its liquidated collateral pending.
+/// Returns the bid owner & amount drawn.
+fn draw_from_bid(
+    storage: &mut dyn Storage,
+    queue: &mut Queue,
+    bid_for: String,
+    premium: u8,
+    bid_id: u128,
+    requested: Uint256,
+) -> Result<(Addr, Uint256), ContractError> {
+    let bid = read_slot_bid(storage, bid_for.clone(), premium, bid_id)?;
+    let mut slot: PremiumSlot = read_premium_slot(queue.clone(), premium)?;
+
+    // calculate spent and reward until this moment
+    let (remaining_bid, residue_bid) = calculate_remaining_bid(&bid, &slot)?;
+    let (liquidated_collateral, residue_collateral) =
+        calculate_liquidated_collateral(storage, &bid, bid_for.clone())?;
+
+    // stack residues, will give it to next claimer if it becomes bigger than 1.0
+    slot.residue_collateral += residue_collateral;
+    slot.residue_bid += residue_bid;
+
+    let draw = if remaining_bid > requested { requested } else { remaining_bid };
+    let pending_liquidated_collateral = bid.pending_liquidated_collateral + liquidated_collateral;
+
+    //remove or update bid
+    if draw == remaining_bid && pending_liquidated_collateral.is_zero() {
+        remove_bid(storage, bid_for, bid.clone());
+    } else {
+        store_bid(
+            storage,
+            bid_for.clone(),
+            Bid {
+                amount: remaining_bid - draw,
+                pending_liquidated_collateral,
+                product_snapshot: slot.product_snapshot,
+                sum_snapshot: slot.sum_snapshot,
+                scale_snapshot: slot.current_scale,
+                ..bid.clone()
+            },
+        )?;
+
+        //Consumed bids stop being fallback capital but stay claimable, include 1 for rounding
+        if remaining_bid - draw <= Uint256::one() {
+            FALLBACK_BIDS.remove(storage, (bid_for, bid_id));
+        }
+    }
+
+    //Remove drawn amount from totals
+    slot.total_bid_amount = slot.total_bid_amount - draw;
+    queue.bid_asset.amount -= Uint128::new(draw.into());
+    store_premium_slot(queue, slot)?;
+
+    Ok((bid.user, draw))
+}
+
+/// Split fallback liquidation collateral to the bidders that repaid, pro-rata to their repayment
+pub fn distribute_fallback(
+    deps: DepsMut,
+    info: MessageInfo,
+    distribution_assets: Vec<Asset>,
+    distribute_for: Uint128,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.positions_contract {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let prop: FallbackPropagation = FALLBACK.load(deps.storage)?;
+
+    //Any repayment that wasn't used was sent back & is returned to the bidders as the bid asset
+    let excess_repayment = prop.total_drawn - Uint256::from(distribute_for.u128()).min(prop.total_drawn);
+    let mut assets = distribution_assets.clone();
+    if !excess_repayment.is_zero() {
+        assets.push(Asset {
+            info: config.bid_asset,
+            amount: Uint128::new(excess_repayment.into()),
+        });
+    }
+
+    for asset in assets {
+        let mut distributed = Uint256::zero();
+        let asset_amount = Uint256::from(asset.amount.u128());
+
+        for (i, (user, drawn)) in prop.drawn.clone().into_iter().enumerate() {
+            //Last user gets the remainder to account for rounding
+            let user_amount = if i == prop.drawn.len() - 1 {
+                asset_amount - distributed
+            } else {
+                asset_amount.multiply_ratio(drawn, prop.total_drawn)
+            };
+            distributed += user_amount;
+
+            if user_amount.is_zero() {
+                continue;
+            }
+
+            FALLBACK_CLAIMS.update(
+                deps.storage,
+                (user, asset.info.to_string()),
+                |claim| -> StdResult<Uint256> { Ok(claim.unwrap_or_default() + user_amount) },
+            )?;
+        }
+    }
+
+    FALLBACK.remove(deps.storage);
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "distribute_fallback"),
+        attr("distribute_for", distribute_for),
+        attr("distribution_assets", format!("{:?}", distribution_assets)),
+    ]))
+}
+
+/// Claim collateral from fallback liquidations
+pub fn claim_fallback(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let claims: Vec<(String, Uint256)> = read_fallback_claims(deps.as_ref(), info.sender.clone())?;
+
+    let mut coins: Vec<Coin> = vec![];
+    for (denom, amount) in claims {
+        FALLBACK_CLAIMS.remove(deps.storage, (info.sender.clone(), denom.clone()));
+
+        if !amount.is_zero() {
+            coins.push(coin(amount.into(), denom));
+        }
+    }
+
+    if coins.is_empty() {
+        return Err(ContractError::CustomError { val: String::from("No fallback claims") });
+    }
+
+    Ok(Response::new()
+        .add_message(CosmosMsg::Bank(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: coins.clone(),
+        }))
+        .add_attributes(vec![
+            attr("method", "claim_fallback"),
+            attr("claimed", format!("{:?}", coins)),
+        ]))
+}
+
+/// Return a user's fallback claims as (asset, amount)
+pub fn read_fallback_claims(deps: Deps, user: Addr) -> StdResult<Vec<(String, Uint256)>> {
+    FALLBACK_CLAIMS
+        .prefix(user)
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(String, Uint256)>>>()
+}
+
+/// Return the active sp_fallback bid amount that a single fallback liquidation can draw from
+pub fn query_fallback_capital(deps: Deps) -> StdResult<FallbackCapitalResponse> {
+    let mut capital = Uint256::zero();
+    let mut queues: Vec<(String, Queue)> = vec![];
+
+    for item in FALLBACK_BIDS.range(deps.storage, None, None, Order::Ascending).take(MAX_FALLBACK_BIDS) {
+        let ((bid_for, bid_id), premium) = item?;
+
+        let queue = match queues.iter().find(|(queue_for, _)| *queue_for == bid_for) {
+            Some((_, queue)) => queue.clone(),
+            None => {
+                let queue = QUEUES.load(deps.storage, bid_for.clone())?;
+                queues.push((bid_for.clone(), queue.clone()));
+                queue
+            }
+        };
+
+        let bid = read_slot_bid(deps.storage, bid_for, premium, bid_id)?;
+        let slot = read_premium_slot(queue, premium)?;
+        let (remaining_bid, _) = calculate_remaining_bid(&bid, &slot)?;
+
+        capital += remaining_bid;
+    }
+
+    Ok(FallbackCapitalResponse { capital })
+}
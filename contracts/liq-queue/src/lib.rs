@@ -5,6 +5,7 @@
 pub mod bid;
 pub mod contract;
 mod error;
+pub mod fallback;
 pub mod query;
 pub mod state;
 
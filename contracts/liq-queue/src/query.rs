@@ -7,10 +7,11 @@ use membrane::math::{Decimal256, Uint256};
 use membrane::oracle::{PriceResponse256, PriceResponse};
 use membrane::types::{AssetInfo, Bid, PremiumSlot, Queue};
 
+use crate::fallback::read_fallback_claims;
 use crate::state::{CONFIG, QUEUES};
 use crate::bid::{
     calculate_liquidated_collateral, calculate_remaining_bid, read_bid, read_bids_by_user,
-    read_premium_slot,
+    read_premium_slot, read_slot_bids,
 };
 
 /// Return Multiple Queues
@@ -133,6 +134,9 @@ pub fn query_premium_slot(
     deps: Deps,
     bid_for: AssetInfo,
     premium: u64, //Taken as %
+    start_after: Option<Uint128>, //Active bid ID
+    waiting_start_after: Option<Uint128>, //Waiting bid ID
+    limit: Option<u32>,
 ) -> StdResult<SlotResponse> {
     let queue = QUEUES.load(deps.storage, bid_for.to_string())?;
 
@@ -149,21 +153,15 @@ pub fn query_premium_slot(
         }
     };
 
-    Ok(SlotResponse {
-        bids: slot.bids,
-        waiting_bids: slot.waiting_bids,
-        liq_premium: slot.liq_premium.to_string(),
-        sum_snapshot: slot.sum_snapshot.to_string(),
-        product_snapshot: slot.product_snapshot.to_string(),
-        total_bid_amount: slot.total_bid_amount.to_string(),
-        current_epoch: slot.current_epoch,
-        current_scale: slot.current_scale,
-        residue_collateral: slot.residue_collateral.to_string(),
-        residue_bid: slot.residue_bid.to_string(),
-    })
+    let mut response = slot_response(slot);
+    response.bids = read_slot_bids(deps.storage, bid_for.to_string(), premium as u8, false, start_after, limit)?;
+    response.waiting_bids = read_slot_bids(deps.storage, bid_for.to_string(), premium as u8, true, waiting_start_after, limit)?;
+
+    Ok(response)
 }
 
-/// Return multiple SlotResponses
+/// Return multiple SlotResponses without their bids.
+/// Use query_premium_slot to page through a slot's bids.
 pub fn query_premium_slots(
     deps: Deps,
     bid_for: AssetInfo,
@@ -176,52 +174,45 @@ pub fn query_premium_slots(
         .unwrap_or_else(|| (queue.max_premium.u128() + 1) as u8)
         .min((queue.max_premium.u128() + 1) as u8) as usize;
 
-    let temp = queue.slots.into_iter();
-    
-    if start_after.is_some() {
-        temp.filter(|slot| {
-            (slot.liq_premium * Decimal256::from_uint256(Uint256::from(100 as u128))) > Decimal256::from_uint256(Uint256::from(start_after.unwrap() as u128))
+    Ok(queue.slots
+        .into_iter()
+        .filter(|slot| match start_after {
+            Some(start_after) => u64::from(premium_from_slot(slot)) > start_after,
+            None => true,
         })
         .take(limit)
-        .map(|slot| {
-            Ok(SlotResponse {
-                bids: slot.bids,
-                waiting_bids: slot.waiting_bids,
-                liq_premium: slot.liq_premium.to_string(),
-                sum_snapshot: slot.sum_snapshot.to_string(),
-                product_snapshot: slot.product_snapshot.to_string(),
-                total_bid_amount: slot.total_bid_amount.to_string(),
-                current_epoch: slot.current_epoch,
-                current_scale: slot.current_scale,
-                residue_collateral: slot.residue_collateral.to_string(),
-                residue_bid: slot.residue_bid.to_string(),
-            })
-        })
-        .collect::<StdResult<Vec<SlotResponse>>>()
-    } else {
-        temp.take(limit)
-            .map(|slot| {
-                Ok(SlotResponse {
-                    bids: slot.bids,
-                    waiting_bids: slot.waiting_bids,
-                    liq_premium: slot.liq_premium.to_string(),
-                    sum_snapshot: slot.sum_snapshot.to_string(),
-                    product_snapshot: slot.product_snapshot.to_string(),
-                    total_bid_amount: slot.total_bid_amount.to_string(),
-                    current_epoch: slot.current_epoch,
-                    current_scale: slot.current_scale,
-                    residue_collateral: slot.residue_collateral.to_string(),
-                    residue_bid: slot.residue_bid.to_string(),
-                })
-            })
-            .collect::<StdResult<Vec<SlotResponse>>>()
+        .map(slot_response)
+        .collect::<Vec<SlotResponse>>())
+}
+
+/// Return a slot's aggregates as a SlotResponse
+fn slot_response(slot: PremiumSlot) -> SlotResponse {
+    SlotResponse {
+        bids: vec![],
+        waiting_bids: vec![],
+        liq_premium: slot.liq_premium.to_string(),
+        sum_snapshot: slot.sum_snapshot.to_string(),
+        product_snapshot: slot.product_snapshot.to_string(),
+        total_bid_amount: slot.total_bid_amount.to_string(),
+        current_epoch: slot.current_epoch,
+        current_scale: slot.current_scale,
+        residue_collateral: slot.residue_collateral.to_string(),
+        residue_bid: slot.residue_bid.to_string(),
     }
 }
 
+/// Return a slot's premium as a whole percent
+fn premium_from_slot(slot: &PremiumSlot) -> u8 {
+    //Hard coded 1% per slot
+    let premium: u128 = (slot.liq_premium * Uint256::from(100u128)).into();
+
+    premium as u8
+}
+
 /// Return BidResponse for a given bid_id
 pub fn query_bid(deps: Deps, bid_for: AssetInfo, bid_id: Uint128) -> StdResult<BidResponse> {
     let queue = QUEUES.load(deps.storage, bid_for.to_string())?;
-    let bid: Bid = read_bid(deps.storage, bid_id, queue.clone())?;
+    let bid: Bid = read_bid(deps.storage, bid_for.to_string(), bid_id, queue.clone())?;
 
     let slot: PremiumSlot = match read_premium_slot( queue.clone(), bid.liq_premium)
     {
@@ -262,6 +253,9 @@ pub fn query_bid(deps: Deps, bid_for: AssetInfo, bid_id: Uint128) -> StdResult<B
         wait_end: bid.wait_end,
         epoch_snapshot: bid.epoch_snapshot,
         scale_snapshot: bid.scale_snapshot,
+        auto_recycle: bid.auto_recycle,
+        sp_fallback: bid.sp_fallback,
+        max_slippage: bid.max_slippage,
     })
 }
 
@@ -274,11 +268,10 @@ pub fn query_bids_by_user(
     start_after: Option<Uint128>,
 ) -> StdResult<Vec<BidResponse>> {
     let valid_user = deps.api.addr_validate(&user)?;
-    let queue = QUEUES.load(deps.storage, bid_for.to_string())?;
 
     let user_bids = read_bids_by_user(
         deps.storage,
-        queue.clone(),
+        bid_for.to_string(),
         valid_user,
         limit,
         start_after,
@@ -319,5 +312,16 @@ pub fn query_user_claims(deps: Deps, user: String) -> StdResult<Vec<ClaimsRespon
         res_list.push(resp);
     }
 
+    //Add collateral from fallback liquidations
+    for (asset, amount) in read_fallback_claims(deps, valid_user)? {
+        match res_list.iter_mut().find(|resp| resp.bid_for == asset) {
+            Some(resp) => resp.pending_liquidated_collateral += amount,
+            None => res_list.push(ClaimsResponse {
+                bid_for: asset,
+                pending_liquidated_collateral: amount,
+            }),
+        }
+    }
+
     Ok(res_list)
 }
@@ -1,14 +1,94 @@
 use membrane::liq_queue::Config;
-use membrane::types::Queue;
+use membrane::math::{Decimal256, Uint256};
+use membrane::oracle::PriceResponse;
+use membrane::types::{Asset, AssetInfo, Bid, Queue};
 
 use cosmwasm_std::{Decimal, Uint128, Addr};
+use cosmwasm_schema::cw_serde;
 use cw_storage_plus::{Item, Map};
 
+#[cw_serde]
+pub struct RecyclePropagation {
+    /// Bidder whose collateral is being recycled
+    pub user: Addr,
+    /// Collateral asset of the queue
+    pub bid_for: AssetInfo,
+    /// Collateral sold per (premium slot, sp_fallback, max_slippage)
+    pub premiums: Vec<(u8, bool, Decimal, Uint256)>,
+    /// Contract's bid asset balance before the swap
+    pub prev_balance: Uint128,
+}
+
+#[cw_serde]
+pub struct LiquidationPrices {
+    /// Collateral price at the last liquidation
+    pub collateral_price: PriceResponse,
+    /// Credit price at the last liquidation
+    pub credit_price: PriceResponse,
+}
+
+#[cw_serde]
+pub struct FallbackPropagation {
+    /// Credit drawn from each bidder
+    pub drawn: Vec<(Addr, Uint256)>,
+    /// Total credit drawn
+    pub total_drawn: Uint256,
+}
+
+/// Queue layout before bids were stored in their own Maps.
+/// Used to migrate queues.
+#[cw_serde]
+pub struct OldQueue {
+    pub bid_asset: Asset,
+    pub max_premium: Uint128,
+    pub slots: Vec<OldPremiumSlot>,
+    pub current_bid_id: Uint128,
+    pub bid_threshold: Uint256,
+}
+
+#[cw_serde]
+pub struct OldPremiumSlot {
+    pub bids: Vec<OldBid>,
+    pub waiting_bids: Vec<OldBid>,
+    pub liq_premium: Decimal256,
+    pub sum_snapshot: Decimal256,
+    pub product_snapshot: Decimal256,
+    pub total_bid_amount: Uint256,
+    pub last_total: u64,
+    pub current_epoch: Uint128,
+    pub current_scale: Uint128,
+    pub residue_collateral: Decimal256,
+    pub residue_bid: Decimal256,
+}
+
+#[cw_serde]
+pub struct OldBid {
+    pub user: Addr,
+    pub id: Uint128,
+    pub amount: Uint256,
+    pub liq_premium: u8,
+    pub product_snapshot: Decimal256,
+    pub sum_snapshot: Decimal256,
+    pub pending_liquidated_collateral: Uint256,
+    pub wait_end: Option<u64>,
+    pub epoch_snapshot: Uint128,
+    pub scale_snapshot: Uint128,
+}
+
 
 pub const CONFIG: Item<Config> = Item::new("config");
 pub const QUEUES: Map<String, Queue> = Map::new("queue"); //Each asset (String of AssetInfo) has a list of PremiumSlots that make up its Queue
+pub const OLD_QUEUES: Map<String, OldQueue> = Map::new("queue");
+pub const BIDS: Map<(String, u8, u128), Bid> = Map::new("bids"); //(bid_for, premium, bid_id) -> Active Bid
+pub const WAITING_BIDS: Map<(String, u8, u128), Bid> = Map::new("waiting_bids"); //(bid_for, premium, bid_id) -> Waiting Bid
+pub const USER_BIDS: Map<(String, Addr, u128), u8> = Map::new("user_bids"); //(bid_for, user, bid_id) -> premium
+pub const FALLBACK_BIDS: Map<(String, u128), u8> = Map::new("fallback_bids"); //(bid_for, bid_id) -> premium of active sp_fallback bids
+pub const FALLBACK_CLAIMS: Map<(Addr, String), Uint256> = Map::new("fallback_claims"); //(user, asset) -> claimable amount
+pub const LIQUIDATION_PRICES: Map<String, LiquidationPrices> = Map::new("liquidation_prices"); //bid_for -> prices of the last liquidation, used to price recycle swaps
                                                           //(bid_for, premium, epoch, scale) -> sum_snapshot
 pub const EPOCH_SCALE_SUM: Map<(String, Uint128, Uint128, Uint128), Decimal> =
     Map::new("epoch_scale_sum");
 
-pub const OWNERSHIP_TRANSFER: Item<Addr> = Item::new("ownership_transfer");
\ No newline at end of file
+pub const OWNERSHIP_TRANSFER: Item<Addr> = Item::new("ownership_transfer");
+pub const RECYCLE: Item<RecyclePropagation> = Item::new("recycle"); //Saved between a recycle swap and its reply
+pub const FALLBACK: Item<FallbackPropagation> = Item::new("fallback"); //Saved between a fallback repayment and its distribution
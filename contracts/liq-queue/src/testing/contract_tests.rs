@@ -1,18 +1,24 @@
-use crate::contract::{execute, instantiate, query};
+use crate::bid::RECYCLE_REPLY_ID;
+use crate::contract::{execute, instantiate, migrate, query, reply};
+use crate::state::{OldBid, OldPremiumSlot, OldQueue, FALLBACK_BIDS, OLD_QUEUES, QUEUES};
 use crate::ContractError;
 
+use membrane::cdp::ExecuteMsg as CDP_ExecuteMsg;
 use membrane::liq_queue::{
     BidResponse, Config, ExecuteMsg, InstantiateMsg, QueryMsg, QueueResponse, ClaimsResponse, SlotResponse,
+    FallbackCapitalResponse, MigrateMsg,
 };
 use membrane::math::{Decimal256, Uint256};
 use membrane::osmosis_proxy::ExecuteMsg as OP_ExecuteMsg;
-use membrane::types::{AssetInfo, BidInput};
+use membrane::types::{Asset, AssetInfo, BidInput, PremiumRange};
+use membrane::helpers::router_native_to_native;
 use membrane::oracle::PriceResponse;
+use membrane::pause::{PauseMsg, MAX_GUARDIAN_PAUSE};
 
-use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    attr, from_binary, to_binary, BankMsg, Coin, CosmosMsg, Decimal, StdError, SubMsg, Uint128,
-    WasmMsg, Addr,
+    attr, coin, from_binary, to_binary, BankMsg, Coin, CosmosMsg, Decimal, Order, Reply, StdError, SubMsg,
+    SubMsgResponse, SubMsgResult, Uint128, WasmMsg, Addr,
 };
 
 #[test]
@@ -50,6 +56,7 @@ fn proper_initialization() {
             },
             minimum_bid: Uint128::zero(),
             maximum_waiting_bids: 100u64,
+            dex_router: None,
         }
     );
 }
@@ -78,6 +85,7 @@ fn update_config() {
         waiting_period: None,
         minimum_bid: None,
         maximum_waiting_bids: None,
+        dex_router: None,
     };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -99,6 +107,7 @@ fn update_config() {
             },
             minimum_bid: Uint128::zero(),
             maximum_waiting_bids: 100u64,
+            dex_router: None,
         }
     );
 
@@ -111,6 +120,7 @@ fn update_config() {
         waiting_period: Some(100u64),
         minimum_bid: Some(Uint128::one()),
         maximum_waiting_bids: Some(10),
+        dex_router: None,
     };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -132,6 +142,7 @@ fn update_config() {
             },            
             minimum_bid: Uint128::one(),
             maximum_waiting_bids: 10u64,
+            dex_router: None,
         }
     );
 
@@ -144,6 +155,7 @@ fn update_config() {
         waiting_period: Some(60u64),
         minimum_bid: None,
         maximum_waiting_bids: None,
+        dex_router: None,
     };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg);
@@ -165,6 +177,7 @@ fn update_config() {
          waiting_period: None,
          minimum_bid: None,
          maximum_waiting_bids: None,
+         dex_router: None,
      };
  
      let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -186,6 +199,7 @@ fn update_config() {
              },            
              minimum_bid: Uint128::one(),
              maximum_waiting_bids: 10u64,
+             dex_router: None,
          }
      );
 }
@@ -223,6 +237,10 @@ fn submit_bid() {
                 denom: "reverse_osmo".to_string(),
             },
             liq_premium: 10u8,
+            premium_range: None,
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         },
         bid_owner: None,
     };
@@ -238,6 +256,10 @@ fn submit_bid() {
                 denom: "fury".to_string(),
             },
             liq_premium: 10u8,
+            premium_range: None,
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         },
         bid_owner: None,
     };
@@ -296,6 +318,10 @@ fn submit_bid() {
                 denom: "fury".to_string(),
             },
             liq_premium: 1u8,
+            premium_range: None,
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         },
         bid_owner: None,
     };
@@ -323,6 +349,10 @@ fn submit_bid() {
                 denom: "fury".to_string(),
             },
             liq_premium: 15u8,
+            premium_range: None,
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         },
         bid_owner: None,
     };
@@ -355,6 +385,10 @@ fn submit_bid() {
                 denom: "not_osmo".to_string(),
             },
             liq_premium: 1u8,
+            premium_range: None,
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         },
         bid_owner: None,
     };
@@ -412,6 +446,9 @@ fn submit_bid() {
             wait_end: None,
             epoch_snapshot: Uint128::zero(),
             scale_snapshot: Uint128::zero(),
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         }
     );
 
@@ -429,6 +466,7 @@ fn submit_bid() {
     //Change config to allow 1 waiting bid
     let config_msg = ExecuteMsg::UpdateConfig {
         maximum_waiting_bids: Some(1),
+        dex_router: None,
         positions_contract: None,
         osmosis_proxy_contract: None,
         owner: None,
@@ -446,6 +484,10 @@ fn submit_bid() {
                 denom: "osmo_again".to_string(),
             },
             liq_premium: 10u8,
+            premium_range: None,
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         },
         bid_owner: None,
     };
@@ -488,6 +530,9 @@ fn submit_bid() {
             wait_end: None,
             epoch_snapshot: Uint128::zero(),
             scale_snapshot: Uint128::zero(),
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         }
     );
 
@@ -519,6 +564,9 @@ fn submit_bid() {
             wait_end: Some(1571797479),
             epoch_snapshot: Uint128::zero(),
             scale_snapshot: Uint128::zero(),
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         }
     );
 }
@@ -555,6 +603,10 @@ fn retract_bid() {
                 denom: "fury".to_string(),
             },
             liq_premium: 10u8,
+            premium_range: None,
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         },
         bid_owner: None,
     };
@@ -672,6 +724,10 @@ fn execute_bid() {
                 denom: "fury".to_string(),
             },
             liq_premium: 1u8,
+            premium_range: None,
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         },
         bid_owner: None,
     };
@@ -776,6 +832,98 @@ fn execute_bid() {
     ]);
 }
 
+#[test]
+fn pause_guard() {
+    let mut deps = mock_dependencies();
+
+    let msg = InstantiateMsg {
+        owner: None, //Defaults to sender
+        positions_contract: String::from("positions_contract"),
+        osmosis_proxy_contract: String::from("osmosis_proxy_contract"),
+        waiting_period: 60u64,
+        minimum_bid: Uint128::zero(),
+        maximum_waiting_bids: 100u64,
+    };
+
+    let info = mock_info("owner0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::AddQueue {
+        bid_for: AssetInfo::NativeToken {
+            denom: "fury".to_string(),
+        },
+        max_premium: Uint128::new(10u128),
+        bid_threshold: Uint256::from(1_000_000_000u128),
+    };
+    let info = mock_info("owner0000", &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let bid_msg = ExecuteMsg::SubmitBid {
+        bid_input: BidInput {
+            bid_for: AssetInfo::NativeToken {
+                denom: "fury".to_string(),
+            },
+            liq_premium: 1u8,
+            premium_range: None,
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
+        },
+        bid_owner: None,
+    };
+    let bid_info = mock_info(
+        "owner0000",
+        &[Coin {
+            denom: "cdt".to_string(),
+            amount: Uint128::from(500_000u128),
+        }],
+    );
+    execute(deps.as_mut(), mock_env(), bid_info.clone(), bid_msg.clone()).unwrap();
+
+    let msg = ExecuteMsg::Pause(PauseMsg::SetGuardian { guardian: Some(String::from("guardian")) });
+    execute(deps.as_mut(), mock_env(), mock_info("owner0000", &[]), msg).unwrap();
+
+    //Error: Liquidations can't be paused
+    let msg = ExecuteMsg::Pause(PauseMsg::Pause { actions: vec![String::from("fallback_liquidate")], duration: MAX_GUARDIAN_PAUSE });
+    let err = execute(deps.as_mut(), mock_env(), mock_info("guardian", &[]), msg).unwrap_err();
+    assert_eq!(err, ContractError::Std(StdError::generic_err("fallback_liquidate isn't a pausable action")));
+
+    //Guardian pauses all actions
+    let msg = ExecuteMsg::Pause(PauseMsg::Pause { actions: vec![String::from("all")], duration: MAX_GUARDIAN_PAUSE });
+    execute(deps.as_mut(), mock_env(), mock_info("guardian", &[]), msg).unwrap();
+
+    let err = execute(deps.as_mut(), mock_env(), bid_info.clone(), bid_msg.clone()).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(format!("submit_bid is paused until {}", mock_env().block.time.seconds() + MAX_GUARDIAN_PAUSE)))
+    );
+
+    //Liquidations still go through
+    let liq_msg = ExecuteMsg::Liquidate {
+        credit_price: PriceResponse {
+            prices: vec![],
+            price: Decimal::one(),
+            decimals: 6u64,
+        },
+        collateral_price: PriceResponse {
+            prices: vec![],
+            price: Decimal::one(),
+            decimals: 6u64,
+        },
+        collateral_amount: Uint256::from(5_000u128),
+        bid_for: AssetInfo::NativeToken {
+            denom: "fury".to_string(),
+        },
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("positions_contract", &[]), liq_msg).unwrap();
+    assert_eq!(res.attributes[0], attr("action", "execute_bid"));
+
+    //Guardian unpauses
+    let msg = ExecuteMsg::Pause(PauseMsg::Unpause { actions: vec![String::from("all")] });
+    execute(deps.as_mut(), mock_env(), mock_info("guardian", &[]), msg).unwrap();
+    execute(deps.as_mut(), mock_env(), bid_info, bid_msg).unwrap();
+}
+
 #[test]
 fn claim_liquidations() {
     let mut deps = mock_dependencies();
@@ -808,6 +956,10 @@ fn claim_liquidations() {
                 denom: "fury".to_string(),
             },
             liq_premium: 1u8,
+            premium_range: None,
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         },
         bid_owner: None,
     };
@@ -961,3 +1113,916 @@ fn update_queue() {
     assert_eq!(slots_response[20].liq_premium, "0.2".to_string());
 
 }
+
+#[test]
+fn waiting_bid_activation() {
+    let mut deps = mock_dependencies();
+
+    let msg = InstantiateMsg {
+        owner: None, //Defaults to sender
+        positions_contract: String::from("positions_contract"),
+        osmosis_proxy_contract: String::from("osmosis_proxy_contract"),
+        waiting_period: 60u64,
+        minimum_bid: Uint128::zero(),
+        maximum_waiting_bids: 10u64,
+    };
+
+    let info = mock_info("owner0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let msg = ExecuteMsg::AddQueue {
+        bid_for: AssetInfo::NativeToken {
+            denom: "fury".to_string(),
+        },
+        max_premium: Uint128::new(10u128), //A slot for each premium is created when queue is created
+        bid_threshold: Uint256::from(1_000_000u128),
+    };
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    //Bid that should create 1 active and 1 waiting bid
+    let msg = ExecuteMsg::SubmitBid {
+        bid_input: BidInput {
+            bid_for: AssetInfo::NativeToken {
+                denom: "fury".to_string(),
+            },
+            liq_premium: 0u8,
+            premium_range: None,
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
+        },
+        bid_owner: None,
+    };
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "cdt".to_string(),
+            amount: Uint128::from(2_000_000u128),
+        }],
+    );
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    //Both bids are returned for the user
+    let bids: Vec<BidResponse> = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::BidsByUser {
+                bid_for: AssetInfo::NativeToken {
+                    denom: "fury".to_string(),
+                },
+                user: String::from("addr0000"),
+                limit: None,
+                start_after: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(bids.len(), 2);
+    assert_eq!(bids[0].wait_end, None);
+    assert_eq!(bids[1].wait_end, Some(mock_env().block.time.plus_seconds(60u64).seconds()));
+
+    //Liquidate after the waiting period to activate the waiting bid
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(61u64);
+    let liq_msg = ExecuteMsg::Liquidate {
+        credit_price: PriceResponse {
+            prices: vec![],
+            price: Decimal::one(),
+            decimals: 6u64,
+        },
+        collateral_price: PriceResponse {
+            prices: vec![],
+            price: Decimal::one(),
+            decimals: 6u64,
+        },
+        collateral_amount: Uint256::from(1_000_000u128),
+        bid_for: AssetInfo::NativeToken {
+            denom: "fury".to_string(),
+        },
+    };
+    let info = mock_info("positions_contract", &[]);
+    execute(deps.as_mut(), env.clone(), info, liq_msg).unwrap();
+
+    //Waiting bid was moved to the active bids
+    let slot: SlotResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            env,
+            QueryMsg::PremiumSlot {
+                bid_for: AssetInfo::NativeToken {
+                    denom: "fury".to_string(),
+                },
+                premium: 0u64,
+                start_after: None,
+                waiting_start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(slot.bids.len(), 2);
+    assert_eq!(slot.waiting_bids.len(), 0);
+    assert_eq!(slot.total_bid_amount, Uint256::from(1_000_000u128).to_string());
+}
+
+#[test]
+fn waiting_bid_activation_out_of_order() {
+    let mut deps = mock_dependencies();
+
+    let msg = InstantiateMsg {
+        owner: None, //Defaults to sender
+        positions_contract: String::from("positions_contract"),
+        osmosis_proxy_contract: String::from("osmosis_proxy_contract"),
+        waiting_period: 60u64,
+        minimum_bid: Uint128::zero(),
+        maximum_waiting_bids: 10u64,
+    };
+
+    let info = mock_info("owner0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let msg = ExecuteMsg::AddQueue {
+        bid_for: AssetInfo::NativeToken {
+            denom: "fury".to_string(),
+        },
+        max_premium: Uint128::new(10u128), //A slot for each premium is created when queue is created
+        bid_threshold: Uint256::from(1_000_000u128),
+    };
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let bid_msg = ExecuteMsg::SubmitBid {
+        bid_input: BidInput {
+            bid_for: AssetInfo::NativeToken {
+                denom: "fury".to_string(),
+            },
+            liq_premium: 0u8,
+            premium_range: None,
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
+        },
+        bid_owner: None,
+    };
+    let liq_msg = ExecuteMsg::Liquidate {
+        credit_price: PriceResponse {
+            prices: vec![],
+            price: Decimal::one(),
+            decimals: 6u64,
+        },
+        collateral_price: PriceResponse {
+            prices: vec![],
+            price: Decimal::one(),
+            decimals: 6u64,
+        },
+        collateral_amount: Uint256::from(1_000u128),
+        bid_for: AssetInfo::NativeToken {
+            denom: "fury".to_string(),
+        },
+    };
+
+    //Fill the slot past the threshold
+    execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[coin(2_000_000, "cdt")]), bid_msg.clone()).unwrap();
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(61u64);
+    execute(deps.as_mut(), env.clone(), mock_info("positions_contract", &[]), liq_msg.clone()).unwrap();
+
+    //Bid waits 60 seconds
+    execute(deps.as_mut(), env.clone(), mock_info("addr0001", &[coin(1_000_000, "cdt")]), bid_msg.clone()).unwrap();
+
+    //Shorten the waiting period so the next bid ends its wait first
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        positions_contract: None,
+        osmosis_proxy_contract: None,
+        waiting_period: Some(10u64),
+        minimum_bid: None,
+        maximum_waiting_bids: None,
+        dex_router: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info("owner0000", &[]), msg).unwrap();
+    execute(deps.as_mut(), env.clone(), mock_info("addr0002", &[coin(1_000_000, "cdt")]), bid_msg).unwrap();
+
+    //Liquidate after the later bid's wait ends
+    env.block.time = env.block.time.plus_seconds(19u64);
+    execute(deps.as_mut(), env.clone(), mock_info("positions_contract", &[]), liq_msg).unwrap();
+
+    //The later bid is activated even though the earlier bid is still waiting
+    let slot: SlotResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            env,
+            QueryMsg::PremiumSlot {
+                bid_for: AssetInfo::NativeToken {
+                    denom: "fury".to_string(),
+                },
+                premium: 0u64,
+                start_after: None,
+                waiting_start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(slot.waiting_bids.len(), 1);
+    assert_eq!(slot.waiting_bids[0].user, Addr::unchecked("addr0001"));
+    assert!(slot.bids.iter().any(|bid| bid.user == Addr::unchecked("addr0002")));
+}
+
+#[test]
+fn range_bids() {
+    let mut deps = mock_dependencies();
+
+    let msg = InstantiateMsg {
+        owner: None, //Defaults to sender
+        positions_contract: String::from("positions_contract"),
+        osmosis_proxy_contract: String::from("osmosis_proxy_contract"),
+        waiting_period: 60u64,
+        minimum_bid: Uint128::zero(),
+        maximum_waiting_bids: 100u64,
+    };
+
+    let info = mock_info("owner0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let msg = ExecuteMsg::AddQueue {
+        bid_for: AssetInfo::NativeToken {
+            denom: "fury".to_string(),
+        },
+        max_premium: Uint128::new(10u128), //A slot for each premium is created when queue is created
+        bid_threshold: Uint256::from(1_000_000_000u128),
+    };
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let submit_info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "cdt".to_string(),
+            amount: Uint128::from(1_000u128),
+        }],
+    );
+
+    //Range outside of the queue's max premium
+    let msg = ExecuteMsg::SubmitBid {
+        bid_input: BidInput {
+            bid_for: AssetInfo::NativeToken {
+                denom: "fury".to_string(),
+            },
+            liq_premium: 0u8,
+            premium_range: Some(PremiumRange {
+                min_premium: 8u8,
+                max_premium: 11u8,
+                weights: None,
+            }),
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
+        },
+        bid_owner: None,
+    };
+    let err = execute(deps.as_mut(), mock_env(), submit_info.clone(), msg).unwrap_err();
+    assert_eq!(err, ContractError::InvalidPremium {});
+
+    //Weights that don't sum to 1
+    let msg = ExecuteMsg::SubmitBid {
+        bid_input: BidInput {
+            bid_for: AssetInfo::NativeToken {
+                denom: "fury".to_string(),
+            },
+            liq_premium: 0u8,
+            premium_range: Some(PremiumRange {
+                min_premium: 4u8,
+                max_premium: 5u8,
+                weights: Some(vec![Decimal::percent(25), Decimal::percent(50)]),
+            }),
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
+        },
+        bid_owner: None,
+    };
+    let err = execute(deps.as_mut(), mock_env(), submit_info.clone(), msg).unwrap_err();
+    assert_eq!(err, ContractError::InvalidBidWeights {});
+
+    //Even split, remainder goes to the last premium
+    let msg = ExecuteMsg::SubmitBid {
+        bid_input: BidInput {
+            bid_for: AssetInfo::NativeToken {
+                denom: "fury".to_string(),
+            },
+            liq_premium: 0u8,
+            premium_range: Some(PremiumRange {
+                min_premium: 1u8,
+                max_premium: 3u8,
+                weights: None,
+            }),
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
+        },
+        bid_owner: None,
+    };
+    execute(deps.as_mut(), mock_env(), submit_info.clone(), msg).unwrap();
+
+    //Weighted split
+    let msg = ExecuteMsg::SubmitBid {
+        bid_input: BidInput {
+            bid_for: AssetInfo::NativeToken {
+                denom: "fury".to_string(),
+            },
+            liq_premium: 0u8,
+            premium_range: Some(PremiumRange {
+                min_premium: 4u8,
+                max_premium: 5u8,
+                weights: Some(vec![Decimal::percent(25), Decimal::percent(75)]),
+            }),
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
+        },
+        bid_owner: None,
+    };
+    execute(deps.as_mut(), mock_env(), submit_info, msg).unwrap();
+
+    let bids: Vec<BidResponse> = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::BidsByUser {
+                bid_for: AssetInfo::NativeToken {
+                    denom: "fury".to_string(),
+                },
+                user: String::from("addr0000"),
+                limit: None,
+                start_after: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        bids.into_iter()
+            .map(|bid| (bid.liq_premium, bid.amount))
+            .collect::<Vec<(u8, Uint256)>>(),
+        vec![
+            (1u8, Uint256::from(333u128)),
+            (2u8, Uint256::from(333u128)),
+            (3u8, Uint256::from(334u128)),
+            (4u8, Uint256::from(250u128)),
+            (5u8, Uint256::from(750u128)),
+        ]
+    );
+
+    //Each split must be at least the minimum bid
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        positions_contract: None,
+        osmosis_proxy_contract: None,
+        waiting_period: None,
+        minimum_bid: Some(Uint128::new(500u128)),
+        maximum_waiting_bids: None,
+        dex_router: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner0000", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::SubmitBid {
+        bid_input: BidInput {
+            bid_for: AssetInfo::NativeToken {
+                denom: "fury".to_string(),
+            },
+            liq_premium: 0u8,
+            premium_range: Some(PremiumRange {
+                min_premium: 4u8,
+                max_premium: 5u8,
+                weights: Some(vec![Decimal::percent(25), Decimal::percent(75)]),
+            }),
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
+        },
+        bid_owner: None,
+    };
+    let err = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[coin(1_000, "cdt")]), msg).unwrap_err();
+    assert_eq!(err, ContractError::BidTooSmall { minimum: Uint128::new(500u128) });
+}
+
+#[test]
+fn recycle_claims() {
+    let mut deps = mock_dependencies();
+
+    let msg = InstantiateMsg {
+        owner: None, //Defaults to sender
+        positions_contract: String::from("positions_contract"),
+        osmosis_proxy_contract: String::from("osmosis_proxy_contract"),
+        waiting_period: 60u64,
+        minimum_bid: Uint128::zero(),
+        maximum_waiting_bids: 100u64,
+    };
+
+    let info = mock_info("owner0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        positions_contract: None,
+        osmosis_proxy_contract: None,
+        waiting_period: None,
+        minimum_bid: None,
+        maximum_waiting_bids: None,
+        dex_router: Some(String::from("router")),
+    };
+    execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let msg = ExecuteMsg::AddQueue {
+        bid_for: AssetInfo::NativeToken {
+            denom: "fury".to_string(),
+        },
+        max_premium: Uint128::new(10u128), //A slot for each premium is created when queue is created
+        bid_threshold: Uint256::from(1_000_000_000u128),
+    };
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::SubmitBid {
+        bid_input: BidInput {
+            bid_for: AssetInfo::NativeToken {
+                denom: "fury".to_string(),
+            },
+            liq_premium: 0u8,
+            premium_range: None,
+            auto_recycle: true,
+            sp_fallback: false,
+            max_slippage: Decimal::percent(2),
+        },
+        bid_owner: None,
+    };
+    let submit_info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "cdt".to_string(),
+            amount: Uint128::from(1_000_000u128),
+        }],
+    );
+    execute(deps.as_mut(), mock_env(), submit_info, msg).unwrap();
+    deps.querier.update_balance(MOCK_CONTRACT_ADDR, vec![coin(1_000_000, "cdt")]);
+
+    let liq_msg = ExecuteMsg::Liquidate {
+        credit_price: PriceResponse {
+            prices: vec![],
+            price: Decimal::one(),
+            decimals: 6u64,
+        },
+        collateral_price: PriceResponse {
+            prices: vec![],
+            price: Decimal::one(),
+            decimals: 6u64,
+        },
+        collateral_amount: Uint256::from(5000u128),
+        bid_for: AssetInfo::NativeToken {
+            denom: "fury".to_string(),
+        },
+    };
+    let info = mock_info("positions_contract", &[]);
+    execute(deps.as_mut(), mock_env(), info, liq_msg).unwrap();
+
+    //Collateral is swapped instead of sent to the bidder
+    let msg = ExecuteMsg::ClaimLiquidations {
+        bid_for: AssetInfo::NativeToken {
+            denom: "fury".to_string(),
+        },
+        bid_ids: None,
+    };
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(res.messages.len(), 1);
+    assert_eq!(res.messages[0].id, RECYCLE_REPLY_ID);
+    //Minimum receive is the collateral's value at the liquidation prices less the bid's 2% max slippage
+    assert_eq!(
+        res.messages[0].msg,
+        router_native_to_native(
+            String::from("router"),
+            AssetInfo::NativeToken { denom: "fury".to_string() },
+            AssetInfo::NativeToken { denom: "cdt".to_string() },
+            None,
+            5000u128,
+            Some(Uint128::new(4_900u128)),
+        ).unwrap()
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "claim_liquidations"),
+            attr("collateral_token", "fury"),
+            attr("collateral_amount", "0"),
+            attr("recycled_collateral_amount", "5000"),
+        ]
+    );
+
+    //Router swap returns 4990 of the bid asset
+    deps.querier.update_balance(MOCK_CONTRACT_ADDR, vec![coin(1_004_990, "cdt")]);
+    reply(
+        deps.as_mut(),
+        mock_env(),
+        Reply {
+            id: RECYCLE_REPLY_ID,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: None,
+            }),
+        },
+    )
+    .unwrap();
+
+    //Swapped amount is re-bid in the same slot
+    let bids: Vec<BidResponse> = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::BidsByUser {
+                bid_for: AssetInfo::NativeToken {
+                    denom: "fury".to_string(),
+                },
+                user: String::from("addr0000"),
+                limit: None,
+                start_after: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        bids.into_iter()
+            .map(|bid| (bid.liq_premium, bid.amount, bid.auto_recycle))
+            .collect::<Vec<(u8, Uint256, bool)>>(),
+        vec![
+            (0u8, Uint256::from(995_000u128), true),
+            (0u8, Uint256::from(4_990u128), true),
+        ]
+    );
+
+    //Failed swaps send the collateral to the bidder
+    let liq_msg = ExecuteMsg::Liquidate {
+        credit_price: PriceResponse {
+            prices: vec![],
+            price: Decimal::one(),
+            decimals: 6u64,
+        },
+        collateral_price: PriceResponse {
+            prices: vec![],
+            price: Decimal::one(),
+            decimals: 6u64,
+        },
+        collateral_amount: Uint256::from(5000u128),
+        bid_for: AssetInfo::NativeToken {
+            denom: "fury".to_string(),
+        },
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("positions_contract", &[]), liq_msg.clone()).unwrap();
+
+    let claim_msg = ExecuteMsg::ClaimLiquidations {
+        bid_for: AssetInfo::NativeToken {
+            denom: "fury".to_string(),
+        },
+        bid_ids: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), claim_msg.clone()).unwrap();
+    let recycled_amount: u128 = res.attributes
+        .into_iter()
+        .find(|attr| attr.key == "recycled_collateral_amount")
+        .unwrap()
+        .value
+        .parse()
+        .unwrap();
+
+    let res = reply(
+        deps.as_mut(),
+        mock_env(),
+        Reply {
+            id: RECYCLE_REPLY_ID,
+            result: SubMsgResult::Err(String::from("Minimum receive not met")),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: String::from("addr0000"),
+            amount: vec![coin(recycled_amount, "fury")],
+        }))]
+    );
+
+    //Amounts below the minimum bid can't be re-bid so they're sent to the bidder
+    execute(deps.as_mut(), mock_env(), mock_info("positions_contract", &[]), liq_msg).unwrap();
+    execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), claim_msg).unwrap();
+
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        positions_contract: None,
+        osmosis_proxy_contract: None,
+        waiting_period: None,
+        minimum_bid: Some(Uint128::new(10_000u128)),
+        maximum_waiting_bids: None,
+        dex_router: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner0000", &[]), msg).unwrap();
+
+    deps.querier.update_balance(MOCK_CONTRACT_ADDR, vec![coin(1_009_980, "cdt")]);
+    let res = reply(
+        deps.as_mut(),
+        mock_env(),
+        Reply {
+            id: RECYCLE_REPLY_ID,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: None,
+            }),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: String::from("addr0000"),
+            amount: vec![coin(4_990, "cdt")],
+        }))]
+    );
+}
+
+#[test]
+fn sp_fallback_bids() {
+    let mut deps = mock_dependencies();
+
+    let msg = InstantiateMsg {
+        owner: None, //Defaults to sender
+        positions_contract: String::from("positions_contract"),
+        osmosis_proxy_contract: String::from("osmosis_proxy_contract"),
+        waiting_period: 60u64,
+        minimum_bid: Uint128::zero(),
+        maximum_waiting_bids: 100u64,
+    };
+
+    let info = mock_info("owner0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let msg = ExecuteMsg::AddQueue {
+        bid_for: AssetInfo::NativeToken {
+            denom: "fury".to_string(),
+        },
+        max_premium: Uint128::new(10u128), //A slot for each premium is created when queue is created
+        bid_threshold: Uint256::from(1_000_000_000u128),
+    };
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    //Fallback bid & regular bid
+    for (user, sp_fallback) in vec![("addr0000", true), ("addr0001", false)] {
+        let msg = ExecuteMsg::SubmitBid {
+            bid_input: BidInput {
+                bid_for: AssetInfo::NativeToken {
+                    denom: "fury".to_string(),
+                },
+                liq_premium: 1u8,
+                premium_range: None,
+                auto_recycle: false,
+                sp_fallback,
+                max_slippage: Decimal::zero(),
+            },
+            bid_owner: None,
+        };
+        let submit_info = mock_info(
+            user,
+            &[Coin {
+                denom: "cdt".to_string(),
+                amount: Uint128::from(1_000_000u128),
+            }],
+        );
+        execute(deps.as_mut(), mock_env(), submit_info, msg).unwrap();
+    }
+
+    //Only the fallback bid is capital
+    let capital: FallbackCapitalResponse = from_binary(
+        &query(deps.as_ref(), mock_env(), QueryMsg::FallbackCapital {}).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(capital.capital, Uint256::from(1_000_000u128));
+
+    //Unauthorized
+    let msg = ExecuteMsg::FallbackLiquidate {
+        liq_amount: Decimal::from_ratio(400_000u128, 1u128),
+    };
+    let err = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    //Fallback bid repays for the Positions contract
+    let res = execute(deps.as_mut(), mock_env(), mock_info("positions_contract", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from("positions_contract"),
+            msg: to_binary(&CDP_ExecuteMsg::LiqRepay {}).unwrap(),
+            funds: vec![Coin {
+                denom: "cdt".to_string(),
+                amount: Uint128::from(400_000u128),
+            }],
+        }))]
+    );
+
+    let capital: FallbackCapitalResponse = from_binary(
+        &query(deps.as_ref(), mock_env(), QueryMsg::FallbackCapital {}).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(capital.capital, Uint256::from(600_000u128));
+
+    //Positions contract distributes collateral at the SP premium
+    let msg = ExecuteMsg::DistributeFallback {
+        distribution_assets: vec![Asset {
+            info: AssetInfo::NativeToken {
+                denom: "fury".to_string(),
+            },
+            amount: Uint128::from(440_000u128),
+        }],
+        distribute_for: Uint128::from(400_000u128),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("positions_contract", &[]), msg).unwrap();
+
+    let claims: Vec<ClaimsResponse> = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::UserClaims {
+                user: String::from("addr0000"),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        claims,
+        vec![ClaimsResponse {
+            bid_for: String::from("fury"),
+            pending_liquidated_collateral: Uint256::from(440_000u128),
+        }]
+    );
+
+    //Claim
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), ExecuteMsg::ClaimFallback {}).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: String::from("addr0000"),
+            amount: vec![Coin {
+                denom: "fury".to_string(),
+                amount: Uint128::from(440_000u128),
+            }],
+        }))]
+    );
+    execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), ExecuteMsg::ClaimFallback {}).unwrap_err();
+
+    //Drawing the rest of the bid removes it from the fallback bids
+    let msg = ExecuteMsg::FallbackLiquidate {
+        liq_amount: Decimal::from_ratio(700_000u128, 1u128),
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("positions_contract", &[]), msg.clone()).unwrap();
+    assert_eq!(res.attributes[1], attr("repay_amount", "600000"));
+    assert_eq!(
+        FALLBACK_BIDS.keys(&deps.storage, None, None, Order::Ascending).count(),
+        0
+    );
+
+    let capital: FallbackCapitalResponse = from_binary(
+        &query(deps.as_ref(), mock_env(), QueryMsg::FallbackCapital {}).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(capital.capital, Uint256::zero());
+
+    //Nothing left to draw
+    let res = execute(deps.as_mut(), mock_env(), mock_info("positions_contract", &[]), msg).unwrap();
+    assert!(res.messages.is_empty());
+}
+
+#[test]
+fn migrate_slot_bids() {
+    let mut deps = mock_dependencies();
+
+    let msg = InstantiateMsg {
+        owner: None, //Defaults to sender
+        positions_contract: String::from("positions_contract"),
+        osmosis_proxy_contract: String::from("osmosis_proxy_contract"),
+        waiting_period: 60u64,
+        minimum_bid: Uint128::zero(),
+        maximum_waiting_bids: 10u64,
+    };
+    let info = mock_info("owner0000", &[]);
+    instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    //Save a queue w/ inline bids
+    let old_bid = |id: u128, wait_end: Option<u64>| OldBid {
+        user: Addr::unchecked("addr0000"),
+        id: Uint128::new(id),
+        amount: Uint256::from(1_000_000u128),
+        liq_premium: 0u8,
+        product_snapshot: Decimal256::one(),
+        sum_snapshot: Decimal256::zero(),
+        pending_liquidated_collateral: Uint256::zero(),
+        wait_end,
+        epoch_snapshot: Uint128::zero(),
+        scale_snapshot: Uint128::zero(),
+    };
+    let old_slot = |premium: u64, bids: Vec<OldBid>, waiting_bids: Vec<OldBid>| OldPremiumSlot {
+        bids,
+        waiting_bids,
+        liq_premium: Decimal256::percent(premium),
+        sum_snapshot: Decimal256::zero(),
+        product_snapshot: Decimal256::one(),
+        total_bid_amount: Uint256::from(2_000_000u128),
+        last_total: 0u64,
+        current_epoch: Uint128::zero(),
+        current_scale: Uint128::zero(),
+        residue_collateral: Decimal256::zero(),
+        residue_bid: Decimal256::zero(),
+    };
+    OLD_QUEUES.save(deps.as_mut().storage, String::from("fury"), &OldQueue {
+        bid_asset: Asset {
+            info: AssetInfo::NativeToken { denom: "cdt".to_string() },
+            amount: Uint128::zero(),
+        },
+        max_premium: Uint128::new(1u128),
+        slots: vec![
+            old_slot(0, vec![old_bid(1, None), old_bid(2, None)], vec![old_bid(3, Some(100u64))]),
+            old_slot(1, vec![], vec![]),
+        ],
+        current_bid_id: Uint128::new(4u128),
+        bid_threshold: Uint256::from(1_000_000u128),
+    }).unwrap();
+
+    //Bids are moved a page at a time, the queue keeps the old layout until all are moved
+    let res = migrate(deps.as_mut(), mock_env(), MigrateMsg { limit: Some(2) }).unwrap();
+    assert_eq!(res.attributes[1], attr("migrated_bids", "2"));
+    assert!(OLD_QUEUES.load(deps.as_ref().storage, String::from("fury")).is_ok());
+
+    let res = migrate(deps.as_mut(), mock_env(), MigrateMsg { limit: Some(2) }).unwrap();
+    assert_eq!(res.attributes[1], attr("migrated_bids", "1"));
+
+    //Page through the slot's bids, active & waiting bids have their own cursors
+    let query_slot = |start_after: Option<Uint128>, waiting_start_after: Option<Uint128>| QueryMsg::PremiumSlot {
+        bid_for: AssetInfo::NativeToken {
+            denom: "fury".to_string(),
+        },
+        premium: 0u64,
+        start_after,
+        waiting_start_after,
+        limit: Some(1u32),
+    };
+    let slot: SlotResponse = from_binary(&query(deps.as_ref(), mock_env(), query_slot(None, None)).unwrap()).unwrap();
+    assert_eq!(slot.bids.len(), 1);
+    assert_eq!(slot.bids[0].id, Uint128::new(1u128));
+    assert_eq!(slot.waiting_bids[0].id, Uint128::new(3u128));
+    assert_eq!(slot.total_bid_amount, Uint256::from(2_000_000u128).to_string());
+
+    let slot: SlotResponse = from_binary(&query(deps.as_ref(), mock_env(), query_slot(Some(Uint128::new(1u128)), None)).unwrap()).unwrap();
+    assert_eq!(slot.bids[0].id, Uint128::new(2u128));
+    assert_eq!(slot.waiting_bids[0].id, Uint128::new(3u128));
+
+    let slot: SlotResponse = from_binary(&query(deps.as_ref(), mock_env(), query_slot(None, Some(Uint128::new(3u128)))).unwrap()).unwrap();
+    assert_eq!(slot.bids[0].id, Uint128::new(1u128));
+    assert!(slot.waiting_bids.is_empty());
+
+    //Waiting bids are counted once the queue is moved
+    let queue = QUEUES.load(deps.as_ref().storage, String::from("fury")).unwrap();
+    assert_eq!(queue.slots[0].waiting_bid_count, 1u64);
+
+    //PremiumSlots only returns aggregates
+    let slots: Vec<SlotResponse> = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::PremiumSlots {
+        bid_for: AssetInfo::NativeToken {
+            denom: "fury".to_string(),
+        },
+        start_after: None,
+        limit: None,
+    }).unwrap()).unwrap();
+    assert_eq!(slots.len(), 2);
+    assert!(slots[0].bids.is_empty());
+
+    //Bids are indexed by user
+    let bids: Vec<BidResponse> = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::BidsByUser {
+                bid_for: AssetInfo::NativeToken {
+                    denom: "fury".to_string(),
+                },
+                user: String::from("addr0000"),
+                limit: None,
+                start_after: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(bids.len(), 3);
+
+    //Migrated queues are skipped
+    let res = migrate(deps.as_mut(), mock_env(), MigrateMsg { limit: None }).unwrap();
+    assert_eq!(res.attributes[1], attr("migrated_bids", "0"));
+}
@@ -40,6 +40,10 @@ fn one_bidder_distribution() {
                 denom: "gamm/pool/5".to_string(),
             },
             liq_premium: 1u8,
+            premium_range: None,
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         },
         bid_owner: None,
     };
@@ -142,6 +146,10 @@ fn two_bidder_distribution() {
                 denom: "fury".to_string(),
             },
             liq_premium: 0u8,
+            premium_range: None,
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         },
         bid_owner: None,
     };
@@ -182,6 +190,10 @@ fn two_bidder_distribution() {
                 denom: "fury".to_string(),
             },
             liq_premium: 0u8,
+            premium_range: None,
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         },
         bid_owner: None,
     };
@@ -317,6 +329,10 @@ fn two_bidder_distribution_big_number() {
                 denom: "fury".to_string(),
             },
             liq_premium: 0u8,
+            premium_range: None,
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         },
         bid_owner: None,
     };
@@ -357,6 +373,10 @@ fn two_bidder_distribution_big_number() {
                 denom: "fury".to_string(),
             },
             liq_premium: 0u8,
+            premium_range: None,
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         },
         bid_owner: None,
     };
@@ -492,6 +512,10 @@ fn one_user_two_slots() {
                 denom: "fury".to_string(),
             },
             liq_premium: 5u8,
+            premium_range: None,
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         },
         bid_owner: None,
     };
@@ -512,6 +536,10 @@ fn one_user_two_slots() {
                 denom: "fury".to_string(),
             },
             liq_premium: 10u8,
+            premium_range: None,
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         },
         bid_owner: None,
     };
@@ -665,6 +693,10 @@ fn completely_empty_pool() {
                 denom: "fury".to_string(),
             },
             liq_premium: 0u8,
+            premium_range: None,
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         },
         bid_owner: None,
     };
@@ -705,6 +737,10 @@ fn completely_empty_pool() {
                 denom: "fury".to_string(),
             },
             liq_premium: 0u8,
+            premium_range: None,
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         },
         bid_owner: None,
     };
@@ -733,6 +769,9 @@ fn completely_empty_pool() {
             denom: "fury".to_string(),
         },
         premium: 0u64,
+        start_after: None,
+        waiting_start_after: None,
+        limit: None,
     };
     let res = query(deps.as_ref(), mock_env(), msg).unwrap();
     let resp: SlotResponse = from_binary(&res).unwrap();
@@ -853,6 +892,10 @@ fn product_truncated_to_zero() {
                     denom: "gamm/pool/5".to_string(),
                 },
                 liq_premium: 0u8,
+                premium_range: None,
+                auto_recycle: false,
+                sp_fallback: false,
+                max_slippage: Decimal::zero(),
             },
             bid_owner: None,
         };
@@ -913,6 +956,9 @@ fn product_truncated_to_zero() {
                     denom: "gamm/pool/5".to_string(),
                 },
                 premium: 0u64,
+                start_after: None,
+                waiting_start_after: None,
+                limit: None,
             },
         )
         .unwrap(),
@@ -973,6 +1019,10 @@ fn two_bidder_distribution_multiple_common_slots() {
                 denom: "fury".to_string(),
             },
             liq_premium: 5u8,
+            premium_range: None,
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         },
         bid_owner: None,
     };
@@ -994,6 +1044,10 @@ fn two_bidder_distribution_multiple_common_slots() {
                 denom: "fury".to_string(),
             },
             liq_premium: 5u8,
+            premium_range: None,
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         },
         bid_owner: None,
     };
@@ -1013,6 +1067,10 @@ fn two_bidder_distribution_multiple_common_slots() {
                 denom: "fury".to_string(),
             },
             liq_premium: 10u8,
+            premium_range: None,
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         },
         bid_owner: None,
     };
@@ -1034,6 +1092,10 @@ fn two_bidder_distribution_multiple_common_slots() {
                 denom: "fury".to_string(),
             },
             liq_premium: 10u8,
+            premium_range: None,
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         },
         bid_owner: None,
     };
@@ -1178,6 +1240,10 @@ fn scalable_reward_distribution_after_multiple_liquidations() {
                 denom: "fury".to_string(),
             },
             liq_premium: 0u8,
+            premium_range: None,
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         },
         bid_owner: None,
     };
@@ -1198,6 +1264,10 @@ fn scalable_reward_distribution_after_multiple_liquidations() {
                 denom: "fury".to_string(),
             },
             liq_premium: 0u8,
+            premium_range: None,
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         },
         bid_owner: None,
     };
@@ -1217,6 +1287,10 @@ fn scalable_reward_distribution_after_multiple_liquidations() {
                 denom: "fury".to_string(),
             },
             liq_premium: 0u8,
+            premium_range: None,
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         },
         bid_owner: None,
     };
@@ -1261,6 +1335,10 @@ fn scalable_reward_distribution_after_multiple_liquidations() {
                 denom: "fury".to_string(),
             },
             liq_premium: 0u8,
+            premium_range: None,
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         },
         bid_owner: None,
     };
@@ -1281,6 +1359,10 @@ fn scalable_reward_distribution_after_multiple_liquidations() {
                 denom: "fury".to_string(),
             },
             liq_premium: 0u8,
+            premium_range: None,
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         },
         bid_owner: None,
     };
@@ -1386,6 +1468,10 @@ fn not_enough_bid_for_collateral() {
                 denom: "fury".to_string(),
             },
             liq_premium: 6u8,
+            premium_range: None,
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         },
         bid_owner: None,
     };
@@ -1406,6 +1492,10 @@ fn not_enough_bid_for_collateral() {
                 denom: "fury".to_string(),
             },
             liq_premium: 6u8,
+            premium_range: None,
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         },
         bid_owner: None,
     };
@@ -166,6 +166,10 @@ fn simulate_bids_with_2_liq_amounts(
                     denom: "fury".to_string(),
                 },
                 liq_premium: 0u8,
+                premium_range: None,
+                auto_recycle: false,
+                sp_fallback: false,
+                max_slippage: Decimal::zero(),
             },
             bid_owner: None,
         };
@@ -39,6 +39,10 @@ fn partial_one_collateral_one_slot() {
                 denom: "fury".to_string(),
             },
             liq_premium: 0u8,
+            premium_range: None,
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         },
         bid_owner: None,
     };
@@ -128,6 +132,10 @@ fn partial_one_collateral_one_slot_w_fees() {
                 denom: "fury".to_string(),
             },
             liq_premium: 10u8,
+            premium_range: None,
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         },
         bid_owner: None,
     };
@@ -216,6 +224,10 @@ fn one_collateral_one_slot() {
                 denom: "fury".to_string(),
             },
             liq_premium: 0u8,
+            premium_range: None,
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         },
         bid_owner: None,
     };
@@ -305,6 +317,10 @@ fn one_collateral_one_slot_w_fees() {
                 denom: "fury".to_string(),
             },
             liq_premium: 10u8,
+            premium_range: None,
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         },
         bid_owner: None,
     };
@@ -394,6 +410,10 @@ fn two_slot_w_fees() {
                 denom: "fury".to_string(),
             },
             liq_premium: 0u8,
+            premium_range: None,
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         },
         bid_owner: None,
     };
@@ -414,6 +434,10 @@ fn two_slot_w_fees() {
                 denom: "fury".to_string(),
             },
             liq_premium: 10u8,
+            premium_range: None,
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         },
         bid_owner: None,
     };
@@ -502,6 +526,10 @@ fn partial_two_slot_w_fees() {
                 denom: "fury".to_string(),
             },
             liq_premium: 0u8,
+            premium_range: None,
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         },
         bid_owner: None,
     };
@@ -522,6 +550,10 @@ fn partial_two_slot_w_fees() {
                 denom: "fury".to_string(),
             },
             liq_premium: 10u8,
+            premium_range: None,
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         },
         bid_owner: None,
     };
@@ -611,6 +643,10 @@ fn partial_two_slot_w_fees_bignums() {
                 denom: "fury".to_string(),
             },
             liq_premium: 0u8,
+            premium_range: None,
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         },
         bid_owner: None,
     };
@@ -631,6 +667,10 @@ fn partial_two_slot_w_fees_bignums() {
                 denom: "fury".to_string(),
             },
             liq_premium: 10u8,
+            premium_range: None,
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         },
         bid_owner: None,
     };
@@ -43,6 +43,10 @@ fn query_liquidatible() {
                 denom: "fury".to_string(),
             },
             liq_premium: 1u8,
+            premium_range: None,
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         },
         bid_owner: None,
     };
@@ -119,6 +123,10 @@ fn query_bid() {
                 denom: "fury".to_string(),
             },
             liq_premium: 1u8,
+            premium_range: None,
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         },
         bid_owner: None,
     };
@@ -156,6 +164,9 @@ fn query_bid() {
             wait_end: None,
             epoch_snapshot: Uint128::zero(),
             scale_snapshot: Uint128::zero(),
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         }
     );
 
@@ -166,6 +177,10 @@ fn query_bid() {
                 denom: "fury".to_string(),
             },
             liq_premium: 10u8,
+            premium_range: None,
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         },
         bid_owner: None,
     };
@@ -206,6 +221,9 @@ fn query_bid() {
                 wait_end: None,
                 epoch_snapshot: Uint128::zero(),
                 scale_snapshot: Uint128::zero(),
+                auto_recycle: false,
+                sp_fallback: false,
+                max_slippage: Decimal::zero(),
             },
             BidResponse {
                 user: String::from("owner0000"),
@@ -218,6 +236,9 @@ fn query_bid() {
                 wait_end: None,
                 epoch_snapshot: Uint128::zero(),
                 scale_snapshot: Uint128::zero(),
+                auto_recycle: false,
+                sp_fallback: false,
+                max_slippage: Decimal::zero(),
             },
         ]
     );
@@ -339,6 +360,10 @@ fn query_slots_queues() {
                 denom: "fury".to_string(),
             },
             liq_premium: 1u8,
+            premium_range: None,
+            auto_recycle: false,
+            sp_fallback: false,
+            max_slippage: Decimal::zero(),
         },
         bid_owner: None,
     };
@@ -359,6 +384,9 @@ fn query_slots_queues() {
             denom: "fury".to_string(),
         },
         premium: 1u64,
+        start_after: None,
+        waiting_start_after: None,
+        limit: None,
     };
 
     let res = query(deps.as_ref(), mock_env(), msg).unwrap();
@@ -377,6 +405,9 @@ fn query_slots_queues() {
                 wait_end: None,
                 epoch_snapshot: Uint128::zero(),
                 scale_snapshot: Uint128::zero(),
+                auto_recycle: false,
+                sp_fallback: false,
+                max_slippage: Decimal::zero(),
             }],
             waiting_bids: vec![],
             liq_premium: Decimal256::percent(1).to_string(),
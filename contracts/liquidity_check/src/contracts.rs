@@ -6,6 +6,7 @@ use cosmwasm_std::{
 };
 use cw2::set_contract_version;
 
+use membrane::pause::{assert_not_paused, execute_pause, PauseActions};
 use membrane::liquidity_check::{Config, ExecuteMsg, InstantiateMsg, QueryMsg, LiquidityResponse};
 use membrane::osmosis_proxy::QueryMsg as OsmoQueryMsg;
 use membrane::types::{AssetInfo, LiquidityInfo, PoolStateResponse, PoolType};
@@ -19,6 +20,12 @@ use crate::state::{ASSETS, CONFIG, OWNERSHIP_TRANSFER};
 const CONTRACT_NAME: &str = "liquidity_check";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+//Execute msgs handled by the pause guard
+const PAUSE_ACTIONS: PauseActions = PauseActions {
+    pausable: &["add_asset", "edit_asset", "remove_asset", "update_config"],
+    unpausable: &[],
+};
+
 //Constants
 const MAX_LIMIT: u64 = 31u64;
 
@@ -61,10 +68,13 @@ pub fn instantiate(
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
+    //Check the pause guard
+    assert_not_paused(deps.storage, &env, &msg, &PAUSE_ACTIONS)?;
+
     match msg {
         ExecuteMsg::AddAsset { asset } => add_asset(deps, info, asset),
         ExecuteMsg::EditAsset { asset } => edit_asset(deps, info, asset),
@@ -75,6 +85,10 @@ pub fn execute(
             positions_contract,
             stableswap_multiplier,
         } => update_config(deps, info, owner, osmosis_proxy, positions_contract, stableswap_multiplier),
+        ExecuteMsg::Pause(pause_msg) => {
+            let config = CONFIG.load(deps.storage)?;
+            Ok(execute_pause(deps.storage, deps.api, &env, &info, info.sender == config.owner, &PAUSE_ACTIONS, pause_msg)?)
+        }
     }
 }
 
@@ -404,6 +404,7 @@ fn handle_loop_reply(
                     collateral,          
                     Some(config.clone().positions_contract.to_string()),
                     (credit_to_sell * Uint128::new(1u128)).u128(),
+                    None,
                 )?;
                 //Add a reply msg to execute the hook msg
                 messages.push(SubMsg::new(msg));
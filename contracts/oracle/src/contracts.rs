@@ -1,3 +1,4 @@
+use std::convert::TryFrom;
 use std::str::FromStr;
 
 use cosmwasm_std::{
@@ -10,11 +11,12 @@ use pyth_sdk_cw::{PriceFeedResponse, query_price_feed, PriceIdentifier, PriceFee
 
 use osmosis_std::types::osmosis::twap::v1beta1 as TWAP;
 
+use membrane::pause::{assert_not_paused, execute_pause, PauseActions};
 use membrane::math::{decimal_division, decimal_multiplication};
 use membrane::cdp::QueryMsg as CDP_QueryMsg;
 use membrane::osmosis_proxy::{QueryMsg as OP_QueryMsg, Config as OP_Config};
-use membrane::oracle::{Config, AssetResponse, ExecuteMsg, InstantiateMsg, PriceResponse, QueryMsg, MigrateMsg};
-use membrane::types::{AssetInfo, AssetOracleInfo, PriceInfo, Basket, TWAPPoolInfo, PoolInfo, Owner, PoolStateResponse};
+use membrane::oracle::{Config, AssetResponse, ExecuteMsg, InstantiateMsg, PriceResponse, QueryMsg, MigrateMsg, ExternalPriceResponse};
+use membrane::types::{AssetInfo, AssetOracleInfo, PriceInfo, Basket, TWAPPoolInfo, PoolInfo, Owner, PoolStateResponse, ExternalOracleInfo};
 
 use crate::error::ContractError;
 use crate::state::{ASSETS, CONFIG, OWNERSHIP_TRANSFER};
@@ -23,6 +25,12 @@ use crate::state::{ASSETS, CONFIG, OWNERSHIP_TRANSFER};
 const CONTRACT_NAME: &str = "oracle";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+//Execute msgs handled by the pause guard
+const PAUSE_ACTIONS: PauseActions = PauseActions {
+    pausable: &["update_config", "add_asset", "edit_asset"],
+    unpausable: &[],
+};
+
 //  Static prices
 const STATIC_USD_PRICE: Decimal = Decimal::one();
 // Mainnet Pyth Price ID
@@ -111,6 +119,9 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
+    //Check the pause guard
+    assert_not_paused(deps.storage, &env, &msg, &PAUSE_ACTIONS)?;
+
     match msg {
         ExecuteMsg::AddAsset {
             asset_info,
@@ -129,6 +140,10 @@ pub fn execute(
             osmo_usd_pyth_feed_id,
             pools_for_usd_par_twap
         } => update_config(deps, env, info, owner, positions_contract, osmosis_proxy_contract, osmo_usd_pyth_feed_id, pyth_osmosis_address, pools_for_usd_par_twap),
+        ExecuteMsg::Pause(pause_msg) => {
+            let config = CONFIG.load(deps.storage)?;
+            Ok(execute_pause(deps.storage, deps.api, &env, &info, info.sender == config.owner, &PAUSE_ACTIONS, pause_msg)?)
+        }
     }
 }
 
@@ -188,6 +203,10 @@ fn edit_asset(
         ASSETS.remove(deps.storage, asset_info.to_string());
     } else if oracle_info.is_some() {
         let oracle_info = oracle_info.unwrap();
+        //Validate external oracle
+        if let Some(external_oracle) = oracle_info.clone().external_oracle {
+            validate_external_oracle(deps.as_ref(), &external_oracle)?;
+        }
         //Update Asset
         ASSETS.update(
             deps.storage,
@@ -259,6 +278,11 @@ fn add_asset(
         AssetInfo::NativeToken { denom: _ } => {}
     };
 
+    //Validate external oracle
+    if let Some(external_oracle) = oracle_info.clone().external_oracle {
+        validate_external_oracle(deps.as_ref(), &external_oracle)?;
+    }
+
     //Save Oracle
     match ASSETS.load(deps.storage, asset_info.to_string()) {
         Err(_err) => {
@@ -524,6 +548,93 @@ fn get_assets(deps: Deps, asset_infos: Vec<AssetInfo>) -> StdResult<Vec<AssetRes
     Ok(resp)
 }
 
+/// Validate an external oracle's address & max deviation
+fn validate_external_oracle(deps: Deps, external_oracle: &ExternalOracleInfo) -> StdResult<()> {
+    deps.api.addr_validate(&external_oracle.contract_addr)?;
+
+    if external_oracle.max_deviation > Decimal::one() {
+        return Err(StdError::GenericErr {
+            msg: String::from("External oracle max_deviation can't be more than 100%"),
+        });
+    }
+
+    Ok(())
+}
+
+/// Query an external oracle contract using its saved query msg
+/// & scale the response using its response decimals.
+/// Errors if the price is older than the oracle_time_limit.
+fn query_external_price(
+    querier: &QuerierWrapper,
+    env: &Env,
+    external_oracle: ExternalOracleInfo,
+    oracle_time_limit: u64, //in seconds
+) -> StdResult<Decimal> {
+    let res: ExternalPriceResponse = querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: external_oracle.contract_addr,
+        msg: external_oracle.query_msg,
+    }))?;
+
+    if res.price.is_zero() {
+        return Err(StdError::GenericErr {
+            msg: String::from("External oracle returned a zero price"),
+        });
+    }
+
+    //Enforce the same time limit as Pyth prices
+    if env.block.time.seconds().saturating_sub(res.last_updated) > oracle_time_limit {
+        return Err(StdError::GenericErr {
+            msg: format!("External oracle price is stale, last updated at {}", res.last_updated),
+        });
+    }
+
+    let response_decimals = u32::try_from(external_oracle.response_decimals)
+        .map_err(|_| StdError::GenericErr { msg: String::from("Invalid external oracle response decimals") })?;
+
+    Ok(Decimal::from_ratio(
+        res.price,
+        Uint128::new(10).checked_pow(response_decimals)?,
+    ))
+}
+
+/// Return the average of an external price & the Osmosis TWAP price.
+/// Two sources can't be medianized, so it errors if the external price deviates from the TWAP by more than the max deviation.
+pub fn get_external_twap_price(
+    external_price: Decimal,
+    twap_price: Decimal,
+    max_deviation: Decimal,
+) -> StdResult<Decimal> {
+    let difference = if external_price > twap_price {
+        external_price - twap_price
+    } else {
+        twap_price - external_price
+    };
+    if decimal_division(difference, twap_price)? > max_deviation {
+        return Err(StdError::GenericErr {
+            msg: format!("External price {} deviates from the TWAP price {} by more than {}", external_price, twap_price, max_deviation),
+        });
+    }
+
+    decimal_division(external_price + twap_price, Decimal::percent(2_00))
+}
+
+/// Return the median of a list of prices
+fn get_median_price(mut prices: Vec<Decimal>) -> StdResult<Decimal> {
+    if prices.is_empty() {
+        return Err(StdError::GenericErr { msg: String::from("No prices to medianize") });
+    }
+    //Sort prices
+    prices.sort();
+
+    let median_index = prices.len() / 2;
+    if prices.len() % 2 == 0 {
+        //Add the two middle prices and divide by 2
+        decimal_division(prices[median_index] + prices[median_index-1], Decimal::percent(2_00))
+    } else {
+        Ok(prices[median_index])
+    }
+}
+
 /// Return Asset price info as a PriceResponse
 fn get_asset_price(
     storage: &dyn Storage,
@@ -558,14 +669,7 @@ fn get_asset_price(
         });
     };
 
-    //twap_timeframe = MINUTES * SECONDS_PER_MINUTE
-    let twap_timeframe: u64 = (twap_timeframe * 60);
-    let start_time: u64 = env.block.time.seconds() - twap_timeframe;
-
     let mut oracle_prices = vec![];
-    let mut asset_price_in_osmo_steps = vec![];
-    let mut usd_par_prices = vec![];
-    let mut quote_price = Decimal::zero();
 
     let mut pyth_feed_errored = false;
 
@@ -637,7 +741,76 @@ fn get_asset_price(
             }
         }
     }
-    /// If there is no return above, query starting from Osmosis TWAPs
+    //Use external oracle USD-quoted price feeds next if available
+    //If the external oracle fails or is stale, only use Osmosis TWAP pricing
+    let external_price = match oracle_info.clone().external_oracle {
+        Some(external_oracle) => query_external_price(&querier, &env, external_oracle.clone(), oracle_time_limit)
+            .ok()
+            .map(|price| (PriceInfo {
+                source: external_oracle.contract_addr,
+                price,
+            }, external_oracle.max_deviation)),
+        None => None,
+    };
+
+    //Query Osmosis TWAP pricing
+    let twap_price = get_twap_asset_price(
+        storage,
+        querier,
+        env,
+        asset_info,
+        oracle_info.clone(),
+        config,
+        twap_timeframe,
+        oracle_time_limit,
+        basket_id_field,
+        queried_asset_prices,
+        osmo_quote_price,
+    );
+
+    match external_price {
+        //Average the external price w/ the TWAP price if they're within the max deviation.
+        //The external price isn't used alone so TWAP errors are returned.
+        Some((external_price, max_deviation)) => {
+            let (mut price_response, quote_price) = twap_price?;
+            price_response.price = get_external_twap_price(external_price.price, price_response.price, max_deviation)?;
+            price_response.prices.insert(0, external_price);
+
+            //If the asset is USD-par the final price has to be less than the static price ($1) to be valid
+            if oracle_info.is_usd_par && price_response.price > STATIC_USD_PRICE {
+                price_response.price = STATIC_USD_PRICE;
+            }
+
+            Ok((price_response, quote_price))
+        },
+        None => twap_price,
+    }
+}
+
+/// Return Asset price info using Osmosis TWAPs as a PriceResponse
+#[allow(clippy::too_many_arguments)]
+fn get_twap_asset_price(
+    storage: &dyn Storage,
+    querier: QuerierWrapper,
+    env: Env,
+    asset_info: AssetInfo,
+    oracle_info: AssetOracleInfo,
+    config: Config,
+    twap_timeframe: u64, //in minutes
+    oracle_time_limit: u64, //in seconds
+    basket_id_field: Option<Uint128>,
+    //For Multi-Asset queries or recursive queries
+    queried_asset_prices: Option<Vec<(String, PriceResponse)>>, //Asset & Price
+    osmo_quote_price: Option<Decimal>,
+) -> StdResult<(PriceResponse, Option<Decimal>)> { //Return Asset Price & Quote Price (FURY/USD)
+    //twap_timeframe = MINUTES * SECONDS_PER_MINUTE
+    let twap_timeframe: u64 = twap_timeframe * 60;
+    let start_time: u64 = env.block.time.seconds() - twap_timeframe;
+
+    let mut oracle_prices = vec![];
+    let mut asset_price_in_osmo_steps = vec![];
+    let mut usd_par_prices = vec![];
+    let mut quote_price = Decimal::zero();
 
     //Query FURY price from the TWAP sources
     //This can use multiple pools to calculate our price
@@ -853,23 +1026,8 @@ fn get_asset_price(
                     usd_par_prices.push(Decimal::from_str(&res.geometric_twap)?);
                 }
                 
-                //Sort & Medianize FURY -> USD-par prices
-                //Sort prices
-                usd_par_prices.sort_by(|a, b| a.partial_cmp(&b).unwrap());
-
-                //Get Median price and set it as the quote price
-                quote_price = if usd_par_prices.len() % 2 == 0 {
-                    let median_index = usd_par_prices.len() / 2;
-
-                    //Add the two middle usd_par_prices and divide by 2
-                    decimal_division(usd_par_prices[median_index] + usd_par_prices[median_index-1], Decimal::percent(2_00)).unwrap()
-                    
-                } else if usd_par_prices.len() != 1 {
-                    let median_index = usd_par_prices.len() / 2;
-                    usd_par_prices[median_index]
-                } else {
-                    usd_par_prices[0]
-                };
+                //Medianize FURY -> USD-par prices & set it as the quote price
+                quote_price = get_median_price(usd_par_prices)?;
 
                 //Push Osmosis FURY USD-par price
                 oracle_prices.push(PriceInfo {
@@ -1,11 +1,12 @@
 #[cfg(test)]
 mod tests {
 
+    use crate::contracts::get_external_twap_price;
     use crate::helpers::OracleContract;
 
     use membrane::oracle::{ExecuteMsg, InstantiateMsg, QueryMsg, PriceResponse};
     use membrane::osmosis_proxy::Config as OP_Config;
-    use membrane::types::{AssetInfo, AssetOracleInfo, TWAPPoolInfo, PriceInfo, Asset, Basket, SupplyCap, Owner};
+    use membrane::types::{AssetInfo, AssetOracleInfo, TWAPPoolInfo, PriceInfo, Asset, Basket, SupplyCap, Owner, ExternalOracleInfo};
 
     use cosmwasm_std::{
         coin, to_binary, Addr, Binary, Empty, Response, StdResult, Uint128, Decimal,
@@ -142,6 +143,61 @@ mod tests {
         Box::new(contract)
     }
 
+    //Mock External Oracle Contract
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    pub enum ExternalOracle_MockExecuteMsg {}
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    pub struct ExternalOracle_MockInstantiateMsg {}
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    pub enum ExternalOracle_MockQueryMsg {
+        ReferenceData { symbol: String },
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    pub struct ExternalOracle_MockResponse {
+        pub rate: Uint128,
+        pub last_updated: u64,
+    }
+
+    pub fn external_oracle_contract() -> Box<dyn Contract<Empty>> {
+        let contract = ContractWrapper::new(
+            |deps, _, info, msg: ExternalOracle_MockExecuteMsg| -> StdResult<Response> {
+                Ok(Response::default())
+            },
+            |_, _, _, _: ExternalOracle_MockInstantiateMsg| -> StdResult<Response> {
+                Ok(Response::default())
+            },
+            |_, env, msg: ExternalOracle_MockQueryMsg| -> StdResult<Binary> {
+                match msg {
+                    ExternalOracle_MockQueryMsg::ReferenceData { symbol } => {
+                        let rate = if symbol == String::from("ZERO") {
+                            Uint128::zero()
+                        } else {
+                            Uint128::new(150_000_000)
+                        };
+                        let last_updated = if symbol == String::from("STALE") {
+                            env.block.time.seconds() - 120
+                        } else {
+                            env.block.time.seconds()
+                        };
+
+                        Ok(to_binary(&ExternalOracle_MockResponse {
+                            rate,
+                            last_updated,
+                        })?)
+                    }
+                }
+            },
+        );
+        Box::new(contract)
+    }
+
     fn mock_app() -> App {
         AppBuilder::new().build(|router, _, storage| {
             let bank = BankKeeper::new();
@@ -245,6 +301,7 @@ mod tests {
                     lp_pool_info: None,
                     decimals: 6,
                     pyth_price_feed_id: None,
+                    external_oracle: None,
                 },
             };
             let cosmos_msg = oracleContract.call(msg, vec![]).unwrap();
@@ -266,6 +323,7 @@ mod tests {
                     lp_pool_info: None,
                     decimals: 6,
                     pyth_price_feed_id: None,
+                    external_oracle: None,
                 },
             };
             let cosmos_msg = oracleContract.call(msg, vec![]).unwrap();
@@ -287,6 +345,7 @@ mod tests {
                     lp_pool_info: None,
                     decimals: 6,
                     pyth_price_feed_id: None,
+                    external_oracle: None,
                 },
             };
             let cosmos_msg = oracleContract.call(msg, vec![]).unwrap();
@@ -354,6 +413,7 @@ mod tests {
                     lp_pool_info: None,
                     decimals: 6,
                     pyth_price_feed_id: None,
+                    external_oracle: None,
                 }),
                 remove: false,
             };
@@ -390,6 +450,7 @@ mod tests {
                     lp_pool_info: None,
                     decimals: 6,
                     pyth_price_feed_id: None,
+                    external_oracle: None,
                 },
             };
             let cosmos_msg = oracleContract.call(msg, vec![]).unwrap();
@@ -495,6 +556,198 @@ mod tests {
         //     assert_eq!(price.price, Decimal::one());
         // }
 
+        #[test]
+        fn external_oracle() {
+            let (mut app, oracle_contract, _cdp_contract) = proper_instantiate();
+
+            //Instantiate External Oracle contract
+            let external_id = app.store_code(external_oracle_contract());
+
+            let external_oracle_addr = app
+                .instantiate_contract(
+                    external_id,
+                    Addr::unchecked(ADMIN),
+                    &ExternalOracle_MockInstantiateMsg {},
+                    &[],
+                    "test",
+                    None,
+                )
+                .unwrap();
+
+            //Error: max_deviation over 100%
+            let msg = ExecuteMsg::AddAsset {
+                asset_info: AssetInfo::NativeToken {
+                    denom: String::from("debit"),
+                },
+                oracle_info: AssetOracleInfo {
+                    basket_id: Uint128::new(1u128),
+                    pools_for_osmo_twap: vec![],
+                    is_usd_par: false,
+                    lp_pool_info: None,
+                    decimals: 6,
+                    pyth_price_feed_id: None,
+                    external_oracle: Some(ExternalOracleInfo {
+                        contract_addr: external_oracle_addr.to_string(),
+                        query_msg: to_binary(&ExternalOracle_MockQueryMsg::ReferenceData {
+                            symbol: String::from("DEBIT"),
+                        }).unwrap(),
+                        response_decimals: 8,
+                        max_deviation: Decimal::percent(101),
+                    }),
+                },
+            };
+            let cosmos_msg = oracle_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap_err();
+
+            //Successful AddAsset using the external oracle
+            let msg = ExecuteMsg::AddAsset {
+                asset_info: AssetInfo::NativeToken {
+                    denom: String::from("debit"),
+                },
+                oracle_info: AssetOracleInfo {
+                    basket_id: Uint128::new(1u128),
+                    pools_for_osmo_twap: vec![],
+                    is_usd_par: false,
+                    lp_pool_info: None,
+                    decimals: 6,
+                    pyth_price_feed_id: None,
+                    external_oracle: Some(ExternalOracleInfo {
+                        contract_addr: external_oracle_addr.to_string(),
+                        query_msg: to_binary(&ExternalOracle_MockQueryMsg::ReferenceData {
+                            symbol: String::from("DEBIT"),
+                        }).unwrap(),
+                        response_decimals: 8,
+                        max_deviation: Decimal::percent(5),
+                    }),
+                },
+            };
+            let cosmos_msg = oracle_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            //Query Price: Errors bc the external price isn't used alone & Osmosis TWAPs aren't set
+            app.wrap()
+                .query_wasm_smart::<Vec<PriceResponse>>(
+                    oracle_contract.addr(),
+                    &QueryMsg::Price {
+                        asset_info: AssetInfo::NativeToken {
+                            denom: String::from("debit"),
+                        },
+                        twap_timeframe: 0u64,
+                        oracle_time_limit: 0u64,
+                        basket_id: None,
+                    },
+                )
+                .unwrap_err();
+
+            //Successful AddAsset w/ an external oracle that returns a zero price
+            let msg = ExecuteMsg::AddAsset {
+                asset_info: AssetInfo::NativeToken {
+                    denom: String::from("2nddebit"),
+                },
+                oracle_info: AssetOracleInfo {
+                    basket_id: Uint128::new(1u128),
+                    pools_for_osmo_twap: vec![],
+                    is_usd_par: false,
+                    lp_pool_info: None,
+                    decimals: 6,
+                    pyth_price_feed_id: None,
+                    external_oracle: Some(ExternalOracleInfo {
+                        contract_addr: external_oracle_addr.to_string(),
+                        query_msg: to_binary(&ExternalOracle_MockQueryMsg::ReferenceData {
+                            symbol: String::from("ZERO"),
+                        }).unwrap(),
+                        response_decimals: 8,
+                        max_deviation: Decimal::percent(5),
+                    }),
+                },
+            };
+            let cosmos_msg = oracle_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            //Query Price: Errors bc it falls back to Osmosis TWAPs which aren't set
+            app.wrap()
+                .query_wasm_smart::<Vec<PriceResponse>>(
+                    oracle_contract.addr(),
+                    &QueryMsg::Price {
+                        asset_info: AssetInfo::NativeToken {
+                            denom: String::from("2nddebit"),
+                        },
+                        twap_timeframe: 0u64,
+                        oracle_time_limit: 0u64,
+                        basket_id: None,
+                    },
+                )
+                .unwrap_err();
+
+            //Successful AddAsset w/ an external oracle that returns a stale price
+            let msg = ExecuteMsg::AddAsset {
+                asset_info: AssetInfo::NativeToken {
+                    denom: String::from("3rddebit"),
+                },
+                oracle_info: AssetOracleInfo {
+                    basket_id: Uint128::new(1u128),
+                    pools_for_osmo_twap: vec![],
+                    is_usd_par: false,
+                    lp_pool_info: None,
+                    decimals: 6,
+                    pyth_price_feed_id: None,
+                    external_oracle: Some(ExternalOracleInfo {
+                        contract_addr: external_oracle_addr.to_string(),
+                        query_msg: to_binary(&ExternalOracle_MockQueryMsg::ReferenceData {
+                            symbol: String::from("STALE"),
+                        }).unwrap(),
+                        response_decimals: 8,
+                        max_deviation: Decimal::percent(5),
+                    }),
+                },
+            };
+            let cosmos_msg = oracle_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            //Query Price: Errors bc the price is older than the time limit & it falls back to Osmosis TWAPs
+            app.wrap()
+                .query_wasm_smart::<Vec<PriceResponse>>(
+                    oracle_contract.addr(),
+                    &QueryMsg::Price {
+                        asset_info: AssetInfo::NativeToken {
+                            denom: String::from("3rddebit"),
+                        },
+                        twap_timeframe: 0u64,
+                        oracle_time_limit: 60u64,
+                        basket_id: None,
+                    },
+                )
+                .unwrap_err();
+
+            //Query external price: Scaled by 8 decimals
+            let res: ExternalOracle_MockResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    external_oracle_addr.clone(),
+                    &ExternalOracle_MockQueryMsg::ReferenceData {
+                        symbol: String::from("DEBIT"),
+                    },
+                )
+                .unwrap();
+            assert_eq!(Decimal::from_ratio(res.rate, Uint128::new(100_000_000)), Decimal::from_str("1.5").unwrap());
+        }
+
+        #[test]
+        fn external_twap_price() {
+            //Average of the external & TWAP prices
+            assert_eq!(
+                get_external_twap_price(Decimal::from_str("1.5").unwrap(), Decimal::from_str("1.45").unwrap(), Decimal::percent(5)).unwrap(),
+                Decimal::from_str("1.475").unwrap()
+            );
+            assert_eq!(
+                get_external_twap_price(Decimal::from_str("1.4").unwrap(), Decimal::from_str("1.45").unwrap(), Decimal::percent(5)).unwrap(),
+                Decimal::from_str("1.425").unwrap()
+            );
+            //Error: External price deviates by more than 5% above or below the TWAP price
+            get_external_twap_price(Decimal::from_str("1.5").unwrap(), Decimal::from_str("1.4").unwrap(), Decimal::percent(5)).unwrap_err();
+            get_external_twap_price(Decimal::from_str("1.3").unwrap(), Decimal::from_str("1.4").unwrap(), Decimal::percent(5)).unwrap_err();
+        }
+
         #[test]
         fn median_test() {
             //Create a vector of PriceInfo
@@ -10,15 +10,16 @@ use cosmwasm_std::{
     Reply, Response, StdError, StdResult, Uint128, SubMsg, CosmosMsg, Decimal, Order, QuerierWrapper,
 };
 use cw2::set_contract_version;
+use membrane::pause::{assert_not_paused, execute_pause, PauseActions};
 use membrane::helpers::get_asset_liquidity;
 use membrane::math::{decimal_multiplication, decimal_division};
 use osmosis_std::types::osmosis::gamm::v1beta1::GammQuerier;
 use osmosis_std::types::osmosis::incentives::MsgCreateGauge;
 
 use crate::error::TokenFactoryError;
-use crate::state::{TokenInfo, CONFIG, TOKENS, PENDING, PendingTokenInfo};
+use crate::state::{MintLimit, TokenInfo, CONFIG, MINT_LIMITS, OWNER_MINT_LIMITS, TOKENS, PENDING, PendingTokenInfo};
 use membrane::osmosis_proxy::{
-    Config, ExecuteMsg, GetDenomResponse, InstantiateMsg, QueryMsg, MigrateMsg, TokenInfoResponse, OwnerResponse, ContractDenomsResponse,
+    Config, ExecuteMsg, GetDenomResponse, InstantiateMsg, QueryMsg, MigrateMsg, TokenInfoResponse, MintLimitResponse, OwnerResponse, ContractDenomsResponse,
 };
 use membrane::cdp::{QueryMsg as CDPQueryMsg, Config as CDPConfig};
 use membrane::oracle::{QueryMsg as OracleQueryMsg, PriceResponse};
@@ -29,6 +30,13 @@ use osmosis_std::types::osmosis::tokenfactory::v1beta1::{self as TokenFactory, Q
 const CONTRACT_NAME: &str = "crates.io:osmosis-proxy";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+//Execute msgs handled by the pause guard
+//Burns repay debt so they're never paused
+const PAUSE_ACTIONS: PauseActions = PauseActions {
+    pausable: &["create_denom", "change_admin", "mint_tokens", "create_osmosis_gauge", "edit_token_max_supply", "edit_mint_limit", "update_config", "edit_owner"],
+    unpausable: &["burn_tokens"],
+};
+
 //Constants
 const MAX_LIMIT: u32 = 64;
 
@@ -73,6 +81,9 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, TokenFactoryError> {
+    //Check the pause guard
+    assert_not_paused(deps.storage, &env, &msg, &PAUSE_ACTIONS)?;
+
     match msg {
         ExecuteMsg::CreateDenom {
             subdenom,
@@ -102,6 +113,9 @@ pub fn execute(
         ExecuteMsg::EditTokenMaxSupply { denom, max_supply } => {
             edit_token_max(deps, info, denom, max_supply)
         }
+        ExecuteMsg::EditMintLimit { denom, owner, limit, window } => {
+            edit_mint_limit(deps, env, info, denom, owner, limit, window)
+        }
         ExecuteMsg::UpdateConfig {
             owners,
             add_owner,
@@ -114,6 +128,14 @@ pub fn execute(
         ExecuteMsg::EditOwner { owner, stability_pool_ratio, non_token_contract_auth } => {
             edit_owner(deps, info, owner, stability_pool_ratio, non_token_contract_auth)
         }
+        ExecuteMsg::Pause(pause_msg) => {
+            let config = CONFIG.load(deps.storage)?;
+            //Only owners with non-token contract authority count as the owner
+            let (authorized, owner_index) = validate_authority(config.clone(), info.clone());
+            let is_owner = authorized && config.owners[owner_index].non_token_contract_auth;
+
+            Ok(execute_pause(deps.storage, deps.api, &env, &info, is_owner, &PAUSE_ACTIONS, pause_msg)?)
+        }
     }
 }
 
@@ -324,6 +346,73 @@ pub fn change_admin(
     Ok(res)
 }
 
+/// Set or remove a denom's rolling-window mint limit
+fn edit_mint_limit(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    denom: String,
+    owner: Option<String>,
+    limit: Uint128,
+    window: u64,
+) -> Result<Response, TokenFactoryError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    //Assert Authority, minters can't edit their own limits
+    let (authorized, owner_index) = validate_authority(config.clone(), info.clone());
+    if !authorized || !config.owners[owner_index].non_token_contract_auth {
+        return Err(TokenFactoryError::Unauthorized {});
+    }
+
+    if !TOKENS.has(deps.storage, denom.clone()) {
+        return Err(TokenFactoryError::CustomError {
+            val: String::from("Denom was not created in this contract"),
+        });
+    }
+    if !limit.is_zero() && window == 0 {
+        return Err(TokenFactoryError::CustomError {
+            val: String::from("Mint limit window can't be 0"),
+        });
+    }
+
+    let current_time = env.block.time.seconds();
+    //Keep mints already counted in the window
+    let update_limit = |mint_limit: Option<MintLimit>| MintLimit {
+        limit,
+        window,
+        used: mint_limit.map(|mint_limit| mint_limit.used_at(current_time)).unwrap_or_default(),
+        last_update: current_time,
+    };
+
+    match owner.clone() {
+        Some(owner) => {
+            let key = (denom.clone(), deps.api.addr_validate(&owner)?);
+            if limit.is_zero() {
+                OWNER_MINT_LIMITS.remove(deps.storage, key);
+            } else {
+                let mint_limit = update_limit(OWNER_MINT_LIMITS.may_load(deps.storage, key.clone())?);
+                OWNER_MINT_LIMITS.save(deps.storage, key, &mint_limit)?;
+            }
+        }
+        None => {
+            if limit.is_zero() {
+                MINT_LIMITS.remove(deps.storage, denom.clone());
+            } else {
+                let mint_limit = update_limit(MINT_LIMITS.may_load(deps.storage, denom.clone())?);
+                MINT_LIMITS.save(deps.storage, denom.clone(), &mint_limit)?;
+            }
+        }
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "edit_mint_limit"),
+        attr("denom", denom),
+        attr("owner", owner.unwrap_or_else(|| String::from("None"))),
+        attr("limit", limit),
+        attr("window", window.to_string()),
+    ]))
+}
+
 /// Edit token max supply
 fn edit_token_max(
     deps: DepsMut,
@@ -394,6 +483,17 @@ pub fn mint_tokens(
     //Validate denom
     validate_denom(denom.clone())?;
 
+    //Enforce rolling-window mint limits
+    let current_time = env.block.time.seconds();
+    let denom_mint_limit = MINT_LIMITS
+        .may_load(deps.storage, denom.clone())?
+        .map(|mint_limit| record_mint(mint_limit, amount, current_time, &denom))
+        .transpose()?;
+    let owner_mint_limit = OWNER_MINT_LIMITS
+        .may_load(deps.storage, (denom.clone(), info.sender.clone()))?
+        .map(|mint_limit| record_mint(mint_limit, amount, current_time, &denom))
+        .transpose()?;
+
     //Debt Auction can mint over max supply
     let mut mint_allowed = false;
     if let Some(debt_auction) = config.clone().debt_auction {
@@ -432,6 +532,16 @@ pub fn mint_tokens(
         },
     )?;
 
+    //Only count mints that are made in their limit windows
+    if mint_allowed {
+        if let Some(mint_limit) = denom_mint_limit {
+            MINT_LIMITS.save(deps.storage, denom.clone(), &mint_limit)?;
+        }
+        if let Some(mint_limit) = owner_mint_limit {
+            OWNER_MINT_LIMITS.save(deps.storage, (denom.clone(), info.sender.clone()), &mint_limit)?;
+        }
+    }
+
     //Create mint msg
     let mint_tokens_msg: CosmosMsg = TokenFactory::MsgMint{
         sender: env.contract.address.to_string(), 
@@ -462,6 +572,26 @@ pub fn mint_tokens(
     Ok(res)
 }
 
+/// Add a mint to a mint limit's window, erroring if it exceeds the limit
+fn record_mint(
+    mut mint_limit: MintLimit,
+    amount: Uint128,
+    current_time: u64,
+    denom: &str,
+) -> Result<MintLimit, TokenFactoryError> {
+    let available = mint_limit.available(current_time);
+    if amount > available {
+        return Err(TokenFactoryError::CustomError {
+            val: format!("Mint exceeds the {} mint limit, {} available", denom, available),
+        });
+    }
+
+    mint_limit.used = mint_limit.used_at(current_time) + amount;
+    mint_limit.last_update = current_time;
+
+    Ok(mint_limit)
+}
+
 /// Create Osmosis Incentive Gauge.
 /// Uses osmosis-std to make it easier for contracts to execute osmosis messages.
 fn create_gauge(
@@ -622,7 +752,7 @@ pub fn burn_tokens(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&CONFIG.load(deps.storage)?),
         QueryMsg::GetOwner { owner } => to_binary(&get_contract_owner(deps, owner)?),
@@ -632,7 +762,7 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
         } => to_binary(&get_denom(deps, creator_address, subdenom)?),
         QueryMsg::GetContractDenoms { limit } => to_binary(&get_contract_denoms(deps, limit)?),
         QueryMsg::PoolState { id } => to_binary(&get_pool_state(deps, id)?),
-        QueryMsg::GetTokenInfo { denom } => to_binary(&get_token_info(deps, denom)?),
+        QueryMsg::GetTokenInfo { denom } => to_binary(&get_token_info(deps, env, denom)?),
     }
 }
 
@@ -653,18 +783,40 @@ fn get_contract_owner(deps: Deps, owner: String) -> StdResult<OwnerResponse> {
 }
 
 /// Returns token info for a specified denom
-fn get_token_info(deps: Deps, denom: String) -> StdResult<TokenInfoResponse> {
+fn get_token_info(deps: Deps, env: Env, denom: String) -> StdResult<TokenInfoResponse> {
     let token_info = TOKENS.load(deps.storage, denom.clone())?;
+    let current_time = env.block.time.seconds();
+
+    //Denom-wide limit first, then owner limits
+    let mut mint_limits: Vec<MintLimitResponse> = MINT_LIMITS
+        .may_load(deps.storage, denom.clone())?
+        .into_iter()
+        .map(|mint_limit| mint_limit_response(None, mint_limit, current_time))
+        .collect();
+    for item in OWNER_MINT_LIMITS.prefix(denom.clone()).range(deps.storage, None, None, Order::Ascending) {
+        let (owner, mint_limit) = item?;
+        mint_limits.push(mint_limit_response(Some(owner), mint_limit, current_time));
+    }
     
     Ok(TokenInfoResponse {
         denom,
         current_supply: token_info.current_supply,
         max_supply: token_info.max_supply.unwrap_or_else(Uint128::zero),
         burned_supply: token_info.burned_supply,
+        mint_limits,
     })
     
 }
 
+fn mint_limit_response(owner: Option<Addr>, mint_limit: MintLimit, current_time: u64) -> MintLimitResponse {
+    MintLimitResponse {
+        owner,
+        limit: mint_limit.limit,
+        window: mint_limit.window,
+        available: mint_limit.available(current_time),
+    }
+}
+
 /// Returns a list of all denoms created by this contract
 fn get_contract_denoms(deps: Deps, limit: Option<u32>) -> StdResult<ContractDenomsResponse> {
     let limit = limit.unwrap_or_else(|| MAX_LIMIT);
@@ -1,7 +1,7 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::Uint128;
+use cosmwasm_std::{Addr, Uint128};
 use cw_storage_plus::{Item, Map};
 
 use membrane::osmosis_proxy::Config;
@@ -16,6 +16,34 @@ pub struct TokenInfo {
     pub burned_supply: Uint128,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct MintLimit {
+    /// Max amount minted per window
+    pub limit: Uint128,
+    /// Window length, in seconds
+    pub window: u64,
+    /// Minted amount counted in the window at last_update
+    pub used: Uint128,
+    /// Time of the last mint, in seconds
+    pub last_update: u64,
+}
+
+impl MintLimit {
+    /// Minted amount still counted in the window.
+    /// Mints decay out of the window linearly over its length.
+    pub fn used_at(&self, time: u64) -> Uint128 {
+        let elapsed = time.saturating_sub(self.last_update).min(self.window);
+        let decay = self.limit.multiply_ratio(elapsed, self.window);
+
+        self.used.saturating_sub(decay)
+    }
+
+    /// Amount that can be minted at the given time
+    pub fn available(&self, time: u64) -> Uint128 {
+        self.limit.saturating_sub(self.used_at(time))
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct PendingTokenInfo {
     /// Chosen subdenom
@@ -26,4 +54,6 @@ pub struct PendingTokenInfo {
 
 pub const CONFIG: Item<Config> = Item::new("config");
 pub const TOKENS: Map<String, TokenInfo> = Map::new("tokens"); //AssetInfo, TokenInfo
+pub const MINT_LIMITS: Map<String, MintLimit> = Map::new("mint_limits"); //Denom, MintLimit
+pub const OWNER_MINT_LIMITS: Map<(String, Addr), MintLimit> = Map::new("owner_mint_limits"); //(Denom, Owner), MintLimit
 pub const PENDING: Item<PendingTokenInfo> = Item::new("pending_denoms");
\ No newline at end of file
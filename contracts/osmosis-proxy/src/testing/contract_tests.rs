@@ -4,14 +4,16 @@ mod tests {
         mock_env, mock_info, mock_dependencies, MOCK_CONTRACT_ADDR,
     };
     use cosmwasm_std::{
-        coins, from_binary, Uint128, StdError, Addr, Decimal
+        attr, coins, from_binary, Uint128, StdError, Addr, Decimal
     };
     use membrane::types::Owner;
 
-    use membrane::osmosis_proxy::{InstantiateMsg, ExecuteMsg, QueryMsg, Config};
+    use membrane::osmosis_proxy::{InstantiateMsg, ExecuteMsg, QueryMsg, Config, MintLimitResponse, TokenInfoResponse};
+    use membrane::pause::{PauseMsg, MAX_GUARDIAN_PAUSE, GUARDIAN_PAUSE_COOLDOWN};
 
     use crate::TokenFactoryError;
     use crate::contract::{instantiate, execute, query};
+    use crate::state::{TokenInfo, TOKENS};
 
     const DENOM_NAME: &str = "mydenom";
     const DENOM_PREFIX: &str = "factory";
@@ -216,6 +218,206 @@ mod tests {
         assert_eq!(expected_error, err);
     }
 
+    #[test]
+    fn pause_guard() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {};
+        let info = mock_info("creator", &coins(1000, "ufury"));
+        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mint_msg = ExecuteMsg::MintTokens {
+            denom: String::from(DENOM_PREFIX),
+            amount: Uint128::new(100_u128),
+            mint_to_address: String::from("newadmin"),
+        };
+
+        //Error: Only the owner can set the guardian
+        let msg = ExecuteMsg::Pause(PauseMsg::SetGuardian { guardian: Some(String::from("guardian")) });
+        execute(deps.as_mut(), mock_env(), mock_info("guardian", &[]), msg.clone()).unwrap_err();
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        //Error: Guardian pauses are capped
+        let pause_msg = |duration: u64| ExecuteMsg::Pause(PauseMsg::Pause {
+            actions: vec![String::from("mint_tokens")],
+            duration,
+        });
+        execute(deps.as_mut(), mock_env(), mock_info("guardian", &[]), pause_msg(MAX_GUARDIAN_PAUSE + 1)).unwrap_err();
+
+        //Error: Actions must be pausable execute msgs
+        for action in ["mint_token", "burn_tokens"] {
+            let msg = ExecuteMsg::Pause(PauseMsg::Pause { actions: vec![String::from(action)], duration: 1 });
+            let err = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap_err();
+            assert_eq!(
+                err,
+                TokenFactoryError::Std(StdError::generic_err(format!("{} isn't a pausable action", action)))
+            );
+        }
+
+        //Guardian pauses minting
+        execute(deps.as_mut(), mock_env(), mock_info("guardian", &[]), pause_msg(MAX_GUARDIAN_PAUSE)).unwrap();
+        let expiry = mock_env().block.time.seconds() + MAX_GUARDIAN_PAUSE;
+
+        let err = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), mint_msg.clone()).unwrap_err();
+        assert_eq!(
+            err,
+            TokenFactoryError::Std(StdError::generic_err(format!("mint_tokens is paused until {}", expiry)))
+        );
+
+        //Error: Only the owner can extend the pause
+        execute(deps.as_mut(), mock_env(), mock_info("guardian", &[]), pause_msg(MAX_GUARDIAN_PAUSE)).unwrap_err();
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), pause_msg(2 * MAX_GUARDIAN_PAUSE)).unwrap();
+
+        //The pause expires
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(2 * MAX_GUARDIAN_PAUSE);
+        let err = execute(deps.as_mut(), env, mock_info("creator", &[]), mint_msg.clone()).unwrap_err();
+        assert_eq!(
+            err,
+            TokenFactoryError::InvalidDenom {
+                denom: String::from(DENOM_PREFIX),
+                message: String::from("denom must have 3 parts separated by /, had 1"),
+            }
+        );
+
+        //Error: Only the owner can lift its own pause
+        let msg = ExecuteMsg::Pause(PauseMsg::Unpause { actions: vec![String::from("mint_tokens")] });
+        let err = execute(deps.as_mut(), mock_env(), mock_info("guardian", &[]), msg.clone()).unwrap_err();
+        assert_eq!(
+            err,
+            TokenFactoryError::Std(StdError::generic_err("mint_tokens was paused by the owner, only the owner can unpause it"))
+        );
+
+        //Owner unpauses
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let err = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), mint_msg.clone()).unwrap_err();
+        assert!(matches!(err, TokenFactoryError::InvalidDenom { .. }));
+
+        //Error: The guardian has to wait out its cooldown
+        let cooldown = expiry + GUARDIAN_PAUSE_COOLDOWN;
+        let err = execute(deps.as_mut(), mock_env(), mock_info("guardian", &[]), pause_msg(1)).unwrap_err();
+        assert_eq!(
+            err,
+            TokenFactoryError::Std(StdError::generic_err(format!("The guardian can't pause again until {}", cooldown)))
+        );
+
+        //Guardian pauses & unpauses after the cooldown
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(MAX_GUARDIAN_PAUSE + GUARDIAN_PAUSE_COOLDOWN);
+        execute(deps.as_mut(), env.clone(), mock_info("guardian", &[]), pause_msg(1)).unwrap();
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), mint_msg.clone()).unwrap_err();
+
+        let msg = ExecuteMsg::Pause(PauseMsg::Unpause { actions: vec![String::from("mint_tokens")] });
+        execute(deps.as_mut(), env.clone(), mock_info("guardian", &[]), msg).unwrap();
+        let err = execute(deps.as_mut(), env, mock_info("creator", &[]), mint_msg).unwrap_err();
+        assert!(matches!(err, TokenFactoryError::InvalidDenom { .. }));
+    }
+
+    #[test]
+    fn mint_limits() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {};
+        let info = mock_info("creator", &coins(1000, "ufury"));
+        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let full_denom_name = format!("{}/{}/{}", DENOM_PREFIX, MOCK_CONTRACT_ADDR, DENOM_NAME);
+        TOKENS.save(deps.as_mut().storage, full_denom_name.clone(), &TokenInfo {
+            current_supply: Uint128::zero(),
+            max_supply: None,
+            burned_supply: Uint128::zero(),
+        }).unwrap();
+
+        //Add a minter without non-token contract authority
+        let msg = ExecuteMsg::UpdateConfig { 
+            owners: Some(vec![ Owner {
+                owner: Addr::unchecked("positions"),
+                total_minted: Uint128::zero(),
+                stability_pool_ratio: None,
+                non_token_contract_auth: false, 
+                is_position_contract: true,
+            }]),
+            liquidity_multiplier: None,
+            add_owner: Some(true), 
+            debt_auction: None,
+            positions_contract: None,
+            liquidity_contract: None,
+            oracle_contract: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let limit_msg = |owner: Option<String>, limit: u128| ExecuteMsg::EditMintLimit {
+            denom: full_denom_name.clone(),
+            owner,
+            limit: Uint128::new(limit),
+            window: 3_600,
+        };
+        let mint_msg = |amount: u128| ExecuteMsg::MintTokens {
+            denom: full_denom_name.clone(),
+            amount: Uint128::new(amount),
+            mint_to_address: String::from("newadmin"),
+        };
+
+        //Error: Minters can't edit mint limits
+        let err = execute(deps.as_mut(), mock_env(), mock_info("positions", &[]), limit_msg(None, 1_000)).unwrap_err();
+        assert_eq!(err, TokenFactoryError::Unauthorized {});
+
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), limit_msg(None, 1_000)).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), limit_msg(Some(String::from("positions")), 600)).unwrap();
+
+        //Owner limit
+        let res = execute(deps.as_mut(), mock_env(), mock_info("positions", &[]), mint_msg(600)).unwrap();
+        assert_eq!(res.attributes[1], attr("mint_status", "true"));
+
+        let err = execute(deps.as_mut(), mock_env(), mock_info("positions", &[]), mint_msg(1)).unwrap_err();
+        assert_eq!(
+            err,
+            TokenFactoryError::CustomError { val: format!("Mint exceeds the {} mint limit, 0 available", full_denom_name) }
+        );
+
+        //Denom limit
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), mint_msg(400)).unwrap();
+        assert_eq!(res.attributes[1], attr("mint_status", "true"));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), mint_msg(1)).unwrap_err();
+
+        //Half the window decays half the limits
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(1_800);
+
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::GetTokenInfo { denom: full_denom_name.clone() }).unwrap();
+        let token_info: TokenInfoResponse = from_binary(&res).unwrap();
+        assert_eq!(token_info.current_supply, Uint128::new(1_000));
+        assert_eq!(token_info.mint_limits, vec![
+            MintLimitResponse {
+                owner: None,
+                limit: Uint128::new(1_000),
+                window: 3_600,
+                available: Uint128::new(500),
+            },
+            MintLimitResponse {
+                owner: Some(Addr::unchecked("positions")),
+                limit: Uint128::new(600),
+                window: 3_600,
+                available: Uint128::new(300),
+            },
+        ]);
+
+        let res = execute(deps.as_mut(), env.clone(), mock_info("positions", &[]), mint_msg(300)).unwrap();
+        assert_eq!(res.attributes[1], attr("mint_status", "true"));
+        execute(deps.as_mut(), env.clone(), mock_info("positions", &[]), mint_msg(1)).unwrap_err();
+
+        //Mints halted by the max supply aren't counted
+        let msg = ExecuteMsg::EditTokenMaxSupply { denom: full_denom_name.clone(), max_supply: Uint128::new(1_000) };
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        let res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), mint_msg(100)).unwrap();
+        assert_eq!(res.attributes[1], attr("mint_status", "false"));
+
+        let res = query(deps.as_ref(), env, QueryMsg::GetTokenInfo { denom: full_denom_name.clone() }).unwrap();
+        let token_info: TokenInfoResponse = from_binary(&res).unwrap();
+        assert_eq!(token_info.current_supply, Uint128::new(1_300));
+        assert_eq!(token_info.mint_limits[0].available, Uint128::new(200));
+    }
+
     // #[test]
     // fn mint_limits() {
     //     let mut deps = mock_dependencies();
@@ -5,36 +5,50 @@ use std::str::FromStr;
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
     attr, to_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, Decimal, Deps,
-    DepsMut, Env, MessageInfo, Response, StdError, StdResult, QuerierWrapper,
-    Storage, Uint128, WasmMsg, coin,
+    DepsMut, Env, MessageInfo, Order, QuerierWrapper, Reply, Response, StdError, StdResult,
+    Storage, SubMsg, Uint128, WasmMsg, coin,
 };
 use cw2::set_contract_version;
 use cw_coins::Coins;
 
-use membrane::cdp::{ExecuteMsg as CDP_ExecuteMsg, QueryMsg as CDP_QueryMsg};
-use membrane::oracle::PriceResponse;
+use membrane::pause::{assert_not_paused, execute_pause, PauseActions};
+use membrane::cdp::ExecuteMsg as CDP_ExecuteMsg;
 use membrane::stability_pool::{
-    Config, ExecuteMsg, InstantiateMsg, QueryMsg, UpdateConfig, MigrateMsg
+    Config, DepositAsset, ExecuteMsg, InstantiateMsg, QueryMsg, UpdateConfig, MigrateMsg
 };
 use membrane::osmosis_proxy::ExecuteMsg as OsmosisProxy_ExecuteMsg;
+use membrane::oracle::{PriceResponse, QueryMsg as OracleQueryMsg};
 use membrane::types::{
-    Asset, AssetInfo, AssetPool, Deposit, User, UserInfo, UserRatio, Basket,
+    Asset, AssetInfo, AssetPool, User, UserInfo,
 };
-use membrane::helpers::{validate_position_owner, withdrawal_msg, assert_sent_native_token_balance, asset_to_coin, accumulate_interest, query_asset_price, query_basket};
-use membrane::math::{decimal_division, decimal_multiplication, decimal_subtraction};
+use membrane::helpers::{validate_position_owner, withdrawal_msg, assert_sent_native_token_balance, asset_to_coin, accumulate_interest, router_native_to_native};
+use membrane::math::{decimal_division, decimal_multiplication, Uint256};
 
 use crate::error::ContractError;
 use crate::query::{query_user_incentives, query_liquidatible, query_user_claims, query_capital_ahead_of_deposits, query_asset_pool};
-use crate::state::{Propagation, ASSET, CONFIG, INCENTIVES, PROP, USERS, OWNERSHIP_TRANSFER};
+use crate::state::{Propagation, SwapPropagation, DepositSwap, ASSET, CONFIG, INCENTIVES, PROP, USERS, OWNERSHIP_TRANSFER, TRANCHES, DEPOSITS, DEPOSITED_ASSETS, HOLDINGS, SWAP, TOTAL_DEPOSITS};
+use crate::stream::{add_incentive_stream, edit_stream_denom, query_incentive_streams, update_streams};
+use crate::tranche::{
+    accrue_tranche, assert_pro_rata_tranches, execute_tranche_liquidation, fifo_tranche_uses, load_tranche, new_tranche,
+    new_user_deposit, open_tranche_id, pro_rata_tranche_uses, read_user_deposits, read_user_total, save_tranche, save_user_deposit, settle_deposit, store_epoch_scale_sums,
+    tranche_id, SECONDS_PER_DAY,
+};
+
+//Reply IDs
+pub const SWAP_REPLY_ID: u64 = 1u64;
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:stability-pool";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
-//Timeframe constants
-const SECONDS_PER_DAY: u64 = 86_400u64;
+//Execute msgs handled by the pause guard
+//Liquidations & repayments called by the Positions contract are never paused
+const PAUSE_ACTIONS: PauseActions = PauseActions {
+    pausable: &["update_config", "deposit", "withdraw", "restake", "claim_rewards", "add_incentive_stream", "edit_stream_denom", "edit_deposit_asset"],
+    unpausable: &["liquidate", "distribute", "repay"],
+};
 
-//FIFO Stability Pool
+//FIFO or pro-rata Stability Pool
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
@@ -54,6 +68,10 @@ pub fn instantiate(
         osmosis_proxy: deps.api.addr_validate(&msg.osmosis_proxy)?,
         positions_contract: deps.api.addr_validate(&msg.positions_contract)?,
         oracle_contract: deps.api.addr_validate(&msg.oracle_contract)?,
+        pro_rata: false,
+        dex_router: None,
+        deposit_assets: vec![],
+        stream_denoms: vec![],
     };
 
     //Set optional config parameters
@@ -74,12 +92,14 @@ pub fn instantiate(
 
     //Initialize Incentive Total
     INCENTIVES.save(deps.storage, &Uint128::zero())?;
+    TOTAL_DEPOSITS.save(deps.storage, &Uint256::zero())?;
 
     //Initialize Asset Pool
     let mut pool = msg.asset_pool;
     pool.deposits = vec![];
 
     ASSET.save(deps.storage, &pool)?;
+    HOLDINGS.save(deps.storage, pool.credit_asset.info.to_string(), &pool.credit_asset.amount)?;
 
     Ok(Response::new().add_attributes(vec![
         attr("method", "instantiate"),
@@ -96,6 +116,9 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
+    //Check the pause guard
+    assert_not_paused(deps.storage, &env, &msg, &PAUSE_ACTIONS)?;
+
     match msg {
         ExecuteMsg::UpdateConfig(update) => update_config(deps, info, update),
         ExecuteMsg::Deposit { user } => {
@@ -111,24 +134,30 @@ pub fn execute(
         }
         ExecuteMsg::Withdraw { amount } => withdraw(deps, env, info, amount),
         ExecuteMsg::Restake { restake_amount } => restake(deps, env, info, restake_amount),
-        ExecuteMsg::Liquidate { liq_amount } => liquidate(deps, info, liq_amount),
+        ExecuteMsg::Liquidate { liq_amount } => liquidate(deps, env, info, liq_amount),
+        ExecuteMsg::EditDepositAsset { asset, haircut } => edit_deposit_asset(deps, info, asset, haircut),
         ExecuteMsg::ClaimRewards {} => claim(deps, env, info),
+        ExecuteMsg::AddIncentiveStream { start_time, end_time } => add_incentive_stream(deps, env, info, start_time, end_time),
+        ExecuteMsg::EditStreamDenom { denom, minimum_amount } => edit_stream_denom(deps, info, denom, minimum_amount),
         ExecuteMsg::Distribute {
             distribution_assets,
-            distribution_asset_ratios,
+            distribution_asset_ratios: _,
             distribute_for,
         } => distribute_funds(
             deps,
             info,
             env,
             distribution_assets,
-            distribution_asset_ratios,
             distribute_for,
         ),
         ExecuteMsg::Repay {
             user_info,
             repayment,
         } => repay(deps, env, info, user_info, repayment),
+        ExecuteMsg::Pause(pause_msg) => {
+            let config = CONFIG.load(deps.storage)?;
+            Ok(execute_pause(deps.storage, deps.api, &env, &info, info.sender == config.owner, &PAUSE_ACTIONS, pause_msg)?)
+        }
     }
 }
 
@@ -203,6 +232,15 @@ fn update_config(
         }
         config.unstaking_period = new_unstaking_period;
     }
+    if let Some(pro_rata) = update.pro_rata {
+        if pro_rata && !config.pro_rata {
+            assert_pro_rata_tranches(deps.storage)?;
+        }
+        config.pro_rata = pro_rata;
+    }
+    if let Some(dex_router) = update.dex_router {
+        config.dex_router = Some(deps.api.addr_validate(&dex_router)?);
+    }
 
     //Save new Config
     CONFIG.save(deps.storage, &config)?;
@@ -211,8 +249,90 @@ fn update_config(
     Ok(Response::new().add_attributes(attrs))
 }
 
-/// Deposit debt tokens into the contract
-/// Warning: Don't deposit twice separately in the same tx. Because ids aren't used, deposits with the same owner, amount & time will be deleted together if one is used for liquidation.
+/// Add or edit an accepted deposit asset
+fn edit_deposit_asset(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset: AssetInfo,
+    haircut: Decimal,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    //Assert Authority
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    //Deposit assets are swapped through the router, so they must be native & not the credit asset
+    let asset_pool = ASSET.load(deps.storage)?;
+    if asset_pool.credit_asset.info.equal(&asset) {
+        return Err(ContractError::InvalidAsset {});
+    }
+    if let AssetInfo::Token { address: _ } = asset {
+        return Err(ContractError::CustomError { val: String::from("Deposit assets must be native tokens") });
+    }
+    if haircut > Decimal::one() {
+        return Err(ContractError::CustomError { val: String::from("Haircut can't be greater than 100%") });
+    }
+
+    match config.deposit_assets.iter_mut().find(|deposit_asset| deposit_asset.info.equal(&asset)) {
+        Some(deposit_asset) => deposit_asset.haircut = haircut,
+        None => config.deposit_assets.push(DepositAsset { info: asset.clone(), haircut }),
+    }
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "edit_deposit_asset"),
+        attr("asset", asset.to_string()),
+        attr("haircut", haircut.to_string()),
+    ]))
+}
+
+/// Return the credit asset value of one unit of a deposit asset, after its haircut
+fn query_deposit_asset_rate(
+    querier: QuerierWrapper,
+    config: &Config,
+    credit_asset: &AssetInfo,
+    deposit_asset: &DepositAsset,
+) -> StdResult<Decimal> {
+    let rate = query_deposit_asset_price(querier, config, credit_asset, &deposit_asset.info)?;
+
+    decimal_multiplication(rate, Decimal::one() - deposit_asset.haircut)
+}
+
+/// Return the credit asset value of one unit of a deposit asset at its oracle price
+fn query_deposit_asset_price(
+    querier: QuerierWrapper,
+    config: &Config,
+    credit_asset: &AssetInfo,
+    deposit_asset: &AssetInfo,
+) -> StdResult<Decimal> {
+    let query_price = |asset_info: AssetInfo| -> StdResult<PriceResponse> {
+        querier.query_wasm_smart::<PriceResponse>(
+            config.oracle_contract.to_string(),
+            &OracleQueryMsg::Price {
+                asset_info,
+                twap_timeframe: 60,
+                oracle_time_limit: 600,
+                basket_id: None,
+            },
+        )
+    };
+    let asset_price = query_price(deposit_asset.clone())?;
+    let credit_price = query_price(credit_asset.clone())?;
+
+    //Price ratio, adjusted for the difference in decimals
+    decimal_multiplication(
+        decimal_division(asset_price.price, credit_price.price)?,
+        Decimal::from_ratio(
+            10u128.pow(credit_price.decimals as u32),
+            10u128.pow(asset_price.decimals as u32),
+        ),
+    )
+}
+
+/// Deposit debt tokens into the contract.
+/// Deposits made in the same day share a Tranche & are used for liquidations pro-rata.
 pub fn deposit(
     deps: DepsMut,
     env: Env,
@@ -223,108 +343,82 @@ pub fn deposit(
     //Load Config
     let config = CONFIG.load(deps.storage)?;
 
+    let valid_owner_addr = validate_position_owner(deps.api, info, position_owner)?;
+
+    //Adding to Asset_Pool totals
+    let mut asset_pool = ASSET.load(deps.storage)?;
+
+    //Deposits are credited in credit asset terms, deposit assets are priced by the oracle & discounted by their haircut
+    let credit_amount = if asset_pool.credit_asset.info.equal(&asset.info){
+        asset.amount
+    } else {
+        match config.deposit_assets.iter().find(|deposit_asset| deposit_asset.info.equal(&asset.info)) {
+            Some(deposit_asset) => {
+                let rate = query_deposit_asset_rate(deps.querier, &config, &asset_pool.credit_asset.info, deposit_asset)?;
+
+                rate * asset.amount
+            },
+            None => return Err(ContractError::InvalidAsset {  }),
+        }
+    };
+
     //Assert minimum deposit amount
-    if asset.amount < config.minimum_deposit_amount {
+    if credit_amount < config.minimum_deposit_amount {
         return Err(ContractError::MinimumDeposit { min: config.minimum_deposit_amount });
     }
 
-    let valid_owner_addr = validate_position_owner(deps.api, info, position_owner)?;
+    //Add user deposit to Pool totals
+    asset_pool.credit_asset.amount += credit_amount;
+    ASSET.save(deps.storage, &asset_pool)?;
+    HOLDINGS.update(deps.storage, asset.info.to_string(), |held| -> StdResult<Uint128> {
+        Ok(held.unwrap_or_default() + asset.amount)
+    })?;
+    //Deposit assets are paid back first on withdrawal
+    if !asset_pool.credit_asset.info.equal(&asset.info) {
+        DEPOSITED_ASSETS.update(deps.storage, (valid_owner_addr.clone(), asset.info.to_string()), |deposited| -> StdResult<Uint128> {
+            Ok(deposited.unwrap_or_default() + asset.amount)
+        })?;
+    }
 
-    //Adding to Asset_Pool totals and deposit's list
-    let mut asset_pool = ASSET.load(deps.storage)?;
+    //Add user deposit to the open Tranche
+    let time = env.block.time.seconds();
+    let stream_indices = update_streams(deps.storage, time)?;
+    let open_id = open_tranche_id(deps.storage, &config, time)?;
+    let mut tranche = load_tranche(deps.storage, &config, &stream_indices, open_id, time)?
+        .unwrap_or_else(|| new_tranche(open_id, time, &stream_indices));
 
-    let deposit = Deposit {
-        user: valid_owner_addr.clone(),
-        amount: Decimal::from_ratio(asset.amount, Uint128::new(1u128)),
-        deposit_time: env.block.time.seconds(),
-        last_accrued: env.block.time.seconds(),
-        unstake_time: None,
+    let mut deposit = match DEPOSITS.may_load(deps.storage, (valid_owner_addr.clone(), tranche.id))? {
+        Some(deposit) => settle_deposit(deps.storage, &config, valid_owner_addr.clone(), deposit, &Some(tranche.clone()), time)?,
+        None => new_user_deposit(&tranche, time),
     };
+    deposit.amount += Uint256::from(credit_amount);
+    deposit.deposit_time = time;
+    tranche.total_deposits += Uint256::from(credit_amount);
+    TOTAL_DEPOSITS.update(deps.storage, |total| -> StdResult<Uint256> { Ok(total + Uint256::from(credit_amount)) })?;
 
-    if asset_pool.credit_asset.info.equal(&asset.info){
-        //Add user deposit to Pool totals
-        asset_pool.credit_asset.amount += asset.amount;
-        //Add user deposit to deposits list
-        asset_pool.deposits.push(deposit);
-
-        ASSET.save(deps.storage, &asset_pool)?;            
-    } else { return Err(ContractError::InvalidAsset {  }) }
+    save_user_deposit(deps.storage, valid_owner_addr.clone(), &deposit)?;
+    save_tranche(deps.storage, &tranche, time)?;
 
     //Response build
-    let response = Response::new();
-    Ok(response.add_attributes(vec![
+    let mut attrs = vec![
         attr("method", "deposit"),
         attr("position_owner", valid_owner_addr.to_string()),
         attr("deposited_asset", format!("{:?}", asset)),
-    ]))
+    ];
+    if credit_amount != asset.amount {
+        attrs.push(attr("credit_value", credit_amount));
+    }
+
+    Ok(Response::new().add_attributes(attrs))
 }
 
 /// Return accrued amount.
 /// Assert max incentives limit.
-fn accrue_incentives(
+pub fn accrue_incentives(
     storage: &mut dyn Storage,
-    querier: QuerierWrapper,
-    env: Env,
     config: Config,
-    stake: Uint128,
-    deposit: &mut Deposit,
-) -> StdResult<Uint128> {    
-    //Time elapsed starting from now or unstake time
-    let time_elapsed = match deposit.unstake_time {
-        Some(_) => {            
-            //Set last_accrued
-            deposit.last_accrued = env.block.time.seconds();
-
-            //Set time elapsed
-            //If its unstaking, there are no rewards
-            0
-
-        },
-        None => {
-            let last_accrued = deposit.last_accrued;
-            
-            //Set last_accrued
-            deposit.last_accrued = env.block.time.seconds();
-
-            //Calculate time elapsed
-            env.block.time.seconds() - last_accrued
-        },
-    };    
-
-    let rate: Decimal = config.clone().incentive_rate;
-    
-    //This calcs the amount of CDT to incentivize so the rate is acting as if TEMA = CDT (1:1) 
-    let mut incentives = accumulate_interest(stake, rate, time_elapsed)?;   
-
-    //Get CDT Price
-    // let basket: Basket = match query_basket(querier, config.clone().positions_contract.to_string()){
-    //     Ok(basket) => basket,
-    //     Err(_) => {
-    //         querier.query_wasm_smart::<Basket>(
-    //         config.clone().positions_contract,
-    //         &CDP_QueryMsg::GetBasket {}
-    //         )?
-    //     },
-    // };
-    // let cdt_price: PriceResponse = basket.credit_price;
-
-    //Get TEMA price
-    // let tema_price: Decimal = match query_asset_price(
-    //     querier, 
-    //     config.clone().oracle_contract.into(), 
-    //     AssetInfo::NativeToken { denom: config.clone().tema_denom },
-    //     60,
-    //     None,
-    // ){
-    //     Ok(price) => price,
-    //     Err(_) => cdt_price.price, //We default to CDT repayment price in the first hour of incentives
-    // };
-
-    //Transmute CDT amount to TEMA incentive amount
-    // incentives = decimal_division(
-    //     cdt_price.get_value(incentives)?
-    //     , tema_price)? * Uint128::one();
-
+    mut incentives: Uint128,
+) -> StdResult<Uint128> {
     let mut total_incentives = INCENTIVES.load(storage)?;
 
     //Assert that incentives aren't over max, set to remaining cap if so.
@@ -348,7 +442,6 @@ pub fn withdraw(
 ) -> Result<Response, ContractError> {    
     let config = CONFIG.load(deps.storage)?;
 
-    let message: CosmosMsg;
     let mut msgs = vec![];
     let mut attrs = vec![
         attr("method", "withdraw"),
@@ -359,20 +452,10 @@ pub fn withdraw(
     
     //This forces withdrawals to be done by the info.sender
     //so no need to check if the withdrawal is done by the position owner
-    let user_deposits: Vec<Deposit> = asset_pool.clone().deposits
-        .into_iter()
-        .filter(|deposit| deposit.user == info.sender)
-        .collect::<Vec<Deposit>>();
-
-    let total_user_deposits: Decimal = user_deposits
-        .iter()
-        .map(|user_deposit| user_deposit.amount)
-        .collect::<Vec<Decimal>>()
-        .into_iter()
-        .sum();
+    let total_user_deposits: Uint256 = read_user_total(deps.storage, info.clone().sender)?;
 
     //Cant withdraw more than the total deposit amount
-    if total_user_deposits < Decimal::from_ratio(amount, Uint128::new(1u128)) {
+    if total_user_deposits < Uint256::from(amount) {
         return Err(ContractError::InvalidWithdrawal {});
     } else {
         let mut skip_unstaking = false;
@@ -382,28 +465,17 @@ pub fn withdraw(
         //Go thru each deposit and withdraw request from state
         let (withdrawable, new_pool) = withdrawal_from_state(
             deps.storage,
-            deps.querier,
             env.clone(),
             config.clone(),
             info.clone().sender,
-            Decimal::from_ratio(amount, Uint128::new(1u128)),
+            amount,
             asset_pool.clone(),
             skip_unstaking,
         )?;
 
-        let user_deposits: Vec<Deposit> = new_pool.clone().deposits
-            .into_iter()
-            .filter(|deposit| deposit.user == info.sender)
-            .collect::<Vec<Deposit>>();
-
-        let new_total_user_deposits: Decimal = user_deposits
-            .iter()
-            .map(|user_deposit| user_deposit.amount)
-            .collect::<Vec<Decimal>>()
-            .into_iter()
-            .sum();
+        let new_total_user_deposits: Uint256 = read_user_total(deps.storage, info.clone().sender)?;
         
-        if withdrawable > total_user_deposits.to_uint_floor() || withdrawable > amount+config.minimum_deposit_amount || withdrawable + new_total_user_deposits.to_uint_floor() != total_user_deposits.to_uint_floor() {
+        if Uint256::from(withdrawable) > total_user_deposits || withdrawable > amount || Uint256::from(withdrawable) + new_total_user_deposits > total_user_deposits {
             return Err(ContractError::CustomError {
                 val: String::from("Invalid withdrawable amount"),
             });
@@ -411,243 +483,204 @@ pub fn withdraw(
         //Update pool
         ASSET.save(deps.storage, &new_pool)?;
 
-        //If there is a withdrawable amount
+        //If there is a withdrawable amount, pay it out of holdings
         if !withdrawable.is_zero() {
-            let withdrawable_asset = Asset {
-                amount: withdrawable,
-                ..asset_pool.clone().credit_asset
-            };
+            for withdrawn_asset in withdraw_holdings(deps.storage, deps.querier, &config, &asset_pool, info.sender.clone(), withdrawable)? {
+                attrs.push(attr("withdrawn_asset", withdrawn_asset.to_string()));
 
-            attrs.push(attr("withdrawn_asset", withdrawable_asset.to_string()));
-
-            //Create withdrawal msg
-            message = withdrawal_msg(withdrawable_asset, info.sender.clone())?;
-            msgs.push(message);
+                //Create withdrawal msg
+                msgs.push(withdrawal_msg(withdrawn_asset, info.sender.clone())?);
+            }
         }
     }    
 
     Ok(Response::new().add_attributes(attrs).add_messages(msgs))
 }
 
-/// Unstake or withdraw tokens from Deposits & update state.
+/// Remove held assets worth the withdrawn credit amount.
+/// The user's deposited assets are paid first at their deposit rate, then the credit asset,
+/// then other deposit assets at their oracle price without the haircut, so withdrawers don't take the haircut from other depositors.
+fn withdraw_holdings(
+    storage: &mut dyn Storage,
+    querier: QuerierWrapper,
+    config: &Config,
+    asset_pool: &AssetPool,
+    user: Addr,
+    credit_amount: Uint128,
+) -> StdResult<Vec<Asset>> {
+    let mut remaining = credit_amount;
+    let mut withdrawn_assets = vec![];
+
+    let (deposited, others): (Vec<DepositAsset>, Vec<DepositAsset>) = config
+        .deposit_assets
+        .clone()
+        .into_iter()
+        .partition(|deposit_asset| DEPOSITED_ASSETS.has(storage, (user.clone(), deposit_asset.info.to_string())));
+
+    //User's deposited assets
+    for deposit_asset in deposited {
+        remaining = withdraw_deposit_asset(storage, querier, config, asset_pool, Some(user.clone()), deposit_asset, remaining, &mut withdrawn_assets)?;
+    }
+
+    //Credit asset
+    if !remaining.is_zero() {
+        let info = asset_pool.credit_asset.info.clone();
+        let held = HOLDINGS.may_load(storage, info.to_string())?.unwrap_or_default();
+        let amount = remaining.min(held);
+
+        if !amount.is_zero() {
+            HOLDINGS.save(storage, info.to_string(), &(held - amount))?;
+            withdrawn_assets.push(Asset { info, amount });
+            remaining -= amount;
+        }
+    }
+
+    //Other deposit assets, if liquidations used up the held credit asset
+    for deposit_asset in others {
+        remaining = withdraw_deposit_asset(storage, querier, config, asset_pool, None, deposit_asset, remaining, &mut withdrawn_assets)?;
+    }
+
+    Ok(withdrawn_assets)
+}
+
+/// Remove a held deposit asset worth up to the remaining credit amount & return what's left.
+/// If a user is passed, only the amount they deposited is used & it's valued after its haircut like on deposit.
+#[allow(clippy::too_many_arguments)]
+fn withdraw_deposit_asset(
+    storage: &mut dyn Storage,
+    querier: QuerierWrapper,
+    config: &Config,
+    asset_pool: &AssetPool,
+    user: Option<Addr>,
+    deposit_asset: DepositAsset,
+    remaining: Uint128,
+    withdrawn_assets: &mut Vec<Asset>,
+) -> StdResult<Uint128> {
+    if remaining.is_zero() {
+        return Ok(remaining);
+    }
+
+    let held = HOLDINGS.may_load(storage, deposit_asset.info.to_string())?.unwrap_or_default();
+    let available = match user.clone() {
+        Some(user) => held.min(DEPOSITED_ASSETS.may_load(storage, (user, deposit_asset.info.to_string()))?.unwrap_or_default()),
+        None => held,
+    };
+    if available.is_zero() {
+        return Ok(remaining);
+    }
+
+    let rate = match user {
+        Some(_) => query_deposit_asset_rate(querier, config, &asset_pool.credit_asset.info, &deposit_asset)?,
+        None => query_deposit_asset_price(querier, config, &asset_pool.credit_asset.info, &deposit_asset.info)?,
+    };
+    if rate.is_zero() {
+        return Ok(remaining);
+    }
+    let amount = available.min(decimal_division(Decimal::from_ratio(remaining, Uint128::new(1u128)), rate)? * Uint128::new(1u128));
+    if amount.is_zero() {
+        return Ok(remaining);
+    }
+
+    HOLDINGS.save(storage, deposit_asset.info.to_string(), &(held - amount))?;
+    if let Some(user) = user {
+        let key = (user, deposit_asset.info.to_string());
+        let deposited = DEPOSITED_ASSETS.load(storage, key.clone())?;
+        if deposited > amount {
+            DEPOSITED_ASSETS.save(storage, key, &(deposited - amount))?;
+        } else {
+            DEPOSITED_ASSETS.remove(storage, key);
+        }
+    }
+    withdrawn_assets.push(Asset { info: deposit_asset.info, amount });
+
+    Ok(remaining - (rate * amount).min(remaining))
+}
+
+/// Unstake or withdraw tokens from the user's deposits & update state.
 /// Add any claimables to user claims.
 fn withdrawal_from_state(
     storage: &mut dyn Storage,
-    querier: QuerierWrapper,
     env: Env,
     config: Config,
     user: Addr,
-    mut withdrawal_amount: Decimal,
+    withdrawal_amount: Uint128,
     mut pool: AssetPool,
     skip_unstaking: bool,
 ) -> Result<(Uint128, AssetPool), ContractError> {
-    
-    let mut tema_incentives = Uint128::zero();
+    let time = env.block.time.seconds();
+    let mut withdrawal_amount = Uint256::from(withdrawal_amount);
+    let mut withdrawable_amount = Uint256::zero();
+    let stream_indices = update_streams(storage, time)?;
+
+    for deposit in read_user_deposits(storage, user.clone())? {
+        let mut tranche = load_tranche(storage, &config, &stream_indices, deposit.tranche_id, time)?;
+        //Settle rewards before editing the deposit
+        let mut deposit = settle_deposit(storage, &config, user.clone(), deposit, &tranche, time)?;
+
+        if !withdrawal_amount.is_zero() {
+            /////Check if deposit is withdrawable
+            let withdrawn = if skip_unstaking {
+                //Allow regular withdraws if from CDP Repay fn or if there is no unstaking period
+                let withdrawn = withdrawal_amount.min(deposit.amount);
+                deposit.unstaking_amount = deposit.unstaking_amount - withdrawn.min(deposit.unstaking_amount);
+
+                withdrawn
+            } else {
+                match deposit.unstake_time {
+                    //If deposit has been "unstaked" ie previously withdrawn, assert the unstaking period has passed before withdrawing
+                    Some(unstake_time) if time - unstake_time >= (config.unstaking_period * SECONDS_PER_DAY) => {
+                        let withdrawn = withdrawal_amount.min(deposit.unstaking_amount);
+                        deposit.unstaking_amount = deposit.unstaking_amount - withdrawn;
+
+                        withdrawn
+                    },
+                    //If unstaking period hasn't passed do nothing
+                    _ => Uint256::zero(),
+                }
+            };
 
-    let mut error: Option<StdError> = None;
-    let mut is_user = false;
-    let mut withdrawable = false;
-    let mut withdrawable_amount = Uint128::zero();
-    let mut returning_deposit: Option<Deposit> = None;
+            //Subtract from deposit & Tranche
+            deposit.amount = deposit.amount - withdrawn;
+            withdrawal_amount = withdrawal_amount - withdrawn;
+            withdrawable_amount += withdrawn;
+            if let Some(tranche) = tranche.as_mut() {
+                tranche.total_deposits = tranche.total_deposits - withdrawn;
+            }
+            if deposit.unstaking_amount.is_zero() {
+                deposit.unstake_time = None;
+            }
 
-    let mut new_deposits: Vec<Deposit> = pool
-        .clone()
-        .deposits
-        .into_iter()
-        .map(|mut deposit_item| {
-            //Only edit user deposits
-            if deposit_item.user == user {
-                is_user = true;
-                
-                //Calc incentives for the deposit
-                let accrued_incentives = match accrue_incentives(
-                    storage,
-                    querier,
-                    env.clone(),
-                    config.clone(),
-                    deposit_item.amount * Uint128::new(1u128),
-                    &mut deposit_item,
-                ){
-                    Ok(incentive) => incentive,
-                    Err(err) => {
-                        error = Some(err);
-                        Uint128::zero()
-                    }
-                };
-                tema_incentives += accrued_incentives;
+            //Unstake the rest from the deposit's staked amount
+            if !skip_unstaking && !withdrawal_amount.is_zero() {
+                let unstake_amount = withdrawal_amount.min(deposit.amount - deposit.unstaking_amount);
 
-                /////Check if deposit is withdrawable
-                if !skip_unstaking {
-                    //If deposit has been "unstaked" ie previously withdrawn, assert the unstaking period has passed before withdrawing
-                    if deposit_item.unstake_time.is_some() {
-                        //If time_elapsed is >= unstaking period
-                        if env.block.time.seconds() - deposit_item.unstake_time.unwrap()
-                            >= (config.unstaking_period * SECONDS_PER_DAY)
-                        {
-                            withdrawable = true;
-                        } //If unstaking period hasn't passed do nothing
-
-                    } else {
-                        //Set unstaking time for the amount getting withdrawn
-                        //Create a Deposit object for the amount getting unstaked so the original deposit doesn't lose its position
-                        if deposit_item.amount > withdrawal_amount
-                            && withdrawal_amount != Decimal::zero()
-                        {
-                            //If withdrawal amount is less than minimum deposit amount, set withdrawal amount to minimum deposit amount
-                            //This ensures all Deposits are at least the minimum deposit amount
-                            if withdrawal_amount * Uint128::new(1u128) < config.minimum_deposit_amount {
-                                //If withdrawal amount is less than minimum deposit amount, set withdrawal amount to minimum deposit amount
-                                withdrawal_amount = Decimal::from_ratio(config.minimum_deposit_amount, Uint128::one());
-
-                                //If the resulting deposit amount is less than minimum deposit amount, withdraw it all
-                                if deposit_item.amount - withdrawal_amount < withdrawal_amount {
-                                    withdrawal_amount = deposit_item.amount;
-                                }
-                            }
-
-                            //Set new deposit
-                            returning_deposit = Some(Deposit {
-                                amount: deposit_item.amount - withdrawal_amount,
-                                unstake_time: None,
-                                ..deposit_item.clone()
-                            });
-
-                            //Update existing deposit state
-                            deposit_item.amount = withdrawal_amount;
-                            //Only set stake time if it hasn't been set yet
-                            //Only 0 withdrawal_amount if this is a new unstaking
-                            if deposit_item.unstake_time.is_none() {
-                                deposit_item.unstake_time = Some(env.block.time.seconds());
-
-                                //Set withdrawal_amount to 0
-                                withdrawal_amount = Decimal::zero();
-                            }
-
-
-                        } else if withdrawal_amount != Decimal::zero() {
-                            //Set unstaking time
-                            //Only set stake time if it hasn't been set yet
-                            //Only subtract from withdrawal_amount if this is a new unstaking
-                            if deposit_item.unstake_time.is_none() {
-                                deposit_item.unstake_time = Some(env.block.time.seconds());
-                                
-                                //Subtract from withdrawal_amount 
-                                withdrawal_amount -= deposit_item.amount;
-                            }
-                        }                        
-                    }
-                } else {
-                    //Allow regular withdraws if from CDP Repay fn
-                    deposit_item.unstake_time = Some( env.block.time.seconds() );
-                    //Withdraws from state
-                    withdrawable = true;
-                }
+                if !unstake_amount.is_zero() {
+                    deposit.unstaking_amount += unstake_amount;
+                    //Unstaking period restarts for the full unstaking amount
+                    deposit.unstake_time = Some(time);
 
-                //Subtract from each deposit until there is none left to withdraw
-                //If not withdrawable we only edit withdraw amount to make sure the deposits...
-                //..that would get parsed through in a valid withdrawal get edited
-                if withdrawal_amount != Decimal::zero() && deposit_item.amount > withdrawal_amount && (skip_unstaking || withdrawable) {
-
-                    withdrawable_amount += withdrawal_amount * Uint128::new(1u128);
-
-                    //Subtract from deposit.amount
-                    deposit_item.amount -= withdrawal_amount;
-
-                    //Check if deposit is below minimum
-                    if deposit_item.amount * Uint128::new(1u128) < config.minimum_deposit_amount {
-                        //If it is, add to withdrawable
-                        withdrawable_amount += deposit_item.amount * Uint128::new(1u128);
-                        //Set deposit amount to 0
-                        deposit_item.amount = Decimal::zero();
-                    }                      
-                    
-
-                    //Calc incentives
-                    let accrued_incentives = match accrue_incentives(
-                        storage,
-                        querier,
-                        env.clone(),
-                        config.clone(),
-                        withdrawal_amount * Uint128::new(1u128),
-                        &mut deposit_item,
-                    ){
-                        Ok(incentive) => incentive,
-                        Err(err) => {
-                            error = Some(err);
-                            Uint128::zero()
-                        }
-                    };
-
-                    tema_incentives += accrued_incentives;
-                    
-                    withdrawal_amount = Decimal::zero();
-
-                } else if withdrawal_amount != Decimal::zero() && deposit_item.amount <= withdrawal_amount && (skip_unstaking || withdrawable) {
-                    //If deposit.amount less than withdrawal_amount, subtract it from the withdrawal amount
-                    withdrawal_amount -= deposit_item.amount;  
-                    
-                    //Add to withdrawable_amount
-                    withdrawable_amount += deposit_item.amount * Uint128::new(1u128);  
-                    //Set deposit amount to 0      
-                    deposit_item.amount = Decimal::zero();                
-                    
+                    withdrawal_amount = withdrawal_amount - unstake_amount;
                 }
+            }
+        }
 
-                withdrawable = false;
-            }                    
-            
-            deposit_item
-        })
-        .collect::<Vec<Deposit>>()
-        .into_iter()
-        .filter(|deposit| deposit.amount != Decimal::zero())
-        .collect::<Vec<Deposit>>();
+        //Save updated state
+        save_user_deposit(storage, user.clone(), &deposit)?;
+        if let Some(tranche) = tranche {
+            save_tranche(storage, &tranche, time)?;
+        }
+    }
 
-    if withdrawal_amount != Decimal::zero() {
+    if !withdrawal_amount.is_zero() {
         return Err(ContractError::InvalidWithdrawal {});
     }
 
-    //Sets returning_deposit to the back of the line, if some
-    if let Some(deposit) = returning_deposit {
-        if deposit.amount != Decimal::zero() {
-            new_deposits.push(deposit);
-        }
-    }//Set new deposits
-    pool.deposits = new_deposits;
-    //Subtract withdrawable from total pool amount
-    pool.credit_asset.amount = pool.credit_asset.amount.checked_sub(withdrawable_amount).unwrap();
+    //Withdrawn deposits stop sharing stream emissions
+    TOTAL_DEPOSITS.update(storage, |total| -> StdResult<Uint256> { Ok(total - withdrawable_amount.min(total)) })?;
 
-    if error.is_some() {
-        return Err(ContractError::CustomError {
-            val: error.unwrap().to_string(),
-        });
-    }
-    
-    //If there are incentives
-    if !tema_incentives.is_zero() {
-        //Add incentives to User Claims
-        USERS.update(
-            storage,
-            user,
-            |user_claims| -> Result<User, ContractError> {
-                match user_claims {
-                    Some(mut user) => {
-                        user.claimable_assets.add(&coin(tema_incentives.u128(), config.clone().tema_denom))?;
-                                
-                        Ok(user)
-                    }
-                    None => {
-                        if is_user {
-                            Ok(User {
-                                claimable_assets: Coins::from_str(&coin(tema_incentives.u128(), config.clone().tema_denom).to_string())?,
-                            })
-                        } else {
-                            Err(ContractError::CustomError {
-                                val: String::from("Invalid user"),
-                            })
-                }}}},
-        )?;
-    }
+    //Subtract withdrawable from total pool amount
+    let withdrawable_amount = Uint128::from(withdrawable_amount);
+    pool.credit_asset.amount = pool.credit_asset.amount.checked_sub(withdrawable_amount).map_err(StdError::from)?;
 
     Ok((withdrawable_amount, pool))
 }
@@ -657,86 +690,42 @@ fn restake(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    mut restake_amount: Decimal,
+    restake_amount: Decimal,
 ) -> Result<Response, ContractError> {
     //Initialize variables
     let initial_restake = restake_amount;
-    let mut incentives = Uint128::zero();
-    let mut error: Option<StdError> = None;
+    let mut restake_amount = Uint256::from(restake_amount * Uint128::new(1u128));
 
-    let mut asset_pool = ASSET.load(deps.storage)?;
     let config = CONFIG.load(deps.storage)?;
+    let time = env.block.time.seconds();
+    let stream_indices = update_streams(deps.storage, time)?;
     
     //Attempt restaking 
-    asset_pool.deposits = asset_pool
-        .deposits
-        .into_iter()
-        .map(|mut deposit| {
-            if deposit.user == info.clone().sender && !restake_amount.is_zero() && deposit.unstake_time.is_some(){
-
-                //Accrue the deposit's incentives
-                incentives += match accrue_incentives(
-                    deps.storage, 
-                    deps.querier,
-                    env.clone(), 
-                    config.clone(),
-                    deposit.amount * Uint128::new(1u128), 
-                    &mut deposit){
-                        Ok(incentive) => incentive,
-                        Err(err) => {
-                            error = Some(err);
-                            Uint128::zero()
-                        }
-                    };
-
-                if deposit.amount >= restake_amount {
-                    //Zero restake_amount
-                    restake_amount = Decimal::zero();
-
-                    //Restake
-                    deposit.unstake_time = None;
-                    deposit.deposit_time = env.block.time.seconds();
-                } else if deposit.amount < restake_amount {
-                    //Sub from restake_amount
-                    restake_amount -= deposit.amount;
-
-                    //Restake
-                    deposit.unstake_time = None;
-                    deposit.deposit_time = env.block.time.seconds();
-                }
-            }
-            deposit
-        })
-        .collect::<Vec<Deposit>>();
-
-    //Return error from the accrue_incentives function if Some()
-    if let Some(error) = error {
-        return Err(ContractError::CustomError {
-            val: error.to_string(),
-        });
-    }
+    for deposit in read_user_deposits(deps.storage, info.clone().sender)? {
+        if restake_amount.is_zero() {
+            break;
+        }
+        if deposit.unstake_time.is_none() {
+            continue;
+        }
 
-    //Save accrued incentives to user claims
-    if !incentives.is_zero(){
-        USERS.update(
-            deps.storage,
-            info.sender,
-            |user_claims| -> Result<User, ContractError> {
-                match user_claims {
-                    Some(mut user) => {
-                        user.claimable_assets.add(&coin(incentives.u128(), config.clone().tema_denom))?;
+        let tranche = load_tranche(deps.storage, &config, &stream_indices, deposit.tranche_id, time)?;
+        //Settle the deposit's rewards, no incentives were accrued while unstaking
+        let mut deposit = settle_deposit(deps.storage, &config, info.clone().sender, deposit, &tranche, time)?;
 
-                        Ok(user)
-                    }
-                    None => {
-                        Ok(User {
-                            claimable_assets: Coins::from_str(&coin(incentives.u128(), config.clone().tema_denom).to_string())?,
-                })}}},
-        )?;
-    }
+        //Restake
+        let restaked = restake_amount.min(deposit.unstaking_amount);
+        deposit.unstaking_amount = deposit.unstaking_amount - restaked;
+        restake_amount = restake_amount - restaked;
 
-    //Save new Deposits
-    ASSET.save(deps.storage, &asset_pool)?;
+        if deposit.unstaking_amount.is_zero() {
+            deposit.unstake_time = None;
+        }
+        deposit.deposit_time = time;
+
+        //Save new Deposit
+        save_user_deposit(deps.storage, info.clone().sender, &deposit)?;
+    }
 
     Ok(Response::new().add_attributes(vec![
         attr("method", "restake"),
@@ -745,9 +734,11 @@ fn restake(
 }
 
 /// Send repayments for the Positions contract.
+/// Held credit asset is used first, deposit assets are swapped for the rest & repaid in the reply.
 /// Positions contract sends back a distribute msg.
 pub fn liquidate(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     credit_amount: Decimal,
 ) -> Result<Response, ContractError> {
@@ -761,65 +752,237 @@ pub fn liquidate(
 
     let liq_amount = credit_amount;
     //Assert repay amount or pay as much as possible
-    let mut repay_asset = Asset {
-        info: asset_pool.credit_asset.info.clone(),
-        amount: Uint128::new(0u128),
-    };
-    let mut leftover = Decimal::zero();
-
-    if liq_amount > Decimal::from_ratio(asset_pool.credit_asset.amount, Uint128::new(1u128)) {
+    let (mut repay_amount, mut leftover) = if liq_amount > Decimal::from_ratio(asset_pool.credit_asset.amount, Uint128::new(1u128)) {
         //If greater then repay what's possible
-        repay_asset.amount = asset_pool.credit_asset.amount;
-        leftover =
-            liq_amount - Decimal::from_ratio(asset_pool.credit_asset.amount, Uint128::new(1u128));
+        (
+            asset_pool.credit_asset.amount,
+            liq_amount - Decimal::from_ratio(asset_pool.credit_asset.amount, Uint128::new(1u128)),
+        )
     } else {
         //Pay what's being asked
-        repay_asset.amount = liq_amount * Uint128::new(1u128); // * 1
+        (liq_amount * Uint128::new(1u128), Decimal::zero()) // * 1
+    };
+
+    //Use held credit asset first
+    let credit_denom = asset_pool.credit_asset.info.to_string();
+    let held_credit = HOLDINGS.may_load(deps.storage, credit_denom.clone())?.unwrap_or_default();
+    let credit_repayment = repay_amount.min(held_credit);
+    HOLDINGS.save(deps.storage, credit_denom.clone(), &(held_credit - credit_repayment))?;
+
+    //Swap deposit assets for the rest, selling the amount their oracle value after haircut covers
+    let mut remaining = repay_amount - credit_repayment;
+    let mut swap_repayment = Uint128::zero();
+    let mut swap_msgs: Vec<CosmosMsg> = vec![];
+    let mut pending_swaps: Vec<DepositSwap> = vec![];
+
+    if !remaining.is_zero() {
+        if let Some(dex_router) = config.clone().dex_router {
+            for deposit_asset in config.clone().deposit_assets {
+                if remaining.is_zero() {
+                    break;
+                }
+
+                let held = HOLDINGS.may_load(deps.storage, deposit_asset.info.to_string())?.unwrap_or_default();
+                if held.is_zero() {
+                    continue;
+                }
+                let rate = query_deposit_asset_rate(deps.querier, &config, &asset_pool.credit_asset.info, &deposit_asset)?;
+                let credit_value = rate * held;
+                let used = remaining.min(credit_value);
+                if used.is_zero() {
+                    continue;
+                }
+
+                //Sell all of it if its full value is used
+                let sell_amount = if used == credit_value {
+                    held
+                } else {
+                    (decimal_division(
+                        Decimal::from_ratio(used, Uint128::new(1u128)),
+                        rate,
+                    )? * Uint128::new(1u128)).min(held)
+                };
+                HOLDINGS.save(deps.storage, deposit_asset.info.to_string(), &(held - sell_amount))?;
+
+                //The swap must return at least the credit it's repaying, the haircut covers slippage
+                swap_msgs.push(router_native_to_native(
+                    dex_router.to_string(),
+                    deposit_asset.info.clone(),
+                    asset_pool.credit_asset.info.clone(),
+                    None,
+                    sell_amount.u128(),
+                    Some(used),
+                )?);
+                pending_swaps.push(DepositSwap {
+                    sold: Asset { info: deposit_asset.info, amount: sell_amount },
+                    repayment: used,
+                });
+
+                remaining -= used;
+                swap_repayment += used;
+            }
+        }
+    }
+
+    //Any amount holdings can't cover is leftover
+    if !remaining.is_zero() {
+        repay_amount -= remaining;
+        leftover += Decimal::from_ratio(remaining, Uint128::new(1u128));
     }
 
     //Save Repaid amount to Propagate
     let mut prop = PROP.load(deps.storage)?;
-    prop.repaid_amount += repay_asset.amount;
+    prop.repaid_amount += repay_amount;
     PROP.save(deps.storage, &prop)?;
     //^This isn't used for anything bc we pass in the distribute_for field
 
-    //Repay for the user
-    let repay_msg = CDP_ExecuteMsg::LiqRepay {};
+    //Subtract repaid_amount from totals
+    asset_pool.credit_asset.amount -= repay_amount;
+    //Save updated Pool
+    ASSET.save(deps.storage, &asset_pool)?;
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut sub_msgs: Vec<SubMsg> = vec![];
 
-    let coin: Coin = asset_to_coin(repay_asset.clone())?;
+    if swap_msgs.is_empty() {
+        //Repay for the user
+        messages.push(liq_repay_msg(&config, &asset_pool, credit_repayment)?);
+    } else {
+        //Repayment is sent in the reply once the swaps are done
+        let prev_balance = deps.querier
+            .query_balance(env.contract.address, credit_denom)?
+            .amount;
+
+        SWAP.save(deps.storage, &SwapPropagation {
+            credit_repayment,
+            swap_repayment,
+            pending_swaps,
+            failed_repayment: Uint128::zero(),
+            prev_balance,
+        })?;
+
+        //Failed swaps don't revert the liquidation, their assets stay in holdings
+        for swap_msg in swap_msgs {
+            sub_msgs.push(SubMsg::reply_always(swap_msg, SWAP_REPLY_ID));
+        }
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_submessages(sub_msgs)
+        .add_attributes(vec![
+            attr("method", "liquidate"),
+            attr(
+                "leftover_repayment",
+               leftover.to_string(),
+            ),
+        ]))
+}
 
-    let message = CosmosMsg::Wasm(WasmMsg::Execute {
+/// Positions contract LiqRepay msg for a credit asset amount
+fn liq_repay_msg(
+    config: &Config,
+    asset_pool: &AssetPool,
+    amount: Uint128,
+) -> StdResult<CosmosMsg> {
+    let coin: Coin = asset_to_coin(Asset {
+        amount,
+        ..asset_pool.clone().credit_asset
+    })?;
+
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
         contract_addr: config.positions_contract.to_string(),
-        msg: to_binary(&repay_msg)?,
+        msg: to_binary(&CDP_ExecuteMsg::LiqRepay {})?,
         funds: vec![coin],
-    });
+    }))
+}
 
-    //Subtract repaid_amount from totals
-    asset_pool.credit_asset.amount -= repay_asset.amount;
-    //Save updated Pool
-    ASSET.save(deps.storage, &asset_pool)?;
-    
-    Ok(Response::new().add_message(message).add_attributes(vec![
-        attr("method", "liquidate"),
-        attr(
-            "leftover_repayment",
-           leftover.to_string(),
-        ),
-    ]))
+/// Handle a deposit asset swap's reply. Failed swaps return their sold asset to holdings.
+/// Once every swap has replied, repay for the Positions contract with the held credit & the credit received from the swaps.
+/// Swap surplus is added to holdings, the repayment of failed swaps is added back to the pool & left for the rest of the liquidation.
+pub fn handle_swap_reply(deps: DepsMut, env: Env, msg: Reply) -> StdResult<Response> {
+    let mut swap = SWAP.load(deps.storage)?;
+    if swap.pending_swaps.is_empty() {
+        return Err(StdError::generic_err("No pending deposit asset swaps"));
+    }
+    let deposit_swap = swap.pending_swaps.remove(0);
+
+    let mut attrs = vec![attr("method", "handle_swap_reply")];
+    if let Err(err) = msg.result.into_result() {
+        //The swap reverted, so the sold asset is still in the contract
+        HOLDINGS.update(deps.storage, deposit_swap.sold.info.to_string(), |held| -> StdResult<Uint128> {
+            Ok(held.unwrap_or_default() + deposit_swap.sold.amount)
+        })?;
+        swap.failed_repayment += deposit_swap.repayment;
+
+        attrs.push(attr("failed_swap", deposit_swap.sold.to_string()));
+        attrs.push(attr("swap_error", err));
+    }
+
+    //Wait for the rest of the swaps
+    if !swap.pending_swaps.is_empty() {
+        SWAP.save(deps.storage, &swap)?;
+        return Ok(Response::new().add_attributes(attrs));
+    }
+    SWAP.remove(deps.storage);
+
+    let config = CONFIG.load(deps.storage)?;
+    let mut asset_pool = ASSET.load(deps.storage)?;
+    let credit_denom = asset_pool.credit_asset.info.to_string();
+
+    //Amount received from the swaps, successful swaps return at least their repayment
+    let current_balance = deps.querier
+        .query_balance(env.contract.address, credit_denom.clone())?
+        .amount;
+    let received = current_balance.checked_sub(swap.prev_balance)?;
+    let swap_repaid = received.min(swap.swap_repayment - swap.failed_repayment);
+
+    if received > swap_repaid {
+        HOLDINGS.update(deps.storage, credit_denom, |held| -> StdResult<Uint128> {
+            Ok(held.unwrap_or_default() + (received - swap_repaid))
+        })?;
+    }
+
+    //Deposits weren't used for the unrepaid amount
+    let unrepaid = swap.swap_repayment - swap_repaid;
+    if !unrepaid.is_zero() {
+        asset_pool.credit_asset.amount += unrepaid;
+        ASSET.save(deps.storage, &asset_pool)?;
+
+        let mut prop = PROP.load(deps.storage)?;
+        prop.repaid_amount = prop.repaid_amount.saturating_sub(unrepaid);
+        PROP.save(deps.storage, &prop)?;
+    }
+
+    let repay_amount = swap.credit_repayment + swap_repaid;
+    let mut messages = vec![];
+    if !repay_amount.is_zero() {
+        messages.push(liq_repay_msg(&config, &asset_pool, repay_amount)?);
+    }
+
+    attrs.extend(vec![
+        attr("credit_received", received),
+        attr("repay_amount", repay_amount),
+        attr("unrepaid", unrepaid),
+    ]);
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(attrs))
 }
 
-/// Calculate which and how much each user gets distributed from the liquidation.
-/// Distributions are done in order of the Deposit list, not deposit_time.
+/// Use Tranches for the repaid amount & add the distributed assets to their sums.
+/// Tranches are used in order, or by weight in pro-rata mode.
+/// Assets are split to each Tranche pro-rata to the amount used from it.
 pub fn distribute_funds(
     deps: DepsMut,
     info: MessageInfo,
     env: Env,
     distribution_assets: Vec<Asset>,
-    distribution_asset_ratios: Vec<Decimal>,
     distribute_for: Uint128, //How much repayment is this distributing for
 ) -> Result<Response, ContractError> {
     //Load State
-    let mut asset_pool = ASSET.load(deps.storage)?;   
+    let asset_pool = ASSET.load(deps.storage)?;   
     let config = CONFIG.load(deps.storage)?;
 
     //Can only be called by the positions contract
@@ -827,121 +990,45 @@ pub fn distribute_funds(
         return Err(ContractError::Unauthorized {});
     } 
 
-    //Set repaid_amount
-    let repaid_amount: Uint128 = distribute_for;
-
-    ///Calculate the user distributions
-    let mut pool_parse = asset_pool.clone().deposits.into_iter();
-    let mut distribution_list: Vec<Deposit> = vec![];
-    let mut current_repay_total: Decimal = Decimal::percent(0);
-    let repaid_amount_decimal = Decimal::from_ratio(repaid_amount, Uint128::new(1u128));
-
-    //Create distribution list
-    while current_repay_total < repaid_amount_decimal {
-        match pool_parse.next() {
-            Some(mut deposit) => {
-
-                //If greater, only add what's necessary and edit the deposit
-                if (current_repay_total + deposit.amount) > repaid_amount_decimal {
-                    //Subtract to calc what's left to repay
-                    let remaining_repayment = repaid_amount_decimal - current_repay_total;
-
-                    deposit.amount -= remaining_repayment;
-                    current_repay_total += remaining_repayment;
-
-                    //Add Deposit w/ amount = to remaining_repayment
-                    //Splits original Deposit amount between both Vecs
-                    distribution_list.push(Deposit {
-                        amount: remaining_repayment,
-                        ..deposit.clone()
-                    });
-
-                    //Calc TEMA incentives
-                    if env.block.time.seconds() > deposit.last_accrued {
-                        let accrued_incentives = accrue_incentives(
-                            deps.storage,
-                            deps.querier,
-                            env.clone(),
-                            config.clone(),
-                            remaining_repayment * Uint128::new(1u128),
-                            &mut deposit,
-                        )?;
-
-                        if !accrued_incentives.is_zero() {                 
-                            //Add incentives to User Claims
-                            add_to_user_claims(deps.storage, deposit.user, AssetInfo::NativeToken { denom: config.clone().tema_denom }, accrued_incentives)?;
-                        }
-                    }
-                } else {
-                    //Else, keep adding
-                    current_repay_total += deposit.amount;
-                    distribution_list.push(deposit.clone());
-                    
-                    if env.block.time.seconds() > deposit.last_accrued { 
-                        //Calc TEMA incentives
-                        let accrued_incentives = accrue_incentives(
-                            deps.storage,
-                            deps.querier,
-                            env.clone(),
-                            config.clone(),
-                            deposit.amount * Uint128::new(1u128),
-                            &mut deposit,
-                        )?;
-
-                        if !accrued_incentives.is_zero() {                            
-                            //Add incentives to User Claims
-                            add_to_user_claims(deps.storage, deposit.user, AssetInfo::NativeToken { denom: config.clone().tema_denom }, accrued_incentives)?;
-                        }
-                    }
-                }
-            }
-            None => {
-                //End of deposit list
-                //If it gets here and the repaid amount != current_repay_total, the state was mismanaged previously
-                //since by now the funds have already been sent.
-                //For safety sake we'll set the values equal, as their job was to act as a limiter for the distribution list.
-                current_repay_total = repaid_amount_decimal;
-            }
-        }
-    }
+    let time = env.block.time.seconds();
+    let stream_indices = update_streams(deps.storage, time)?;
 
-    //This doesn't filter partial uses
-    let mut edited_deposits: Vec<Deposit> = asset_pool
-        .clone()
-        .deposits
-        .into_iter()
-        .filter(|deposit| !deposit.equal(&distribution_list))
-        .collect::<Vec<Deposit>>();
-        
-    //If there is an overlap between the lists, meaning there was a partial usage, account for it
-    if distribution_list.len() + edited_deposits.len() > asset_pool.deposits.len() {
-        edited_deposits[0].amount -= distribution_list[distribution_list.len() - 1].amount;
-    }
+    //Get the amount used from each Tranche
+    let repaid_amount = Uint256::from(distribute_for);
+    let tranche_uses = if config.pro_rata {
+        pro_rata_tranche_uses(deps.storage, repaid_amount)?
+    } else {
+        fifo_tranche_uses(deps.storage, repaid_amount)?
+    };
+    let mut distributed: Vec<Uint128> = vec![Uint128::zero(); distribution_assets.len()];
 
-    //Set deposits
-    asset_pool.deposits = edited_deposits;
+    for (index, (mut tranche, used_amount)) in tranche_uses.clone().into_iter().enumerate() {
+        //Accrue incentives up to the liquidation
+        if accrue_tranche(&mut tranche, config.incentive_rate, &stream_indices, time) {
+            store_epoch_scale_sums(deps.storage, &tranche)?;
+        }
 
-    //Save pool w/ edited deposits to state
-    ASSET.save(deps.storage, &asset_pool)?;
+        //Split assets to the Tranche, the last Tranche gets the remainder to account for rounding
+        let is_last = index == tranche_uses.len() - 1;
+        let tranche_assets: Vec<Asset> = distribution_assets
+            .clone()
+            .into_iter()
+            .enumerate()
+            .map(|(asset_index, asset)| {
+                let amount = if is_last {
+                    asset.amount - distributed[asset_index]
+                } else {
+                    asset.amount.multiply_ratio(Uint128::from(used_amount), distribute_for)
+                };
+                distributed[asset_index] += amount;
 
-    //Calc user ratios and distribute collateral based on them
-    //Distribute 1 collateral at a time (not pro-rata) for gas and UX optimizations (ie if a user wants to sell they won't have to sell on 4 different pairs)
-    let (ratios, user_deposits) = get_distribution_ratios(distribution_list.clone())?;
+                Asset { amount, ..asset }
+            })
+            .collect::<Vec<Asset>>();
 
-    let distribution_ratios: Vec<UserRatio> = user_deposits
-        .into_iter()
-        .enumerate()
-        .map(|(index, deposit)| UserRatio {
-            user: deposit.user,
-            ratio: ratios[index],
-        })
-        .collect::<Vec<UserRatio>>();
-
-    //1) Calc cAsset's ratios of total value
-    let cAsset_ratios = distribution_asset_ratios;
-    
-    //2) Split assets to users
-    split_assets_to_users(deps.storage, cAsset_ratios, distribution_assets.clone(), distribution_ratios)?;
+        execute_tranche_liquidation(deps.storage, &mut tranche, used_amount, tranche_assets)?;
+        save_tranche(deps.storage, &tranche, time)?;
+    }
 
     //Response Builder
     let res = Response::new();
@@ -979,34 +1066,26 @@ fn repay(
 
         //This forces repayments to be done by the position_owner
         //so no need to check if the withdrawal is done by the position owner
-        let user_deposits: Vec<Deposit> = asset_pool
-            .clone()
-            .deposits
-            .into_iter()
-            .filter(|deposit| deposit.user == position_owner)
-            .collect::<Vec<Deposit>>();
-
-        let total_user_deposits: Decimal = user_deposits
-            .iter()
-            .map(|user_deposit| user_deposit.amount)
-            .collect::<Vec<Decimal>>()
-            .into_iter()
-            .sum();
+        let total_user_deposits: Uint256 = read_user_total(deps.storage, position_owner.clone())?;
 
         //Cant repay more than the total deposit amount
-        if total_user_deposits < Decimal::from_ratio(repayment.amount, Uint128::new(1u128)) {
+        if total_user_deposits < Uint256::from(repayment.amount) {
             return Err(ContractError::InvalidWithdrawal {});
         } else if total_user_deposits.is_zero() {
             return Err(ContractError::InvalidWithdrawal {});
         } else {
+            //Repayments are paid from the held credit asset
+            let credit_denom = asset_pool.credit_asset.info.to_string();
+            let held_credit = HOLDINGS.may_load(deps.storage, credit_denom.clone())?.unwrap_or_default();
+            repayment.amount = repayment.amount.min(held_credit);
+
             //Go thru each deposit and withdraw request from state
             let (withdrawable, new_pool) = withdrawal_from_state(
                 deps.storage,
-                deps.querier,
                 env,
                 config.clone(),
                 position_owner.clone(),
-                Decimal::from_ratio(repayment.amount, Uint128::new(1u128)),
+                repayment.amount,
                 asset_pool,
                 true,
             )?;
@@ -1017,6 +1096,7 @@ fn repay(
             
             //Update pool
             ASSET.save(deps.storage, &new_pool)?;
+            HOLDINGS.save(deps.storage, credit_denom, &(held_credit - repayment.amount))?;
 
             /////This is where the function differs from withdraw()
             //Add Positions RepayMsg
@@ -1050,14 +1130,15 @@ pub fn claim(
 ) -> Result<Response, ContractError> {
     let config: Config = CONFIG.load(deps.storage)?;    
 
-    let mut accrued_incentives = Uint128::zero();
-    let asset_pool = ASSET.load(deps.storage)?;
-    //Add newly accrued incentives to claimables
-    accrued_incentives += get_user_incentives(deps.storage, env.clone(), info.clone().sender, asset_pool, config.clone().incentive_rate)?;    
+    let time = env.block.time.seconds();
+    let stream_indices = update_streams(deps.storage, time)?;
+
+    //Add liquidation rewards & newly accrued incentives to claimables
+    for deposit in read_user_deposits(deps.storage, info.clone().sender)? {
+        let tranche = load_tranche(deps.storage, &config, &stream_indices, deposit.tranche_id, time)?;
+        let deposit = settle_deposit(deps.storage, &config, info.clone().sender, deposit, &tranche, time)?;
 
-    if !accrued_incentives.is_zero(){
-        //Add incentives to User Claims
-        add_to_user_claims(deps.storage, info.clone().sender, AssetInfo::NativeToken { denom: config.clone().tema_denom }, accrued_incentives)?;
+        save_user_deposit(deps.storage, info.clone().sender, &deposit)?;
     }
     
     //Create claim msgs
@@ -1120,81 +1201,8 @@ fn user_claims_msgs(
     Ok((messages, native_claims))
 }
 
-/// Split distribution assets to users based on ratios
-fn split_assets_to_users(
-    storage: &mut dyn Storage,
-    mut cAsset_ratios: Vec<Decimal>,
-    mut distribution_assets: Vec<Asset>,
-    distribution_ratios: Vec<UserRatio>,
-) -> Result<(), ContractError>{
-    
-    for mut user_ratio in distribution_ratios {
-        for (index, mut cAsset_ratio) in cAsset_ratios.clone().into_iter().enumerate() {
-            if cAsset_ratio == Decimal::zero() {
-                continue;
-            }
-
-            if user_ratio.ratio == cAsset_ratio {
-                //Allocate the full ratio worth of asset to the User
-                let send_amount = distribution_assets[index].amount;
-
-                //Set distribution_asset amount to difference
-                distribution_assets[index].amount = Uint128::zero();
-
-                //Add all of this asset to existing claims
-                //Add to existing user claims
-                add_to_user_claims(storage, user_ratio.clone().user, distribution_assets[index].clone().info, send_amount)?;
-
-                //Set cAsset_ratios[index] to 0
-                cAsset_ratios[index] = Decimal::zero();
-
-                break;
-            } else if user_ratio.ratio < cAsset_ratio {
-
-                //Allocate full user ratio of the asset
-                let send_ratio = decimal_division(user_ratio.ratio, cAsset_ratio)?;
-                let send_amount = decimal_multiplication(
-                    send_ratio,
-                    Decimal::from_ratio(distribution_assets[index].amount, Uint128::new(1u128)),
-                )? * Uint128::new(1u128);
-
-                //Set distribution_asset amount to difference
-                distribution_assets[index].amount -= send_amount;
-                                
-                //Add to existing user claims
-                add_to_user_claims(storage, user_ratio.clone().user, distribution_assets[index].clone().info, send_amount)?;
-
-                //Set cAsset_ratio to the difference
-                cAsset_ratio = decimal_subtraction(cAsset_ratio, user_ratio.ratio)?;
-                cAsset_ratios[index] = cAsset_ratio;
-
-                break;
-            } else if user_ratio.ratio > cAsset_ratio {
-
-                //Allocate the full ratio worth of asset to the User
-                let send_amount = distribution_assets[index].amount;
-
-                //Set distribution_asset amount to difference
-                distribution_assets[index].amount = Uint128::zero();
-
-                //Add to existing user claims
-                add_to_user_claims(storage, user_ratio.clone().user, distribution_assets[index].clone().info, send_amount)?;
-
-                //Set user_ratio as leftover
-                user_ratio.ratio = decimal_subtraction(user_ratio.ratio, cAsset_ratio)?;                                
-
-                //Set cAsset_ratio to 0
-                cAsset_ratios[index] = Decimal::zero();
-                //continue loop
-            }
-        }
-    }
-
-    Ok(())
-}
-
 /// Add assets to user claims
-fn add_to_user_claims(
+pub fn add_to_user_claims(
     storage: &mut dyn Storage,
     user: Addr,
     distribution_asset: AssetInfo,
@@ -1227,132 +1235,14 @@ fn add_to_user_claims(
     Ok(())
 }
 
-/// Get user ratios of deposits from a list of deposits
-pub fn get_distribution_ratios(deposits: Vec<Deposit>) -> StdResult<(Vec<Decimal>, Vec<Deposit>)> {
-    let mut user_deposits: Vec<Deposit> = vec![];
-    let mut total_amount: Decimal = Decimal::percent(0);
-    let mut new_deposits: Vec<Deposit> = vec![];
-
-    //For each Deposit, create a condensed Deposit for its user.
-    //Add to an existing one if found.
-    for deposit in deposits.into_iter() {
-        match user_deposits
-            .clone()
-            .into_iter()
-            .find(|user_deposit| user_deposit.user == deposit.user)
-        {
-            Some(mut user_deposit) => {
-                user_deposit.amount += deposit.amount;
-
-                //Recreating edited user deposits due to lifetime issues
-                new_deposits = user_deposits
-                    .into_iter()
-                    .filter(|deposit| deposit.user != user_deposit.user)
-                    .collect::<Vec<Deposit>>();
-
-                new_deposits.push(user_deposit);
-                total_amount += deposit.amount;
-            }
-            None => {
-                new_deposits.push(Deposit { ..deposit });
-                total_amount += deposit.amount;
-            }
-        }
-        user_deposits = new_deposits.clone();
-    }
-
-    //Getting each user's % of total amount
-    let mut user_ratios: Vec<Decimal> = vec![];
-    for deposit in user_deposits.iter() {
-        user_ratios.push(decimal_division(deposit.amount, total_amount)?);
-    }
-
-    Ok((user_ratios, user_deposits))
-}
-
-/// Calculate a user's incentives from each deposit
-fn get_user_incentives(
-    storage: &mut dyn Storage,
-    env: Env,
-    user: Addr,
-    mut asset_pool: AssetPool,
-    rate: Decimal,
-) -> StdResult<Uint128>{
-    let mut total_user_incentives = Uint128::zero();
-    let mut error: Option<StdError> = None;
-
-    //Calc and add new_incentives
-    //Update deposit.last_accrued time
-    let new_deposits: Vec<Deposit> = asset_pool.clone().deposits.into_iter().map(|mut deposit| {
-
-        if deposit.user == user {
-            match deposit.unstake_time {
-                Some(unstake_time) => {
-                    let time_elapsed = unstake_time - deposit.last_accrued;
-                    let stake = deposit.amount * Uint128::one();
-    
-                    if time_elapsed != 0 {
-                        //Add accrued incentives
-                        total_user_incentives += match accumulate_interest(stake, rate, time_elapsed){
-                            Ok(incentives) => incentives,
-                            Err(err) => {
-                                error = Some(err);
-                                Uint128::zero()
-                            },
-                        };
-                    }                    
-                    
-                    deposit.last_accrued = unstake_time;
-                },
-                None => {
-                    let time_elapsed = env.block.time.seconds() - deposit.last_accrued;
-                    let stake = deposit.amount * Uint128::one();
-    
-                    if time_elapsed != 0 {
-                        //Add accrued incentives
-                        total_user_incentives += match accumulate_interest(stake, rate, time_elapsed){
-                            Ok(incentives) => incentives,
-                            Err(err) => {
-                                error = Some(err);
-                                Uint128::zero()
-                            },
-                        };
-                    }
-                    
-                    deposit.last_accrued = env.block.time.seconds();
-                },
-            }
-        }
-
-        deposit
-    }).collect::<Vec<Deposit>>();
-
-    //Return error if any
-    if let Some(err) = error {
-        return Err(err);
-    }
-
-    //Set new deposits
-    asset_pool.deposits = new_deposits;
-
-    //Save pool
-    ASSET.save( storage, &asset_pool )?;
-
-    let mut total_incentives = INCENTIVES.load(storage)?;
-    let config = CONFIG.load(storage)?;
-    //Assert that incentives aren't over max, set to remaining cap if so.
-    if total_incentives + total_user_incentives > config.max_incentives {
-        total_user_incentives = config.max_incentives - total_incentives;
-        INCENTIVES.save(storage, &config.max_incentives)?;
-    } else {
-        total_incentives += total_user_incentives;
-        INCENTIVES.save(storage, &total_incentives)?;
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> StdResult<Response> {
+    match msg.id {
+        SWAP_REPLY_ID => handle_swap_reply(deps, env, msg),
+        id => Err(StdError::generic_err(format!("invalid reply id: {}", id))),
     }
-
-    Ok(total_user_incentives)
 }
 
-
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
@@ -1362,6 +1252,7 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::CheckLiquidatible { amount } => to_binary(&query_liquidatible(deps, amount)?),
         QueryMsg::UserClaims { user } => to_binary(&query_user_claims(deps, user)?),
         QueryMsg::AssetPool { user, deposit_limit , start_after} => to_binary(&query_asset_pool(deps, user, deposit_limit, start_after)?),
+        QueryMsg::IncentiveStreams { start_after, limit } => to_binary(&query_incentive_streams(deps, start_after, limit)?),
     }
 }
 
@@ -1377,10 +1268,11 @@ pub fn validate_assets(
     if in_pool {
         //Validate sent assets against accepted assets
         let asset_pool = ASSET.load(deps)?;
+        let config = CONFIG.load(deps)?;
 
         for asset in assets {
             //Validate its balance
-            if asset_pool.credit_asset.info.equal(&asset){
+            if asset_pool.credit_asset.info.equal(&asset) || config.deposit_assets.iter().any(|deposit_asset| deposit_asset.info.equal(&asset)){
                 if let Ok(valid_asset) = assert_sent_native_token_balance(asset, &info) {
                     valid_assets.push(valid_asset);
                 }
@@ -1400,6 +1292,75 @@ pub fn validate_assets(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
-    Ok(Response::default())
+pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    let mut asset_pool = ASSET.load(deps.storage)?;
+    let time = env.block.time.seconds();
+
+    //Move the deposit list to Tranches by deposit day, or to a single Tranche if switching to pro-rata
+    let pro_rata = msg.pro_rata.unwrap_or(config.pro_rata);
+    for deposit in asset_pool.clone().deposits {
+        //Add incentives accrued in the list to the user's claims
+        let time_elapsed = match deposit.unstake_time {
+            Some(unstake_time) => unstake_time.saturating_sub(deposit.last_accrued),
+            None => time - deposit.last_accrued,
+        };
+        let incentives = accrue_incentives(
+            deps.storage,
+            config.clone(),
+            accumulate_interest(deposit.amount * Uint128::one(), config.incentive_rate, time_elapsed)?,
+        )?;
+        add_to_user_claims(deps.storage, deposit.user.clone(), AssetInfo::NativeToken { denom: config.clone().tema_denom }, incentives)?;
+
+        let amount = Uint256::from(deposit.amount * Uint128::one());
+        let deposit_tranche_id = if pro_rata { tranche_id(time) } else { tranche_id(deposit.deposit_time) };
+        let mut tranche = TRANCHES.may_load(deps.storage, deposit_tranche_id)?
+            .unwrap_or_else(|| new_tranche(deposit_tranche_id, time, &[]));
+        let mut user_deposit = DEPOSITS.may_load(deps.storage, (deposit.user.clone(), tranche.id))?
+            .unwrap_or_else(|| new_user_deposit(&tranche, deposit.deposit_time));
+
+        user_deposit.amount += amount;
+        user_deposit.last_accrued = time;
+        if let Some(unstake_time) = deposit.unstake_time {
+            user_deposit.unstaking_amount += amount;
+            user_deposit.unstake_time = Some(unstake_time.max(user_deposit.unstake_time.unwrap_or_default()));
+        }
+        tranche.total_deposits += amount;
+
+        save_user_deposit(deps.storage, deposit.user, &user_deposit)?;
+        TRANCHES.save(deps.storage, tranche.id, &tranche)?;
+    }
+
+    asset_pool.deposits = vec![];
+    ASSET.save(deps.storage, &asset_pool)?;
+
+    //Pools from before deposit assets only hold the credit asset
+    if HOLDINGS.may_load(deps.storage, asset_pool.credit_asset.info.to_string())?.is_none() {
+        HOLDINGS.save(deps.storage, asset_pool.credit_asset.info.to_string(), &asset_pool.credit_asset.amount)?;
+    }
+
+    //Streams are shared by all Tranche deposits
+    if TOTAL_DEPOSITS.may_load(deps.storage)?.is_none() {
+        let mut total_deposits = Uint256::zero();
+        for item in TRANCHES.range(deps.storage, None, None, Order::Ascending) {
+            let (_, tranche) = item?;
+            total_deposits += tranche.total_deposits;
+        }
+        TOTAL_DEPOSITS.save(deps.storage, &total_deposits)?;
+    }
+
+    //Switch liquidation mode.
+    //Tranches keep their own accounting so existing deposits don't need to be moved.
+    if let Some(pro_rata) = msg.pro_rata {
+        if pro_rata && !config.pro_rata {
+            assert_pro_rata_tranches(deps.storage)?;
+        }
+        config.pro_rata = pro_rata;
+        CONFIG.save(deps.storage, &config)?;
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "migrate"),
+        attr("pro_rata", config.pro_rata.to_string()),
+    ]))
 }
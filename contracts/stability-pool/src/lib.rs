@@ -7,6 +7,8 @@ mod error;
 pub mod helpers;
 pub mod state;
 pub mod query;
+pub mod stream;
+pub mod tranche;
 
 #[cfg(test)]
 #[allow(unused_variables)]
@@ -1,9 +1,10 @@
-use cosmwasm_std::{Deps, Env, StdResult, Uint128, Decimal, StdError};
+use cosmwasm_std::{Deps, Env, StdResult, Uint128, Decimal, StdError, Order, Addr, Coin, coin};
 use membrane::types::{AssetPool, Deposit};
 use membrane::stability_pool::{LiquidatibleResponse, ClaimsResponse, DepositPositionResponse, UserIncentivesResponse};
-use membrane::helpers::accumulate_interest;
 
-use crate::state::{CONFIG, ASSET, USERS};
+use crate::state::{CONFIG, ASSET, USERS, DEPOSITS, TRANCHES, UserDeposit};
+use crate::stream::read_stream_indices;
+use crate::tranche::{accrue_tranche, calculate_deposit_rewards, read_user_deposits, to_deposit_responses};
 
 /// Return AssetPool with customizers for the deposit list
 pub fn query_asset_pool(
@@ -11,63 +12,79 @@ pub fn query_asset_pool(
     user: Option<String>,
     deposit_limit: Option<u32>,
     start_after: Option<u32>,
-) -> StdResult<AssetPool>{    
+) -> StdResult<AssetPool>{
     let mut asset_pool = ASSET.load(deps.storage)?;
 
     //Optional User deposits
-    if let Some(user) = user {
+    let user_deposits: Vec<(Addr, UserDeposit)> = if let Some(user) = user {
         let user = deps.api.addr_validate(&user)?;
-        asset_pool.deposits = asset_pool.clone().deposits
+
+        read_user_deposits(deps.storage, user.clone())?
             .into_iter()
-            .filter(|deposit| deposit.user.to_string() == user)
-            .collect::<Vec<Deposit>>();
+            .map(|deposit| (user.clone(), deposit))
+            .collect::<Vec<(Addr, UserDeposit)>>()
+    } else {
+        DEPOSITS
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|item| {
+                let ((user, _), deposit) = item?;
+                Ok((user, deposit))
+            })
+            .collect::<StdResult<Vec<(Addr, UserDeposit)>>>()?
     };
 
+    let mut deposits: Vec<Deposit> = vec![];
+    for (user, deposit) in user_deposits {
+        let tranche = TRANCHES.may_load(deps.storage, deposit.tranche_id)?;
+        deposits.extend(to_deposit_responses(user, &deposit, &tranche));
+    }
+
     //Optional start_after deposits
     let start_after: u32  = if let Some(start) = start_after {
         start
     } else { 0 };
-    
+
     //Optional deposits limit
-    if let Some(limit) = deposit_limit {
-        if start_after + limit > asset_pool.deposits.len() as u32 {
-            return Err(StdError::GenericErr { msg: format!("Invalid limit, deposit length: {}", asset_pool.deposits.len()) });
-        }
-        asset_pool.deposits = asset_pool.deposits[start_after as usize..(start_after+limit) as usize].to_vec();
-    } else {
-        asset_pool.deposits = asset_pool.deposits[start_after as usize..].to_vec();
-    }
-    
-    Ok(asset_pool)    
+    asset_pool.deposits = deposits
+        .into_iter()
+        .skip(start_after as usize)
+        .take(deposit_limit.unwrap_or(u32::MAX) as usize)
+        .collect::<Vec<Deposit>>();
+
+    Ok(asset_pool)
 }
 
-/// Return a user's frontmost deposit and the amount of capital ahead of it
+/// Return a user's deposits and the amount of capital in Tranches ahead of them.
+/// Capital ahead is 0 in pro-rata mode since all Tranches are used by weight.
 pub fn query_capital_ahead_of_deposits(
     deps: Deps,
     user: String,
 )-> StdResult<Vec<DepositPositionResponse>>{
-
-    let asset_pool: AssetPool = ASSET.load(deps.storage)?;
     let user = deps.api.addr_validate(&user)?;
+    let user_deposits = read_user_deposits(deps.storage, user.clone())?;
+    let pro_rata = CONFIG.load(deps.storage)?.pro_rata;
 
     let mut capital_ahead = Decimal::zero();
     let mut resp: Vec<DepositPositionResponse> = vec![];
-    
-    for deposit in asset_pool.deposits{
+
+    for item in TRANCHES.range(deps.storage, None, None, Order::Ascending) {
+        let (tranche_id, tranche) = item?;
+
         //Push new response if we've reached a user deposit
-        if deposit.user == user {
-            resp.push(
-                DepositPositionResponse { 
-                    deposit: deposit.clone(), 
-                    capital_ahead, 
-                }
-            );
-
-            //Increase capital_ahead
-            capital_ahead += deposit.amount;
-        } else { 
-            //Add to capital ahead of the next user deposit
-            capital_ahead += deposit.amount;
+        if let Some(deposit) = user_deposits.iter().find(|deposit| deposit.tranche_id == tranche_id) {
+            for deposit in to_deposit_responses(user.clone(), deposit, &Some(tranche.clone())) {
+                resp.push(
+                    DepositPositionResponse {
+                        deposit,
+                        capital_ahead,
+                    }
+                );
+            }
+        }
+
+        //Add to capital ahead of the next Tranche
+        if !pro_rata {
+            capital_ahead += Decimal::from_ratio(Uint128::from(tranche.total_deposits), Uint128::new(1u128));
         }
     }
 
@@ -76,41 +93,46 @@ pub fn query_capital_ahead_of_deposits(
 
 /// Return user's available incentives
 pub fn query_user_incentives(
-    deps: Deps, 
+    deps: Deps,
     env: Env,
     user: String,
 ) -> StdResult<UserIncentivesResponse>{
-    let resp: Vec<Deposit> = query_deposits(deps, user)?;
+    let user = deps.api.addr_validate(&user)?;
     let rate = CONFIG.load(deps.storage)?.incentive_rate;
+    let (stream_indices, _) = read_stream_indices(deps.storage, env.block.time.seconds())?;
 
     let mut total_incentives = Uint128::zero();
-    for deposit in resp {
-        match deposit.unstake_time{
-            Some(unstake_time) => {
-                let time_elapsed = unstake_time - deposit.last_accrued;
-                let stake = deposit.amount * Uint128::one();
-
-                total_incentives += accumulate_interest(stake, rate, time_elapsed)?;
-            },
-            None => {
-                let time_elapsed = env.block.time.seconds() - deposit.last_accrued;
-                let stake = deposit.amount * Uint128::one();
-
-                total_incentives += accumulate_interest(stake, rate, time_elapsed)?;
-            },
-        }        
+    let mut stream_incentives: Vec<Coin> = vec![];
+    for deposit in read_user_deposits(deps.storage, user)? {
+        //Accrue the Tranche's incentives up to now
+        let tranche = TRANCHES.may_load(deps.storage, deposit.tranche_id)?
+            .map(|mut tranche| {
+                accrue_tranche(&mut tranche, rate, &stream_indices, env.block.time.seconds());
+                tranche
+            });
+
+        let rewards = calculate_deposit_rewards(deps.storage, &deposit, &tranche)?;
+        total_incentives += Uint128::from(rewards.incentives);
+
+        for (denom, amount) in rewards.streams {
+            match stream_incentives.iter_mut().find(|stream| stream.denom == denom) {
+                Some(stream) => stream.amount += Uint128::from(amount),
+                None => stream_incentives.push(coin(Uint128::from(amount).u128(), denom)),
+            }
+        }
     }
-    
+
     Ok(
         UserIncentivesResponse {
             incentives: total_incentives,
+            stream_incentives,
         }
     )
 }
 
 /// Return leftover amount from a hypothetical liquidation amount
 pub fn query_liquidatible(deps: Deps, amount: Decimal) -> StdResult<LiquidatibleResponse> {
-    
+
     let asset_pool = ASSET.load(deps.storage)?;
     let asset_amount_uint128 = amount * Uint128::new(1u128);
     let liquidatible_amount = asset_pool.credit_asset.amount;
@@ -125,38 +147,35 @@ pub fn query_liquidatible(deps: Deps, amount: Decimal) -> StdResult<Liquidatible
             leftover: Decimal::from_ratio(leftover, Uint128::new(1u128)),
         })
     }
-    
-}
-
-/// Return user's deposits 
-pub fn query_deposits(
-    deps: Deps,
-    user: String,
-) -> StdResult<Vec<Deposit>> {
-    let valid_user = deps.api.addr_validate(&user)?;
-    let asset_pool = ASSET.load(deps.storage)?;
 
-    Ok(asset_pool
-        .deposits
-        .into_iter()
-        .filter(|deposit| deposit.user == valid_user)
-        .collect::<Vec<Deposit>>())
 }
 
-/// Return user's claimable assets
+/// Return user's claimable assets, including liquidation rewards that haven't been settled yet
 pub fn query_user_claims(deps: Deps, user: String) -> StdResult<ClaimsResponse> {
     let valid_user = deps.api.addr_validate(&user)?;
 
-    match USERS.load(deps.storage, valid_user) {
-        Ok(user) => {
-            Ok(ClaimsResponse {
-                claims: user.claimable_assets.to_vec(),
-            })
-        }
-        Err(_) => {
-            Err(StdError::GenericErr {
-                msg: "User has no claimable assets".to_string(),
-            })
+    let mut claims: Vec<Coin> = match USERS.load(deps.storage, valid_user.clone()) {
+        Ok(user) => user.claimable_assets.to_vec(),
+        Err(_) => vec![],
+    };
+
+    for deposit in read_user_deposits(deps.storage, valid_user)? {
+        let tranche = TRANCHES.may_load(deps.storage, deposit.tranche_id)?;
+        let rewards = calculate_deposit_rewards(deps.storage, &deposit, &tranche)?;
+
+        for (asset, amount) in rewards.assets {
+            match claims.iter_mut().find(|claim| claim.denom == asset) {
+                Some(claim) => claim.amount += Uint128::from(amount),
+                None => claims.push(coin(Uint128::from(amount).u128(), asset)),
+            }
         }
     }
+
+    if claims.is_empty() {
+        return Err(StdError::GenericErr {
+            msg: "User has no claimable assets".to_string(),
+        })
+    }
+
+    Ok(ClaimsResponse { claims })
 }
@@ -1,11 +1,13 @@
 
-use membrane::stability_pool::Config;
+use membrane::stability_pool::{Config, IncentiveStream};
 use membrane::types::{AssetPool, User};
+use membrane::math::{Decimal256, Uint256};
 
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use cosmwasm_std::{Addr, Uint128};
+use membrane::types::Asset;
 use cw_storage_plus::{Item, Map};
 
 
@@ -14,10 +16,105 @@ pub struct Propagation {
     pub repaid_amount: Uint128,
 }
 
+/// Saved between deposit asset swaps & their replies in a liquidation
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct SwapPropagation {
+    /// Credit asset repaid from holdings
+    pub credit_repayment: Uint128,
+    /// Credit asset expected from deposit asset swaps
+    pub swap_repayment: Uint128,
+    /// Swaps that haven't replied yet, in order
+    pub pending_swaps: Vec<DepositSwap>,
+    /// Repayment of swaps that failed
+    pub failed_repayment: Uint128,
+    /// Contract's credit asset balance before the swaps
+    pub prev_balance: Uint128,
+}
+
+/// Deposit asset sold in a liquidation swap
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct DepositSwap {
+    /// Sold deposit asset
+    pub sold: Asset,
+    /// Credit asset the swap repays
+    pub repayment: Uint128,
+}
+
+/// Rewards per unit of deposit, scaled by the Tranche product
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema, Default)]
+pub struct TrancheSums {
+    /// Distributed assets (String of AssetInfo, sum)
+    pub assets: Vec<(String, Decimal256)>,
+    /// TEMA incentives
+    pub incentives: Decimal256,
+    /// Incentive streams (denom, sum)
+    #[serde(default)]
+    pub streams: Vec<(String, Decimal256)>,
+}
+
+/// Deposits made in the same day share a Tranche.
+/// Tranches are used for liquidations in order, deposits within a Tranche are used pro-rata.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Tranche {
+    /// Day the Tranche was opened (block time / seconds per day)
+    pub id: u64,
+    /// Sum of deposits in the Tranche
+    pub total_deposits: Uint256,
+    /// Product snapshot
+    pub product_snapshot: Decimal256,
+    /// Sum snapshots
+    pub sums: TrancheSums,
+    /// Current epoch, incremented when the Tranche is emptied
+    pub current_epoch: Uint128,
+    /// Current scale, incremented when the product gets too small
+    pub current_scale: Uint128,
+    /// Last time incentives were added to the sums
+    pub last_accrued: u64,
+    /// Stream indices at the last accrual (denom, index)
+    #[serde(default)]
+    pub stream_indices: Vec<(String, Decimal256)>,
+}
+
+/// A user's deposit in a Tranche
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct UserDeposit {
+    /// Tranche id
+    pub tranche_id: u64,
+    /// Deposit amount at the time of the snapshots
+    pub amount: Uint256,
+    /// Portion of the amount that is unstaking
+    pub unstaking_amount: Uint256,
+    /// Deposit time in seconds
+    pub deposit_time: u64,
+    /// Last time rewards were settled
+    pub last_accrued: u64,
+    /// Unstake time in seconds
+    pub unstake_time: Option<u64>,
+    /// Tranche product at the time of the snapshots
+    pub product_snapshot: Decimal256,
+    /// Tranche sums at the time of the snapshots
+    pub sum_snapshots: TrancheSums,
+    /// Tranche epoch at the time of the snapshots
+    pub epoch_snapshot: Uint128,
+    /// Tranche scale at the time of the snapshots
+    pub scale_snapshot: Uint128,
+}
+
 pub const CONFIG: Item<Config> = Item::new("config");
 pub const ASSET: Item<AssetPool> = Item::new("asset"); //Acts as the asset WL and the sum of all deposits for said asset
 pub const PROP: Item<Propagation> = Item::new("propagation");
 pub const INCENTIVES: Item<Uint128> = Item::new("incentives_total");
 pub const USERS: Map<Addr, User> = Map::new("users"); //Used to map claims to users
+pub const TRANCHES: Map<u64, Tranche> = Map::new("tranches"); //tranche_id -> Tranche
+pub const DEPOSITS: Map<(Addr, u64), UserDeposit> = Map::new("deposits"); //(user, tranche_id) -> UserDeposit
+pub const HOLDINGS: Map<String, Uint128> = Map::new("holdings"); //asset -> amount held, for the credit asset & deposit assets
+pub const SWAP: Item<SwapPropagation> = Item::new("swap");
+pub const DEPOSITED_ASSETS: Map<(Addr, String), Uint128> = Map::new("deposited_assets"); //(user, deposit asset) -> amount deposited, paid out first on withdrawal
+pub const EPOCH_SCALE_SUMS: Map<(u64, u128, u128), TrancheSums> = Map::new("epoch_scale_sums"); //(tranche_id, epoch, scale) -> sums
+
+pub const TOTAL_DEPOSITS: Item<Uint256> = Item::new("total_deposits"); //Sum of Tranche deposits, shares stream emissions
+pub const STREAMS: Map<u64, IncentiveStream> = Map::new("streams"); //stream_id -> IncentiveStream
+pub const STREAM_ID: Item<u64> = Item::new("stream_id");
+pub const STREAM_INDICES: Map<String, Decimal256> = Map::new("stream_indices"); //denom -> stream rewards per unit of deposit
 
-pub const OWNERSHIP_TRANSFER: Item<Addr> = Item::new("ownership_transfer");
\ No newline at end of file
+pub const OWNERSHIP_TRANSFER: Item<Addr> = Item::new("ownership_transfer");
@@ -0,0 +1,219 @@
+use cosmwasm_std::{attr, Coin, Deps, DepsMut, Env, MessageInfo, Order, Response, StdResult, Storage, Uint128};
+use cw_storage_plus::Bound;
+
+use membrane::math::{Decimal256, Uint256};
+use membrane::stability_pool::IncentiveStream;
+use membrane::types::AssetInfo;
+
+use crate::contract::add_to_user_claims;
+use crate::error::ContractError;
+use crate::state::{CONFIG, STREAMS, STREAM_ID, STREAM_INDICES, TOTAL_DEPOSITS};
+
+//Constants
+const MAX_LIMIT: u32 = 31;
+/// Max streams that can be active or pending at once
+pub const MAX_STREAMS: usize = 10;
+
+/// Add, edit or remove an accepted stream denom & its minimum stream amount
+pub fn edit_stream_denom(
+    deps: DepsMut,
+    info: MessageInfo,
+    denom: String,
+    minimum_amount: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    //Assert Authority
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    //TEMA claims are minted, so TEMA can't be streamed
+    if denom == config.tema_denom {
+        return Err(ContractError::CustomError {
+            val: String::from("TEMA incentives are set by the incentive rate"),
+        });
+    }
+
+    config.stream_denoms.retain(|stream_denom| stream_denom.denom != denom);
+    if let Some(minimum_amount) = minimum_amount {
+        config.stream_denoms.push(Coin { denom: denom.clone(), amount: minimum_amount });
+    }
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "edit_stream_denom"),
+        attr("denom", denom),
+        attr("minimum_amount", format!("{:?}", minimum_amount)),
+    ]))
+}
+
+/// Fund a reward stream with the sent native token
+pub fn add_incentive_stream(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    start_time: u64,
+    end_time: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.funds.len() != 1 || info.funds[0].amount.is_zero() {
+        return Err(ContractError::CustomError {
+            val: String::from("Streams are funded with exactly one native token"),
+        });
+    }
+    let funds = info.funds[0].clone();
+
+    //TEMA claims are minted, so TEMA can't be streamed
+    if funds.denom == config.tema_denom {
+        return Err(ContractError::CustomError {
+            val: String::from("TEMA incentives are set by the incentive rate"),
+        });
+    }
+    //Streams are limited to accepted denoms above a minimum so they can't be spammed
+    match config.stream_denoms.iter().find(|stream_denom| stream_denom.denom == funds.denom) {
+        Some(stream_denom) if funds.amount < stream_denom.amount => {
+            return Err(ContractError::CustomError {
+                val: format!("Stream amount is below the minimum of {}", stream_denom),
+            });
+        }
+        Some(_) => {}
+        None => {
+            return Err(ContractError::CustomError {
+                val: format!("{} isn't an accepted stream denom", funds.denom),
+            });
+        }
+    }
+    if start_time < env.block.time.seconds() || end_time <= start_time {
+        return Err(ContractError::InvalidParameters {});
+    }
+
+    //Accrue existing streams before adding a new one, this removes finished streams
+    update_streams(deps.storage, env.block.time.seconds())?;
+
+    if STREAMS.keys(deps.storage, None, None, Order::Ascending).count() >= MAX_STREAMS {
+        return Err(ContractError::CustomError {
+            val: format!("Max {} streams can be active at once", MAX_STREAMS),
+        });
+    }
+
+    let id = STREAM_ID.may_load(deps.storage)?.unwrap_or_default() + 1;
+    STREAM_ID.save(deps.storage, &id)?;
+
+    STREAMS.save(deps.storage, id, &IncentiveStream {
+        id,
+        funder: info.sender.clone(),
+        denom: funds.denom.clone(),
+        amount: funds.amount,
+        start_time,
+        end_time,
+        last_accrued: start_time,
+    })?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "add_incentive_stream"),
+        attr("stream_id", id.to_string()),
+        attr("funder", info.sender),
+        attr("stream", funds.to_string()),
+        attr("start_time", start_time.to_string()),
+        attr("end_time", end_time.to_string()),
+    ]))
+}
+
+/// Amount a stream has emitted by the given time
+fn stream_emitted(stream: &IncentiveStream, time: u64) -> Uint128 {
+    if time <= stream.start_time {
+        Uint128::zero()
+    } else {
+        let elapsed = time.min(stream.end_time) - stream.start_time;
+
+        stream.amount.multiply_ratio(elapsed, stream.end_time - stream.start_time)
+    }
+}
+
+/// Return stream indices & streams accrued up to the given time, without saving.
+/// Emissions are shared by all Tranche deposits, emissions while the pool is empty are returned as the stream's refund to its funder.
+pub fn read_stream_indices(
+    storage: &dyn Storage,
+    time: u64,
+) -> StdResult<(Vec<(String, Decimal256)>, Vec<(IncentiveStream, Uint128)>)> {
+    let total_deposits = TOTAL_DEPOSITS.may_load(storage)?.unwrap_or_default();
+
+    let mut indices: Vec<(String, Decimal256)> = STREAM_INDICES
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(String, Decimal256)>>>()?;
+    let mut streams: Vec<(IncentiveStream, Uint128)> = vec![];
+
+    for item in STREAMS.range(storage, None, None, Order::Ascending) {
+        let (_, mut stream) = item?;
+
+        let accrue_to = time.min(stream.end_time);
+        let mut refund = Uint128::zero();
+        if accrue_to > stream.last_accrued {
+            let emitted = stream_emitted(&stream, accrue_to) - stream_emitted(&stream, stream.last_accrued);
+
+            //No one earns emissions while the pool is empty, so they're refunded to the funder
+            if total_deposits.is_zero() {
+                refund = emitted;
+            } else if !emitted.is_zero() {
+                // E / D
+                let index_increase = Decimal256::from_ratio(Uint256::from(emitted), total_deposits);
+
+                match indices.iter_mut().find(|(denom, _)| *denom == stream.denom) {
+                    Some((_, index)) => *index += index_increase,
+                    None => indices.push((stream.denom.clone(), index_increase)),
+                }
+            }
+            stream.last_accrued = accrue_to;
+        }
+
+        streams.push((stream, refund));
+    }
+
+    Ok((indices, streams))
+}
+
+/// Accrue streams up to the given time, add empty pool emissions to funder claims & return the stream indices.
+/// Must be called before TOTAL_DEPOSITS changes.
+pub fn update_streams(storage: &mut dyn Storage, time: u64) -> StdResult<Vec<(String, Decimal256)>> {
+    let (indices, streams) = read_stream_indices(storage, time)?;
+
+    for (denom, index) in indices.clone() {
+        STREAM_INDICES.save(storage, denom, &index)?;
+    }
+    for (stream, refund) in streams {
+        add_to_user_claims(
+            storage,
+            stream.funder.clone(),
+            AssetInfo::NativeToken { denom: stream.denom.clone() },
+            refund,
+        )?;
+
+        //Remove finished streams
+        if stream.last_accrued >= stream.end_time {
+            STREAMS.remove(storage, stream.id);
+        } else {
+            STREAMS.save(storage, stream.id, &stream)?;
+        }
+    }
+
+    Ok(indices)
+}
+
+/// Return active incentive streams
+pub fn query_incentive_streams(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<IncentiveStream>> {
+    let limit = limit.unwrap_or(MAX_LIMIT).min(MAX_LIMIT) as usize;
+
+    STREAMS
+        .range(deps.storage, start_after.map(Bound::exclusive), None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (_, stream) = item?;
+            Ok(stream)
+        })
+        .collect::<StdResult<Vec<IncentiveStream>>>()
+}
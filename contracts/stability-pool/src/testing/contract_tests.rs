@@ -1,19 +1,22 @@
-use crate::contract::{execute, instantiate, query};
-use crate::state::CONFIG;
+use crate::contract::{execute, instantiate, query, reply, SWAP_REPLY_ID};
+use crate::state::{CONFIG, TRANCHES};
 use crate::ContractError;
 
 use cosmwasm_std::testing::{
-    mock_dependencies, mock_dependencies_with_balance, mock_env, mock_info,
+    mock_dependencies, mock_dependencies_with_balance, mock_env, mock_info, MOCK_CONTRACT_ADDR,
 };
 use cosmwasm_std::{
-    attr, coins, from_binary, to_binary, Addr, Coin, CosmosMsg, BankMsg, Decimal, SubMsg, Uint128,
-    WasmMsg,
+    attr, coins, from_binary, to_binary, Addr, Coin, ContractResult, CosmosMsg, BankMsg, Decimal, Order, Reply, SubMsg,
+    SubMsgResponse, SubMsgResult, SystemResult, Uint128, WasmMsg, WasmQuery,
 };
 
 use membrane::cdp::ExecuteMsg as CDP_ExecuteMsg;
+use membrane::helpers::router_native_to_native;
+use membrane::oracle::{PriceResponse, QueryMsg as OracleQueryMsg};
+use membrane::pause::PauseMsg;
 use membrane::stability_pool::{
     Config, ClaimsResponse, ExecuteMsg, InstantiateMsg, LiquidatibleResponse,
-    QueryMsg, DepositPositionResponse, UpdateConfig
+    QueryMsg, DepositPositionResponse, UpdateConfig, UserIncentivesResponse, IncentiveStream
 };
 use membrane::types::{Asset, AssetInfo, AssetPool, Deposit};
 
@@ -406,6 +409,69 @@ fn liquidate() {
     );
 }
 
+#[test]
+fn pause_guard() {
+    let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+    let msg = InstantiateMsg {
+        owner: Some("sender88".to_string()),
+        asset_pool: AssetPool {
+            credit_asset: Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "credit".to_string(),
+                },
+                amount: Uint128::zero(),
+            },
+            liq_premium: Decimal::zero(),
+            deposits: vec![],
+        },
+        osmosis_proxy: String::from("osmosis_proxy"),
+        tema_denom: String::from("tema_denom"),
+        incentive_rate: None,
+        positions_contract: String::from("positions_contract"),
+        oracle_contract: String::from("oracle_contract"),
+        max_incentives: None,
+        minimum_deposit_amount: Uint128::new(5)
+    };
+
+    let info = mock_info("sender88", &coins(11, "credit"));
+    let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let deposit_msg = ExecuteMsg::Deposit { user: None };
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), deposit_msg.clone()).unwrap();
+
+    //Error: Liquidations can't be paused
+    let pause_msg = ExecuteMsg::Pause(PauseMsg::Pause { actions: vec![String::from("liquidate")], duration: 86_400 });
+    let err = execute(deps.as_mut(), mock_env(), mock_info("sender88", &[]), pause_msg).unwrap_err();
+    assert_eq!(err.to_string(), String::from("Generic error: liquidate isn't a pausable action"));
+
+    //Pause all actions
+    let pause_msg = ExecuteMsg::Pause(PauseMsg::Pause { actions: vec![String::from("all")], duration: 86_400 });
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("sender88", &[]), pause_msg).unwrap();
+
+    let err = execute(deps.as_mut(), mock_env(), info.clone(), deposit_msg.clone()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!("Generic error: deposit is paused until {}", mock_env().block.time.seconds() + 86_400)
+    );
+
+    //Liquidations still go through
+    let liq_msg = ExecuteMsg::Liquidate { liq_amount: Decimal::from_ratio(10u128, 1u128) };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("positions_contract", &vec![]), liq_msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("method", "liquidate"),
+            attr("leftover_repayment", "0"),
+        ]
+    );
+
+    //Unpause
+    let unpause_msg = ExecuteMsg::Pause(PauseMsg::Unpause { actions: vec![String::from("all")] });
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("sender88", &[]), unpause_msg).unwrap();
+    let _res = execute(deps.as_mut(), mock_env(), info, deposit_msg).unwrap();
+}
+
 #[test]
 fn liquidate_bignums() {
     let mut deps = mock_dependencies();
@@ -533,9 +599,11 @@ fn distribute() {
     let deposit_msg = ExecuteMsg::Deposit { user: None };
     let _res = execute(deps.as_mut(), mock_env(), info.clone(), deposit_msg).unwrap();
 
-    //Deposit for second user
+    //Deposit for second user the next day, pro-rata deposits join the latest Tranche
+    let mut next_day_env = mock_env();
+    next_day_env.block.time = next_day_env.block.time.plus_seconds(86400u64); //Added a day
     let deposit_msg = ExecuteMsg::Deposit { user: Some(String::from("2nduser")) };
-    let _res = execute(deps.as_mut(), mock_env(), info, deposit_msg).unwrap();
+    let _res = execute(deps.as_mut(), next_day_env, info, deposit_msg).unwrap();
 
     //Successful attempt
     //Liquidation
@@ -594,8 +662,22 @@ fn distribute() {
 
     let resp: ClaimsResponse = from_binary(&res).unwrap();
 
-    assert_eq!(resp.claims[0].to_string(), "62debit".to_string());
-    assert_eq!(resp.claims[1].to_string(), "12328tema_denom".to_string());
+    //The first Tranche is used fully, so it gets 5/8 of each asset
+    assert_eq!(resp.claims[0].to_string(), "53debit".to_string());
+    assert_eq!(resp.claims[1].to_string(), "9second_debit_LP".to_string());
+
+    //Query and assert User incentives accrued up to the liquidation
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::UnclaimedIncentives {
+            user: "user".to_string(),
+        },
+    )
+    .unwrap();
+
+    let resp: UserIncentivesResponse = from_binary(&res).unwrap();
+    assert_eq!(resp.incentives, Uint128::new(12328));
 
     //Query and assert 2ndUser claimables
     let res = query(
@@ -609,9 +691,8 @@ fn distribute() {
 
     let resp: ClaimsResponse = from_binary(&res).unwrap();
 
-    assert_eq!(resp.claims[0].to_string(), "23debit".to_string());
-    assert_eq!(resp.claims[1].to_string(), "7397tema_denom".to_string());
-    assert_eq!(resp.claims[2].to_string(), "15second_debit_LP".to_string());
+    assert_eq!(resp.claims[0].to_string(), "32debit".to_string());
+    assert_eq!(resp.claims[1].to_string(), "6second_debit_LP".to_string());
 
     //Query position data to make sure 0 is leftover for "user"
     let res = query(
@@ -700,19 +781,157 @@ fn distribute() {
         vec![
             Coin {
                 denom: "debit".to_string(),
-                amount: Uint128::new(123)
+                amount: Uint128::new(132)
             },
-            Coin {
-                denom: "tema_denom".to_string(),
-                amount: Uint128::new(7397)
-            },      
             Coin {
                 denom: "second_debit_LP".to_string(),
-                amount: Uint128::new(115)
+                amount: Uint128::new(106)
+            },
+        ],
+    )
+
+}
+
+#[test]
+fn distribute_pro_rata() {
+    let mut deps = mock_dependencies_with_balance(&coins(2, "credit"));
+
+    let msg = InstantiateMsg {
+        owner: Some("sender88".to_string()),
+        asset_pool: AssetPool {
+            credit_asset: Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "credit".to_string(),
+                },
+                amount: Uint128::zero(),
+            },
+            liq_premium: Decimal::zero(),
+            deposits: vec![],
+        },
+        osmosis_proxy: String::from("osmosis_proxy"),
+        tema_denom: String::from("tema_denom"),
+        incentive_rate: None,
+        positions_contract: String::from("positions_contract"),
+        oracle_contract: String::from("oracle_contract"),
+        max_incentives: None,
+        minimum_deposit_amount: Uint128::new(5)
+    };
+
+    //Instantiating contract
+    let info = mock_info("user", &coins(50000000, "credit"));
+    let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    //Switch to pro-rata
+    let msg = ExecuteMsg::UpdateConfig(UpdateConfig { 
+        owner: None,
+        incentive_rate: None,
+        max_incentives: None,
+        unstaking_period: None,
+        minimum_deposit_amount: None,
+        osmosis_proxy: None,
+        oracle_contract: None,
+        positions_contract: None,
+        tema_denom: None,
+        pro_rata: Some(true),
+        dex_router: None,
+    });
+    execute(deps.as_mut(), mock_env(), mock_info("sender88", &vec![]), msg).unwrap();
+
+    //Deposit for first user
+    let deposit_msg = ExecuteMsg::Deposit { user: None };
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), deposit_msg).unwrap();
+
+    //Deposit for second user in the next day's Tranche
+    let mut next_day_env = mock_env();
+    next_day_env.block.time = next_day_env.block.time.plus_seconds(86400u64); //Added a day
+    let deposit_msg = ExecuteMsg::Deposit { user: Some(String::from("2nduser")) };
+    let _res = execute(deps.as_mut(), next_day_env.clone(), info, deposit_msg).unwrap();
+
+    //No capital is ahead of the later deposit
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::CapitalAheadOfDeposit { user: String::from("2nduser") },
+    )
+    .unwrap();
+    let resp: Vec<DepositPositionResponse> = from_binary(&res).unwrap();
+    assert_eq!(resp[0].capital_ahead, Decimal::zero());
+
+    //Liquidation
+    let liq_msg = ExecuteMsg::Liquidate { liq_amount: Decimal::from_ratio(80000000u128, 1u128) };
+    let cdp_info = mock_info("positions_contract", &vec![]);
+    let _res = execute(deps.as_mut(), next_day_env.clone(), cdp_info, liq_msg).unwrap();
+
+    //Distribute
+    let distribute_msg = ExecuteMsg::Distribute {
+        distribution_assets: vec![
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "debit".to_string(),
+                },
+                amount: Uint128::new(85),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "second_debit_LP".to_string(),
+                },
+                amount: Uint128::new(15),
             },
         ],
+        distribution_asset_ratios: vec![Decimal::percent(85), Decimal::percent(15)],
+        distribute_for: Uint128::new(80000000),
+    };
+
+    let mut coin = coins(85, "debit");
+    coin.append(&mut coins(15, "second_debit_LP"));
+    let cdp_info = mock_info("positions_contract", &coin);
+    execute(deps.as_mut(), next_day_env, cdp_info, distribute_msg).unwrap();
+
+    //Both deposits share the Tranche equally, rounding dust stays in the pool
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::UserClaims {
+            user: "user".to_string(),
+        },
     )
+    .unwrap();
+    let resp: ClaimsResponse = from_binary(&res).unwrap();
+    assert_eq!(resp.claims[0].to_string(), "42debit".to_string());
+    assert_eq!(resp.claims[1].to_string(), "7second_debit_LP".to_string());
 
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::UserClaims {
+            user: "2nduser".to_string(),
+        },
+    )
+    .unwrap();
+    let resp: ClaimsResponse = from_binary(&res).unwrap();
+    assert_eq!(resp.claims[0].to_string(), "42debit".to_string());
+    assert_eq!(resp.claims[1].to_string(), "7second_debit_LP".to_string());
+
+    //No new Tranche was opened
+    assert_eq!(
+        TRANCHES.keys(&deps.storage, None, None, Order::Ascending).count(),
+        1
+    );
+
+    //Both users have the same amount left
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::AssetPool {
+            user: None,
+            deposit_limit: None,
+            start_after: None,
+        }
+    )
+    .unwrap();
+    let resp: AssetPool = from_binary(&res).unwrap();
+    assert_eq!(resp.deposits[0].to_string(), String::from("2nduser 10000000"));
+    assert_eq!(resp.deposits[1].to_string(), String::from("user 10000000"));
 }
 
 #[test]
@@ -772,9 +991,11 @@ fn distribute_bignums() {
     let deposit_msg = ExecuteMsg::Deposit { user: None };
     let _res = execute(deps.as_mut(), mock_env(), info.clone(), deposit_msg).unwrap();
 
-    //Deposit for second user
+    //Deposit for second user in the next day's Tranche
+    let mut next_day_env = mock_env();
+    next_day_env.block.time = next_day_env.block.time.plus_seconds(86400u64); //Added a day
     let deposit_msg = ExecuteMsg::Deposit { user: Some("2nduser".to_string()) };
-    let _res = execute(deps.as_mut(), mock_env(), info, deposit_msg).unwrap();
+    let _res = execute(deps.as_mut(), next_day_env, info, deposit_msg).unwrap();
 
     //Succesfful attempt
     let cdp_info = mock_info("positions_contract", &vec![]);
@@ -832,11 +1053,11 @@ fn distribute_bignums() {
 
     assert_eq!(
         resp.claims[0].to_string(),
-        "100000000000000debit".to_string()
+        "62500000000000debit".to_string()
     );
     assert_eq!(
         resp.claims[1].to_string(),
-        "25000000000000second_debit".to_string()
+        "62500000000000second_debit".to_string()
     );
 
     //Query and assert User claimables
@@ -853,7 +1074,11 @@ fn distribute_bignums() {
 
     assert_eq!(
         resp.claims[0].to_string(),
-        "75000000000000second_debit".to_string()
+        "37500000000000debit".to_string()
+    );
+    assert_eq!(
+        resp.claims[1].to_string(),
+        "37500000000000second_debit".to_string()
     );
 
     //Query position data to assert leftovers
@@ -913,9 +1138,11 @@ fn claims() {
     let deposit_msg = ExecuteMsg::Deposit { user: Some("sender88".to_string()) };
     let _res = execute(deps.as_mut(), mock_env(), info.clone(), deposit_msg).unwrap();
 
-    //Deposit for second user
+    //Deposit for second user in the next day's Tranche
+    let mut next_day_env = mock_env();
+    next_day_env.block.time = next_day_env.block.time.plus_seconds(86400u64); //Added a day
     let deposit_msg = ExecuteMsg::Deposit { user: Some("2nduser".to_string()) };
-    let _res = execute(deps.as_mut(), mock_env(), info, deposit_msg).unwrap();
+    let _res = execute(deps.as_mut(), next_day_env, info, deposit_msg).unwrap();
 
     //Liquidation
     let liq_msg = ExecuteMsg::Liquidate { liq_amount: Decimal::from_ratio(8u128, 1u128) };
@@ -959,11 +1186,11 @@ fn claims() {
             amount: vec![
                 Coin {
                     denom: "debit".to_string(),
-                    amount: Uint128::new(100u128)
+                    amount: Uint128::new(62u128)
                 },
                 Coin {
                     denom: "second_debit".to_string(),
-                    amount: Uint128::new(25u128)
+                    amount: Uint128::new(62u128)
                 }, 
             ],
         }))
@@ -982,10 +1209,16 @@ fn claims() {
     assert_eq!(res.messages, vec![
         SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
             to_address: "2nduser".to_string(),
-            amount: vec![Coin {
-                denom: "second_debit".to_string(),
-                amount: Uint128::new(75u128)
-            }],
+            amount: vec![
+                Coin {
+                    denom: "debit".to_string(),
+                    amount: Uint128::new(38u128)
+                },
+                Coin {
+                    denom: "second_debit".to_string(),
+                    amount: Uint128::new(38u128)
+                },
+            ],
         }))
     ]);
 }
@@ -1198,6 +1431,8 @@ fn update_config(){
         positions_contract: Some(String::from("new_cdp")),
         oracle_contract: Some(String::from("new_oracle")),
         tema_denom: Some(String::from("new_denom")), 
+        pro_rata: Some(true),
+        dex_router: None,
     });
 
     execute(
@@ -1229,6 +1464,10 @@ fn update_config(){
             oracle_contract: Addr::unchecked("new_oracle"),
             positions_contract: Addr::unchecked("new_cdp"), 
             tema_denom: String::from("new_denom"), 
+            pro_rata: true,
+            dex_router: None,
+            deposit_assets: vec![],
+            stream_denoms: vec![],
         },
     );
 
@@ -1243,6 +1482,8 @@ fn update_config(){
         oracle_contract: None,
         positions_contract: None,
         tema_denom: None,
+        pro_rata: None,
+        dex_router: None,
     });
 
     execute(
@@ -1274,6 +1515,10 @@ fn update_config(){
             positions_contract: Addr::unchecked("new_cdp"),
             oracle_contract: Addr::unchecked("new_oracle"), 
             tema_denom: String::from("new_denom"), 
+            pro_rata: true,
+            dex_router: None,
+            deposit_assets: vec![],
+            stream_denoms: vec![],
         },
     );
 }
@@ -1318,15 +1563,19 @@ fn capital_ahead_of_deposits() {
     let info = mock_info("user2", &coins(11, "credit"));
     let _res = execute(deps.as_mut(), mock_env(), info.clone(), deposit_msg).unwrap();
 
+    //2nd Deposits are in the next day's Tranche
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(86400u64); //Added a day
+
     //2nd Deposit by user 1
     let deposit_msg = ExecuteMsg::Deposit { user: None };
     let info = mock_info("user1", &coins(11, "credit"));
-    let _res = execute(deps.as_mut(), mock_env(), info, deposit_msg).unwrap();
+    let _res = execute(deps.as_mut(), env.clone(), info, deposit_msg).unwrap();
 
     //2nd Deposit by user 2
     let deposit_msg = ExecuteMsg::Deposit { user: None };
     let info = mock_info("user2", &coins(11, "credit"));
-    let _res = execute(deps.as_mut(), mock_env(), info.clone(), deposit_msg).unwrap();
+    let _res = execute(deps.as_mut(), env.clone(), info.clone(), deposit_msg).unwrap();
 
     let query_msg = QueryMsg::CapitalAheadOfDeposit { user: String::from("user1") };
     let res = query(
@@ -1355,8 +1604,8 @@ fn capital_ahead_of_deposits() {
                 deposit: Deposit { 
                     user: Addr::unchecked("user1"), 
                     amount: Decimal::percent(11_00), 
-                    deposit_time: mock_env().block.time.seconds(), 
-                    last_accrued: mock_env().block.time.seconds(), 
+                    deposit_time: env.block.time.seconds(), 
+                    last_accrued: env.block.time.seconds(), 
                     unstake_time: None 
                 }, 
                 capital_ahead: Decimal::percent(22_00),
@@ -1386,18 +1635,494 @@ fn capital_ahead_of_deposits() {
                     last_accrued: mock_env().block.time.seconds(), 
                     unstake_time: None 
                 }, 
-                capital_ahead: Decimal::percent(11_00),
+                capital_ahead: Decimal::zero(),
             },
             DepositPositionResponse { 
                 deposit: Deposit { 
                     user: Addr::unchecked("user2"), 
                     amount: Decimal::percent(11_00), 
-                    deposit_time: mock_env().block.time.seconds(), 
-                    last_accrued: mock_env().block.time.seconds(), 
+                    deposit_time: env.block.time.seconds(), 
+                    last_accrued: env.block.time.seconds(), 
                     unstake_time: None 
                 }, 
-                capital_ahead: Decimal::percent(33_00),
+                capital_ahead: Decimal::percent(22_00),
+            },
+        ]
+    );
+}
+
+#[test]
+fn deposit_assets() {
+    let mut deps = mock_dependencies();
+
+    let msg = InstantiateMsg {
+        owner: Some("sender88".to_string()),
+        asset_pool: AssetPool {
+            credit_asset: Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "credit".to_string(),
+                },
+                amount: Uint128::zero(),
+            },
+            liq_premium: Decimal::zero(),
+            deposits: vec![],
+        },
+        osmosis_proxy: String::from("osmosis_proxy"),
+        tema_denom: String::from("tema_denom"),
+        incentive_rate: None,
+        positions_contract: String::from("positions_contract"),
+        oracle_contract: String::from("oracle_contract"),
+        max_incentives: None,
+        minimum_deposit_amount: Uint128::new(5)
+    };
+
+    //Instantiating contract
+    let info = mock_info("sender88", &vec![]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    //Oracle prices atom at 10 & credit at 1
+    deps.querier.update_wasm(|query| {
+        let price = match query {
+            WasmQuery::Smart { msg, .. } => match from_binary(msg).unwrap() {
+                OracleQueryMsg::Price { asset_info, .. } if asset_info.to_string() == "atom" => Decimal::percent(1000),
+                _ => Decimal::one(),
+            },
+            _ => Decimal::one(),
+        };
+
+        SystemResult::Ok(ContractResult::Ok(
+            to_binary(&PriceResponse { prices: vec![], price, decimals: 6 }).unwrap(),
+        ))
+    });
+
+    //Unauthorized
+    let msg = ExecuteMsg::EditDepositAsset {
+        asset: AssetInfo::NativeToken { denom: "atom".to_string() },
+        haircut: Decimal::percent(10),
+    };
+    let err = execute(deps.as_mut(), mock_env(), mock_info("not_owner", &vec![]), msg).unwrap_err();
+    assert_eq!(err.to_string(), String::from("Unauthorized"));
+
+    //Credit asset can't be a deposit asset
+    let msg = ExecuteMsg::EditDepositAsset {
+        asset: AssetInfo::NativeToken { denom: "credit".to_string() },
+        haircut: Decimal::percent(10),
+    };
+    let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(err.to_string(), String::from("Asset pool hasn't been added for this asset yet"));
+
+    //Successful edit
+    let msg = ExecuteMsg::EditDepositAsset {
+        asset: AssetInfo::NativeToken { denom: "atom".to_string() },
+        haircut: Decimal::percent(10),
+    };
+    execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    //Set router to swap deposit assets
+    let msg = ExecuteMsg::UpdateConfig(UpdateConfig { 
+        owner: None,
+        incentive_rate: None,
+        max_incentives: None,
+        unstaking_period: None,
+        minimum_deposit_amount: None,
+        osmosis_proxy: None,
+        oracle_contract: None,
+        positions_contract: None,
+        tema_denom: None,
+        pro_rata: None,
+        dex_router: Some(String::from("router")),
+    });
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    //Credit deposit
+    let deposit_msg = ExecuteMsg::Deposit { user: None };
+    let info = mock_info("user1", &coins(100, "credit"));
+    execute(deps.as_mut(), mock_env(), info, deposit_msg).unwrap();
+
+    //Deposit asset is credited its oracle value after haircut
+    let deposit_msg = ExecuteMsg::Deposit { user: None };
+    let info = mock_info("user2", &coins(10, "atom"));
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), deposit_msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("method", "deposit"),
+            attr("position_owner", "user2"),
+            attr("deposited_asset", "Asset { info: NativeToken { denom: \"atom\" }, amount: Uint128(10) }"),
+            attr("credit_value", "90"),
+        ]
+    );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::AssetPool { 
+            user: Some(String::from("user2")),
+            deposit_limit: None,
+            start_after: None,
+        }
+    )
+    .unwrap();
+    let resp: AssetPool = from_binary(&res).unwrap();
+    assert_eq!(resp.credit_asset.to_string(), "190 credit".to_string());
+    assert_eq!(resp.deposits[0].to_string(), "user2 90".to_string());
+
+    //Withdrawals are paid in the deposited asset first
+    let withdraw_msg = ExecuteMsg::Withdraw { amount: Uint128::new(45u128) };
+    execute(deps.as_mut(), mock_env(), info.clone(), withdraw_msg.clone()).unwrap();
+
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(86400u64); //Added a day
+    let res = execute(deps.as_mut(), env.clone(), info, withdraw_msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("method", "withdraw"),
+            attr("position_owner", "user2"),
+            attr("withdrawn_asset", "5 atom"),
+        ]
+    );
+
+    //Liquidation uses the 100 held credit & swaps atom for the rest
+    let liq_msg = ExecuteMsg::Liquidate { liq_amount: Decimal::from_ratio(109u128, 1u128) };
+    let cdp_info = mock_info("positions_contract", &vec![]);
+    let res = execute(deps.as_mut(), env.clone(), cdp_info, liq_msg).unwrap();
+    assert_eq!(res.messages.len(), 1);
+    assert_eq!(res.messages[0].id, SWAP_REPLY_ID);
+    //1 atom is sold for at least the 9 credit it repays
+    assert_eq!(
+        res.messages[0].msg,
+        router_native_to_native(
+            String::from("router"),
+            AssetInfo::NativeToken { denom: "atom".to_string() },
+            AssetInfo::NativeToken { denom: "credit".to_string() },
+            None,
+            1u128,
+            Some(Uint128::new(9)),
+        ).unwrap()
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("method", "liquidate"),
+            attr("leftover_repayment", "0"),
+        ]
+    );
+
+    //A failed swap doesn't revert the liquidation, the SP repays what it holds
+    let res = reply(
+        deps.as_mut(),
+        env.clone(),
+        Reply {
+            id: SWAP_REPLY_ID,
+            result: SubMsgResult::Err(String::from("Minimum receive not met")),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from("positions_contract"),
+            msg: to_binary(&CDP_ExecuteMsg::LiqRepay {}).unwrap(),
+            funds: coins(100, "credit"),
+        }))]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("method", "handle_swap_reply"),
+            attr("failed_swap", "1 atom"),
+            attr("swap_error", "Minimum receive not met"),
+            attr("credit_received", "0"),
+            attr("repay_amount", "100"),
+            attr("unrepaid", "9"),
+        ]
+    );
+
+    //The unrepaid amount stays in the pool
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::AssetPool { 
+            user: None,
+            deposit_limit: None,
+            start_after: None,
+        }
+    )
+    .unwrap();
+    let resp: AssetPool = from_binary(&res).unwrap();
+    assert_eq!(resp.credit_asset.to_string(), "45 credit".to_string());
+
+    //The atom is back in holdings & swapped in the next liquidation
+    let liq_msg = ExecuteMsg::Liquidate { liq_amount: Decimal::from_ratio(9u128, 1u128) };
+    let cdp_info = mock_info("positions_contract", &vec![]);
+    let res = execute(deps.as_mut(), env.clone(), cdp_info, liq_msg).unwrap();
+    assert_eq!(res.messages.len(), 1);
+    assert_eq!(res.messages[0].id, SWAP_REPLY_ID);
+
+    //Router swap returns 10 credit, 1 more than needed
+    deps.querier.update_balance(MOCK_CONTRACT_ADDR, vec![Coin { denom: "credit".to_string(), amount: Uint128::new(10) }]);
+    let res = reply(
+        deps.as_mut(),
+        env.clone(),
+        Reply {
+            id: SWAP_REPLY_ID,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: None,
+            }),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from("positions_contract"),
+            msg: to_binary(&CDP_ExecuteMsg::LiqRepay {}).unwrap(),
+            funds: coins(9, "credit"),
+        }))]
+    );
+
+    //Pool capital is reduced by the repayments
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::AssetPool { 
+            user: None,
+            deposit_limit: None,
+            start_after: None,
+        }
+    )
+    .unwrap();
+    let resp: AssetPool = from_binary(&res).unwrap();
+    assert_eq!(resp.credit_asset.to_string(), "36 credit".to_string());
+
+    //Credit depositors are paid other deposit assets at their oracle value without the haircut
+    let withdraw_msg = ExecuteMsg::Withdraw { amount: Uint128::new(19u128) };
+    let info = mock_info("user1", &vec![]);
+    execute(deps.as_mut(), env.clone(), info.clone(), withdraw_msg.clone()).unwrap();
+
+    env.block.time = env.block.time.plus_seconds(86400u64); //Added a day
+    let res = execute(deps.as_mut(), env, info, withdraw_msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("method", "withdraw"),
+            attr("position_owner", "user1"),
+            attr("withdrawn_asset", "1 credit"),
+            attr("withdrawn_asset", "1 atom"),
+        ]
+    );
+}
+
+#[test]
+fn incentive_streams() {
+    let mut deps = mock_dependencies();
+
+    let msg = InstantiateMsg {
+        owner: Some("sender88".to_string()),
+        asset_pool: AssetPool {
+            credit_asset: Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "credit".to_string(),
+                },
+                amount: Uint128::zero(),
             },
+            liq_premium: Decimal::zero(),
+            deposits: vec![],
+        },
+        osmosis_proxy: String::from("osmosis_proxy"),
+        tema_denom: String::from("tema_denom"),
+        incentive_rate: Some(Decimal::zero()),
+        positions_contract: String::from("positions_contract"),
+        oracle_contract: String::from("oracle_contract"),
+        max_incentives: None,
+        minimum_deposit_amount: Uint128::new(5)
+    };
+
+    //Instantiating contract
+    let info = mock_info("sender88", &vec![]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    //Deposits
+    let deposit_msg = ExecuteMsg::Deposit { user: None };
+    let info = mock_info("user1", &coins(100, "credit"));
+    execute(deps.as_mut(), mock_env(), info, deposit_msg.clone()).unwrap();
+
+    let info = mock_info("user2", &coins(300, "credit"));
+    execute(deps.as_mut(), mock_env(), info, deposit_msg).unwrap();
+
+    let start_time = mock_env().block.time.seconds();
+
+    //Error: TEMA incentives are minted
+    let stream_msg = ExecuteMsg::AddIncentiveStream { start_time, end_time: start_time + 1000 };
+    let info = mock_info("funder", &coins(1000, "tema_denom"));
+    let err = execute(deps.as_mut(), mock_env(), info, stream_msg.clone()).unwrap_err();
+    assert_eq!(err.to_string(), String::from("Custom Error val: \"TEMA incentives are set by the incentive rate\""));
+
+    //Error: Denom isn't accepted
+    let info = mock_info("funder", &coins(1000, "reward"));
+    let err = execute(deps.as_mut(), mock_env(), info.clone(), stream_msg.clone()).unwrap_err();
+    assert_eq!(err.to_string(), String::from("Custom Error val: \"reward isn't an accepted stream denom\""));
+
+    //Accept the denom
+    let msg = ExecuteMsg::EditStreamDenom { denom: String::from("reward"), minimum_amount: Some(Uint128::new(500)) };
+    let err = execute(deps.as_mut(), mock_env(), mock_info("not_owner", &[]), msg.clone()).unwrap_err();
+    assert_eq!(err.to_string(), String::from("Unauthorized"));
+    execute(deps.as_mut(), mock_env(), mock_info("sender88", &[]), msg).unwrap();
+
+    //Error: Stream is below the minimum
+    let err = execute(deps.as_mut(), mock_env(), mock_info("funder", &coins(499, "reward")), stream_msg.clone()).unwrap_err();
+    assert_eq!(err.to_string(), String::from("Custom Error val: \"Stream amount is below the minimum of 500reward\""));
+
+    //Error: Stream ends before it starts
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        ExecuteMsg::AddIncentiveStream { start_time, end_time: start_time },
+    )
+    .unwrap_err();
+    assert_eq!(err.to_string(), String::from("Invalid function parameters"));
+
+    //Successful stream
+    let res = execute(deps.as_mut(), mock_env(), info, stream_msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("method", "add_incentive_stream"),
+            attr("stream_id", "1"),
+            attr("funder", "funder"),
+            attr("stream", "1000reward"),
+            attr("start_time", start_time.to_string()),
+            attr("end_time", (start_time + 1000).to_string()),
         ]
     );
-}
\ No newline at end of file
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::IncentiveStreams { start_after: None, limit: None },
+    )
+    .unwrap();
+    let resp: Vec<IncentiveStream> = from_binary(&res).unwrap();
+    assert_eq!(resp.len(), 1);
+    assert_eq!(resp[0].amount, Uint128::new(1000));
+
+    //Halfway through the stream, rewards are split by deposit
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(500u64);
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::UnclaimedIncentives { user: "user1".to_string() },
+    )
+    .unwrap();
+    let resp: UserIncentivesResponse = from_binary(&res).unwrap();
+    assert_eq!(resp.incentives, Uint128::zero());
+    assert_eq!(resp.stream_incentives, coins(125, "reward"));
+
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::UnclaimedIncentives { user: "user2".to_string() },
+    )
+    .unwrap();
+    let resp: UserIncentivesResponse = from_binary(&res).unwrap();
+    assert_eq!(resp.stream_incentives, coins(375, "reward"));
+
+    //Claim after the stream ends
+    env.block.time = env.block.time.plus_seconds(1000u64);
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user1", &[]), ExecuteMsg::ClaimRewards {}).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "user1".to_string(),
+            amount: coins(250, "reward"),
+        }))]
+    );
+
+    //Finished streams are removed
+    let res = query(
+        deps.as_ref(),
+        env,
+        QueryMsg::IncentiveStreams { start_after: None, limit: None },
+    )
+    .unwrap();
+    let resp: Vec<IncentiveStream> = from_binary(&res).unwrap();
+    assert_eq!(resp, vec![]);
+}
+
+#[test]
+fn incentive_stream_limits() {
+    let mut deps = mock_dependencies();
+
+    let msg = InstantiateMsg {
+        owner: Some("sender88".to_string()),
+        asset_pool: AssetPool {
+            credit_asset: Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "credit".to_string(),
+                },
+                amount: Uint128::zero(),
+            },
+            liq_premium: Decimal::zero(),
+            deposits: vec![],
+        },
+        osmosis_proxy: String::from("osmosis_proxy"),
+        tema_denom: String::from("tema_denom"),
+        incentive_rate: Some(Decimal::zero()),
+        positions_contract: String::from("positions_contract"),
+        oracle_contract: String::from("oracle_contract"),
+        max_incentives: None,
+        minimum_deposit_amount: Uint128::new(5)
+    };
+
+    //Instantiating contract
+    let info = mock_info("sender88", &vec![]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let msg = ExecuteMsg::EditStreamDenom { denom: String::from("reward"), minimum_amount: Some(Uint128::new(100)) };
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    //Stream starts while the pool is empty
+    let start_time = mock_env().block.time.seconds();
+    let stream_msg = ExecuteMsg::AddIncentiveStream { start_time, end_time: start_time + 1000 };
+    execute(deps.as_mut(), mock_env(), mock_info("funder", &coins(1000, "reward")), stream_msg).unwrap();
+
+    //Deposit halfway through the stream
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(500u64);
+    let deposit_msg = ExecuteMsg::Deposit { user: None };
+    execute(deps.as_mut(), env.clone(), mock_info("user1", &coins(100, "credit")), deposit_msg).unwrap();
+
+    //Emissions from while the pool was empty are refunded to the funder
+    let res = execute(deps.as_mut(), env.clone(), mock_info("funder", &[]), ExecuteMsg::ClaimRewards {}).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "funder".to_string(),
+            amount: coins(500, "reward"),
+        }))]
+    );
+
+    //The depositor only earns emissions from after their deposit
+    env.block.time = env.block.time.plus_seconds(500u64);
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::UnclaimedIncentives { user: "user1".to_string() },
+    )
+    .unwrap();
+    let resp: UserIncentivesResponse = from_binary(&res).unwrap();
+    assert_eq!(resp.stream_incentives, coins(500, "reward"));
+
+    //Streams are capped, finished streams don't count
+    let start_time = env.block.time.seconds();
+    let stream_msg = ExecuteMsg::AddIncentiveStream { start_time, end_time: start_time + 1000 };
+    for _ in 0..10 {
+        execute(deps.as_mut(), env.clone(), mock_info("funder", &coins(100, "reward")), stream_msg.clone()).unwrap();
+    }
+    let err = execute(deps.as_mut(), env, mock_info("funder", &coins(100, "reward")), stream_msg).unwrap_err();
+    assert_eq!(err.to_string(), String::from("Custom Error val: \"Max 10 streams can be active at once\""));
+}
@@ -79,6 +79,7 @@ mod tests {
                             current_supply: Uint128::new(110_000u128),
                             max_supply: Uint128::zero(),
                             burned_supply: Uint128::zero(),
+                            mint_limits: vec![],
                         })?)
                     }
                 }
@@ -566,7 +567,7 @@ mod tests {
                 .unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
 
-            //Query to make sure the remaining amount of the 2nd "credit" deposit is still staked
+            //Query to make sure the remaining amount is still staked
             let resp: AssetPool = app
                 .wrap()
                 .query_wasm_smart(sp_contract.addr(), &QueryMsg::AssetPool { user: None, deposit_limit: None, start_after: None })
@@ -576,18 +577,11 @@ mod tests {
                 vec![
                     Deposit {
                         user: Addr::unchecked(USER),
-                        amount: Decimal::percent(100_000_00),
+                        amount: Decimal::percent(100_005_00),
                         deposit_time: app.block_info().time.seconds(),
                         last_accrued: app.block_info().time.seconds(),
                         unstake_time: Some(app.block_info().time.seconds()),
                     },
-                    Deposit {
-                        user: Addr::unchecked(USER),
-                        amount: Decimal::percent(5_00),
-                        deposit_time: app.block_info().time.seconds(),
-                        last_accrued: app.block_info().time.seconds(),
-                        unstake_time: Some(app.block_info().time.seconds()),
-                    },                    
                     Deposit {
                         user: Addr::unchecked(USER),
                         amount: Decimal::percent(5_00),
@@ -615,21 +609,7 @@ mod tests {
                 vec![
                     Deposit {
                         user: Addr::unchecked(USER),
-                        amount: Decimal::percent(100_000_00),
-                        deposit_time: app.block_info().time.seconds(),
-                        last_accrued: app.block_info().time.seconds(),
-                        unstake_time: None,
-                    },
-                    Deposit {
-                        user: Addr::unchecked(USER),
-                        amount: Decimal::percent(5_00),
-                        deposit_time: app.block_info().time.seconds(),
-                        last_accrued: app.block_info().time.seconds(),
-                        unstake_time: None,
-                    },
-                    Deposit {
-                        user: Addr::unchecked(USER),
-                        amount: Decimal::percent(5_00),
+                        amount: Decimal::percent(100_010_00),
                         deposit_time: app.block_info().time.seconds(),
                         last_accrued: app.block_info().time.seconds(),
                         unstake_time: None,
@@ -654,21 +634,14 @@ mod tests {
                 vec![
                     Deposit {
                         user: Addr::unchecked(USER),
-                        amount: Decimal::percent(100_000_00),
+                        amount: Decimal::percent(100_001_00),
                         deposit_time: app.block_info().time.seconds(),
                         last_accrued: app.block_info().time.seconds(),
                         unstake_time: Some(app.block_info().time.seconds()),
                     },
                     Deposit {
                         user: Addr::unchecked(USER),
-                        amount: Decimal::percent(5_00),
-                        deposit_time: app.block_info().time.seconds(),
-                        last_accrued: app.block_info().time.seconds(),
-                        unstake_time: Some(app.block_info().time.seconds()),
-                    },
-                    Deposit {
-                        user: Addr::unchecked(USER),
-                        amount: Decimal::percent(5_00),
+                        amount: Decimal::percent(9_00),
                         deposit_time: app.block_info().time.seconds(),
                         last_accrued: app.block_info().time.seconds(),
                         unstake_time: None,
@@ -677,14 +650,14 @@ mod tests {
             );
 
             
-            //Test unstaking a new deposit that doesn't stop at the already unstaking deposits
+            //Test unstaking more of the deposit, which resets the unstaking timer
             let withdraw_msg = ExecuteMsg::Withdraw { amount: Uint128::new(5u128) };
             let cosmos_msg = sp_contract
                 .call(withdraw_msg, vec![])
                 .unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
 
-            //Withdrawl Success, anything between 100_001 & 100_005 will withdraw the first 2 deposits to enforce the minimum
+            //Withdrawl Success, only the matured unstaking amount can be withdrawn
             let withdraw_msg = ExecuteMsg::Withdraw { amount: Uint128::new(100_004u128) };
             let cosmos_msg = sp_contract
                 .call(withdraw_msg, vec![])
@@ -699,7 +672,6 @@ mod tests {
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
 
             //Assert success
-            //3rd is left
             let resp: AssetPool = app
                 .wrap()
                 .query_wasm_smart(sp_contract.addr(), &QueryMsg::AssetPool { user: None, deposit_limit: None, start_after: None })
@@ -709,12 +681,19 @@ mod tests {
                 vec![
                     Deposit {
                         user: Addr::unchecked(USER),
-                        amount: Decimal::percent(5_00),
+                        amount: Decimal::percent(2_00),
                         deposit_time: 1571797419,
                         last_accrued: app.block_info().time.seconds(),
                         unstake_time: Some(1571797419),
                     },
-                    ]
+                    Deposit {
+                        user: Addr::unchecked(USER),
+                        amount: Decimal::percent(4_00),
+                        deposit_time: 1571797419,
+                        last_accrued: app.block_info().time.seconds(),
+                        unstake_time: None,
+                    },
+                ]
             );
         }
 
@@ -892,7 +871,7 @@ mod tests {
             app.execute(cdp_contract_addr, cosmos_msg).unwrap();
 
             //Query and Assert Claimables
-            //Since incentives were claimed earlier, these are only from the most recent timeskip
+            //Liquidation rewards are pending in the Tranche until claimed
             let query_msg = QueryMsg::UserClaims {
                 user: String::from(USER),
             };
@@ -907,13 +886,18 @@ mod tests {
                         denom: String::from("debit"),
                         amount: Uint128::new(100u128),
                     },
-                    Coin {
-                        denom: String::from("tema_denom"),
-                        amount: Uint128::new(10_000u128),
-                    },
                 ]
             );
 
+            //Query Incentives
+            //Since incentives were claimed earlier, these are only from the most recent timeskip
+            let query_msg = QueryMsg::UnclaimedIncentives { user: String::from(USER) };
+            let total_incentives: UserIncentivesResponse = app
+                .wrap()
+                .query_wasm_smart(sp_contract.addr(), &query_msg)
+                .unwrap();
+            assert_eq!(total_incentives.incentives, Uint128::new(10000));
+
             //Claim 
             let claim_msg = ExecuteMsg::ClaimRewards { };
             let cosmos_msg = sp_contract.call(claim_msg, vec![]).unwrap();
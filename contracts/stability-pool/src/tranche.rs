@@ -0,0 +1,614 @@
+use cosmwasm_std::{Addr, Decimal, Order, StdResult, Storage, Uint128};
+use cw_storage_plus::Bound;
+
+use membrane::helpers::SECONDS_PER_YEAR;
+use membrane::math::{Decimal256, Uint256, U256};
+use membrane::stability_pool::Config;
+use membrane::types::{Asset, AssetInfo, Deposit};
+
+use crate::contract::{accrue_incentives, add_to_user_claims};
+use crate::error::ContractError;
+use crate::state::{Tranche, TrancheSums, UserDeposit, DEPOSITS, EPOCH_SCALE_SUMS, TOTAL_DEPOSITS, TRANCHES};
+
+//Timeframe constants
+pub const SECONDS_PER_DAY: u64 = 86_400u64;
+
+/// Return the id of the Tranche open at the given time
+pub fn tranche_id(time: u64) -> u64 {
+    time / SECONDS_PER_DAY
+}
+
+/// Max Tranches shared by a pro-rata liquidation
+pub const MAX_PRO_RATA_TRANCHES: usize = 50;
+
+/// Return the id of the Tranche new deposits join.
+/// Pro-rata deposits join the latest Tranche so liquidations don't need to load a Tranche per day.
+pub fn open_tranche_id(storage: &dyn Storage, config: &Config, time: u64) -> StdResult<u64> {
+    if config.pro_rata {
+        if let Some(id) = TRANCHES
+            .keys(storage, None, None, Order::Descending)
+            .next()
+            .transpose()?
+        {
+            return Ok(id);
+        }
+    }
+
+    Ok(tranche_id(time))
+}
+
+/// Assert the Tranches can be shared pro-rata in a single liquidation
+pub fn assert_pro_rata_tranches(storage: &dyn Storage) -> Result<(), ContractError> {
+    let tranche_count = TRANCHES
+        .keys(storage, None, None, Order::Ascending)
+        .take(MAX_PRO_RATA_TRANCHES + 1)
+        .count();
+
+    if tranche_count > MAX_PRO_RATA_TRANCHES {
+        return Err(ContractError::CustomError {
+            val: format!("Too many Tranches to switch to pro-rata, max is {}", MAX_PRO_RATA_TRANCHES),
+        });
+    }
+
+    Ok(())
+}
+
+/// Create an empty Tranche
+pub fn new_tranche(id: u64, time: u64, stream_indices: &[(String, Decimal256)]) -> Tranche {
+    Tranche {
+        id,
+        total_deposits: Uint256::zero(),
+        product_snapshot: Decimal256::one(),
+        sums: TrancheSums::default(),
+        current_epoch: Uint128::zero(),
+        current_scale: Uint128::zero(),
+        last_accrued: time,
+        stream_indices: stream_indices.to_vec(),
+    }
+}
+
+/// Rewards owed to a deposit since its snapshots
+pub struct DepositRewards {
+    /// Distributed assets
+    pub assets: Vec<(String, Uint256)>,
+    /// Incentive stream rewards
+    pub streams: Vec<(String, Uint256)>,
+    /// TEMA incentives
+    pub incentives: Uint256,
+}
+
+/// Load a Tranche & add incentives accrued up to the given time to its sums
+pub fn load_tranche(
+    storage: &mut dyn Storage,
+    config: &Config,
+    stream_indices: &[(String, Decimal256)],
+    id: u64,
+    time: u64,
+) -> StdResult<Option<Tranche>> {
+    match TRANCHES.may_load(storage, id)? {
+        Some(mut tranche) => {
+            if accrue_tranche(&mut tranche, config.incentive_rate, stream_indices, time) {
+                store_epoch_scale_sums(storage, &tranche)?;
+            }
+            TRANCHES.save(storage, id, &tranche)?;
+
+            Ok(Some(tranche))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Save a Tranche, removing it if it's empty & closed to new deposits
+pub fn save_tranche(storage: &mut dyn Storage, tranche: &Tranche, time: u64) -> StdResult<()> {
+    if tranche.total_deposits.is_zero() && tranche.id < tranche_id(time) {
+        TRANCHES.remove(storage, tranche.id);
+        Ok(())
+    } else {
+        TRANCHES.save(storage, tranche.id, tranche)
+    }
+}
+
+/// Add incentives & stream rewards per unit of deposit to the Tranche's sums.
+/// Returns true if the sums changed.
+pub fn accrue_tranche(
+    tranche: &mut Tranche,
+    rate: Decimal,
+    stream_indices: &[(String, Decimal256)],
+    time: u64,
+) -> bool {
+    let mut accrued = false;
+
+    for (denom, index) in stream_indices {
+        let snapshot = tranche
+            .stream_indices
+            .iter()
+            .find(|(snapshot_denom, _)| snapshot_denom == denom)
+            .map(|(_, snapshot)| *snapshot)
+            .unwrap_or_default();
+
+        if *index > snapshot && !tranche.total_deposits.is_zero() {
+            // P * (index - snapshot)
+            let sum = tranche.product_snapshot * (*index - snapshot);
+
+            match tranche.sums.streams.iter_mut().find(|(sum_denom, _)| sum_denom == denom) {
+                Some((_, stream_sum)) => *stream_sum += sum,
+                None => tranche.sums.streams.push((denom.clone(), sum)),
+            }
+            accrued = true;
+        }
+    }
+    tranche.stream_indices = stream_indices.to_vec();
+
+    if time > tranche.last_accrued {
+        let time_elapsed = time - tranche.last_accrued;
+
+        // P * rate * time / year
+        let incentives = tranche.product_snapshot
+            * Decimal256::from(rate)
+            * Decimal256::from_ratio(time_elapsed, SECONDS_PER_YEAR);
+
+        if !incentives.is_zero() && !tranche.total_deposits.is_zero() {
+            tranche.sums.incentives += incentives;
+            accrued = true;
+        }
+        tranche.last_accrued = time;
+    }
+
+    accrued
+}
+
+/// Return Tranches & the amount used from each, using Tranches in order until the amount is accounted for
+pub fn fifo_tranche_uses(storage: &dyn Storage, amount: Uint256) -> StdResult<Vec<(Tranche, Uint256)>> {
+    let mut remaining = amount;
+    let mut uses: Vec<(Tranche, Uint256)> = vec![];
+    let mut last_tranche: Option<u64> = None;
+
+    while !remaining.is_zero() {
+        let next_tranche = TRANCHES
+            .range(storage, last_tranche.map(Bound::exclusive), None, Order::Ascending)
+            .next()
+            .transpose()?;
+
+        let tranche = match next_tranche {
+            Some((_, tranche)) => tranche,
+            //End of Tranches
+            //If it gets here the state was mismanaged previously since by now the funds have already been sent.
+            None => break,
+        };
+        last_tranche = Some(tranche.id);
+
+        if tranche.total_deposits.is_zero() {
+            continue;
+        }
+
+        let used_amount = remaining.min(tranche.total_deposits);
+        remaining = remaining - used_amount;
+
+        uses.push((tranche, used_amount));
+    }
+
+    Ok(uses)
+}
+
+/// Return Tranches & the amount used from each, sharing the amount across all Tranches by weight.
+/// Pro-rata deposits don't open new Tranches so this is bounded by MAX_PRO_RATA_TRANCHES.
+pub fn pro_rata_tranche_uses(storage: &dyn Storage, amount: Uint256) -> StdResult<Vec<(Tranche, Uint256)>> {
+    let mut tranches: Vec<Tranche> = vec![];
+    for item in TRANCHES.range(storage, None, None, Order::Ascending).take(MAX_PRO_RATA_TRANCHES) {
+        let (_, tranche) = item?;
+
+        if !tranche.total_deposits.is_zero() {
+            tranches.push(tranche);
+        }
+    }
+
+    let total_deposits = tranches
+        .iter()
+        .fold(Uint256::zero(), |total, tranche| total + tranche.total_deposits);
+    if total_deposits.is_zero() {
+        return Ok(vec![]);
+    }
+    let amount = amount.min(total_deposits);
+
+    //Each Tranche is used by its share of total deposits
+    let mut uses: Vec<(Tranche, Uint256)> = tranches
+        .into_iter()
+        .map(|tranche| {
+            let used_amount = amount.multiply_ratio(tranche.total_deposits, total_deposits);
+            (tranche, used_amount)
+        })
+        .collect();
+
+    //Rounding leftovers are used from Tranches with room left, in order
+    let mut leftover = amount - uses.iter().fold(Uint256::zero(), |total, (_, used)| total + *used);
+    for (tranche, used_amount) in uses.iter_mut() {
+        if leftover.is_zero() {
+            break;
+        }
+        let added = leftover.min(tranche.total_deposits - *used_amount);
+        *used_amount += added;
+        leftover = leftover - added;
+    }
+
+    Ok(uses
+        .into_iter()
+        .filter(|(_, used_amount)| !used_amount.is_zero())
+        .collect())
+}
+
+/// Use an amount of the Tranche's deposits for a liquidation & add the distributed assets to its sums
+pub fn execute_tranche_liquidation(
+    storage: &mut dyn Storage,
+    tranche: &mut Tranche,
+    used_amount: Uint256,
+    distribution_assets: Vec<Asset>,
+) -> StdResult<()> {
+    //Used deposits stop sharing stream emissions
+    let total_deposits = TOTAL_DEPOSITS.load(storage)?;
+    let used_deposits = used_amount.min(tranche.total_deposits).min(total_deposits);
+    TOTAL_DEPOSITS.save(storage, &(total_deposits - used_deposits))?;
+
+    ///////// Update sums /////////
+    for asset in distribution_assets {
+        if asset.amount.is_zero() {
+            continue;
+        }
+
+        // E / D * P
+        let sum = tranche.product_snapshot
+            * Decimal256::from_ratio(Uint256::from(asset.amount), tranche.total_deposits);
+
+        match tranche
+            .sums
+            .assets
+            .iter_mut()
+            .find(|(asset_info, _)| *asset_info == asset.info.to_string())
+        {
+            Some((_, asset_sum)) => *asset_sum += sum,
+            None => tranche.sums.assets.push((asset.info.to_string(), sum)),
+        }
+    }
+
+    // save reward sums for current epoch and scale
+    store_epoch_scale_sums(storage, tranche)?;
+
+    ///////// Update product /////////
+    // Check if the Tranche is emptied, if it is, reset (P = 1, S = 0)
+    if used_amount >= tranche.total_deposits {
+        tranche.total_deposits = Uint256::zero();
+        tranche.product_snapshot = Decimal256::one();
+        tranche.sums = TrancheSums::default();
+        tranche.current_scale = Uint128::zero();
+
+        tranche.current_epoch += Uint128::from(1u128);
+    } else {
+        // 1 - Q / D
+        let product = Decimal256::one() - Decimal256::from_ratio(used_amount, tranche.total_deposits);
+        tranche.total_deposits = tranche.total_deposits - used_amount;
+
+        // check if scale needs to be increased (in case product truncates to zero)
+        let new_product = tranche.product_snapshot * product;
+        tranche.product_snapshot = if new_product < Decimal256(U256::from(1_000_000_000u64)) {
+            tranche.current_scale += Uint128::from(1u128);
+
+            Decimal256(tranche.product_snapshot.0 * U256::from(1_000_000_000u64)) * product
+        } else {
+            new_product
+        };
+    }
+
+    Ok(())
+}
+
+/// Store the Tranche's sums for its current epoch & scale
+pub fn store_epoch_scale_sums(storage: &mut dyn Storage, tranche: &Tranche) -> StdResult<()> {
+    EPOCH_SCALE_SUMS.save(
+        storage,
+        (tranche.id, tranche.current_epoch.u128(), tranche.current_scale.u128()),
+        &tranche.sums,
+    )
+}
+
+/// Read a Tranche's sums for an epoch & scale.
+/// Uses the Tranche's current sums if they are for the same epoch & scale.
+fn read_epoch_scale_sums(
+    storage: &dyn Storage,
+    tranche: &Option<Tranche>,
+    tranche_id: u64,
+    epoch: Uint128,
+    scale: Uint128,
+) -> StdResult<Option<TrancheSums>> {
+    if let Some(tranche) = tranche {
+        if tranche.current_epoch == epoch && tranche.current_scale == scale {
+            return Ok(Some(tranche.sums.clone()));
+        }
+    }
+
+    EPOCH_SCALE_SUMS.may_load(storage, (tranche_id, epoch.u128(), scale.u128()))
+}
+
+/// Calculate a deposit's remaining (amount, unstaking amount)
+pub fn calculate_remaining_deposit(
+    deposit: &UserDeposit,
+    tranche: &Option<Tranche>,
+) -> (Uint256, Uint256) {
+    match tranche {
+        Some(tranche) if tranche.current_epoch == deposit.epoch_snapshot => {
+            let scale_diff = tranche.current_scale - deposit.scale_snapshot;
+
+            let remaining = |amount: Uint256| -> Uint256 {
+                if amount.is_zero() {
+                    Uint256::zero()
+                } else if scale_diff.is_zero() {
+                    amount.multiply_ratio(tranche.product_snapshot.0, deposit.product_snapshot.0)
+                } else if scale_diff == Uint128::from(1u128) {
+                    // product has been scaled
+                    amount
+                        .multiply_ratio(tranche.product_snapshot.0, deposit.product_snapshot.0)
+                        .multiply_ratio(1u64, 1_000_000_000u64)
+                } else {
+                    Uint256::zero()
+                }
+            };
+
+            (remaining(deposit.amount), remaining(deposit.unstaking_amount))
+        }
+        // Tranche was emptied
+        _ => (Uint256::zero(), Uint256::zero()),
+    }
+}
+
+/// Calculate a deposit's rewards since its snapshots
+pub fn calculate_deposit_rewards(
+    storage: &dyn Storage,
+    deposit: &UserDeposit,
+    tranche: &Option<Tranche>,
+) -> StdResult<DepositRewards> {
+    let reference_sums = read_epoch_scale_sums(
+        storage,
+        tranche,
+        deposit.tranche_id,
+        deposit.epoch_snapshot,
+        deposit.scale_snapshot,
+    )?
+    .unwrap_or_default();
+    let next_scale_sums = read_epoch_scale_sums(
+        storage,
+        tranche,
+        deposit.tranche_id,
+        deposit.epoch_snapshot,
+        deposit.scale_snapshot + Uint128::from(1u128),
+    )?;
+
+    // reward = reward from first scale + reward from second scale (if any)
+    let reward = |amount: Uint256, snapshot: Decimal256, reference: Decimal256, next: Option<Decimal256>| -> Uint256 {
+        let first_portion = reference - snapshot;
+        let second_portion = match next {
+            Some(next) => Decimal256((next.0 - reference.0) / U256::from(1_000_000_000u64)),
+            None => Decimal256::zero(),
+        };
+
+        Decimal256::from_uint256(amount) * (first_portion + second_portion) / deposit.product_snapshot
+            * Uint256::one()
+    };
+
+    //Assets distributed in either scale
+    let mut assets: Vec<String> = reference_sums
+        .assets
+        .iter()
+        .map(|(asset, _)| asset.clone())
+        .collect();
+    if let Some(next_scale_sums) = next_scale_sums.clone() {
+        for (asset, _) in next_scale_sums.assets {
+            if !assets.contains(&asset) {
+                assets.push(asset);
+            }
+        }
+    }
+
+    let find_sum = |sums: &TrancheSums, asset: &String| -> Decimal256 {
+        sums.assets
+            .iter()
+            .find(|(asset_info, _)| asset_info == asset)
+            .map(|(_, sum)| *sum)
+            .unwrap_or_default()
+    };
+
+    let mut asset_rewards: Vec<(String, Uint256)> = vec![];
+    for asset in assets {
+        let amount = reward(
+            deposit.amount,
+            find_sum(&deposit.sum_snapshots, &asset),
+            find_sum(&reference_sums, &asset),
+            next_scale_sums.as_ref().map(|sums| find_sum(sums, &asset)),
+        );
+
+        if !amount.is_zero() {
+            asset_rewards.push((asset, amount));
+        }
+    }
+
+    //Streams emitted in either scale
+    let mut streams: Vec<String> = reference_sums
+        .streams
+        .iter()
+        .map(|(denom, _)| denom.clone())
+        .collect();
+    if let Some(next_scale_sums) = next_scale_sums.clone() {
+        for (denom, _) in next_scale_sums.streams {
+            if !streams.contains(&denom) {
+                streams.push(denom);
+            }
+        }
+    }
+
+    let find_stream_sum = |sums: &TrancheSums, denom: &String| -> Decimal256 {
+        sums.streams
+            .iter()
+            .find(|(sum_denom, _)| sum_denom == denom)
+            .map(|(_, sum)| *sum)
+            .unwrap_or_default()
+    };
+
+    //Streams are shared by all deposits, unstaking included
+    let mut stream_rewards: Vec<(String, Uint256)> = vec![];
+    for denom in streams {
+        let amount = reward(
+            deposit.amount,
+            find_stream_sum(&deposit.sum_snapshots, &denom),
+            find_stream_sum(&reference_sums, &denom),
+            next_scale_sums.as_ref().map(|sums| find_stream_sum(sums, &denom)),
+        );
+
+        if !amount.is_zero() {
+            stream_rewards.push((denom, amount));
+        }
+    }
+
+    //Unstaking deposits don't earn incentives
+    let incentives = reward(
+        deposit.amount - deposit.unstaking_amount,
+        deposit.sum_snapshots.incentives,
+        reference_sums.incentives,
+        next_scale_sums.map(|sums| sums.incentives),
+    );
+
+    Ok(DepositRewards {
+        assets: asset_rewards,
+        streams: stream_rewards,
+        incentives,
+    })
+}
+
+/// Add a deposit's rewards to the user's claims & reset its snapshots to the Tranche's.
+/// The Tranche should have its incentives accrued beforehand.
+pub fn settle_deposit(
+    storage: &mut dyn Storage,
+    config: &Config,
+    user: Addr,
+    mut deposit: UserDeposit,
+    tranche: &Option<Tranche>,
+    time: u64,
+) -> Result<UserDeposit, ContractError> {
+    let rewards = calculate_deposit_rewards(storage, &deposit, tranche)?;
+
+    for (asset, amount) in rewards.assets.into_iter().chain(rewards.streams) {
+        add_to_user_claims(
+            storage,
+            user.clone(),
+            AssetInfo::NativeToken { denom: asset },
+            Uint128::from(amount),
+        )?;
+    }
+
+    if !rewards.incentives.is_zero() {
+        //Assert max incentives limit
+        let incentives = accrue_incentives(storage, config.clone(), Uint128::from(rewards.incentives))?;
+
+        add_to_user_claims(
+            storage,
+            user,
+            AssetInfo::NativeToken { denom: config.clone().tema_denom },
+            incentives,
+        )?;
+    }
+
+    let (remaining, remaining_unstaking) = calculate_remaining_deposit(&deposit, tranche);
+    deposit.amount = remaining;
+    deposit.unstaking_amount = remaining_unstaking;
+    deposit.last_accrued = time;
+    if deposit.unstaking_amount.is_zero() {
+        deposit.unstake_time = None;
+    }
+
+    if let Some(tranche) = tranche {
+        deposit.product_snapshot = tranche.product_snapshot;
+        deposit.sum_snapshots = tranche.sums.clone();
+        deposit.epoch_snapshot = tranche.current_epoch;
+        deposit.scale_snapshot = tranche.current_scale;
+    }
+
+    Ok(deposit)
+}
+
+/// Create a deposit with the Tranche's snapshots
+pub fn new_user_deposit(tranche: &Tranche, time: u64) -> UserDeposit {
+    UserDeposit {
+        tranche_id: tranche.id,
+        amount: Uint256::zero(),
+        unstaking_amount: Uint256::zero(),
+        deposit_time: time,
+        last_accrued: time,
+        unstake_time: None,
+        product_snapshot: tranche.product_snapshot,
+        sum_snapshots: tranche.sums.clone(),
+        epoch_snapshot: tranche.current_epoch,
+        scale_snapshot: tranche.current_scale,
+    }
+}
+
+/// Save a user's deposit, removing it if it's empty
+pub fn save_user_deposit(storage: &mut dyn Storage, user: Addr, deposit: &UserDeposit) -> StdResult<()> {
+    if deposit.amount.is_zero() {
+        DEPOSITS.remove(storage, (user, deposit.tranche_id));
+        Ok(())
+    } else {
+        DEPOSITS.save(storage, (user, deposit.tranche_id), deposit)
+    }
+}
+
+/// Return a user's deposits in order of Tranche
+pub fn read_user_deposits(storage: &dyn Storage, user: Addr) -> StdResult<Vec<UserDeposit>> {
+    DEPOSITS
+        .prefix(user)
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (_, deposit) = item?;
+            Ok(deposit)
+        })
+        .collect::<StdResult<Vec<UserDeposit>>>()
+}
+
+/// Return a user's total remaining deposits
+pub fn read_user_total(storage: &dyn Storage, user: Addr) -> StdResult<Uint256> {
+    let mut total = Uint256::zero();
+
+    for deposit in read_user_deposits(storage, user)? {
+        let tranche = TRANCHES.may_load(storage, deposit.tranche_id)?;
+        total += calculate_remaining_deposit(&deposit, &tranche).0;
+    }
+
+    Ok(total)
+}
+
+/// Convert a user's deposit to the Deposit response type.
+/// Unstaking & staked amounts are returned as separate Deposits.
+pub fn to_deposit_responses(
+    user: Addr,
+    deposit: &UserDeposit,
+    tranche: &Option<Tranche>,
+) -> Vec<Deposit> {
+    let (remaining, remaining_unstaking) = calculate_remaining_deposit(deposit, tranche);
+    let mut deposits = vec![];
+
+    if !remaining_unstaking.is_zero() {
+        deposits.push(Deposit {
+            user: user.clone(),
+            amount: Decimal::from_ratio(Uint128::from(remaining_unstaking), Uint128::new(1u128)),
+            deposit_time: deposit.deposit_time,
+            last_accrued: deposit.last_accrued,
+            unstake_time: deposit.unstake_time,
+        });
+    }
+    if remaining > remaining_unstaking {
+        deposits.push(Deposit {
+            user,
+            amount: Decimal::from_ratio(Uint128::from(remaining - remaining_unstaking), Uint128::new(1u128)),
+            deposit_time: deposit.deposit_time,
+            last_accrued: deposit.last_accrued,
+            unstake_time: None,
+        });
+    }
+
+    deposits
+}
@@ -2,7 +2,7 @@ use std::{fs::create_dir_all, env::current_dir};
 
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 
-use membrane::staking::{InstantiateMsg, ExecuteMsg, QueryMsg, Config, RewardsResponse, StakedResponse, StakerResponse, TotalStakedResponse, FeeEventsResponse, DelegationResponse};
+use membrane::staking::{InstantiateMsg, ExecuteMsg, QueryMsg, Config, RewardsResponse, StakedResponse, StakerResponse, TotalStakedResponse, RewardIndicesResponse, DelegationResponse, UserLocksResponse, DelegateParticipationResponse, LiquidStakeResponse, AutoCompoundResponse, UnbondingResponse, StakeCheckpointResponse};
 use membrane::types::StakeDistributionLog;
 fn main() {
     let mut out_dir = current_dir().unwrap();
@@ -18,7 +18,13 @@ fn main() {
     export_schema(&schema_for!(StakedResponse), &out_dir);
     export_schema(&schema_for!(StakerResponse), &out_dir);
     export_schema(&schema_for!(TotalStakedResponse), &out_dir);
-    export_schema(&schema_for!(FeeEventsResponse), &out_dir);
+    export_schema(&schema_for!(RewardIndicesResponse), &out_dir);
     export_schema(&schema_for!(StakeDistributionLog), &out_dir);
     export_schema(&schema_for!(DelegationResponse), &out_dir);
+    export_schema(&schema_for!(UserLocksResponse), &out_dir);
+    export_schema(&schema_for!(LiquidStakeResponse), &out_dir);
+    export_schema(&schema_for!(AutoCompoundResponse), &out_dir);
+    export_schema(&schema_for!(UnbondingResponse), &out_dir);
+    export_schema(&schema_for!(StakeCheckpointResponse), &out_dir);
+    export_schema(&schema_for!(DelegateParticipationResponse), &out_dir);
 }
@@ -1,36 +1,52 @@
 use std::cmp::min;
 #[cfg(not(feature = "library"))]
 use std::env;
+use std::collections::BTreeSet;
 
 
 use cosmwasm_std::{entry_point, Coin};
 use cosmwasm_std::{
     attr, coin, to_binary, Addr, Api, BankMsg, Binary, CosmosMsg, Decimal, Deps,
-    DepsMut, Env, MessageInfo, Response, StdError, StdResult, Storage, Uint128, WasmMsg, QueryRequest, WasmQuery, QuerierWrapper,
+    DepsMut, Env, MessageInfo, Reply, Response, StdError, StdResult, Storage, SubMsg, Uint128, WasmMsg, QueryRequest, WasmQuery, QuerierWrapper,
 };
 use cw2::set_contract_version;
 
-use membrane::governance::{QueryMsg as Gov_QueryMsg, ProposalListResponse, ProposalStatus};
-use membrane::helpers::{assert_sent_native_token_balance, validate_position_owner, asset_to_coin, query_basket};
+use membrane::pause::{assert_not_paused, execute_pause, PauseActions};
+use membrane::governance::{QueryMsg as Gov_QueryMsg, ProposalListResponse, ProposalStatus, ProposalVoterResponse};
+use membrane::helpers::{assert_sent_native_token_balance, validate_position_owner, asset_to_coin, query_basket, get_lock_multiplier, router_native_to_native};
+use membrane::oracle::{PriceResponse, QueryMsg as OracleQueryMsg};
 use membrane::osmosis_proxy::ExecuteMsg as OsmoExecuteMsg;
 use membrane::auction::ExecuteMsg as AuctionExecuteMsg;
-use membrane::staking::{ Config, ExecuteMsg, InstantiateMsg, QueryMsg, Totals, MigrateMsg};
+use membrane::stability_pool::{Config as SP_Config, ExecuteMsg as SP_ExecuteMsg, QueryMsg as SP_QueryMsg};
+use membrane::staking::{ Config, ExecuteMsg, InstantiateMsg, QueryMsg, Totals, MigrateMsg, LOCK_FEE_BOOST, LOCK_VOTING_BOOST, MAX_KEEPER_TIP, MAX_LOCK_DAYS, MAX_SLIPPAGE, MIN_COMPOUND_INTERVAL, PENALTY_STREAM_DURATION};
 use membrane::vesting::{QueryMsg as Vesting_QueryMsg, RecipientsResponse};
-use membrane::types::{Asset, AssetInfo, Basket, Delegate, Delegation, DelegationInfo, FeeEvent, LiqAsset, StakeDeposit, StakeDistribution, StakeDistributionLog};
+use membrane::types::{Asset, AssetInfo, Basket, Delegate, Delegation, DelegationInfo, InstantUnstakePenalty, LiqAsset, LiquidStakePool, ParticipationRequirements, PenaltyRecipient, ProposalParticipation, StakeDeposit, StakeDistribution, StakeDistributionLog, StakeLock};
 use membrane::math::{decimal_division, decimal_multiplication};
 
 use crate::error::ContractError;
-use crate::query::{query_declared_delegates, query_delegations, query_fee_events, query_staked, query_totals, query_user_rewards, query_user_stake};
-use crate::state::{CONFIG, DELEGATE_CLAIMS, DELEGATE_INFO, DELEGATIONS, FEE_EVENTS, INCENTIVE_SCHEDULING, OWNERSHIP_TRANSFER, STAKED, STAKING_TOTALS, VESTING_REV_MULTIPLIER, VESTING_STAKE_TIME};
+use crate::query::{query_auto_compounders, query_stake_at, query_stake_checkpoints, query_total_stake_at, query_unbonding, query_declared_delegates, query_delegate_participation, query_delegations, query_liquid_stake, query_locks, query_reward_indices, query_staked, query_totals, query_user_locks, query_user_rewards, query_user_stake};
+use crate::state::{AUTO_COMPOUND, COMPOUND, CompoundPropagation, CONFIG, PENALTY, PENALTY_BATCH, PenaltyPropagation, DELEGATE_CLAIMS, DELEGATE_INFO, DELEGATE_PARTICIPATION, DELEGATIONS, FEE_EVENTS, INCENTIVE_SCHEDULING, LIQUID_FEES, LIQUID_STAKE, LOCKS, LOCK_BOOSTS, LOCK_BOOST_CLAIMS, LOCK_BOOST_INDICES, OWNERSHIP_TRANSFER, REWARD_INDICES, STAKED, STAKER_INDICES, STAKE_CHECKPOINTS, STAKING_TOTALS, TOTAL_LOCK_BOOST, TOTAL_QUADRATIC_STAKE_CHECKPOINTS, TOTAL_STAKE_CHECKPOINTS, VESTING_REV_MULTIPLIER, VESTING_STAKE_TIME};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:staking";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+//Execute msgs handled by the pause guard
+//Fee deposits & participation reports from other contracts are never paused
+const PAUSE_ACTIONS: PauseActions = PauseActions {
+    pausable: &["update_config", "stake", "unstake", "restake", "claim_rewards", "update_delegations", "delegate_fluid_delegations", "declare_delegate", "create_receipt_denom", "liquid_stake", "liquid_unstake", "compound_liquid_stake", "instant_unstake", "stream_penalties", "set_auto_compound", "auto_compound", "refresh_locks"],
+    unpausable: &["deposit_fee", "report_participation"],
+};
+
 //Constants
 const SECONDS_PER_YEAR: u64 = 31_536_000u64;
 pub const SECONDS_PER_DAY: u64 = 86_400u64;
 
+//Reply IDs
+pub const COMPOUND_REPLY_ID: u64 = 1u64;
+pub const PENALTY_REPLY_ID: u64 = 2u64;
+pub const PENALTY_STREAM_REPLY_ID: u64 = 3u64;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
@@ -57,6 +73,16 @@ pub fn instantiate(
             keep_raw_cdt: true,
             vesting_rev_multiplier: Decimal::percent(20),
             tema_denom: msg.tema_denom,
+            participation_requirements: Some(ParticipationRequirements {
+                threshold: Decimal::percent(50),
+                window: 10,
+            }),
+            dex_router: None,
+            keeper_tip: Decimal::percent(1),
+            instant_unstake_penalty: None,
+            stability_pool: None,
+            oracle_contract: None,
+            max_slippage: Decimal::percent(1),
         };
     } else {
         config = Config {
@@ -75,6 +101,16 @@ pub fn instantiate(
             keep_raw_cdt: true,
             vesting_rev_multiplier: Decimal::percent(20),
             tema_denom: msg.tema_denom,
+            participation_requirements: Some(ParticipationRequirements {
+                threshold: Decimal::percent(50),
+                window: 10,
+            }),
+            dex_router: None,
+            keeper_tip: Decimal::percent(1),
+            instant_unstake_penalty: None,
+            stability_pool: None,
+            oracle_contract: None,
+            max_slippage: Decimal::percent(1),
         };
     }
 
@@ -106,8 +142,8 @@ pub fn instantiate(
             vesting_contract: Uint128::zero(),
         },
     )?;
-    //Initialize fee events
-    FEE_EVENTS.save(deps.storage, &vec![])?;
+    //Initialize reward indices
+    REWARD_INDICES.save(deps.storage, &vec![])?;
 
     //Initialize Vesting stake time
     VESTING_STAKE_TIME.save(deps.storage, &env.block.time.seconds())?;
@@ -147,6 +183,9 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
+    //Check the pause guard
+    assert_not_paused(deps.storage, &env, &msg, &PAUSE_ACTIONS)?;
+
     match msg {
         ExecuteMsg::UpdateConfig {
             owner,
@@ -161,6 +200,13 @@ pub fn execute(
             max_commission_rate,
             keep_raw_cdt,
             vesting_rev_multiplier,
+            participation_requirements,
+            dex_router,
+            keeper_tip,
+            instant_unstake_penalty,
+            stability_pool,
+            oracle_contract,
+            max_slippage,
         } => update_config(
             deps,
             info,
@@ -177,8 +223,15 @@ pub fn execute(
             max_commission_rate,
             keep_raw_cdt,            
             vesting_rev_multiplier,
+            participation_requirements,
+            dex_router,
+            keeper_tip,
+            instant_unstake_penalty,
+            stability_pool,
+            oracle_contract,
+            max_slippage,
         ),
-        ExecuteMsg::Stake { user } => stake(deps, env, info, user),
+        ExecuteMsg::Stake { user, lock_duration } => stake(deps, env, info, user, lock_duration),
         ExecuteMsg::Unstake { tema_amount } => unstake(deps, env, info, tema_amount),
         ExecuteMsg::UpdateDelegations { governator_addr, tema_amount, delegate, fluid, voting_power_delegation, commission } => update_delegations(
             deps,
@@ -219,7 +272,8 @@ pub fn execute(
         ExecuteMsg::DepositFee {} => {
             let config = CONFIG.load(deps.storage)?;
 
-            if info.sender != config.positions_contract.unwrap() && info.sender != config.auction_contract.unwrap(){
+            //Governance sends the deposits of removed proposals
+            if info.sender != config.positions_contract.unwrap() && info.sender != config.auction_contract.unwrap() && Some(info.sender.clone()) != config.governance_contract {
                 return Err(ContractError::Unauthorized {});
             }
 
@@ -237,7 +291,37 @@ pub fn execute(
 
             deposit_fee(deps, info, env, fee_assets)
         },
-        ExecuteMsg::TrimFeeEvents {  } => trim_fee_events(deps.storage, info),
+        ExecuteMsg::ReportParticipation { proposal_id, voters } => report_participation(deps, info, proposal_id, voters),
+        ExecuteMsg::RefreshLocks { stakers } => refresh_locks(deps, env, stakers),
+        ExecuteMsg::InstantUnstake {} => instant_unstake(deps, env, info),
+        ExecuteMsg::StreamPenalties {} => {
+            let config = CONFIG.load(deps.storage)?;
+            let sub_msgs = stream_penalties(deps.storage, deps.querier, &env, &config)?;
+            if sub_msgs.is_empty() {
+                return Err(ContractError::CustomError {
+                    val: String::from("No penalties can be streamed yet"),
+                });
+            }
+
+            Ok(Response::new().add_submessages(sub_msgs).add_attribute("method", "stream_penalties"))
+        },
+        ExecuteMsg::SetAutoCompound { enabled } => set_auto_compound(deps, info, enabled),
+        ExecuteMsg::AutoCompound { user } => auto_compound(deps, env, info, user),
+        ExecuteMsg::CreateReceiptDenom { subdenom } => create_receipt_denom(deps, info, subdenom),
+        ExecuteMsg::LiquidStake {} => liquid_stake(deps, env, info),
+        ExecuteMsg::LiquidUnstake {} => liquid_unstake(deps, env, info),
+        ExecuteMsg::CompoundLiquidStake {} => {
+            let config = CONFIG.load(deps.storage)?;
+            LIQUID_STAKE.load(deps.storage)?;
+
+            let (msgs, _) = compound_liquid_stake(deps.storage, env, config)?;
+
+            Ok(Response::new().add_messages(msgs).add_attribute("method", "compound_liquid_stake"))
+        },
+        ExecuteMsg::Pause(pause_msg) => {
+            let config = CONFIG.load(deps.storage)?;
+            Ok(execute_pause(deps.storage, deps.api, &env, &info, info.sender == config.owner, &PAUSE_ACTIONS, pause_msg)?)
+        }
     }
 }
 
@@ -300,6 +384,745 @@ fn delegate_declarations(
 
 }
 
+/// Create the receipt token for liquid staking
+fn create_receipt_denom(
+    deps: DepsMut,
+    info: MessageInfo,
+    subdenom: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if LIQUID_STAKE.may_load(deps.storage)?.is_some() {
+        return Err(ContractError::CustomError {
+            val: String::from("Receipt denom already created"),
+        });
+    }
+    let osmosis_proxy = match config.osmosis_proxy {
+        Some(osmosis_proxy) => osmosis_proxy,
+        None => return Err(ContractError::CustomError {
+            val: String::from("No proxy contract setup"),
+        }),
+    };
+
+    //Tokenfactory denoms are namespaced by their creator
+    let receipt_denom = format!("factory/{}/{}", osmosis_proxy, subdenom);
+    LIQUID_STAKE.save(deps.storage, &LiquidStakePool {
+        receipt_denom: receipt_denom.clone(),
+        receipt_supply: Uint128::zero(),
+    })?;
+    LIQUID_FEES.save(deps.storage, &vec![])?;
+
+    //Forward funds for the denom creation fee
+    let msg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: osmosis_proxy.to_string(),
+        msg: to_binary(&OsmoExecuteMsg::CreateDenom {
+            subdenom,
+            max_supply: None,
+        })?,
+        funds: info.funds,
+    });
+
+    Ok(Response::new().add_message(msg).add_attributes(vec![
+        attr("method", "create_receipt_denom"),
+        attr("receipt_denom", receipt_denom),
+    ]))
+}
+
+/// Return the TEMA staked by the liquid pool
+pub fn get_liquid_stake_total(storage: &dyn Storage, env: &Env) -> StdResult<Uint128> {
+    Ok(STAKED.may_load(storage, env.contract.address.clone())?
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|deposit| deposit.unstake_start_time.is_none())
+        .map(|deposit| deposit.amount)
+        .sum())
+}
+
+/// Return the osmosis proxy, which mints & burns for the contract
+fn get_osmosis_proxy(config: &Config) -> StdResult<Addr> {
+    match config.osmosis_proxy.clone() {
+        Some(osmosis_proxy) => Ok(osmosis_proxy),
+        None => Err(StdError::GenericErr { msg: String::from("No proxy contract setup") }),
+    }
+}
+
+/// Claim the liquid pool's rewards & return the TEMA incentives minted.
+/// TEMA incentives are restaked to grow the receipt's exchange rate & fee revenue is held for the pool.
+fn compound_liquid_stake(
+    storage: &mut dyn Storage,
+    env: Env,
+    config: Config,
+) -> StdResult<(Vec<CosmosMsg>, Uint128)> {
+    let pool = env.contract.address.clone();
+    if STAKED.may_load(storage, pool.clone())?.is_none() {
+        return Ok((vec![], Uint128::zero()));
+    }
+
+    let (claimables, accrued_interest) = get_user_claimables(storage, env.clone(), pool.clone())?;
+
+    //Hold fee revenue
+    if !claimables.is_empty() {
+        let mut fees = LIQUID_FEES.may_load(storage)?.unwrap_or_default();
+        for claim in claimables {
+            let claim = asset_to_coin(claim)?;
+            match fees.iter_mut().find(|fee| fee.denom == claim.denom) {
+                Some(fee) => fee.amount += claim.amount,
+                None => fees.push(claim),
+            }
+        }
+        LIQUID_FEES.save(storage, &fees)?;
+    }
+
+    let mut msgs = vec![];
+    //Restake TEMA incentives
+    if !accrued_interest.is_zero() {
+        add_staking_deposit(storage, env.clone(), config.clone(), pool, accrued_interest, None)?;
+
+        //Mint to contract for accounting purposes
+        msgs.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: get_osmosis_proxy(&config)?.to_string(),
+            msg: to_binary(&OsmoExecuteMsg::MintTokens {
+                denom: config.tema_denom,
+                amount: accrued_interest,
+                mint_to_address: env.contract.address.to_string(),
+            })?,
+            funds: vec![],
+        }));
+    }
+
+    Ok((msgs, accrued_interest))
+}
+
+/// Compound the liquid pool's held fees into its stake so they count towards the exchange rate.
+/// Fees stay held if there is no router & oracle to swap them.
+fn compound_liquid_fees(
+    storage: &mut dyn Storage,
+    querier: QuerierWrapper,
+    env: Env,
+    config: Config,
+    minted_interest: Uint128,
+) -> StdResult<(Vec<SubMsg>, Uint128)> {
+    let dex_router = match (config.clone().dex_router, config.clone().oracle_contract) {
+        (Some(dex_router), Some(_)) => dex_router,
+        _ => return Ok((vec![], Uint128::zero())),
+    };
+    let fees = LIQUID_FEES.may_load(storage)?.unwrap_or_default();
+    LIQUID_FEES.save(storage, &vec![])?;
+
+    compound_fees(storage, querier, env.clone(), config, dex_router, env.contract.address.clone(), env.contract.address, fees, minted_interest)
+}
+
+/// Return the TEMA value of the liquid pool's held fees that compounding will swap, at oracle prices.
+/// TEMA fees are restaked as is so they aren't included.
+fn get_liquid_fees_value(
+    storage: &dyn Storage,
+    querier: QuerierWrapper,
+    config: &Config,
+) -> StdResult<Uint128> {
+    if config.dex_router.is_none() || config.oracle_contract.is_none() {
+        return Ok(Uint128::zero());
+    }
+
+    let mut value = Uint128::zero();
+    for fee in LIQUID_FEES.may_load(storage)?.unwrap_or_default() {
+        if fee.amount.is_zero() || fee.denom == config.tema_denom {
+            continue;
+        }
+        value += get_oracle_amount(querier, config, &fee, config.clone().tema_denom)?;
+    }
+
+    Ok(value)
+}
+
+/// Return the minimum amount of an asset to receive for a swap at oracle prices, less the max slippage
+fn get_min_receive(
+    querier: QuerierWrapper,
+    config: &Config,
+    offer: &Coin,
+    ask_denom: String,
+) -> StdResult<Uint128> {
+    Ok(get_oracle_amount(querier, config, offer, ask_denom)? * (Decimal::one() - config.max_slippage))
+}
+
+/// Return the amount of an asset equal in value to the offer at oracle prices
+fn get_oracle_amount(
+    querier: QuerierWrapper,
+    config: &Config,
+    offer: &Coin,
+    ask_denom: String,
+) -> StdResult<Uint128> {
+    let oracle_contract = match config.oracle_contract.clone() {
+        Some(oracle_contract) => oracle_contract,
+        None => return Err(StdError::GenericErr { msg: String::from("No oracle contract setup") }),
+    };
+    let query_price = |denom: String| -> StdResult<PriceResponse> {
+        querier.query_wasm_smart::<PriceResponse>(
+            oracle_contract.to_string(),
+            &OracleQueryMsg::Price {
+                asset_info: AssetInfo::NativeToken { denom },
+                twap_timeframe: 60,
+                oracle_time_limit: 600,
+                basket_id: None,
+            },
+        )
+    };
+    let offer_value = query_price(offer.denom.clone())?.get_value(offer.amount)?;
+
+    query_price(ask_denom)?.get_amount(offer_value)
+}
+
+/// Restake TEMA fees & swap the rest to TEMA through the router.
+/// Each swap's reply restakes the received TEMA for the user & tips the keeper.
+#[allow(clippy::too_many_arguments)]
+fn compound_fees(
+    storage: &mut dyn Storage,
+    querier: QuerierWrapper,
+    env: Env,
+    config: Config,
+    dex_router: Addr,
+    user: Addr,
+    keeper: Addr,
+    fees: Vec<Coin>,
+    minted_interest: Uint128,
+) -> StdResult<(Vec<SubMsg>, Uint128)> {
+    //Swap fee assets to TEMA, TEMA fees are restaked as is
+    let mut swap_msgs: Vec<CosmosMsg> = vec![];
+    let mut swaps: Vec<Coin> = vec![];
+    let mut restaked_fees = Uint128::zero();
+    for fee in fees {
+        if fee.amount.is_zero() {
+            continue;
+        }
+        if fee.denom == config.tema_denom {
+            add_staking_deposit(storage, env.clone(), config.clone(), user.clone(), fee.amount, None)?;
+            restaked_fees += fee.amount;
+            continue;
+        }
+
+        let minimum_receive = get_min_receive(querier, &config, &fee, config.clone().tema_denom)?;
+        swap_msgs.push(router_native_to_native(
+            dex_router.to_string(),
+            AssetInfo::NativeToken { denom: fee.clone().denom },
+            AssetInfo::NativeToken { denom: config.clone().tema_denom },
+            None,
+            fee.amount.u128(),
+            Some(minimum_receive),
+        )?);
+        swaps.push(fee);
+    }
+
+    //Reply on every swap so failed swaps don't revert the compound
+    let mut sub_msgs: Vec<SubMsg> = vec![];
+    if !swap_msgs.is_empty() {
+        //Minted interest is already staked
+        let prev_balance = querier
+            .query_balance(env.contract.address.clone(), config.clone().tema_denom)?
+            .amount + minted_interest;
+
+        COMPOUND.save(storage, &CompoundPropagation {
+            user,
+            keeper,
+            prev_balance,
+            swaps,
+        })?;
+
+        for swap_msg in swap_msgs {
+            sub_msgs.push(SubMsg::reply_always(swap_msg, COMPOUND_REPLY_ID));
+        }
+    }
+
+    Ok((sub_msgs, restaked_fees))
+}
+
+/// Stake TEMA in the liquid pool & mint receipt tokens at the current exchange rate
+fn liquid_stake(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut pool = match LIQUID_STAKE.may_load(deps.storage)? {
+        Some(pool) => pool,
+        None => return Err(ContractError::CustomError {
+            val: String::from("Liquid staking isn't enabled"),
+        }),
+    };
+
+    //Assert only TEMA was sent && its at least 1 TEMA
+    if info.funds.len() != 1 || info.funds[0].denom != config.tema_denom || info.funds[0].amount < Uint128::from(1_000_000u128) {
+        return Err(ContractError::CustomError {
+            val: "Must stake at least 1 TEMA".to_string(),
+        });
+    }
+    let amount = info.funds[0].amount;
+
+    //Compound first so new stakers don't share pending rewards.
+    //Swapped fees are restaked after this exchange rate is set, so their oracle value is priced in.
+    let (mut msgs, minted_interest) = compound_liquid_stake(deps.storage, env.clone(), config.clone())?;
+    let fees_value = get_liquid_fees_value(deps.storage, deps.querier, &config)?;
+    let (sub_msgs, _) = compound_liquid_fees(deps.storage, deps.querier, env.clone(), config.clone(), minted_interest)?;
+
+    //Calc receipts
+    let total_staked = get_liquid_stake_total(deps.storage, &env)? + fees_value;
+    let receipts = if pool.receipt_supply.is_zero() || total_staked.is_zero() {
+        amount
+    } else {
+        amount.multiply_ratio(pool.receipt_supply, total_staked)
+    };
+
+    add_staking_deposit(deps.storage, env.clone(), config.clone(), env.contract.address.clone(), amount, None)?;
+    pool.receipt_supply += receipts;
+    LIQUID_STAKE.save(deps.storage, &pool)?;
+
+    //Mint receipts
+    msgs.push(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: get_osmosis_proxy(&config)?.to_string(),
+        msg: to_binary(&OsmoExecuteMsg::MintTokens {
+            denom: pool.receipt_denom,
+            amount: receipts,
+            mint_to_address: info.sender.to_string(),
+        })?,
+        funds: vec![],
+    }));
+
+    Ok(Response::new().add_messages(msgs).add_submessages(sub_msgs).add_attributes(vec![
+        attr("method", "liquid_stake"),
+        attr("staker", info.sender),
+        attr("amount", amount),
+        attr("receipts", receipts),
+    ]))
+}
+
+/// Burn receipt tokens & move their TEMA into the sender's unstaking queue
+fn liquid_unstake(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut pool = match LIQUID_STAKE.may_load(deps.storage)? {
+        Some(pool) => pool,
+        None => return Err(ContractError::CustomError {
+            val: String::from("Liquid staking isn't enabled"),
+        }),
+    };
+    let receipts = assert_sent_native_token_balance(
+        AssetInfo::NativeToken { denom: pool.clone().receipt_denom },
+        &info,
+    )?.amount;
+
+    //Compound first so redemptions include pending incentives.
+    //Swapped fees are restaked after this exchange rate is set.
+    let (mut msgs, minted_interest) = compound_liquid_stake(deps.storage, env.clone(), config.clone())?;
+    let (sub_msgs, _) = compound_liquid_fees(deps.storage, deps.querier, env.clone(), config.clone(), minted_interest)?;
+
+    //Calc TEMA owed
+    let total_staked = get_liquid_stake_total(deps.storage, &env)?;
+    let tema_amount = total_staked.multiply_ratio(receipts, pool.receipt_supply);
+    if tema_amount.is_zero() {
+        return Err(ContractError::CustomError {
+            val: String::from("Receipt amount too small"),
+        });
+    }
+    pool.receipt_supply -= receipts;
+    LIQUID_STAKE.save(deps.storage, &pool)?;
+
+    //Subtract from the pool's deposits
+    let mut remaining = tema_amount;
+    let pool_deposits = STAKED.load(deps.storage, env.contract.address.clone())?
+        .into_iter()
+        .map(|mut deposit| {
+            let subtraction = min(deposit.amount, remaining);
+            deposit.amount -= subtraction;
+            remaining -= subtraction;
+            deposit
+        })
+        .filter(|deposit| !deposit.amount.is_zero())
+        .collect::<Vec<StakeDeposit>>();
+    STAKED.save(deps.storage, env.contract.address.clone(), &pool_deposits)?;
+    checkpoint_stake(deps.storage, env.clone(), config.clone(), env.contract.address.clone())?;
+
+    //Add unstaking deposit for the sender
+    STAKED.update(deps.storage, info.sender.clone(), |deposits| -> StdResult<_> {
+        let mut deposits = deposits.unwrap_or_default();
+        deposits.push(StakeDeposit {
+            staker: info.sender.clone(),
+            amount: tema_amount,
+            stake_time: env.block.time.seconds(),
+            unstake_start_time: Some(env.block.time.seconds()),
+            last_accrued: Some(env.block.time.seconds()),
+        });
+        Ok(deposits)
+    })?;
+
+    //Unstaking TEMA isn't in the totals
+    let mut totals = STAKING_TOTALS.load(deps.storage)?;
+    totals.stakers = totals.stakers.checked_sub(tema_amount)?;
+    STAKING_TOTALS.save(deps.storage, &totals)?;
+
+    //Burn receipts
+    msgs.push(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: get_osmosis_proxy(&config)?.to_string(),
+        msg: to_binary(&OsmoExecuteMsg::BurnTokens {
+            denom: pool.receipt_denom,
+            amount: receipts,
+            burn_from_address: env.contract.address.to_string(),
+        })?,
+        funds: vec![],
+    }));
+
+    Ok(Response::new().add_messages(msgs).add_submessages(sub_msgs).add_attributes(vec![
+        attr("method", "liquid_unstake"),
+        attr("staker", info.sender),
+        attr("receipts", receipts),
+        attr("unstake_amount", tema_amount),
+    ]))
+}
+
+/// Opt in/out of auto-compounding
+fn set_auto_compound(
+    deps: DepsMut,
+    info: MessageInfo,
+    enabled: bool,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    //Vesting contract doesn't compound
+    if info.sender == config.vesting_contract.unwrap_or_else(|| Addr::unchecked("")) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if enabled {
+        if AUTO_COMPOUND.may_load(deps.storage, info.sender.clone())?.is_none() {
+            AUTO_COMPOUND.save(deps.storage, info.sender.clone(), &0u64)?;
+        }
+    } else {
+        AUTO_COMPOUND.remove(deps.storage, info.sender.clone());
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "set_auto_compound"),
+        attr("user", info.sender),
+        attr("enabled", enabled.to_string()),
+    ]))
+}
+
+/// Swap a user's fee revenue to TEMA through the router & restake it.
+/// The reply restakes the swapped TEMA & tips the caller.
+fn auto_compound(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    user: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let dex_router = match config.clone().dex_router {
+        Some(dex_router) => dex_router,
+        None => return Err(ContractError::CustomError {
+            val: String::from("No router contract setup"),
+        }),
+    };
+    let user = deps.api.addr_validate(&user)?;
+
+    //Liquid pool is always opted in
+    let last_compound = if user == env.contract.address {
+        AUTO_COMPOUND.may_load(deps.storage, user.clone())?.unwrap_or(0u64)
+    } else {
+        match AUTO_COMPOUND.may_load(deps.storage, user.clone())? {
+            Some(last_compound) => last_compound,
+            None => return Err(ContractError::CustomError {
+                val: String::from("User hasn't opted into auto-compounding"),
+            }),
+        }
+    };
+    if env.block.time.seconds() < last_compound + MIN_COMPOUND_INTERVAL {
+        return Err(ContractError::CustomError {
+            val: format!("Can only auto-compound every {} seconds", MIN_COMPOUND_INTERVAL),
+        });
+    }
+    AUTO_COMPOUND.save(deps.storage, user.clone(), &env.block.time.seconds())?;
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut minted_interest = Uint128::zero();
+    let fee_claims: Vec<Coin> = if user == env.contract.address {
+        //Claim the pool's rewards & take its held fees
+        let (msgs, accrued_interest) = compound_liquid_stake(deps.storage, env.clone(), config.clone())?;
+        messages.extend(msgs);
+        minted_interest = accrued_interest;
+        let fees = LIQUID_FEES.may_load(deps.storage)?.unwrap_or_default();
+        LIQUID_FEES.save(deps.storage, &vec![])?;
+
+        fees
+    } else {
+        let (claimables, accrued_interest) = get_user_claimables(deps.storage, env.clone(), user.clone())?;
+
+        //Restake TEMA incentives
+        if !accrued_interest.is_zero() {
+            add_staking_deposit(deps.storage, env.clone(), config.clone(), user.clone(), accrued_interest, None)?;
+
+            messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: get_osmosis_proxy(&config)?.to_string(),
+                msg: to_binary(&OsmoExecuteMsg::MintTokens {
+                    denom: config.clone().tema_denom,
+                    amount: accrued_interest,
+                    mint_to_address: env.contract.address.to_string(),
+                })?,
+                funds: vec![],
+            }));
+            minted_interest = accrued_interest;
+        }
+
+        claimables
+            .into_iter()
+            .map(asset_to_coin)
+            .collect::<StdResult<Vec<Coin>>>()?
+    };
+
+    let (sub_msgs, restaked_fees) = compound_fees(
+        deps.storage,
+        deps.querier,
+        env.clone(),
+        config.clone(),
+        dex_router,
+        user.clone(),
+        info.sender.clone(),
+        fee_claims,
+        minted_interest,
+    )?;
+
+    if messages.is_empty() && sub_msgs.is_empty() && restaked_fees.is_zero() {
+        return Err(ContractError::CustomError {
+            val: String::from("Nothing to compound"),
+        });
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_submessages(sub_msgs)
+        .add_attributes(vec![
+            attr("method", "auto_compound"),
+            attr("user", user),
+            attr("keeper", info.sender),
+            attr("tema_rewards", minted_interest),
+        ]))
+}
+
+/// Restake TEMA received from an auto-compound swap & tip the keeper.
+/// Failed swaps return the fee asset to the liquid pool's held fees or the user.
+pub fn handle_compound_reply(deps: DepsMut, env: Env, msg: Reply) -> StdResult<Response> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut compound = COMPOUND.load(deps.storage)?;
+    if compound.swaps.is_empty() {
+        return Err(StdError::GenericErr { msg: String::from("No auto-compound swaps pending") });
+    }
+    let swapped = compound.swaps.remove(0);
+
+    let current_balance = deps.querier
+        .query_balance(env.contract.address.clone(), config.clone().tema_denom)?
+        .amount;
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut attrs = vec![
+        attr("method", "handle_compound_reply"),
+        attr("user", compound.user.clone()),
+        attr("swapped", swapped.to_string()),
+    ];
+    let mut tip = Uint128::zero();
+    match msg.result.into_result() {
+        Ok(_result) => {
+            //Amount received from the swap
+            let received = current_balance.checked_sub(compound.prev_balance)?;
+
+            //Tip keeper, the liquid pool doesn't tip itself
+            if compound.keeper != env.contract.address {
+                tip = received * config.keeper_tip;
+            }
+            let restake_amount = received - tip;
+
+            if !restake_amount.is_zero() {
+                add_staking_deposit(deps.storage, env.clone(), config.clone(), compound.user.clone(), restake_amount, None)?;
+            }
+            if !tip.is_zero() {
+                messages.push(CosmosMsg::Bank(BankMsg::Send {
+                    to_address: compound.keeper.to_string(),
+                    amount: vec![coin(tip.u128(), config.clone().tema_denom)],
+                }));
+            }
+
+            attrs.push(attr("restaked", restake_amount));
+            attrs.push(attr("keeper_tip", tip));
+        },
+        Err(err) => {
+            if compound.user == env.contract.address {
+                LIQUID_FEES.update(deps.storage, |mut fees| -> StdResult<_> {
+                    match fees.iter_mut().find(|fee| fee.denom == swapped.denom) {
+                        Some(fee) => fee.amount += swapped.amount,
+                        None => fees.push(swapped.clone()),
+                    }
+                    Ok(fees)
+                })?;
+            } else {
+                messages.push(CosmosMsg::Bank(BankMsg::Send {
+                    to_address: compound.user.to_string(),
+                    amount: vec![swapped],
+                }));
+            }
+
+            attrs.push(attr("error", err));
+        }
+    }
+
+    //The tip is sent before the next swap
+    if compound.swaps.is_empty() {
+        COMPOUND.remove(deps.storage);
+    } else {
+        compound.prev_balance = current_balance - tip;
+        COMPOUND.save(deps.storage, &compound)?;
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(attrs))
+}
+
+/// Record which declared delegates voted on an ended proposal.
+/// Delegates below the participation requirements have their fluid delegations returned.
+fn report_participation(
+    deps: DepsMut,
+    info: MessageInfo,
+    proposal_id: u64,
+    voters: Vec<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    //Only the governance contract can report
+    if config.governance_contract.is_none() || info.sender != config.clone().governance_contract.unwrap() {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let requirements = match config.participation_requirements {
+        Some(requirements) => requirements,
+        None => return Ok(Response::new().add_attributes(vec![
+            attr("method", "report_participation"),
+            attr("proposal_id", proposal_id.to_string()),
+        ])),
+    };
+
+    let voters = voters
+        .into_iter()
+        .map(|voter| deps.api.addr_validate(&voter))
+        .collect::<StdResult<BTreeSet<Addr>>>()?;
+
+    let mut penalized_delegates: Vec<String> = vec![];
+    let mut undelegated_amount = Uint128::zero();
+
+    for delegate in DELEGATE_INFO.may_load(deps.storage)?.unwrap_or_default() {
+        let mut participation = DELEGATE_PARTICIPATION.may_load(deps.storage, delegate.delegate.clone())?.unwrap_or_default();
+        //Skip if this proposal was already reported
+        if participation.iter().any(|record| record.proposal_id == proposal_id) {
+            continue;
+        }
+
+        participation.push(ProposalParticipation {
+            proposal_id,
+            voted: voters.contains(&delegate.delegate),
+        });
+        //Only keep the window
+        if participation.len() as u64 > requirements.window {
+            participation.drain(..participation.len() - requirements.window as usize);
+        }
+        DELEGATE_PARTICIPATION.save(deps.storage, delegate.delegate.clone(), &participation)?;
+
+        //Return fluid delegations of penalized delegates
+        if is_below_requirements(&participation, &requirements) {
+            undelegated_amount += undelegate_fluid_delegations(deps.storage, delegate.delegate.clone())?;
+            penalized_delegates.push(delegate.delegate.to_string());
+        }
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "report_participation"),
+        attr("proposal_id", proposal_id.to_string()),
+        attr("penalized_delegates", format!("{:?}", penalized_delegates)),
+        attr("undelegated_amount", undelegated_amount),
+    ]))
+}
+
+/// Returns true if a delegate voted on less than the threshold of the most recent proposals.
+/// Delegates aren't judged until the window is full.
+pub fn is_below_requirements(participation: &[ProposalParticipation], requirements: &ParticipationRequirements) -> bool {
+    if requirements.window == 0 || (participation.len() as u64) < requirements.window {
+        return false;
+    }
+
+    Decimal::from_ratio(get_votes_in_window(participation, requirements.window), requirements.window) < requirements.threshold
+}
+
+/// Returns # of votes in the most recent window of proposals
+pub fn get_votes_in_window(participation: &[ProposalParticipation], window: u64) -> u64 {
+    participation
+        .iter()
+        .rev()
+        .take(window as usize)
+        .filter(|record| record.voted)
+        .count() as u64
+}
+
+/// Return a delegate's commission, 0 if they're below the participation requirements
+pub fn get_delegate_commission(storage: &dyn Storage, delegate: Addr, commission: Decimal) -> StdResult<Decimal> {
+    if let Some(requirements) = CONFIG.load(storage)?.participation_requirements {
+        let participation = DELEGATE_PARTICIPATION.may_load(storage, delegate)?.unwrap_or_default();
+        if is_below_requirements(&participation, &requirements) {
+            return Ok(Decimal::zero());
+        }
+    }
+
+    Ok(commission)
+}
+
+/// Remove a delegate's fluid delegations & return the amount undelegated.
+/// Rewards aren't settled beforehand bc penalized delegates earn no commission.
+fn undelegate_fluid_delegations(storage: &mut dyn Storage, delegate: Addr) -> StdResult<Uint128> {
+    let mut delegate_info = match DELEGATIONS.may_load(storage, delegate.clone())? {
+        Some(delegate_info) => delegate_info,
+        None => return Ok(Uint128::zero()),
+    };
+    let mut undelegated_amount = Uint128::zero();
+
+    //Remove fluid delegations from each delegator's delegated_to
+    for delegation in delegate_info.delegated.clone().into_iter().filter(|delegation| delegation.fluidity) {
+        if let Some(mut delegator_info) = DELEGATIONS.may_load(storage, delegation.delegate.clone())? {
+            if let Some((index, _)) = delegator_info.delegated_to.iter().enumerate().find(|(_i, delegation)| delegation.delegate == delegate) {
+                delegator_info.delegated_to[index].amount = delegator_info.delegated_to[index].amount.saturating_sub(delegation.amount);
+                if delegator_info.delegated_to[index].amount.is_zero() {
+                    delegator_info.delegated_to.remove(index);
+                }
+            }
+
+            //Remove if empty, save otherwise
+            if delegator_info.delegated.is_empty() && delegator_info.delegated_to.is_empty() {
+                DELEGATIONS.remove(storage, delegation.delegate.clone());
+            } else {
+                DELEGATIONS.save(storage, delegation.delegate.clone(), &delegator_info)?;
+            }
+        }
+        undelegated_amount += delegation.amount;
+    }
+
+    //Remove fluid delegations from the delegate's delegated
+    delegate_info.delegated.retain(|delegation| !delegation.fluidity);
+    if delegate_info.delegated.is_empty() && delegate_info.delegated_to.is_empty() {
+        DELEGATIONS.remove(storage, delegate);
+    } else {
+        DELEGATIONS.save(storage, delegate, &delegate_info)?;
+    }
+
+    Ok(undelegated_amount)
+}
+
 /// Update contract configuration
 fn update_config(
     deps: DepsMut,
@@ -317,6 +1140,13 @@ fn update_config(
     max_commission_rate: Option<Decimal>,
     keep_raw_cdt: Option<bool>,
     vesting_rev_multiplier: Option<Decimal>,
+    participation_requirements: Option<ParticipationRequirements>,
+    dex_router: Option<String>,
+    keeper_tip: Option<Decimal>,
+    instant_unstake_penalty: Option<InstantUnstakePenalty>,
+    stability_pool: Option<String>,
+    oracle_contract: Option<String>,
+    max_slippage: Option<Decimal>,
 ) -> Result<Response, ContractError> {
     let mut config = CONFIG.load(deps.storage)?;
 
@@ -368,6 +1198,44 @@ fn update_config(
     if let Some(tema_denom) = tema_denom {
         config.tema_denom = tema_denom.clone();
     };
+    if let Some(participation_requirements) = participation_requirements {
+        config.participation_requirements = Some(participation_requirements);
+    };
+    if let Some(keeper_tip) = keeper_tip {
+        //Bound the tip
+        if keeper_tip > Decimal::percent(MAX_KEEPER_TIP) {
+            return Err(ContractError::CustomError {
+                val: format!("Keeper tip can't be more than {}%", MAX_KEEPER_TIP),
+            });
+        }
+        config.keeper_tip = keeper_tip;
+    };
+    if let Some(dex_router) = dex_router {
+        config.dex_router = Some(deps.api.addr_validate(&dex_router)?);
+    };
+    if let Some(instant_unstake_penalty) = instant_unstake_penalty {
+        if instant_unstake_penalty.max_penalty > Decimal::one() {
+            return Err(ContractError::CustomError {
+                val: String::from("Instant unstake penalty can't be more than 100%"),
+            });
+        }
+        config.instant_unstake_penalty = Some(instant_unstake_penalty);
+    };
+    if let Some(stability_pool) = stability_pool {
+        config.stability_pool = Some(deps.api.addr_validate(&stability_pool)?);
+    };
+    if let Some(oracle_contract) = oracle_contract {
+        config.oracle_contract = Some(deps.api.addr_validate(&oracle_contract)?);
+    };
+    if let Some(max_slippage) = max_slippage {
+        //Bound the slippage
+        if max_slippage > Decimal::percent(MAX_SLIPPAGE) {
+            return Err(ContractError::CustomError {
+                val: format!("Max slippage can't be more than {}%", MAX_SLIPPAGE),
+            });
+        }
+        config.max_slippage = max_slippage;
+    };
     if let Some(vesting_contract) = vesting_contract {
         config.vesting_contract = Some(deps.api.addr_validate(&vesting_contract)?);
     };
@@ -397,6 +1265,7 @@ pub fn stake(
     env: Env,
     info: MessageInfo,
     user: Option<String>,
+    lock_duration: Option<u64>,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
 
@@ -425,21 +1294,47 @@ pub fn stake(
     //Set valid address
     let valid_owner_addr = validate_position_owner(deps.api, info.clone(), user)?;
 
+    //Create lock
+    let lock = match lock_duration {
+        Some(lock_duration) => {
+            if lock_duration == 0 || lock_duration > MAX_LOCK_DAYS {
+                return Err(ContractError::CustomError {
+                    val: format!("Lock duration must be between 1 and {} days", MAX_LOCK_DAYS),
+                });
+            }
+            //The vesting contract's revenue is already set by the vesting_rev_multiplier
+            if config.vesting_contract.is_some() && valid_owner_addr == config.clone().vesting_contract.unwrap() {
+                return Err(ContractError::Unauthorized {});
+            }
+
+            Some(StakeLock {
+                amount: valid_asset.amount,
+                start_time: env.block.time.seconds(),
+                end_time: env.block.time.seconds() + (lock_duration * SECONDS_PER_DAY),
+            })
+        },
+        None => None,
+    };
+
     add_staking_deposit(
         deps.storage, 
         env,
         config,
         valid_owner_addr.clone(),
-        valid_asset.amount
+        valid_asset.amount,
+        lock.clone(),
     )?;
 
     //Response build
     let response = Response::new();
-    let attrs = vec![
+    let mut attrs = vec![
         attr("method", "stake"),
         attr("staker", valid_owner_addr.to_string()),
         attr("amount", valid_asset.amount.to_string()),
     ];
+    if let Some(lock) = lock {
+        attrs.push(attr("lock_end_time", lock.end_time.to_string()));
+    }
 
     Ok(response.add_attributes(attrs))
 }
@@ -451,7 +1346,33 @@ fn add_staking_deposit(
     config: Config,
     staker: Addr,
     amount: Uint128,
+    lock: Option<StakeLock>,
 ) -> StdResult<()>{
+    //Weigh the staker's index snapshot so the new stake only earns fees from now on
+    let staked_amount: Uint128 = STAKED.may_load(storage, staker.clone())?
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|deposit| deposit.unstake_start_time.is_none())
+        .map(|deposit| deposit.amount)
+        .sum();
+    //Settle the lock boost's fees before the staker's snapshot or boost changes
+    if lock.is_some() || LOCK_BOOSTS.has(storage, staker.clone()) {
+        settle_lock_boost(storage, staker.clone())?;
+    }
+    weigh_staker_indices(storage, staker.clone(), staked_amount, amount)?;
+
+    //Add lock & its fee boost
+    if let Some(lock) = lock {
+        let new_lock_boost = get_lock_fee_boost(&lock, env.block.time.seconds());
+        LOCKS.update(storage, staker.clone(), |locks| -> StdResult<_> {
+            let mut locks = locks.unwrap_or_default();
+            locks.push(lock);
+            Ok(locks)
+        })?;
+        LOCK_BOOSTS.update(storage, staker.clone(), |lock_boost| -> StdResult<_> { Ok(lock_boost.unwrap_or_default() + new_lock_boost) })?;
+        TOTAL_LOCK_BOOST.update(storage, |total| -> StdResult<_> { Ok(total + new_lock_boost) })?;
+    }
+
     //Add new deposit to staker's list of StakeDeposits
     STAKED.update(storage, staker.clone(), |current_deposits| -> StdResult<_> {
         match current_deposits {
@@ -492,9 +1413,235 @@ fn add_staking_deposit(
     }
     STAKING_TOTALS.save(storage, &totals)?;    
 
+    //Checkpoint voting stake
+    checkpoint_stake(storage, env, config, staker)?;
+
+    Ok(())
+}
+
+/// Checkpoint a staker's voting power (deposits that aren't unstaking plus the lock voting boost) & the totals at the current block time.
+/// The vesting contract isn't checkpointed & the liquid pool's power is zeroed, neither votes with its stake.
+fn checkpoint_stake(
+    storage: &mut dyn Storage,
+    env: Env,
+    config: Config,
+    staker: Addr,
+) -> StdResult<()>{
+    if Some(staker.clone()) == config.vesting_contract {
+        return Ok(());
+    }
+    let time = env.block.time.seconds();
+
+    let power = if staker == env.contract.address {
+        Uint128::zero()
+    } else {
+        let stake: Uint128 = STAKED.may_load(storage, staker.clone())?
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|deposit| deposit.unstake_start_time.is_none())
+            .map(|deposit| deposit.amount)
+            .sum();
+        let lock_boost: Uint128 = LOCKS.may_load(storage, staker.clone())?
+            .unwrap_or_default()
+            .iter()
+            .map(|lock| get_lock_voting_boost(lock, time))
+            .sum();
+        stake + lock_boost
+    };
+
+    let prev_power = STAKE_CHECKPOINTS.may_load(storage, staker.clone())?.unwrap_or_default();
+    if power == prev_power {
+        return Ok(());
+    }
+    STAKE_CHECKPOINTS.save(storage, staker, &power, time)?;
+
+    //Update totals by the difference
+    let total = TOTAL_STAKE_CHECKPOINTS.may_load(storage)?.unwrap_or_default();
+    TOTAL_STAKE_CHECKPOINTS.save(storage, &(total + power).checked_sub(prev_power)?, time)?;
+
+    let quadratic_total = TOTAL_QUADRATIC_STAKE_CHECKPOINTS.may_load(storage)?.unwrap_or_default();
+    TOTAL_QUADRATIC_STAKE_CHECKPOINTS.save(
+        storage,
+        &(quadratic_total + get_quadratic_power(power)).checked_sub(get_quadratic_power(prev_power))?,
+        time,
+    )?;
+
     Ok(())
 }
 
+/// Return the quadratic voting power of an amount of voting power
+pub fn get_quadratic_power(power: Uint128) -> Uint128 {
+    Decimal::from_ratio(power, Uint128::one()).sqrt().to_uint_ceil()
+}
+
+/// Average a staker's index snapshot with the current indices, weighted by their staked & new amounts.
+/// Pending fee rewards stay intact without being settled.
+fn weigh_staker_indices(
+    storage: &mut dyn Storage,
+    staker: Addr,
+    staked_amount: Uint128,
+    new_amount: Uint128,
+) -> StdResult<()>{
+    let total = staked_amount + new_amount;
+    if total.is_zero() {
+        return Ok(());
+    }
+
+    let reward_indices = REWARD_INDICES.load(storage)?;
+    let snapshot = STAKER_INDICES.may_load(storage, staker.clone())?.unwrap_or_default();
+
+    let mut new_snapshot: Vec<LiqAsset> = vec![];
+    for index in reward_indices {
+        let staked_portion = decimal_multiplication(get_reward_index(&snapshot, &index.info), Decimal::from_ratio(staked_amount, total))?;
+        let new_portion = decimal_multiplication(index.amount, Decimal::from_ratio(new_amount, total))?;
+
+        new_snapshot.push(LiqAsset {
+            info: index.info,
+            amount: staked_portion + new_portion,
+        });
+    }
+    STAKER_INDICES.save(storage, staker, &new_snapshot)
+}
+
+/// Return the index of an asset, 0 if it isn't in the list
+pub fn get_reward_index(indices: &[LiqAsset], info: &AssetInfo) -> Decimal {
+    indices
+        .iter()
+        .find(|index| index.info == *info)
+        .map(|index| index.amount)
+        .unwrap_or_default()
+}
+
+/// Return rewards per staked TEMA since the snapshot
+pub fn get_reward_deltas(reward_indices: &[LiqAsset], snapshot: &[LiqAsset]) -> Vec<LiqAsset> {
+    reward_indices
+        .iter()
+        .map(|index| {
+            let staker_index = get_reward_index(snapshot, &index.info);
+
+            LiqAsset {
+                info: index.info.clone(),
+                amount: if index.amount > staker_index { index.amount - staker_index } else { Decimal::zero() },
+            }
+        })
+        .filter(|delta| !delta.amount.is_zero())
+        .collect::<Vec<LiqAsset>>()
+}
+
+/// Return a lock's extra fee weight at a given time
+fn get_lock_fee_boost(lock: &StakeLock, time: u64) -> Uint128 {
+    (get_lock_multiplier(lock, time, Decimal::percent(LOCK_FEE_BOOST)) - Decimal::one()) * lock.amount
+}
+
+/// Return a lock's extra voting power at a given time
+fn get_lock_voting_boost(lock: &StakeLock, time: u64) -> Uint128 {
+    (get_lock_multiplier(lock, time, Decimal::percent(LOCK_VOTING_BOOST)) - Decimal::one()) * lock.amount
+}
+
+/// Return the amount of a staker's stake that is still locked
+pub fn get_locked_amount(storage: &dyn Storage, staker: Addr, time: u64) -> StdResult<Uint128> {
+    Ok(LOCKS.may_load(storage, staker)?
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|lock| lock.end_time > time)
+        .map(|lock| lock.amount)
+        .sum())
+}
+
+/// Return a staker's unclaimed lock boost fees, settled & accrued since the boost's snapshot
+pub fn get_lock_boost_claims(storage: &dyn Storage, staker: Addr, reward_indices: &[LiqAsset]) -> StdResult<Vec<Asset>> {
+    let mut claims = LOCK_BOOST_CLAIMS.may_load(storage, staker.clone())?.unwrap_or_default();
+    //Boosts from before boosts had their own snapshot share the staker's
+    let snapshot = match LOCK_BOOST_INDICES.may_load(storage, staker.clone())? {
+        Some(snapshot) => snapshot,
+        None => STAKER_INDICES.may_load(storage, staker.clone())?.unwrap_or_default(),
+    };
+    add_lock_boost_claimables(
+        LOCK_BOOSTS.may_load(storage, staker)?.unwrap_or_default(),
+        get_reward_deltas(reward_indices, &snapshot),
+        &mut claims,
+    );
+    claims.retain(|claim| !claim.amount.is_zero());
+
+    Ok(claims)
+}
+
+/// Move a staker's accrued lock boost fees to LOCK_BOOST_CLAIMS & snapshot the boost's indices.
+/// Must be called before the staker's boost or index snapshot changes.
+fn settle_lock_boost(storage: &mut dyn Storage, staker: Addr) -> StdResult<()> {
+    let reward_indices = REWARD_INDICES.load(storage)?;
+
+    let claims = get_lock_boost_claims(storage, staker.clone(), &reward_indices)?;
+    if !claims.is_empty() {
+        LOCK_BOOST_CLAIMS.save(storage, staker.clone(), &claims)?;
+    }
+    LOCK_BOOST_INDICES.save(storage, staker, &reward_indices)
+}
+
+/// Refresh the lock boosts of a list of stakers
+fn refresh_locks(
+    deps: DepsMut,
+    env: Env,
+    stakers: Vec<String>,
+) -> Result<Response, ContractError> {
+    let mut refreshed: Vec<String> = vec![];
+
+    let config = CONFIG.load(deps.storage)?;
+
+    for staker in stakers {
+        let staker = deps.api.addr_validate(&staker)?;
+
+        if LOCKS.has(deps.storage, staker.clone()) {
+            refresh_lock_boost(deps.storage, env.clone(), config.clone(), staker.clone())?;
+            refreshed.push(staker.to_string());
+        }
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "refresh_locks"),
+        attr("refreshed_stakers", format!("{:?}", refreshed)),
+        attr("total_lock_boost", TOTAL_LOCK_BOOST.may_load(deps.storage)?.unwrap_or_default()),
+    ]))
+}
+
+/// Remove a staker's expired locks, reset their fee boost to the current multipliers & checkpoint their voting boost.
+/// The boost's fees are settled first so they aren't lost.
+fn refresh_lock_boost(storage: &mut dyn Storage, env: Env, config: Config, staker: Addr) -> StdResult<()> {
+    let time = env.block.time.seconds();
+    let locks = match LOCKS.may_load(storage, staker.clone())? {
+        Some(locks) => locks,
+        None => return Ok(()),
+    };
+    settle_lock_boost(storage, staker.clone())?;
+    let old_boost = LOCK_BOOSTS.may_load(storage, staker.clone())?.unwrap_or_default();
+
+    //Remove expired locks
+    let locks = locks
+        .into_iter()
+        .filter(|lock| lock.end_time > time)
+        .collect::<Vec<StakeLock>>();
+    let new_boost: Uint128 = locks
+        .iter()
+        .map(|lock| get_lock_fee_boost(lock, time))
+        .sum();
+
+    if locks.is_empty() {
+        LOCKS.remove(storage, staker.clone());
+        LOCK_BOOSTS.remove(storage, staker.clone());
+        LOCK_BOOST_INDICES.remove(storage, staker);
+    } else {
+        LOCKS.save(storage, staker.clone(), &locks)?;
+        LOCK_BOOSTS.save(storage, staker, &new_boost)?;
+    }
+
+    //Update total
+    let total_boost = TOTAL_LOCK_BOOST.may_load(storage)?.unwrap_or_default();
+    TOTAL_LOCK_BOOST.save(storage, &(total_boost.saturating_sub(old_boost) + new_boost))?;
+
+    //Checkpoint the decayed voting boost
+    checkpoint_stake(storage, env, config, staker)
+}
+
 /// First call is an unstake
 /// 2nd call after unstake period is a withdrawal
 pub fn unstake(
@@ -527,8 +1674,19 @@ pub fn unstake(
         total_staker_deposits
     };
 
+    //Locked stake can't be unstaked
+    let unlocked_stake = match total_stake.checked_sub(get_locked_amount(deps.storage, info.sender.clone(), env.block.time.seconds())?){
+        Ok(unlocked) => unlocked,
+        Err(_) => Uint128::zero(),
+    };
+
     //Enforce valid withdraw amount
-    let mut withdraw_amount = tema_withdraw_amount.unwrap_or(total_stake).min(total_stake);
+    let mut withdraw_amount = tema_withdraw_amount.unwrap_or(unlocked_stake).min(total_stake);
+    if withdraw_amount > unlocked_stake {
+        return Err(ContractError::CustomError {
+            val: format!("Only {} TEMA is unlocked", unlocked_stake),
+        });
+    }
 
     //info.sender is user
     let (claimables, accrued_interest, withdrawable_amount) = withdraw_from_state(
@@ -560,98 +1718,25 @@ pub fn unstake(
     //if withdrawable_amount is greater than total stake or there is a stake discrepancy, error
     if withdrawable_amount > total_stake || withdrawable_amount + new_total_staked != total_stake {
         return Err(ContractError::CustomError {
-            val: format!("Invalid withdrawable amount: {}", withdrawable_amount),
-        });
-    }
-
-    //Initialize variables
-    let mut native_claims = vec![];
-
-    //If user can withdraw, add to native_claims & update delegations
-    if !withdrawable_amount.is_zero() {
-        //Push to native claims list
-        native_claims.push(asset_to_coin(Asset {
-            info: AssetInfo::NativeToken {
-                denom: config.clone().tema_denom,
-            },
-            amount: withdrawable_amount,
-        })?);     
-
-        //Get user's delegation info
-        if let Ok(mut staker_delegation_info) = DELEGATIONS.load(deps.storage, info.sender.clone()){
-            //Save indices to remove 
-            let mut remove_indices = vec![];
-            //Get user's delegated stake
-            let total_delegations: Uint128 = staker_delegation_info.clone()
-                .delegated_to
-                .into_iter()
-                .map(|delegation| delegation.amount)
-                .collect::<Vec<Uint128>>()
-                .into_iter()
-                .sum();
-
-            //If withdrawing more than is undelegated, undelegate the excess
-            let undelegated_stake = match total_stake.checked_sub(total_delegations){
-                Ok(undelegated) => undelegated,
-                Err(_) => Uint128::zero(),
-            };
-            if withdrawable_amount > undelegated_stake {
-                let mut undelegate_amount = match withdrawable_amount.checked_sub(undelegated_stake){
-                    Ok(undelegated) => undelegated,
-                    Err(_) => return Err(ContractError::CustomError {
-                        val: format!("Undelegated stake ({}) is some how greater than withdrawable amount ({})", withdrawable_amount, undelegated_stake),
-                    }),
-                };
-                for (i, delegation) in staker_delegation_info.clone().delegated_to.into_iter().enumerate() {
-                    
-                    //If undelegate amount is greater than the current delegation, undelegate the whole delegation & update undelegate amount
-                    if undelegate_amount >= delegation.amount && !undelegate_amount.is_zero() {
-                        undelegate_amount -= delegation.amount;
-                        
-                        //Remove staker delegation
-                        remove_indices.push(i);
-
-                        //Remove delegate delegation
-                        let mut delegate_delegation_info = DELEGATIONS.load(deps.storage, delegation.delegate.clone())?;
-                        for (i, delegate_delegation) in delegate_delegation_info.clone().delegated.into_iter().enumerate() {
-                            if delegate_delegation.delegate == info.sender.clone() {
-                                delegate_delegation_info.delegated.remove(i);
-                                break;
-                            }
-                        }
-                        DELEGATIONS.save(deps.storage, delegation.delegate.clone(), &delegate_delegation_info)?;
-                    } else if staker_delegation_info.delegated_to[i].amount > undelegate_amount && !undelegate_amount.is_zero(){
-                        //If undelegate amount is less than the current delegation, undelegate the undelegate amount & break
-                        staker_delegation_info.delegated_to[i].amount -= undelegate_amount;
-
-                        //Update delegate delegation
-                        let mut delegate_delegation_info = DELEGATIONS.load(deps.storage, delegation.delegate.clone())?;
-                        for (i, delegate_delegation) in delegate_delegation_info.clone().delegated.into_iter().enumerate() {
-                            if delegate_delegation.delegate == info.sender.clone() {
-                                delegate_delegation_info.delegated[i].amount = match delegate_delegation_info.delegated[i].amount.checked_sub(undelegate_amount){
-                                    Ok(diff) => diff,
-                                    Err(_) => {
-                                        undelegate_amount -= delegate_delegation_info.delegated[i].amount;
-                                        
-                                        Uint128::zero()
-                                    },
-                                };
-                                break;
-                            }
-                        }
-                        DELEGATIONS.save(deps.storage, delegation.delegate.clone(), &delegate_delegation_info)?;
-                        break;
-                    }
-                }
-                //Remove indices now that we've iterated through the delegations
-                for i in remove_indices.into_iter(){
-                    staker_delegation_info.delegated_to.remove(i);
-                }
+            val: format!("Invalid withdrawable amount: {}", withdrawable_amount),
+        });
+    }
 
-                //Save updated delegation info
-                DELEGATIONS.save(deps.storage, info.sender.clone(), &staker_delegation_info)?;
-            }
-        };
+    //Initialize variables
+    let mut native_claims = vec![];
+
+    //If user can withdraw, add to native_claims & update delegations
+    if !withdrawable_amount.is_zero() {
+        //Push to native claims list
+        native_claims.push(asset_to_coin(Asset {
+            info: AssetInfo::NativeToken {
+                denom: config.clone().tema_denom,
+            },
+            amount: withdrawable_amount,
+        })?);     
+
+        //Undelegate withdrawn stake
+        undelegate_withdrawn_stake(deps.storage, info.sender.clone(), total_stake, withdrawable_amount)?;
         
     }
 
@@ -716,6 +1801,359 @@ pub fn unstake(
     Ok(response)
 }
 
+/// Return the penalty to withdraw an unstaking deposit instantly.
+/// The penalty decays linearly over the unstaking period.
+pub fn get_instant_unstake_penalty(
+    deposit: &StakeDeposit,
+    max_penalty: Decimal,
+    unstaking_period: u64,
+    time: u64,
+) -> Uint128 {
+    let unstake_start_time = match deposit.unstake_start_time {
+        Some(unstake_start_time) => unstake_start_time,
+        None => return Uint128::zero(),
+    };
+    if unstaking_period == 0 {
+        return Uint128::zero();
+    }
+    let time_left = (unstake_start_time + unstaking_period).saturating_sub(time);
+
+    deposit.amount.multiply_ratio(time_left, unstaking_period) * max_penalty
+}
+
+/// Withdraw all unstaking deposits without waiting for the unstaking period.
+/// The penalty goes to the remaining stakers or the Stability Pool.
+fn instant_unstake(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let penalty_config = match config.clone().instant_unstake_penalty {
+        Some(penalty_config) => penalty_config,
+        None => return Err(ContractError::CustomError {
+            val: String::from("Instant unstaking is disabled"),
+        }),
+    };
+
+    //Restrict unstaking
+    can_this_addr_unstake(deps.querier, info.clone().sender, config.clone())?;
+
+    //Settle rewards before removing deposits
+    let (claimables, accrued_interest) = get_user_claimables(deps.storage, env.clone(), info.sender.clone())?;
+
+    let deposits: Vec<StakeDeposit> = match STAKED.load(deps.storage, info.sender.clone()){
+        Ok(deposits) => deposits,
+        Err(_) => return Err(ContractError::CustomError {
+            val: String::from("User has no stake"),
+        }),
+    };
+    let total_stake: Uint128 = deposits.iter().map(|deposit| deposit.amount).sum();
+
+    //Split unstaking deposits from the rest
+    let unstaking_period = config.unstaking_period * SECONDS_PER_DAY;
+    let mut withdraw_amount = Uint128::zero();
+    let mut penalty = Uint128::zero();
+    let mut remaining_deposits: Vec<StakeDeposit> = vec![];
+    for deposit in deposits {
+        if deposit.unstake_start_time.is_some() {
+            withdraw_amount += deposit.amount;
+            penalty += get_instant_unstake_penalty(&deposit, penalty_config.max_penalty, unstaking_period, env.block.time.seconds());
+        } else {
+            remaining_deposits.push(deposit);
+        }
+    }
+    if withdraw_amount.is_zero() {
+        return Err(ContractError::CustomError {
+            val: String::from("No unstaking deposits, use Unstake to start unstaking"),
+        });
+    }
+    STAKED.save(deps.storage, info.sender.clone(), &remaining_deposits)?;
+
+    //Undelegate withdrawn stake
+    undelegate_withdrawn_stake(deps.storage, info.sender.clone(), total_stake, withdraw_amount)?;
+
+    //Send penalty
+    let mut sub_msgs: Vec<SubMsg> = vec![];
+    if !penalty.is_zero() {
+        match penalty_config.recipient {
+            PenaltyRecipient::Stakers => {
+                add_to_reward_indices(deps.storage, deps.querier, config.clone(), vec![Asset {
+                    info: AssetInfo::NativeToken { denom: config.clone().tema_denom },
+                    amount: penalty,
+                }])?;
+            },
+            PenaltyRecipient::StabilityPool => {
+                if config.dex_router.is_none() {
+                    return Err(ContractError::CustomError {
+                        val: String::from("No router contract setup"),
+                    });
+                }
+                if config.stability_pool.is_none() {
+                    return Err(ContractError::CustomError {
+                        val: String::from("No stability pool setup"),
+                    });
+                }
+
+                //Batch penalties into one stream per period
+                let mut batch = PENALTY_BATCH.may_load(deps.storage)?.unwrap_or_default();
+                batch.amount += penalty;
+                PENALTY_BATCH.save(deps.storage, &batch)?;
+
+                sub_msgs.extend(stream_penalties(deps.storage, deps.querier, &env, &config)?);
+            },
+        }
+    }
+
+    //Create claimable msgs
+    let claims_msgs = create_rewards_msgs(
+        deps.storage,
+        deps.querier,
+        deps.api,
+        env,
+        config.clone(),
+        claimables,
+        accrued_interest,
+        info.clone().sender.to_string(),
+        vec![coin((withdraw_amount - penalty).u128(), config.tema_denom)],
+    )?;
+
+    Ok(Response::new()
+        .add_messages(claims_msgs)
+        .add_submessages(sub_msgs)
+        .add_attributes(vec![
+            attr("method", "instant_unstake"),
+            attr("staker", info.sender),
+            attr("unstake_amount", withdraw_amount - penalty),
+            attr("penalty", penalty),
+        ]))
+}
+
+/// Swap batched penalties to the credit asset to stream to Stability Pool depositors, at most once per PENALTY_STREAM_DURATION.
+/// Penalties stay batched until the Stability Pool accepts the credit asset as a stream denom & the swap meets its minimum.
+fn stream_penalties(
+    storage: &mut dyn Storage,
+    querier: QuerierWrapper,
+    env: &Env,
+    config: &Config,
+) -> StdResult<Vec<SubMsg>> {
+    let mut batch = PENALTY_BATCH.may_load(storage)?.unwrap_or_default();
+    if batch.amount.is_zero() || env.block.time.seconds() < batch.last_stream_time + PENALTY_STREAM_DURATION {
+        return Ok(vec![]);
+    }
+    let (dex_router, stability_pool) = match (config.clone().dex_router, config.clone().stability_pool) {
+        (Some(dex_router), Some(stability_pool)) => (dex_router, stability_pool),
+        _ => return Ok(vec![]),
+    };
+    if config.oracle_contract.is_none() {
+        return Ok(vec![]);
+    }
+
+    let basket: Basket = query_basket(querier, config.clone().positions_contract.unwrap_or_else(|| Addr::unchecked("")).to_string())?;
+    let credit_denom = basket.credit_asset.info.to_string();
+    let minimum_receive = get_min_receive(querier, config, &coin(batch.amount.u128(), config.clone().tema_denom), credit_denom.clone())?;
+
+    //Stream denoms must be whitelisted by the Stability Pool & meet its minimum
+    let sp_config: SP_Config = querier.query_wasm_smart(stability_pool.to_string(), &SP_QueryMsg::Config {})?;
+    match sp_config.stream_denoms.iter().find(|stream_denom| stream_denom.denom == credit_denom) {
+        Some(stream_denom) if minimum_receive >= stream_denom.amount => {},
+        _ => return Ok(vec![]),
+    }
+
+    let prev_balance = querier
+        .query_balance(env.contract.address.clone(), credit_denom.clone())?
+        .amount;
+    PENALTY.save(storage, &PenaltyPropagation {
+        credit_denom,
+        prev_balance,
+        tema_amount: batch.amount,
+        streamed: Uint128::zero(),
+    })?;
+
+    let swap_msg = router_native_to_native(
+        dex_router.to_string(),
+        AssetInfo::NativeToken { denom: config.clone().tema_denom },
+        basket.credit_asset.info,
+        None,
+        batch.amount.u128(),
+        Some(minimum_receive),
+    )?;
+
+    batch.amount = Uint128::zero();
+    batch.last_stream_time = env.block.time.seconds();
+    PENALTY_BATCH.save(storage, &batch)?;
+
+    Ok(vec![SubMsg::reply_always(swap_msg, PENALTY_REPLY_ID)])
+}
+
+/// Stream the credit asset received from a penalty swap to Stability Pool depositors.
+/// Failed swaps return the TEMA to the batch.
+pub fn handle_penalty_reply(deps: DepsMut, env: Env, msg: Reply) -> StdResult<Response> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut penalty = PENALTY.load(deps.storage)?;
+
+    match msg.result.into_result() {
+        Ok(_result) => {
+            //Amount received from the swap
+            let current_balance = deps.querier
+                .query_balance(env.contract.address.clone(), penalty.credit_denom.clone())?
+                .amount;
+            let received = current_balance.checked_sub(penalty.prev_balance)?;
+
+            let mut sub_msgs: Vec<SubMsg> = vec![];
+            if !received.is_zero() {
+                penalty.streamed = received;
+                PENALTY.save(deps.storage, &penalty)?;
+
+                sub_msgs.push(SubMsg::reply_always(
+                    CosmosMsg::Wasm(WasmMsg::Execute {
+                        contract_addr: config.stability_pool.unwrap_or_else(|| Addr::unchecked("")).to_string(),
+                        msg: to_binary(&SP_ExecuteMsg::AddIncentiveStream {
+                            start_time: env.block.time.seconds(),
+                            end_time: env.block.time.seconds() + PENALTY_STREAM_DURATION,
+                        })?,
+                        funds: vec![coin(received.u128(), penalty.credit_denom)],
+                    }),
+                    PENALTY_STREAM_REPLY_ID,
+                ));
+            } else {
+                PENALTY.remove(deps.storage);
+            }
+
+            Ok(Response::new()
+                .add_submessages(sub_msgs)
+                .add_attributes(vec![
+                    attr("method", "handle_penalty_reply"),
+                    attr("streamed_to_stability_pool", received),
+                ]))
+        },
+        Err(err) => {
+            PENALTY_BATCH.update(deps.storage, |mut batch| -> StdResult<_> {
+                batch.amount += penalty.tema_amount;
+                Ok(batch)
+            })?;
+            PENALTY.remove(deps.storage);
+
+            Ok(Response::new().add_attributes(vec![
+                attr("method", "handle_penalty_reply"),
+                attr("error", err),
+            ]))
+        }
+    }
+}
+
+/// Distribute penalties to stakers if the Stability Pool rejects their stream
+pub fn handle_penalty_stream_reply(deps: DepsMut, msg: Reply) -> StdResult<Response> {
+    let config = CONFIG.load(deps.storage)?;
+    let penalty = PENALTY.load(deps.storage)?;
+    PENALTY.remove(deps.storage);
+
+    match msg.result.into_result() {
+        Ok(_result) => Ok(Response::new().add_attributes(vec![
+            attr("method", "handle_penalty_stream_reply"),
+            attr("streamed", penalty.streamed),
+        ])),
+        Err(err) => {
+            add_to_reward_indices(deps.storage, deps.querier, config, vec![Asset {
+                info: AssetInfo::NativeToken { denom: penalty.credit_denom },
+                amount: penalty.streamed,
+            }])?;
+
+            Ok(Response::new().add_attributes(vec![
+                attr("method", "handle_penalty_stream_reply"),
+                attr("distributed_to_stakers", penalty.streamed),
+                attr("error", err),
+            ]))
+        }
+    }
+}
+
+/// Undelegate the part of a withdrawal that isn't covered by the staker's undelegated stake
+fn undelegate_withdrawn_stake(
+    storage: &mut dyn Storage,
+    staker: Addr,
+    total_stake: Uint128,
+    withdrawable_amount: Uint128,
+) -> Result<(), ContractError> {
+    //Get user's delegation info
+    if let Ok(mut staker_delegation_info) = DELEGATIONS.load(storage, staker.clone()){
+        //Save indices to remove 
+        let mut remove_indices = vec![];
+        //Get user's delegated stake
+        let total_delegations: Uint128 = staker_delegation_info.clone()
+            .delegated_to
+            .into_iter()
+            .map(|delegation| delegation.amount)
+            .collect::<Vec<Uint128>>()
+            .into_iter()
+            .sum();
+
+        //If withdrawing more than is undelegated, undelegate the excess
+        let undelegated_stake = match total_stake.checked_sub(total_delegations){
+            Ok(undelegated) => undelegated,
+            Err(_) => Uint128::zero(),
+        };
+        if withdrawable_amount > undelegated_stake {
+            let mut undelegate_amount = match withdrawable_amount.checked_sub(undelegated_stake){
+                Ok(undelegated) => undelegated,
+                Err(_) => return Err(ContractError::CustomError {
+                    val: format!("Undelegated stake ({}) is some how greater than withdrawable amount ({})", withdrawable_amount, undelegated_stake),
+                }),
+            };
+            for (i, delegation) in staker_delegation_info.clone().delegated_to.into_iter().enumerate() {
+                
+                //If undelegate amount is greater than the current delegation, undelegate the whole delegation & update undelegate amount
+                if undelegate_amount >= delegation.amount && !undelegate_amount.is_zero() {
+                    undelegate_amount -= delegation.amount;
+                    
+                    //Remove staker delegation
+                    remove_indices.push(i);
+
+                    //Remove delegate delegation
+                    let mut delegate_delegation_info = DELEGATIONS.load(storage, delegation.delegate.clone())?;
+                    for (i, delegate_delegation) in delegate_delegation_info.clone().delegated.into_iter().enumerate() {
+                        if delegate_delegation.delegate == staker.clone() {
+                            delegate_delegation_info.delegated.remove(i);
+                            break;
+                        }
+                    }
+                    DELEGATIONS.save(storage, delegation.delegate.clone(), &delegate_delegation_info)?;
+                } else if staker_delegation_info.delegated_to[i].amount > undelegate_amount && !undelegate_amount.is_zero(){
+                    //If undelegate amount is less than the current delegation, undelegate the undelegate amount & break
+                    staker_delegation_info.delegated_to[i].amount -= undelegate_amount;
+
+                    //Update delegate delegation
+                    let mut delegate_delegation_info = DELEGATIONS.load(storage, delegation.delegate.clone())?;
+                    for (i, delegate_delegation) in delegate_delegation_info.clone().delegated.into_iter().enumerate() {
+                        if delegate_delegation.delegate == staker.clone() {
+                            delegate_delegation_info.delegated[i].amount = match delegate_delegation_info.delegated[i].amount.checked_sub(undelegate_amount){
+                                Ok(diff) => diff,
+                                Err(_) => {
+                                    undelegate_amount -= delegate_delegation_info.delegated[i].amount;
+                                    
+                                    Uint128::zero()
+                                },
+                            };
+                            break;
+                        }
+                    }
+                    DELEGATIONS.save(storage, delegation.delegate.clone(), &delegate_delegation_info)?;
+                    break;
+                }
+            }
+            //Remove indices now that we've iterated through the delegations
+            for i in remove_indices.into_iter(){
+                staker_delegation_info.delegated_to.remove(i);
+            }
+
+            //Save updated delegation info
+            DELEGATIONS.save(storage, staker.clone(), &staker_delegation_info)?;
+        }
+    }
+
+    Ok(())
+}
+
 /// (Un)Delegate TEMA to a Governator
 /// If tema_amount is None, then act on the user's total stake
 /// Only edits delegations for the user's stake, not their fluid delegated stake
@@ -1265,6 +2703,9 @@ fn restake(
     totals.stakers += initial_restake - restake_amount;
     STAKING_TOTALS.save(deps.storage, &totals)?;
 
+    //Checkpoint voting stake
+    checkpoint_stake(deps.storage, env.clone(), config.clone(), info.sender.clone())?;
+
     //Create rewards msgs
     let rewards_msgs = create_rewards_msgs(
         deps.storage,
@@ -1346,6 +2787,7 @@ pub fn claim_rewards(
                     contract_addr: env.contract.address.to_string(),
                     msg: to_binary(&ExecuteMsg::Stake {
                         user: Some(info.sender.to_string()),
+                        lock_duration: None,
                     })?,
                     funds: vec![coin(accrued_interest.u128(), config.tema_denom)],
                 });
@@ -1397,7 +2839,7 @@ pub fn claim_rewards(
 fn deposit_fee(
     deps: DepsMut,
     info: MessageInfo,
-    env: Env,
+    _env: Env,
     fee_assets: Vec<Asset>,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
@@ -1471,54 +2913,68 @@ fn deposit_fee(
             CDT_assets.clone()
         };
     
-        //Load Fee Events
-        let mut fee_events = FEE_EVENTS.load(deps.storage)?;
+        //Add fees to the reward indices
+        add_to_reward_indices(deps.storage, deps.querier, config.clone(), fee_assets)?;
+    }
+    
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        attr("method", "deposit_fee"),
+        attr("fee_assets", format!("{:?}", string_fee_assets)),
+    ]))
+}
 
-        //Load Total staked
-        let mut totals = STAKING_TOTALS.load(deps.storage)?;
+/// Distribute fee assets to stakers by adding them to the reward indices
+fn add_to_reward_indices(
+    storage: &mut dyn Storage,
+    querier: QuerierWrapper,
+    config: Config,
+    fee_assets: Vec<Asset>,
+) -> StdResult<()> {
+    //Load reward indices
+    let mut reward_indices = REWARD_INDICES.load(storage)?;
 
-        //Update vesting total
-        if let Some(vesting_contract) = config.clone().vesting_contract {        
-            let vesting_total = get_total_vesting(deps.querier, vesting_contract.to_string())?;
+    //Load Total staked
+    let mut totals = STAKING_TOTALS.load(storage)?;
 
-            totals.vesting_contract = vesting_total;
-            STAKING_TOTALS.save(deps.storage, &totals)?;
-            
-            //Transform total with vesting rev multiplier
-            totals.vesting_contract = decimal_multiplication(
-                Decimal::from_ratio(vesting_total, Uint128::one()),
-                config.vesting_rev_multiplier)?
-            .to_uint_floor();
-        }
+    //Update vesting total
+    if let Some(vesting_contract) = config.clone().vesting_contract {        
+        let vesting_total = get_total_vesting(querier, vesting_contract.to_string())?;
 
-        //Set total
-        let mut total: Uint128 = totals.vesting_contract + totals.stakers;
-        if total.is_zero() {
-            total = Uint128::new(1u128)
-        }
-        let decimal_total = Decimal::from_ratio(total, Uint128::new(1u128));
+        totals.vesting_contract = vesting_total;
+        STAKING_TOTALS.save(storage, &totals)?;
         
-        //Add new Fee Event
-        for asset in fee_assets.clone() {        
-            let amount = Decimal::from_ratio(asset.amount, Uint128::new(1u128));
-
-            fee_events.push(FeeEvent {
-                time_of_event: env.block.time.seconds(),
-                fee: LiqAsset {
-                    //Amount = Amount per Staked TEMA
-                    info: asset.info,
-                    amount: decimal_division(amount, decimal_total)?,
-                },
-            });
-        }
+        //Transform total with vesting rev multiplier
+        totals.vesting_contract = decimal_multiplication(
+            Decimal::from_ratio(vesting_total, Uint128::one()),
+            config.vesting_rev_multiplier)?
+        .to_uint_floor();
+    }
 
-        FEE_EVENTS.save(deps.storage, &fee_events)?;
+    //Set total, locked stake is weighted by its fee boost
+    let mut total: Uint128 = totals.vesting_contract + totals.stakers + TOTAL_LOCK_BOOST.may_load(storage)?.unwrap_or_default();
+    if total.is_zero() {
+        total = Uint128::new(1u128)
     }
+    let decimal_total = Decimal::from_ratio(total, Uint128::new(1u128));
     
-    Ok(Response::new().add_messages(messages).add_attributes(vec![
-        attr("method", "deposit_fee"),
-        attr("fee_assets", format!("{:?}", string_fee_assets)),
-    ]))
+    //Add fees to the reward indices
+    for asset in fee_assets.clone() {        
+        let amount = Decimal::from_ratio(asset.amount, Uint128::new(1u128));
+        //Amount per Staked TEMA
+        let amount_per_stake = decimal_division(amount, decimal_total)?;
+
+        match reward_indices.iter_mut().find(|index| index.info == asset.info) {
+            Some(index) => index.amount += amount_per_stake,
+            None => reward_indices.push(LiqAsset {
+                info: asset.info,
+                amount: amount_per_stake,
+            }),
+        }
+    }
+
+    REWARD_INDICES.save(storage, &reward_indices)?;
+
+    Ok(())
 }
 
 /// Create rewards msgs from claimables and accrued interest
@@ -1569,7 +3025,7 @@ fn create_rewards_msgs(
     //Add accrued interest as a staking deposit && mint the amount to the contract
     if !accrued_interest.is_zero(){
         //Add accrued interest as a staking deposit
-        add_staking_deposit(storage, env.clone(), config.clone(), staker, accrued_interest)?;
+        add_staking_deposit(storage, env.clone(), config.clone(), staker, accrued_interest, None)?;
 
         //mint to contract for accounting purposes
         let msg = CosmosMsg::Wasm(WasmMsg::Execute {
@@ -1593,6 +3049,16 @@ fn create_rewards_msgs(
             .collect::<Vec<Coin>>();
         //Sort alphabetically
         native_claims.sort_by(|a, b| a.denom.cmp(&b.denom));
+        //Merge coins of the same denom, i.e. withdrawn TEMA & TEMA fee revenue
+        native_claims = native_claims
+            .into_iter()
+            .fold(vec![], |mut merged: Vec<Coin>, coin| {
+                match merged.last_mut() {
+                    Some(last) if last.denom == coin.denom => last.amount += coin.amount,
+                    _ => merged.push(coin),
+                }
+                merged
+            });
         //Remove assets not owned by the contract
         let contract_balances = querier.query_all_balances(env.contract.address.clone())?;
         native_claims = native_claims
@@ -1625,7 +3091,7 @@ pub fn add_deposit_claimables(
     config: Config,
     incentive_schedule: StakeDistributionLog,
     env: Env,
-    fee_events: Vec<FeeEvent>,
+    reward_deltas: Vec<LiqAsset>,
     deposit: StakeDeposit,
     delegated_to: Vec<Delegation>,
     delegated: Vec<Delegation>,
@@ -1640,7 +3106,7 @@ pub fn add_deposit_claimables(
         config.clone(),
         incentive_schedule.clone(),
         env.clone(),
-        fee_events.clone(),
+        reward_deltas.clone(),
         deposit.clone(),
         delegated.clone(),
         delegated_to.clone(),
@@ -1665,6 +3131,26 @@ pub fn add_deposit_claimables(
 
     Ok(())
 }
+/// Add fee claims for a staker's lock boost to their list of claims
+pub fn add_lock_boost_claimables(
+    lock_boost: Uint128,
+    reward_deltas: Vec<LiqAsset>,
+    claimables: &mut Vec<Asset>,
+) {
+    if lock_boost.is_zero() {
+        return;
+    }
+
+    for delta in reward_deltas {
+        let amount = delta.amount * lock_boost;
+
+        match claimables.iter_mut().find(|asset| asset.info == delta.info) {
+            Some(asset) => asset.amount += amount,
+            None => claimables.push(Asset { info: delta.info, amount }),
+        }
+    }
+}
+
 /// Can't Unstake if...
 /// 1. There is an active proposal by the address
 /// 2. The address has voted for a proposal that has passed but not yet executed
@@ -1689,14 +3175,14 @@ pub fn can_this_addr_unstake(
     //Can't unstake if the user has voted for a proposal that is Active or has passed but not yet executed
     //Get list of proposals that have passed & have executables or are active
     for proposal in proposal_list.proposal_list {
-        if (proposal.status == ProposalStatus::Passed && proposal.messages.is_some()) || proposal.status == ProposalStatus::Active{
+        if ((proposal.status == ProposalStatus::Passed || proposal.status == ProposalStatus::Queued) && proposal.messages.is_some()) || proposal.status == ProposalStatus::Active{
             //Get list of voters for this proposal
-            let _voters: Vec<Addr> = match querier.query_wasm_smart::<Vec<Addr>>(
+            let _voters: Vec<ProposalVoterResponse> = match querier.query_wasm_smart::<Vec<ProposalVoterResponse>>(
                 config.clone().governance_contract.unwrap().to_string(), 
                 &Gov_QueryMsg::ProposalVoters { 
                     proposal_id: proposal.proposal_id.into(), 
                     vote_option: membrane::governance::ProposalVoteOption::For, 
-                    start: None, 
+                    start_after: None, 
                     limit: None,
                     specific_user: Some(user.to_string())
                 }
@@ -1833,13 +3319,16 @@ fn withdraw_from_state(
     //Before we save, claim rewards for the staker
     let (claimables, accrued_interest) = get_user_claimables(
         storage, 
-        env, 
+        env.clone(), 
         staker.clone(),
     )?;
 
     //Save new deposit stack
     STAKED.save(storage, staker.clone(), &unstaking_deposits)?;
 
+    //Checkpoint voting stake
+    checkpoint_stake(storage, env, config, staker)?;
+
     Ok((claimables, accrued_interest, total_withdrawable))
 }
 
@@ -1917,7 +3406,7 @@ fn get_user_claimables(
     //Load contract state
     let mut config = CONFIG.load(storage)?;
     let incentive_schedule = INCENTIVE_SCHEDULING.load(storage)?;
-    let fee_events = FEE_EVENTS.load(storage)?;
+    let reward_indices = REWARD_INDICES.load(storage)?;
     //Load User State
     let deposits: Vec<StakeDeposit> = match STAKED.load(storage, user.clone()){
         Ok(deposits) => { deposits },
@@ -1931,6 +3420,12 @@ fn get_user_claimables(
             commission: Decimal::zero(),
         },
     };
+    let user_commission_rate = get_delegate_commission(storage, user.clone(), user_commission_rate)?;
+    //Fees per staked TEMA since the user's last claim
+    let reward_deltas = get_reward_deltas(
+        &reward_indices,
+        &STAKER_INDICES.may_load(storage, user.clone())?.unwrap_or_default(),
+    );
 
     //Find rewards from deposits
     if deposits != vec![] {
@@ -1968,7 +3463,7 @@ fn get_user_claimables(
                 config.clone(), 
                 incentive_schedule.clone(), 
                 env.clone(), 
-                fee_events.clone(), 
+                reward_deltas.clone(), 
                 deposit.clone(), 
                 delegated_to.clone(),
                 delegated.clone(),
@@ -1979,6 +3474,18 @@ fn get_user_claimables(
             )?;
         }
 
+        //Add claims from the staker's lock boost
+        for claim in get_lock_boost_claims(storage, user.clone(), &reward_indices)? {
+            match claimables.iter_mut().find(|asset| asset.info == claim.info) {
+                Some(asset) => asset.amount += claim.amount,
+                None => claimables.push(claim),
+            }
+        }
+        LOCK_BOOST_CLAIMS.remove(storage, user.clone());
+        if LOCK_BOOSTS.has(storage, user.clone()) {
+            LOCK_BOOST_INDICES.save(storage, user.clone(), &reward_indices)?;
+        }
+
         //Add condensed deposit to returning_deposits
         returning_deposits.push(
             StakeDeposit {
@@ -1992,12 +3499,20 @@ fn get_user_claimables(
 
         //Save new condensed deposit for user
         STAKED.save(storage, user.clone(), &returning_deposits)?;
+        //Snapshot indices since rewards are settled
+        STAKER_INDICES.save(storage, user.clone(), &reward_indices)?;
+        //Reset fee boost now that it has been claimed
+        refresh_lock_boost(storage, env.clone(), config.clone(), user.clone())?;
 
         //Find and save claimables for the user's delegates
         if !delegated_to.is_empty(){
             for delegate in delegated_to {
                 //Load delegate's commission
-                let commission = DELEGATIONS.load(storage, delegate.delegate.clone())?.commission;
+                let commission = get_delegate_commission(
+                    storage,
+                    delegate.delegate.clone(),
+                    DELEGATIONS.load(storage, delegate.delegate.clone())?.commission,
+                )?;
                 
                 //Update last_accrued for delegate
                 DELEGATIONS.update(storage, delegate.delegate.clone(), |delegation_info| -> StdResult<DelegationInfo>{
@@ -2062,7 +3577,7 @@ fn get_user_claimables(
                     config.clone(), 
                     incentive_schedule.clone(), 
                     env.clone(), 
-                    fee_events.clone(), 
+                    reward_deltas.clone(), 
                     delegate_temp_deposit.clone(), 
                     vec![],
                     vec![],
@@ -2135,15 +3650,16 @@ fn get_user_claimables(
             CONFIG.save(storage, &config)?;
         };
 
-        //Set new vesting stake time to move up claims & to put it past claimed events
+        //Set new vesting stake time & snapshot indices to move up claims
         VESTING_STAKE_TIME.save(storage, &env.block.time.seconds())?;
+        STAKER_INDICES.save(storage, user.clone(), &reward_indices)?;
 
         let (claims, _) = get_deposit_claimables(
             storage, 
             config.clone(), 
             incentive_schedule.clone(), 
             env.clone(), 
-            fee_events.clone(), 
+            reward_deltas.clone(), 
             temp_deposit,
             vec![],
             vec![],
@@ -2164,56 +3680,6 @@ fn get_user_claimables(
     Ok((vec![], Uint128::zero()))
 }
 
-/// Trim fee events to only include events after the earliest deposit
-fn trim_fee_events(
-    storage: &mut dyn Storage,
-    info: MessageInfo,
-) -> Result<Response, ContractError>{
-
-    let config = CONFIG.load(storage)?;
-
-    if info.sender != config.owner { return Err( ContractError::Unauthorized {  } )}
-
-    let mut fee_events = FEE_EVENTS.load(storage)?;
-
-    //Initialize earliest deposit
-    let mut earliest_accrue = None;
-
-    let _iter = STAKED
-        .range(storage, None, None, cosmwasm_std::Order::Ascending)
-        .map(|stakers| {
-            let (_, deposits) = stakers.unwrap();
-
-            //Set earliest accrue to first deposit
-            let mut earliest_accrue_loop = deposits[0].clone().last_accrued.unwrap_or_else(|| deposits[0].stake_time);
-
-            //Find the earliest deposit
-            for deposit in deposits {
-                if deposit.last_accrued.unwrap_or_else(|| deposit.stake_time) < earliest_accrue_loop {
-                    earliest_accrue_loop = deposit.last_accrued.unwrap_or_else(|| deposit.stake_time);
-                }
-            }
-
-            earliest_accrue = Some(earliest_accrue_loop);
-        })
-        .collect::<Vec<()>>();
-
-    //Filter for fee events that are after the earliest last_accrued to trim state
-    if let Some(earliest_accrue) = earliest_accrue{
-        fee_events = fee_events.clone()
-            .into_iter()
-            .filter(|event| event.time_of_event > earliest_accrue)
-            .collect::<Vec<FeeEvent>>();
-    }
-    //In a situation where no one is staked the contract will need to be upgraded to handle its assets
-    //This won't happen as long as the builder's allocation is vesting so the functionality isn't necessary rn
-    
-    //Save Fee events
-    FEE_EVENTS.save(storage, &fee_events)?;
-
-    Ok(Response::new().add_attribute("trimmed", "true"))
-}
-
 pub fn get_delegation_commission(
     storage: &dyn Storage,
     delegated: Vec<Delegation>,
@@ -2234,7 +3700,11 @@ pub fn get_delegation_commission(
 
         //Create tuples for (Commission rate + total delegated_to) for each delegate
         for delegation in delegated_to.clone() {
-            let delegator_commission = DELEGATIONS.load(storage, delegation.delegate.clone())?.commission;
+            let delegator_commission = get_delegate_commission(
+                storage,
+                delegation.delegate.clone(),
+                DELEGATIONS.load(storage, delegation.delegate.clone())?.commission,
+            )?;
 
             let like_delegations = delegated_to.clone()
                 .into_iter()
@@ -2287,28 +3757,19 @@ pub fn get_delegation_commission(
     Ok((per_deposit_commission_subtraction, per_deposit_commission_addition))
 }
 
-/// Get deposit's claimable fee assets based on which FeeEvents it experienced
+/// Get deposit's claimable fee assets from the rewards per staked TEMA since its last claim
 pub fn get_deposit_claimables(
     storage: &dyn Storage,
     mut config: Config,
     incentive_schedule: StakeDistributionLog,
     env: Env,
-    fee_events: Vec<FeeEvent>,
+    reward_deltas: Vec<LiqAsset>,
     mut deposit: StakeDeposit,
     delegated: Vec<Delegation>,
     delegated_to: Vec<Delegation>,
     total_rewarding_stake: Uint128, //stake thats being rewarded
     user_commission_rate: Decimal,
 ) -> StdResult<(Vec<Asset>, Uint128)> {
-    let mut claimables: Vec<Asset> = vec![];
-
-    //Filter for events that the deposit was staked for
-    //ie event times after the deposit 
-    let events_experienced = fee_events
-        .into_iter()
-        .filter(|event| event.time_of_event > deposit.last_accrued.unwrap_or_else(|| deposit.stake_time) && event.time_of_event <= env.block.time.seconds())
-        .collect::<Vec<FeeEvent>>();
-        
     //Filter for delegations who were accrued before the current_time
     let delegated = delegated
         .into_iter()
@@ -2344,23 +3805,15 @@ pub fn get_deposit_claimables(
         )?.to_uint_floor();
     }
 
-    //Calc & condense claimables
+    //Calc claimables
     //due to the above, claims incorporate the delegation commissions
-    for event in events_experienced {
-        //Check if asset is already in the list of claimables and add accordingly
-        match claimables
-            .clone()
-            .into_iter()
-            .enumerate()
-            .find(|(_i, asset)| asset.info == event.fee.info)
-        {
-            Some((index, _asset)) => claimables[index].amount += event.fee.amount * deposit.amount,
-            None => claimables.push(Asset {
-                info: event.fee.info,
-                amount: event.fee.amount * deposit.amount,
-            }),
-        }
-    }
+    let claimables: Vec<Asset> = reward_deltas
+        .into_iter()
+        .map(|delta| Asset {
+            info: delta.info,
+            amount: delta.amount * deposit.amount,
+        })
+        .collect();
 
     //Assert staking rate is still active, if not set to 0
     let rate_duration = incentive_schedule.ownership_distribution.duration * SECONDS_PER_DAY;
@@ -2379,6 +3832,16 @@ pub fn get_deposit_claimables(
     Ok((claimables, deposit_interest))
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> StdResult<Response> {
+    match msg.id {
+        COMPOUND_REPLY_ID => handle_compound_reply(deps, env, msg),
+        PENALTY_REPLY_ID => handle_penalty_reply(deps, env, msg),
+        PENALTY_STREAM_REPLY_ID => handle_penalty_stream_reply(deps, msg),
+        id => Err(StdError::generic_err(format!("invalid reply id: {}", id))),
+    }
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
@@ -2404,72 +3867,110 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::DeclaredDelegates { limit, start_after, end_before, user } => {
             to_binary(&query_declared_delegates(deps, env, limit, start_after, end_before, user)?)
         }
-        QueryMsg::FeeEvents { limit, start_after } => {
-            to_binary(&query_fee_events(deps, limit, start_after)?)
+        QueryMsg::DelegateParticipation { limit, start_after, user } => {
+            to_binary(&query_delegate_participation(deps, env, limit, start_after, user)?)
         }
+        QueryMsg::Unbonding { limit, start_after, user } => to_binary(&query_unbonding(deps, env, limit, start_after, user)?),
+        QueryMsg::AutoCompounders { limit, start_after } => to_binary(&query_auto_compounders(deps, limit, start_after)?),
+        QueryMsg::LiquidStake {} => to_binary(&query_liquid_stake(deps, env)?),
+        QueryMsg::UserLocks { user } => to_binary(&query_user_locks(deps, env, user)?),
+        QueryMsg::Locks { limit, start_after } => to_binary(&query_locks(deps, env, limit, start_after)?),
+        QueryMsg::StakeAt { user, time } => to_binary(&query_stake_at(deps, user, time)?),
+        QueryMsg::TotalStakeAt { time, quadratic } => to_binary(&query_total_stake_at(deps, time, quadratic.unwrap_or(false))?),
+        QueryMsg::StakeCheckpoints { time, limit, start_after } => to_binary(&query_stake_checkpoints(deps, time, limit, start_after)?),
+        QueryMsg::RewardIndices {} => to_binary(&query_reward_indices(deps)?),
         QueryMsg::TotalStaked {} => to_binary(&query_totals(deps)?),
         QueryMsg::IncentiveSchedule {  } => to_binary(&INCENTIVE_SCHEDULING.load(deps.storage)?),
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
-
-    let current_delegates = vec![
-        Delegate { 
-            delegate: Addr::unchecked("furya197lfuc8s5amj4lhkmyv5dex7qsdu3m5jh40tp6"), 
-            alias: Some(String::from("Argentina")), 
-            discord_username: Some(String::from("JinxVentures")),
-            twitter_username: Some(String::from("JinxVentures")),
-            url: None,
-        },        
-        Delegate { 
-            delegate: Addr::unchecked("furya133dalktxc8uj94q07hjekrpucpl49fg783q628"), 
-            alias: Some(String::from("Arsenal")), 
-            discord_username: Some(String::from("ArgentinaDao")),
-            twitter_username: Some(String::from("ArgentinaDao")),
-            url: None,
-        },        
-        Delegate { 
-            delegate: Addr::unchecked("furya1kdhac2qmk2y0zpfmh3hhquxqkw463tunjpgejt"), 
-            alias: Some(String::from("Brooklyn Nets")), 
-            discord_username: Some(String::from("bnetts")),
-            twitter_username: Some(String::from("BNetts")),
-            url: None,
-        },        
-        Delegate { 
-            delegate: Addr::unchecked("furya1fdqgj600jv0ydjas89kgel2gt880j44jt0l7ls"), 
-            alias: Some(String::from("Brazil")), 
-            discord_username: Some(String::from("SoSoDao")),
-            twitter_username: Some(String::from("SoSoDao")),
-            url: None,
-        },        
-        Delegate { 
-            delegate: Addr::unchecked("furya1kt4pap9rdcm72gcppvm0l2khnku98padg8nrte"), 
-            alias: Some(String::from("England")), 
-            discord_username: Some(String::from("polaris")),
-            twitter_username: Some(String::from("polaris")),
-            url: None,
-        },        
-        Delegate { 
-            delegate: Addr::unchecked("furya1fqspuscrlzrtpghqnmqmj8j9dqr9hdmzlatcu6"), 
-            alias: Some(String::from("LA Lakers")), 
-            discord_username: Some(String::from("Cali")),
-            twitter_username: Some(String::from("Cali")),
-            url: None,
-        },        
-        Delegate { 
-            delegate: Addr::unchecked("furya1pg9t4kxgwhtsp72dgd42x42pxzswaze35fxxlq"), 
-            alias: Some(String::from("NY Yankees")), 
-            discord_username: Some(String::from("stateofmind")),
-            twitter_username: Some(String::from("StateOfMind")),
-            url: None,
+pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    //Convert FeeEvents to reward indices
+    if let Some(fee_events) = FEE_EVENTS.may_load(deps.storage)? {
+        //Sum of event amounts per asset up to a time
+        let indices_at = |time: Option<u64>| -> Vec<LiqAsset> {
+            let mut indices: Vec<LiqAsset> = vec![];
+            for event in fee_events.clone() {
+                if let Some(time) = time {
+                    if event.time_of_event > time {
+                        continue;
+                    }
+                }
+                match indices.iter_mut().find(|index| index.info == event.fee.info) {
+                    Some(index) => index.amount += event.fee.amount,
+                    None => indices.push(event.fee),
+                }
+            }
+            indices
+        };
+        let reward_indices = indices_at(None);
+        REWARD_INDICES.save(deps.storage, &reward_indices)?;
+
+        //Stakers' snapshots are weighted by their staked deposits so unclaimed fees carry over
+        let stakers = STAKED
+            .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+            .collect::<StdResult<Vec<(Addr, Vec<StakeDeposit>)>>>()?;
+        for (staker, deposits) in stakers {
+            let staked_deposits = deposits
+                .into_iter()
+                .filter(|deposit| deposit.unstake_start_time.is_none())
+                .collect::<Vec<StakeDeposit>>();
+            let total_staked: Uint128 = staked_deposits.iter().map(|deposit| deposit.amount).sum();
+
+            if total_staked.is_zero() {
+                STAKER_INDICES.save(deps.storage, staker, &reward_indices)?;
+                continue;
+            }
+
+            let mut snapshot: Vec<LiqAsset> = vec![];
+            for deposit in staked_deposits {
+                let deposit_indices = indices_at(Some(deposit.last_accrued.unwrap_or(deposit.stake_time)));
+                let weight = Decimal::from_ratio(deposit.amount, total_staked);
+
+                for index in deposit_indices {
+                    let weighted_index = decimal_multiplication(index.amount, weight)?;
+
+                    match snapshot.iter_mut().find(|staker_index| staker_index.info == index.info) {
+                        Some(staker_index) => staker_index.amount += weighted_index,
+                        None => snapshot.push(LiqAsset { info: index.info, amount: weighted_index }),
+                    }
+                }
+            }
+            STAKER_INDICES.save(deps.storage, staker, &snapshot)?;
         }
-    ];
 
-    //Initialize Delegate state
-    DELEGATE_INFO.save(deps.storage, &current_delegates)?;
+        //Vesting contract has claimed up to its stake time
+        if let Some(vesting_contract) = config.clone().vesting_contract {
+            let vesting_stake_time = VESTING_STAKE_TIME.load(deps.storage)?;
+            STAKER_INDICES.save(deps.storage, vesting_contract, &indices_at(Some(vesting_stake_time)))?;
+        }
 
-    Ok(Response::default())
-}
+        FEE_EVENTS.remove(deps.storage);
+    }
 
+    //Seed voting power checkpoints, existing checkpoints are redone to add lock voting boosts & zero the liquid pool
+    if TOTAL_QUADRATIC_STAKE_CHECKPOINTS.may_load(deps.storage)?.is_none() {
+        let quadratic_total: Uint128 = STAKE_CHECKPOINTS
+            .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+            .map(|checkpoint| Ok(get_quadratic_power(checkpoint?.1)))
+            .collect::<StdResult<Vec<Uint128>>>()?
+            .into_iter()
+            .sum();
+        TOTAL_QUADRATIC_STAKE_CHECKPOINTS.save(deps.storage, &quadratic_total, env.block.time.seconds())?;
+        if TOTAL_STAKE_CHECKPOINTS.may_load(deps.storage)?.is_none() {
+            TOTAL_STAKE_CHECKPOINTS.save(deps.storage, &Uint128::zero(), env.block.time.seconds())?;
+        }
+
+        let stakers = STAKED
+            .keys(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+            .collect::<StdResult<Vec<Addr>>>()?;
+        for staker in stakers {
+            checkpoint_stake(deps.storage, env.clone(), config.clone(), staker)?;
+        }
+    }
+
+    Ok(Response::new().add_attribute("method", "migrate"))
+}
@@ -1,11 +1,12 @@
 use cosmwasm_std::{Deps, StdResult, Uint128, Env, Addr, Decimal, StdError};
 use cw_storage_plus::Bound;
 use membrane::math::decimal_multiplication;
-use membrane::staking::{TotalStakedResponse, FeeEventsResponse, StakerResponse, RewardsResponse, StakedResponse, DelegationResponse};
-use membrane::types::{Asset, Delegate, Delegation, DelegationInfo, FeeEvent, OldDelegation, OldDelegationInfo, StakeDeposit};
+use membrane::helpers::get_lock_multiplier;
+use membrane::staking::{AutoCompoundResponse, StakeCheckpointResponse, UnbondingResponse, DelegateParticipationResponse, LiquidStakeResponse, TotalStakedResponse, RewardIndicesResponse, StakerResponse, RewardsResponse, StakedResponse, DelegationResponse, LockResponse, UserLocksResponse, LOCK_FEE_BOOST, LOCK_VOTING_BOOST};
+use membrane::types::{Asset, Delegate, Delegation, DelegationInfo, OldDelegation, OldDelegationInfo, StakeDeposit, StakeLock, UnbondingEntry};
 
-use crate::contract::{get_deposit_claimables, get_total_vesting};
-use crate::state::{CONFIG, DELEGATE_CLAIMS, DELEGATE_INFO, DELEGATIONS, FEE_EVENTS, INCENTIVE_SCHEDULING, STAKED, STAKING_TOTALS, VESTING_STAKE_TIME};
+use crate::contract::{SECONDS_PER_DAY, get_lock_boost_claims, get_instant_unstake_penalty, get_deposit_claimables, get_reward_deltas, get_total_vesting, get_delegate_commission, get_liquid_stake_total, get_votes_in_window, is_below_requirements};
+use crate::state::{AUTO_COMPOUND, CONFIG, DELEGATE_CLAIMS, DELEGATE_INFO, DELEGATE_PARTICIPATION, DELEGATIONS, INCENTIVE_SCHEDULING, LIQUID_FEES, LIQUID_STAKE, LOCKS, REWARD_INDICES, STAKED, STAKER_INDICES, STAKE_CHECKPOINTS, STAKING_TOTALS, TOTAL_QUADRATIC_STAKE_CHECKPOINTS, TOTAL_STAKE_CHECKPOINTS, VESTING_STAKE_TIME};
 
 const DEFAULT_LIMIT: u32 = 32u32;
 
@@ -56,9 +57,14 @@ pub fn query_user_rewards(deps: Deps, env: Env, user: String) -> StdResult<Rewar
     //Load state
     let config = CONFIG.load(deps.storage)?;
     let incentive_schedule = INCENTIVE_SCHEDULING.load(deps.storage)?;
-    let fee_events = FEE_EVENTS.load(deps.storage)?;
+    let reward_indices = REWARD_INDICES.load(deps.storage)?;
     //Validate address
     let valid_addr = deps.api.addr_validate(&user)?;
+    //Fees per staked TEMA since the user's last claim
+    let reward_deltas = get_reward_deltas(
+        &reward_indices,
+        &STAKER_INDICES.may_load(deps.storage, valid_addr.clone())?.unwrap_or_default(),
+    );
     //Load user state
     let user_deposits: Vec<StakeDeposit> = match STAKED.load(deps.storage, valid_addr.clone()){
         Ok(deposits) => { deposits }
@@ -73,6 +79,8 @@ pub fn query_user_rewards(deps: Deps, env: Env, user: String) -> StdResult<Rewar
         }
     };
 
+    let commission = get_delegate_commission(deps.storage, valid_addr.clone(), commission)?;
+
     //Get claimables for each deposit
     if user_deposits != vec![] {  
         let mut claimables: Vec<Asset> = vec![];
@@ -102,7 +110,7 @@ pub fn query_user_rewards(deps: Deps, env: Env, user: String) -> StdResult<Rewar
                 config.clone(), 
                 incentive_schedule.clone(), 
                 env.clone(), 
-                fee_events.clone(), 
+                reward_deltas.clone(), 
                 deposit,
                 delegated.clone(),
                 delegated_to.clone(),
@@ -113,6 +121,14 @@ pub fn query_user_rewards(deps: Deps, env: Env, user: String) -> StdResult<Rewar
             accrued_interest += incentives;
         }
 
+        //Add claims from the staker's lock boost
+        for claim in get_lock_boost_claims(deps.storage, valid_addr.clone(), &reward_indices)? {
+            match claimables.iter_mut().find(|asset| asset.info == claim.info) {
+                Some(asset) => asset.amount += claim.amount,
+                None => claimables.push(claim),
+            }
+        }
+
         //Filter out empty claimables
         claimables = claimables
             .into_iter()
@@ -149,7 +165,7 @@ pub fn query_user_rewards(deps: Deps, env: Env, user: String) -> StdResult<Rewar
             config.clone(), 
             incentive_schedule.clone(), 
             env.clone(), 
-            fee_events.clone(), 
+            reward_deltas.clone(), 
             deposit,
             vec![],
             vec![],
@@ -203,6 +219,8 @@ pub fn query_staked(
             
             let stakers_in_loop = stakers_in_loop.clone()
                 .into_iter()
+                //The liquid pool's stake has no voting power
+                .filter(|deposit| deposit.staker != env.contract.address)
                 .filter(|deposit| deposit.stake_time > start_after && deposit.stake_time < end_before)
                 .collect::<Vec<StakeDeposit>>();
 
@@ -239,23 +257,237 @@ pub fn query_staked(
     Ok(StakedResponse { stakers })
 }
 
-/// Returns historical fee events
-pub fn query_fee_events(
+/// Returns users' unstaking deposits, their maturity times & current instant unstake penalties
+pub fn query_unbonding(
     deps: Deps,
+    env: Env,
     limit: Option<u32>,
-    start_after: Option<u64>,
-) -> StdResult<FeeEventsResponse> {
-    let limit = limit.unwrap_or(DEFAULT_LIMIT);
-    let start_after = start_after.unwrap_or(0u64);
+    start_after: Option<String>,
+    user: Option<String>,
+) -> StdResult<Vec<UnbondingResponse>> {
+    let config = CONFIG.load(deps.storage)?;
+    let unstaking_period = config.unstaking_period * SECONDS_PER_DAY;
+    let max_penalty = match config.instant_unstake_penalty {
+        Some(penalty) => penalty.max_penalty,
+        None => Decimal::zero(),
+    };
+
+    let get_entries = |deposits: Vec<StakeDeposit>| -> Vec<UnbondingEntry> {
+        deposits
+            .into_iter()
+            .filter(|deposit| deposit.unstake_start_time.is_some())
+            .map(|deposit| {
+                let unstake_start_time = deposit.unstake_start_time.unwrap_or_default();
+
+                UnbondingEntry {
+                    amount: deposit.amount,
+                    unstake_start_time,
+                    maturity_time: unstake_start_time + unstaking_period,
+                    instant_unstake_penalty: get_instant_unstake_penalty(&deposit, max_penalty, unstaking_period, env.block.time.seconds()),
+                }
+            })
+            .collect()
+    };
+
+    if let Some(user) = user {
+        let user = deps.api.addr_validate(&user)?;
+        let deposits = STAKED.load(deps.storage, user.clone())?;
+
+        return Ok(vec![UnbondingResponse {
+            user: user.to_string(),
+            entries: get_entries(deposits),
+        }]);
+    }
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT) as usize;
+    let start = if let Some(start) = start_after {
+        Some(Bound::exclusive(deps.api.addr_validate(&start)?))
+    } else {
+        None
+    };
 
-    let fee_events = FEE_EVENTS
-        .load(deps.storage)?
+    STAKED
+        .range(deps.storage, start, None, cosmwasm_std::Order::Ascending)
+        .map(|item| {
+            let (user, deposits) = item?;
+            Ok(UnbondingResponse {
+                user: user.to_string(),
+                entries: get_entries(deposits),
+            })
+        })
+        .filter(|res| match res {
+            Ok(res) => !res.entries.is_empty(),
+            Err(_) => true,
+        })
+        .take(limit)
+        .collect()
+}
+
+/// Returns users opted into auto-compounding & their last compound time
+/// Returns a user's checkpointed voting power at a time, current if None
+pub fn query_stake_at(deps: Deps, user: String, time: Option<u64>) -> StdResult<Uint128> {
+    let user = deps.api.addr_validate(&user)?;
+
+    let stake = match time {
+        Some(time) => STAKE_CHECKPOINTS.may_load_at_height(deps.storage, user, time)?,
+        None => STAKE_CHECKPOINTS.may_load(deps.storage, user)?,
+    };
+
+    Ok(stake.unwrap_or_default())
+}
+
+/// Returns the checkpointed total voting power at a time, current if None
+pub fn query_total_stake_at(deps: Deps, time: Option<u64>, quadratic: bool) -> StdResult<Uint128> {
+    let checkpoints = if quadratic { TOTAL_QUADRATIC_STAKE_CHECKPOINTS } else { TOTAL_STAKE_CHECKPOINTS };
+    let total = match time {
+        Some(time) => checkpoints.may_load_at_height(deps.storage, time)?,
+        None => checkpoints.may_load(deps.storage)?,
+    };
+
+    Ok(total.unwrap_or_default())
+}
+
+/// Returns each user's checkpointed voting power at a time, skipping users with none
+pub fn query_stake_checkpoints(
+    deps: Deps,
+    time: Option<u64>,
+    limit: Option<u32>,
+    start_after: Option<String>,
+) -> StdResult<Vec<StakeCheckpointResponse>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT) as usize;
+    let start = if let Some(start) = start_after {
+        Some(Bound::exclusive(deps.api.addr_validate(&start)?))
+    } else {
+        None
+    };
+
+    STAKE_CHECKPOINTS
+        .keys(deps.storage, start, None, cosmwasm_std::Order::Ascending)
+        .map(|user| {
+            let user = user?;
+            let stake = match time {
+                Some(time) => STAKE_CHECKPOINTS.may_load_at_height(deps.storage, user.clone(), time)?,
+                None => STAKE_CHECKPOINTS.may_load(deps.storage, user.clone())?,
+            };
+
+            Ok(StakeCheckpointResponse {
+                user: user.to_string(),
+                stake: stake.unwrap_or_default(),
+            })
+        })
+        .filter(|res| match res {
+            Ok(res) => !res.stake.is_zero(),
+            Err(_) => true,
+        })
+        .take(limit)
+        .collect()
+}
+
+pub fn query_auto_compounders(
+    deps: Deps,
+    limit: Option<u32>,
+    start_after: Option<String>,
+) -> StdResult<Vec<AutoCompoundResponse>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT) as usize;
+    let start = if let Some(start) = start_after {
+        Some(Bound::exclusive(deps.api.addr_validate(&start)?))
+    } else {
+        None
+    };
+
+    AUTO_COMPOUND
+        .range(deps.storage, start, None, cosmwasm_std::Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (user, last_compound) = item?;
+            Ok(AutoCompoundResponse {
+                user: user.to_string(),
+                last_compound,
+            })
+        })
+        .collect()
+}
+
+/// Returns liquid staking pool info
+pub fn query_liquid_stake(deps: Deps, env: Env) -> StdResult<LiquidStakeResponse> {
+    let pool = LIQUID_STAKE.load(deps.storage)?;
+    let total_staked = get_liquid_stake_total(deps.storage, &env)?;
+
+    let exchange_rate = if pool.receipt_supply.is_zero() {
+        Decimal::one()
+    } else {
+        Decimal::from_ratio(total_staked, pool.receipt_supply)
+    };
+
+    Ok(LiquidStakeResponse {
+        receipt_denom: pool.receipt_denom,
+        receipt_supply: pool.receipt_supply,
+        total_staked,
+        exchange_rate,
+        fee_claims: LIQUID_FEES.may_load(deps.storage)?.unwrap_or_default(),
+    })
+}
+
+/// Returns a user's active locks & their current multipliers
+pub fn query_user_locks(deps: Deps, env: Env, user: String) -> StdResult<UserLocksResponse> {
+    let valid_addr = deps.api.addr_validate(&user)?;
+    let locks = LOCKS.may_load(deps.storage, valid_addr.clone())?.unwrap_or_default();
+
+    Ok(get_user_locks_response(valid_addr, locks, env.block.time.seconds()))
+}
+
+/// Returns active locks for all users
+pub fn query_locks(
+    deps: Deps,
+    env: Env,
+    limit: Option<u32>,
+    start_after: Option<String>,
+) -> StdResult<Vec<UserLocksResponse>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT) as usize;
+    let start = if let Some(start) = start_after {
+        Some(Bound::exclusive(deps.api.addr_validate(&start)?))
+    } else {
+        None
+    };
+
+    LOCKS
+        .range(deps.storage, start, None, cosmwasm_std::Order::Ascending)
+        .map(|item| {
+            let (user, locks) = item?;
+            Ok(get_user_locks_response(user, locks, env.block.time.seconds()))
+        })
+        .filter(|res| match res {
+            Ok(res) => !res.locks.is_empty(),
+            Err(_) => true,
+        })
+        .take(limit)
+        .collect()
+}
+
+fn get_user_locks_response(user: Addr, locks: Vec<StakeLock>, time: u64) -> UserLocksResponse {
+    //Filter out expired locks
+    let locks = locks
         .into_iter()
-        .filter(|event| event.time_of_event >= start_after)
-        .take(limit as usize)
-        .collect::<Vec<FeeEvent>>();
+        .filter(|lock| lock.end_time > time)
+        .map(|lock| LockResponse {
+            voting_multiplier: get_lock_multiplier(&lock, time, Decimal::percent(LOCK_VOTING_BOOST)),
+            fee_multiplier: get_lock_multiplier(&lock, time, Decimal::percent(LOCK_FEE_BOOST)),
+            lock,
+        })
+        .collect::<Vec<LockResponse>>();
 
-    Ok(FeeEventsResponse { fee_events })
+    UserLocksResponse {
+        user: user.to_string(),
+        total_locked: locks.iter().map(|lock| lock.lock.amount).sum(),
+        locks,
+    }
+}
+
+/// Returns cumulative fee rewards per staked TEMA
+pub fn query_reward_indices(deps: Deps) -> StdResult<RewardIndicesResponse> {
+    Ok(RewardIndicesResponse {
+        reward_indices: REWARD_INDICES.load(deps.storage)?,
+    })
 }
 
 /// Return staked tokens totals
@@ -429,4 +661,38 @@ pub fn query_declared_delegates(
     delegate_infos = delegate_infos[start_index..end_index].to_vec();
 
     Ok(delegate_infos.into_iter().take(limit as usize).collect())
-}
\ No newline at end of file
+}
+/// Returns declared delegates' participation in the most recent proposals
+pub fn query_delegate_participation(
+    deps: Deps,
+    env: Env,
+    limit: Option<u32>,
+    start_after: Option<String>,
+    user: Option<String>,
+) -> StdResult<Vec<DelegateParticipationResponse>> {
+    let config = CONFIG.load(deps.storage)?;
+    let delegates = query_declared_delegates(deps, env, limit, start_after, None, user)?;
+
+    delegates
+        .into_iter()
+        .map(|delegate| {
+            let participation = DELEGATE_PARTICIPATION.may_load(deps.storage, delegate.delegate.clone())?.unwrap_or_default();
+            let participation_rate = if participation.is_empty() {
+                Decimal::one()
+            } else {
+                Decimal::from_ratio(get_votes_in_window(&participation, participation.len() as u64), participation.len() as u64)
+            };
+            let penalized = match config.clone().participation_requirements {
+                Some(requirements) => is_below_requirements(&participation, &requirements),
+                None => false,
+            };
+
+            Ok(DelegateParticipationResponse {
+                delegate: delegate.delegate,
+                participation,
+                participation_rate,
+                penalized,
+            })
+        })
+        .collect()
+}
@@ -1,19 +1,94 @@
-use membrane::{types::{FeeEvent, StakeDeposit, StakeDistributionLog, DelegationInfo, Delegate}, staking::Totals};
+use membrane::{types::{Asset, FeeEvent, LiqAsset, StakeDeposit, StakeDistributionLog, DelegationInfo, Delegate, StakeLock, ProposalParticipation, LiquidStakePool}, staking::Totals};
 
 use cosmwasm_std::{Uint128, Addr, Coin, Decimal};
-use cw_storage_plus::{Item, Map};
+use cw_storage_plus::{Item, Map, SnapshotItem, SnapshotMap, Strategy};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
 use membrane::staking::Config;
 
+/// Saved between fee asset swaps & their reply in an auto-compound
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct CompoundPropagation {
+    /// User whose fees are compounded
+    pub user: Addr,
+    /// Caller to tip
+    pub keeper: Addr,
+    /// Contract's TEMA balance before the next swap
+    pub prev_balance: Uint128,
+    /// Fee assets left to swap, in reply order
+    pub swaps: Vec<Coin>,
+}
+
+/// Saved between an instant unstake penalty swap, its stream & their replies
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct PenaltyPropagation {
+    /// Credit asset denom
+    pub credit_denom: String,
+    /// Contract's credit asset balance before the swap
+    pub prev_balance: Uint128,
+    /// TEMA swapped, returned to the batch if the swap fails
+    pub tema_amount: Uint128,
+    /// Credit asset streamed, distributed to stakers if the stream fails
+    pub streamed: Uint128,
+}
+
+/// Instant unstake penalties waiting to be streamed to the Stability Pool
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema, Default)]
+pub struct PenaltyBatch {
+    /// TEMA penalties
+    pub amount: Uint128,
+    /// Time of the last stream in seconds
+    pub last_stream_time: u64,
+}
+
 pub const CONFIG: Item<Config> = Item::new("config");
 pub const STAKING_TOTALS: Item<Totals> = Item::new("totals"); 
 pub const STAKED: Map<Addr, Vec<StakeDeposit>> = Map::new("stake"); //Stack of staking deposits
 pub const DELEGATIONS: Map<Addr, DelegationInfo> = Map::new("delegations"); //Info for each user's delegations (sent and received)
 pub const DELEGATE_CLAIMS: Map<Addr, (Vec<Coin>, Uint128)> = Map::new("delegate_claims"); //Staking rewards that can be claimed by a delegate
-pub const FEE_EVENTS: Item<Vec<FeeEvent>> = Item::new("fee_events"); //<timestamp, asset> //The amount saved is the amount of the asset per TEMA staked
+pub const FEE_EVENTS: Item<Vec<FeeEvent>> = Item::new("fee_events"); //Legacy, converted to REWARD_INDICES in migrate
+pub const REWARD_INDICES: Item<Vec<LiqAsset>> = Item::new("reward_indices"); //Cumulative amount of each fee asset per TEMA staked
+pub const STAKER_INDICES: Map<Addr, Vec<LiqAsset>> = Map::new("staker_indices"); //Staker's REWARD_INDICES snapshot, missing assets were added after it
+pub const LOCKS: Map<Addr, Vec<StakeLock>> = Map::new("locks"); //Staker's active locks
+pub const LOCK_BOOSTS: Map<Addr, Uint128> = Map::new("lock_boosts"); //Staker's extra fee weight from locks, refreshed on claims & RefreshLocks
+pub const LOCK_BOOST_INDICES: Map<Addr, Vec<LiqAsset>> = Map::new("lock_boost_indices"); //REWARD_INDICES snapshot for the staker's lock boost, stakers without one share STAKER_INDICES
+pub const LOCK_BOOST_CLAIMS: Map<Addr, Vec<Asset>> = Map::new("lock_boost_claims"); //Lock boost fees settled before a refresh, paid on the next claim
+pub const TOTAL_LOCK_BOOST: Item<Uint128> = Item::new("total_lock_boost"); //Sum of LOCK_BOOSTS
+pub const LIQUID_STAKE: Item<LiquidStakePool> = Item::new("liquid_stake"); //Receipt token info, the pool's stake is in STAKED under the contract's address
+pub const LIQUID_FEES: Item<Vec<Coin>> = Item::new("liquid_fees"); //Fee revenue claimed by the liquid pool
+pub const AUTO_COMPOUND: Map<Addr, u64> = Map::new("auto_compound"); //Opted in users -> last auto-compound time
+pub const COMPOUND: Item<CompoundPropagation> = Item::new("compound");
+pub const PENALTY: Item<PenaltyPropagation> = Item::new("penalty");
+pub const PENALTY_BATCH: Item<PenaltyBatch> = Item::new("penalty_batch");
+/// Staker's voting power, i.e. stake that isn't unstaking plus the lock voting boost, checkpointed by block time in seconds.
+/// The boost is checkpointed as of the staker's last stake, unstake, claim or lock refresh.
+pub const STAKE_CHECKPOINTS: SnapshotMap<Addr, Uint128> = SnapshotMap::new(
+    "stake_checkpoints",
+    "stake_checkpoints__checkpoints",
+    "stake_checkpoints__changelog",
+    Strategy::EveryBlock,
+);
+/// Sum of STAKE_CHECKPOINTS checkpointed by block time in seconds
+pub const TOTAL_STAKE_CHECKPOINTS: SnapshotItem<Uint128> = SnapshotItem::new(
+    "total_stake_checkpoints",
+    "total_stake_checkpoints__checkpoints",
+    "total_stake_checkpoints__changelog",
+    Strategy::EveryBlock,
+);
+/// Sum of the square roots of STAKE_CHECKPOINTS checkpointed by block time in seconds, for quadratic voting
+pub const TOTAL_QUADRATIC_STAKE_CHECKPOINTS: SnapshotItem<Uint128> = SnapshotItem::new(
+    "total_quadratic_stake_checkpoints",
+    "total_quadratic_stake_checkpoints__checkpoints",
+    "total_quadratic_stake_checkpoints__changelog",
+    Strategy::EveryBlock,
+);
 pub const INCENTIVE_SCHEDULING: Item<StakeDistributionLog> = Item::new("stake_incentives_log"); 
 /// Filled with info of addresses that want to be delegates
 pub const DELEGATE_INFO: Item<Vec<Delegate>> = Item::new("delegate_info"); 
+/// Declared delegates' participation in the most recent proposals
+pub const DELEGATE_PARTICIPATION: Map<Addr, Vec<ProposalParticipation>> = Map::new("delegate_participation");
 
 //Vesting specific
 pub const VESTING_STAKE_TIME: Item<u64> = Item::new("vesting_stake_time"); //The time to use for vesting contract claims
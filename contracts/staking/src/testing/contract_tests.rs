@@ -13,9 +13,9 @@ use cosmwasm_std::{
 use membrane::osmosis_proxy::ExecuteMsg as OsmoExecuteMsg;
 use membrane::staking::{
     Config, ExecuteMsg, InstantiateMsg, QueryMsg, 
-    StakedResponse, TotalStakedResponse, StakerResponse, DelegationResponse, RewardsResponse,
+    StakedResponse, TotalStakedResponse, StakerResponse, DelegationResponse, RewardsResponse, DelegateParticipationResponse,
 };
-use membrane::types::{Delegate, OldDelegation, OldDelegationInfo, OldStakeDeposit, StakeDistribution};
+use membrane::types::{Delegate, OldDelegation, OldDelegationInfo, OldStakeDeposit, ParticipationRequirements, ProposalParticipation, StakeDistribution};
 
 #[test]
 fn update_config(){
@@ -51,6 +51,13 @@ fn update_config(){
         max_commission_rate: Some(Decimal::percent(11)),
         keep_raw_cdt: Some(false),
         vesting_rev_multiplier: None,
+        participation_requirements: None,
+        dex_router: Some(String::from("new_router")),
+        keeper_tip: Some(Decimal::percent(2)),
+        instant_unstake_penalty: None,
+        stability_pool: None,
+        oracle_contract: Some(String::from("new_oracle")),
+        max_slippage: Some(Decimal::percent(2)),
     };
 
     execute(
@@ -86,6 +93,13 @@ fn update_config(){
             max_commission_rate: Decimal::percent(11),
             keep_raw_cdt: false,
             vesting_rev_multiplier: Decimal::percent(20),      
+            participation_requirements: Some(ParticipationRequirements { threshold: Decimal::percent(50), window: 10 }),
+            dex_router: Some(Addr::unchecked("new_router")),
+            keeper_tip: Decimal::percent(2),
+            instant_unstake_penalty: None,
+            stability_pool: None,
+            oracle_contract: Some(Addr::unchecked("new_oracle")),
+            max_slippage: Decimal::percent(2),
         },
     );
     //Previous owner can still update bc the ownership hasn't transferred yet
@@ -102,6 +116,13 @@ fn update_config(){
         max_commission_rate: None,
         keep_raw_cdt: None,
         vesting_rev_multiplier: None,
+        participation_requirements: None,
+        dex_router: None,
+        keeper_tip: None,
+        instant_unstake_penalty: None,
+        stability_pool: None,
+        oracle_contract: None,
+        max_slippage: None,
     };
 
     execute(
@@ -126,6 +147,13 @@ fn update_config(){
         max_commission_rate: None,
         keep_raw_cdt: None,
         vesting_rev_multiplier: None,
+        participation_requirements: None,
+        dex_router: None,
+        keeper_tip: None,
+        instant_unstake_penalty: None,
+        stability_pool: None,
+        oracle_contract: None,
+        max_slippage: None,
     };
 
     execute(
@@ -161,6 +189,13 @@ fn update_config(){
             max_commission_rate: Decimal::percent(11),  
             keep_raw_cdt: false,
             vesting_rev_multiplier: Decimal::percent(20),
+            participation_requirements: Some(ParticipationRequirements { threshold: Decimal::percent(50), window: 10 }),
+            dex_router: Some(Addr::unchecked("new_router")),
+            keeper_tip: Decimal::percent(2),
+            instant_unstake_penalty: None,
+            stability_pool: None,
+            oracle_contract: Some(Addr::unchecked("new_oracle")),
+            max_slippage: Decimal::percent(2),
         },
     );
 }
@@ -186,7 +221,7 @@ fn stake() {
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     //Stake non-TEMA asset
-    let msg = ExecuteMsg::Stake { user: None };
+    let msg = ExecuteMsg::Stake { user: None, lock_duration: None };
     let info = mock_info("sender88", &[coin(10_000_000, "not-tema")]);
     let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
     assert_eq!(
@@ -195,7 +230,7 @@ fn stake() {
     );
 
     //Successful Stake
-    let msg = ExecuteMsg::Stake { user: None };
+    let msg = ExecuteMsg::Stake { user: None, lock_duration: None };
     let info = mock_info("sender88", &[coin(10_000_000, "tema_denom")]);
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
     assert_eq!(
@@ -208,7 +243,7 @@ fn stake() {
     );
 
     //Successful Stake from vesting contract
-    let msg = ExecuteMsg::Stake { user: None };
+    let msg = ExecuteMsg::Stake { user: None, lock_duration: None };
     let info = mock_info("vesting_contract", &[coin(11_000_000, "tema_denom")]);
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
     assert_eq!(
@@ -306,12 +341,12 @@ fn delegate() {
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     //Stake TEMA: sender88
-    let msg = ExecuteMsg::Stake { user: None };
+    let msg = ExecuteMsg::Stake { user: None, lock_duration: None };
     let info = mock_info("sender88", &[coin(10_000_000, "tema_denom")]);
     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     //Stake TEMA: placeholder99
-    let msg = ExecuteMsg::Stake { user: None };
+    let msg = ExecuteMsg::Stake { user: None, lock_duration: None };
     let info = mock_info("placeholder99", &[coin(10_000_000, "tema_denom")]);
     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -541,7 +576,7 @@ fn commissions() {
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     //Stake TEMA: sender88
-    let msg = ExecuteMsg::Stake { user: None };
+    let msg = ExecuteMsg::Stake { user: None, lock_duration: None };
     let info = mock_info("sender88", &[coin(10_000000, "tema_denom")]);
     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -613,7 +648,7 @@ fn fluid_delegations() {
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     //Stake TEMA: sender88
-    let msg = ExecuteMsg::Stake { user: None };
+    let msg = ExecuteMsg::Stake { user: None, lock_duration: None };
     let info = mock_info("sender88", &[coin(10_000_000, "tema_denom")]);
     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -839,7 +874,7 @@ fn unstake() {
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     //Successful Stake
-    let msg = ExecuteMsg::Stake { user: None };
+    let msg = ExecuteMsg::Stake { user: None, lock_duration: None };
     let info = mock_info("sender88", &[coin(10_000_000, "tema_denom")]);
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
     assert_eq!(
@@ -851,7 +886,7 @@ fn unstake() {
         ]
     );
     //Successful Stake
-    let msg = ExecuteMsg::Stake { user: None };
+    let msg = ExecuteMsg::Stake { user: None, lock_duration: None };
     let info = mock_info("first_delegate", &[coin(2_000_000, "tema_denom")]);
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
     assert_eq!(
@@ -898,7 +933,7 @@ fn unstake() {
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     //Successful Stake from vesting contract
-    let msg = ExecuteMsg::Stake { user: None };
+    let msg = ExecuteMsg::Stake { user: None, lock_duration: None };
     let info = mock_info("vesting_contract", &[coin(11_000_000, "tema_denom")]);
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
     assert_eq!(
@@ -1191,23 +1226,23 @@ fn unstake_v2() {
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     //Successful Stake
-    let msg = ExecuteMsg::Stake { user: None };
+    let msg = ExecuteMsg::Stake { user: None, lock_duration: None };
     let info = mock_info("sender88", &[coin(2_000_000, "tema_denom")]);
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-    let msg = ExecuteMsg::Stake { user: None };
+    let msg = ExecuteMsg::Stake { user: None, lock_duration: None };
     let info = mock_info("sender88", &[coin(2_000_000, "tema_denom")]);
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-    let msg = ExecuteMsg::Stake { user: None };
+    let msg = ExecuteMsg::Stake { user: None, lock_duration: None };
     let info = mock_info("sender88", &[coin(2_000_000, "tema_denom")]);
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-    let msg = ExecuteMsg::Stake { user: None };
+    let msg = ExecuteMsg::Stake { user: None, lock_duration: None };
     let info = mock_info("sender88", &[coin(2_000_000, "tema_denom")]);
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-    let msg = ExecuteMsg::Stake { user: None };
+    let msg = ExecuteMsg::Stake { user: None, lock_duration: None };
     let info = mock_info("sender88", &[coin(2_000_000, "tema_denom")]);
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
     //Fake interest
-    let msg = ExecuteMsg::Stake { user: None };
+    let msg = ExecuteMsg::Stake { user: None, lock_duration: None };
     let info = mock_info("sender88", &[coin(1_000_000, "tema_denom")]);
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -1533,6 +1568,119 @@ fn declare_delegates() {
     ]);
 
 }
+#[test]
+fn delegate_participation() {
+    let mut deps = mock_dependencies();
+
+    let msg = InstantiateMsg {
+        owner: Some("owner0000".to_string()),
+        positions_contract: Some("positions_contract".to_string()),
+        auction_contract: Some("auction_contract".to_string()),
+        vesting_contract: Some("vesting_contract".to_string()),
+        governance_contract: Some("gov_contract".to_string()),
+        osmosis_proxy: Some("osmosis_proxy".to_string()),
+        incentive_schedule: Some(StakeDistribution { rate: Decimal::percent(10), duration: 90 }),
+        tema_denom: String::from("tema_denom"),
+        unstaking_period: None,
+    };
+
+    //Instantiating contract
+    let info = mock_info("sender88", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    //Judge delegates on the last 2 proposals
+    let msg = ExecuteMsg::UpdateConfig { 
+        owner: None,
+        unstaking_period: None,
+        osmosis_proxy: None,
+        positions_contract: None,
+        auction_contract: None,
+        governance_contract: None,
+        tema_denom: None,
+        vesting_contract: None,
+        incentive_schedule: None,
+        max_commission_rate: None,
+        keep_raw_cdt: None,
+        vesting_rev_multiplier: None,
+        participation_requirements: Some(ParticipationRequirements { threshold: Decimal::percent(50), window: 2 }),
+        dex_router: None,
+        keeper_tip: None,
+        instant_unstake_penalty: None,
+        stability_pool: None,
+        oracle_contract: None,
+        max_slippage: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner0000", &[]), msg).unwrap();
+
+    //Stake TEMA: sender88
+    let msg = ExecuteMsg::Stake { user: None, lock_duration: None };
+    let info = mock_info("sender88", &[coin(10_000_000, "tema_denom")]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    //Declare delegate: sender99
+    let msg = ExecuteMsg::DeclareDelegate { 
+        delegate_info: Delegate {
+            delegate: Addr::unchecked("sender99"),
+            alias: None,
+            discord_username: None,
+            twitter_username: None,
+            url: None,
+        }, 
+        remove: false
+    };
+    let info = mock_info("sender99", &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    //Fluid delegate TEMA to sender99
+    let msg = ExecuteMsg::UpdateDelegations { 
+        governator_addr: Some(String::from("sender99")), 
+        tema_amount: Some(Uint128::new(5_000_000u128)), 
+        delegate: Some(true), 
+        fluid: Some(true), 
+        voting_power_delegation: None,
+        commission: None,
+    };
+    let info = mock_info("sender88", &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    //Error: Only governance can report
+    let msg = ExecuteMsg::ReportParticipation { proposal_id: 1, voters: vec![] };
+    let info = mock_info("sender88", &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+
+    //Report 2 proposals, sender99 votes on neither
+    let msg = ExecuteMsg::ReportParticipation { proposal_id: 1, voters: vec![String::from("sender88")] };
+    let info = mock_info("gov_contract", &[]);
+    execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    //Not judged until the window is full
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::DelegateParticipation { limit: None, start_after: None, user: Some(String::from("sender99")) }).unwrap();
+    let resp: Vec<DelegateParticipationResponse> = from_binary(&res).unwrap();
+    assert_eq!(resp[0].participation.len(), 1);
+    assert!(!resp[0].penalized);
+
+    let msg = ExecuteMsg::ReportParticipation { proposal_id: 2, voters: vec![] };
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    //Assert participation history
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::DelegateParticipation { limit: None, start_after: None, user: Some(String::from("sender99")) }).unwrap();
+    let resp: Vec<DelegateParticipationResponse> = from_binary(&res).unwrap();
+    assert_eq!(resp, vec![
+        DelegateParticipationResponse {
+            delegate: Addr::unchecked("sender99"),
+            participation: vec![
+                ProposalParticipation { proposal_id: 1, voted: false },
+                ProposalParticipation { proposal_id: 2, voted: false },
+            ],
+            participation_rate: Decimal::zero(),
+            penalized: true,
+        }
+    ]);
+
+    //Fluid delegation was returned to sender88
+    query(deps.as_ref(), mock_env(), QueryMsg::Delegations { limit: None, start_after: None, end_before: None, user: Some(String::from("sender88")) }).unwrap_err();
+}
+
 // #[test]
 // fn claim_rewards() {
 //     let mut deps = mock_dependencies();
@@ -1557,22 +1705,22 @@ fn declare_delegates() {
 //     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
 //     //Successful Stake for User 1
-//     let msg = ExecuteMsg::Stake { user: None };
+//     let msg = ExecuteMsg::Stake { user: None, lock_duration: None };
 //     let info = mock_info("user_1", &[coin(10_000_000, "tema_denom")]);
 //     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
 //     //Successful Stake for User 2
-//     let msg = ExecuteMsg::Stake { user: None };
+//     let msg = ExecuteMsg::Stake { user: None, lock_duration: None };
 //     let info = mock_info("user_2", &[coin(10_000_000, "tema_denom")]);
 //     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
 //     //Successful Stake for User 3
-//     let msg = ExecuteMsg::Stake { user: None };
+//     let msg = ExecuteMsg::Stake { user: None, lock_duration: None };
 //     let info = mock_info("user_3", &[coin(10_000_000, "tema_denom")]);
 //     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
 //     //Successful Stake for User 4
-//     let msg = ExecuteMsg::Stake { user: None };
+//     let msg = ExecuteMsg::Stake { user: None, lock_duration: None };
 //     let info = mock_info("user_4", &[coin(10_000_000, "tema_denom")]);
 //     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
     
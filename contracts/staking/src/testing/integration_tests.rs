@@ -1,11 +1,11 @@
 #[cfg(test)]
 mod tests {
     use membrane::oracle::PriceResponse;
-    use membrane::staking::{ExecuteMsg, InstantiateMsg, QueryMsg, FeeEventsResponse, RewardsResponse};
-    use membrane::types::{AssetInfo, StakeDistribution, Asset, UserInfo, Basket, FeeEvent, LiqAsset, Allocation, VestingPeriod};
+    use membrane::staking::{ExecuteMsg, InstantiateMsg, QueryMsg, RewardIndicesResponse, RewardsResponse};
+    use membrane::types::{AssetInfo, StakeDistribution, Asset, UserInfo, Basket, LiqAsset, Allocation, VestingPeriod, InstantUnstakePenalty, PenaltyRecipient, UnbondingEntry};
 
     use cosmwasm_std::{
-        coin, to_binary, BlockInfo, Addr, Binary, Decimal, Empty, Response, StdResult, Uint128, CosmosMsg, Coin, WasmMsg,
+        coin, to_binary, BlockInfo, Addr, BankMsg, Binary, Decimal, Empty, Response, StdResult, Uint128, CosmosMsg, Coin, WasmMsg,
     };
     use cw_multi_test::{App, AppBuilder, BankKeeper, Contract, ContractWrapper, Executor};
     use membrane::vesting::{RecipientsResponse, RecipientResponse};
@@ -44,6 +44,90 @@ mod tests {
             crate::contract::execute,
             crate::contract::instantiate,
             crate::contract::query,
+        )
+        .with_reply(crate::contract::reply);
+        Box::new(contract)
+    }
+
+    //Mock Router Contract
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    pub enum Router_MockExecuteMsg {
+        BasketLiquidate {
+            minimum_receive: Option<Uint128>,
+            to: Option<String>,
+        },
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    pub struct Router_MockInstantiateMsg {}
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    pub enum Router_MockQueryMsg {}
+
+    pub fn router_contract() -> Box<dyn Contract<Empty>> {
+        let contract = ContractWrapper::new(
+            |deps, _, info, msg: Router_MockExecuteMsg| -> StdResult<Response> {
+                match msg {
+                    //Swaps 1:1 for TEMA
+                    Router_MockExecuteMsg::BasketLiquidate { .. } => {
+                        Ok(Response::new().add_message(BankMsg::Send {
+                            to_address: info.sender.to_string(),
+                            amount: vec![coin(info.funds[0].amount.u128(), "tema_denom")],
+                        }))
+                    }
+                }
+            },
+            |_, _, _, _: Router_MockInstantiateMsg| -> StdResult<Response> {
+                Ok(Response::default())
+            },
+            |_, _, msg: Router_MockQueryMsg| -> StdResult<Binary> {
+                Ok(to_binary(&MockResponse {})?)
+            },
+        );
+        Box::new(contract)
+    }
+
+    //Mock Oracle Contract
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    pub enum Oracle_MockExecuteMsg {}
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    pub struct Oracle_MockInstantiateMsg {}
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    pub enum Oracle_MockQueryMsg {
+        Price {
+            asset_info: AssetInfo,
+            twap_timeframe: u64,
+            oracle_time_limit: u64,
+            basket_id: Option<Uint128>,
+        },
+    }
+
+    pub fn oracle_contract() -> Box<dyn Contract<Empty>> {
+        let contract = ContractWrapper::new(
+            |_, _, _, _: Oracle_MockExecuteMsg| -> StdResult<Response> {
+                Ok(Response::default())
+            },
+            |_, _, _, _: Oracle_MockInstantiateMsg| -> StdResult<Response> {
+                Ok(Response::default())
+            },
+            |_, _, msg: Oracle_MockQueryMsg| -> StdResult<Binary> {
+                match msg {
+                    //Every asset is $1
+                    Oracle_MockQueryMsg::Price { .. } => Ok(to_binary(&PriceResponse {
+                        prices: vec![],
+                        price: Decimal::one(),
+                        decimals: 6,
+                    })?),
+                }
+            },
         );
         Box::new(contract)
     }
@@ -56,7 +140,16 @@ mod tests {
             denom: String,
             amount: Uint128,
             mint_to_address: String,
-        }
+        },
+        CreateDenom {
+            subdenom: String,
+            max_supply: Option<Uint128>,
+        },
+        BurnTokens {
+            denom: String,
+            amount: Uint128,
+            burn_from_address: String,
+        },
     }
     
     #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
@@ -73,7 +166,7 @@ mod tests {
 
     pub fn osmosis_proxy_contract() -> Box<dyn Contract<Empty>> {
         let contract = ContractWrapper::new(
-            |deps, _, info, msg: Osmo_MockExecuteMsg| -> StdResult<Response> {
+            |deps, env, info, msg: Osmo_MockExecuteMsg| -> StdResult<Response> {
                 match msg {
                     Osmo_MockExecuteMsg::MintTokens {
                         denom,
@@ -89,8 +182,18 @@ mod tests {
                         && (amount != Uint128::new(4109) || denom != String::from("tema_denom") || mint_to_address != String::from("governator_addr")){
                             // panic!("MintTokens called with incorrect parameters, {}, {}, {}", amount, denom, mint_to_address);
                         }
+                        //Mint TEMA to the staking contract if the proxy is funded
+                        let balance = deps.querier.query_balance(env.contract.address, denom.clone())?.amount;
+                        if denom == "tema_denom" && mint_to_address == "contract4" && !amount.is_zero() && balance >= amount {
+                            return Ok(Response::new().add_message(BankMsg::Send {
+                                to_address: mint_to_address,
+                                amount: vec![coin(amount.u128(), denom)],
+                            }));
+                        }
                         Ok(Response::default())
                     }
+                    Osmo_MockExecuteMsg::CreateDenom { .. } => Ok(Response::default()),
+                    Osmo_MockExecuteMsg::BurnTokens { .. } => Ok(Response::default()),
                 }
             },
             |_, _, _, _: Osmo_MockInstantiateMsg| -> StdResult<Response> {
@@ -259,7 +362,7 @@ mod tests {
             bank.init_balance(
                 storage,
                 &Addr::unchecked("user_1"),
-                vec![coin(10_000_000, "tema_denom")],
+                vec![coin(10_000_000, "tema_denom"), coin(1_000_000, "factory/contract0/stema")], //Receipts the mock proxy doesn't mint
             )
             .unwrap();
             bank.init_balance(
@@ -361,14 +464,14 @@ mod tests {
         use std::str::FromStr;
 
         use super::*;
-        use membrane::staking::{TotalStakedResponse, StakerResponse};
+        use membrane::staking::{AutoCompoundResponse, StakeCheckpointResponse, UnbondingResponse, TotalStakedResponse, StakerResponse, StakedResponse, LiquidStakeResponse, UserLocksResponse, MAX_LOCK_DAYS};
         
         #[test]
         fn commission_claims() {
             let (mut app, staking_contract, auction_contract) = proper_instantiate();
 
             //Stake TEMA as user
-            let msg = ExecuteMsg::Stake { user: None };
+            let msg = ExecuteMsg::Stake { user: None, lock_duration: None };
             let cosmos_msg = staking_contract.call(msg, vec![coin(10_000000, "tema_denom")]).unwrap();
             app.execute(Addr::unchecked("user_1"), cosmos_msg).unwrap();
 
@@ -568,7 +671,7 @@ mod tests {
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap_err();
 
             //Stake TEMA as user
-            let msg = ExecuteMsg::Stake { user: None };
+            let msg = ExecuteMsg::Stake { user: None, lock_duration: None };
             let cosmos_msg = staking_contract.call(msg, vec![coin(1_000_000, "tema_denom")]).unwrap();
             app.execute(Addr::unchecked("user_1"), cosmos_msg).unwrap();
 
@@ -595,36 +698,27 @@ mod tests {
             let cosmos_msg = staking_contract.call(msg, vec![coin(1000, "fee_asset")]).unwrap();
             app.execute(auction_contract, cosmos_msg).unwrap();
 
-            //Assert FeeEventsResponse
-            let resp: FeeEventsResponse = app
+            //Assert RewardIndicesResponse
+            let resp: RewardIndicesResponse = app
                 .wrap()
                 .query_wasm_smart(
                     staking_contract.addr(),
-                    &QueryMsg::FeeEvents {
-                        limit: None,
-                        start_after: None,
-                    },
+                    &QueryMsg::RewardIndices {},
                 )
                 .unwrap();
-            assert_eq!(resp.fee_events, vec![
-                FeeEvent {
-                    time_of_event: 1571797420,
-                    fee: LiqAsset {
-                        info: AssetInfo::NativeToken {
-                            denom: String::from("credit_fulldenom")
-                        },
-                        amount: Decimal::from_str("0.000833333333333333").unwrap(), 
+            assert_eq!(resp.reward_indices, vec![
+                LiqAsset {
+                    info: AssetInfo::NativeToken {
+                        denom: String::from("credit_fulldenom")
                     },
+                    amount: Decimal::from_str("0.000833333333333333").unwrap(), 
                 },
-                FeeEvent {
-                    time_of_event: 1571797420,
-                    fee: LiqAsset {
-                        info: AssetInfo::NativeToken {
-                            denom: String::from("fee_asset")
-                        },
-                        amount: Decimal::from_str("0.000833333333333333").unwrap(), 
+                LiqAsset {
+                    info: AssetInfo::NativeToken {
+                        denom: String::from("fee_asset")
                     },
-                }
+                    amount: Decimal::from_str("0.000833333333333333").unwrap(), 
+                },
             ]);
 
             //Add staking rewards
@@ -738,23 +832,771 @@ mod tests {
 
             //Assert that the stake was restaked
             assert_eq!(resp_before.total_staked + Uint128::new(8_219), resp_after.total_staked);
+        }
+
+        #[test]
+        fn reward_index_snapshots() {
+            let (mut app, staking_contract, _auction_contract) = proper_instantiate();
+
+            //Stake TEMA as user
+            let msg = ExecuteMsg::Stake { user: None, lock_duration: None };
+            let cosmos_msg = staking_contract.call(msg, vec![coin(1_000_000, "tema_denom")]).unwrap();
+            app.execute(Addr::unchecked("user_1"), cosmos_msg).unwrap();
+
+            //DepositFees
+            let msg = ExecuteMsg::DepositFee {  };
+            let cosmos_msg = staking_contract.call(msg, vec![coin(1000, "credit_fulldenom")]).unwrap();
+            app.execute(Addr::unchecked("contract1"), cosmos_msg).unwrap();
+
+            //Stake again, the new stake doesn't earn the previous fees
+            let msg = ExecuteMsg::Stake { user: None, lock_duration: None };
+            let cosmos_msg = staking_contract.call(msg, vec![coin(1_000_000, "tema_denom")]).unwrap();
+            app.execute(Addr::unchecked("user_1"), cosmos_msg).unwrap();
+
+            //Assert User Claims are the 1st stake's share, split across both deposits
+            let resp: RewardsResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    staking_contract.addr(),
+                    &QueryMsg::UserRewards {
+                        user: String::from("user_1"),
+                    }
+                )
+                .unwrap();
+            assert_eq!(resp.claimables, vec![
+                Asset {
+                    amount: Uint128::new(416),
+                    info: AssetInfo::NativeToken { denom: String::from("credit_fulldenom") },
+                },
+                Asset {
+                    amount: Uint128::new(416),
+                    info: AssetInfo::NativeToken { denom: String::from("credit_fulldenom") },
+                },
+            ]);
+
+            //Claim
+            let claim_msg = ExecuteMsg::ClaimRewards {
+                send_to: None,
+                restake: false,
+            };
+            let cosmos_msg = staking_contract.call(claim_msg, vec![]).unwrap();
+            app.execute(Addr::unchecked("user_1"), cosmos_msg).unwrap();
+
+            //Claims are reset to the current indices
+            let resp: RewardsResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    staking_contract.addr(),
+                    &QueryMsg::UserRewards {
+                        user: String::from("user_1"),
+                    }
+                )
+                .unwrap();
+            assert_eq!(resp.claimables, vec![]);
+        }
+
+        #[test]
+        fn locked_stake() {
+            let (mut app, staking_contract, _auction_contract) = proper_instantiate();
+
+            //Error: Lock longer than the max
+            let msg = ExecuteMsg::Stake { user: None, lock_duration: Some(MAX_LOCK_DAYS + 1) };
+            let cosmos_msg = staking_contract.call(msg, vec![coin(1_000_000, "tema_denom")]).unwrap();
+            app.execute(Addr::unchecked("user_1"), cosmos_msg).unwrap_err();
+
+            //Stake & lock TEMA for the max duration
+            let msg = ExecuteMsg::Stake { user: None, lock_duration: Some(MAX_LOCK_DAYS) };
+            let cosmos_msg = staking_contract.call(msg, vec![coin(1_000_000, "tema_denom")]).unwrap();
+            app.execute(Addr::unchecked("user_1"), cosmos_msg).unwrap();
+
+            //Assert lock schedule
+            let resp: UserLocksResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    staking_contract.addr(),
+                    &QueryMsg::UserLocks {
+                        user: String::from("user_1"),
+                    }
+                )
+                .unwrap();
+            assert_eq!(resp.total_locked, Uint128::new(1_000_000));
+            assert_eq!(resp.locks[0].voting_multiplier, Decimal::percent(200));
+            assert_eq!(resp.locks[0].fee_multiplier, Decimal::percent(150));
+
+            //DepositFees: 1M staked + 500k lock boost + 200k vesting
+            let msg = ExecuteMsg::DepositFee {  };
+            let cosmos_msg = staking_contract.call(msg, vec![coin(1700, "credit_fulldenom")]).unwrap();
+            app.execute(Addr::unchecked("contract1"), cosmos_msg).unwrap();
+
+            //Assert User Claims include the lock boost
+            let resp: RewardsResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    staking_contract.addr(),
+                    &QueryMsg::UserRewards {
+                        user: String::from("user_1"),
+                    }
+                )
+                .unwrap();
+            assert_eq!(resp.claimables, vec![
+                Asset {
+                    amount: Uint128::new(1500),
+                    info: AssetInfo::NativeToken { denom: String::from("credit_fulldenom") },
+                },
+            ]);
+
+            //Error: Locked stake can't be unstaked
+            let msg = ExecuteMsg::Unstake {
+                tema_amount: None,
+            };
+            let cosmos_msg = staking_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked("user_1"), cosmos_msg).unwrap_err();
+
+            //Multipliers decay halfway
+            app.set_block(BlockInfo {
+                height: app.block_info().height,
+                time: app.block_info().time.plus_seconds(86_400u64 * (MAX_LOCK_DAYS / 2)),
+                chain_id: app.block_info().chain_id,
+            });
+            let resp: UserLocksResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    staking_contract.addr(),
+                    &QueryMsg::UserLocks {
+                        user: String::from("user_1"),
+                    }
+                )
+                .unwrap();
+            assert_eq!(resp.locks[0].voting_multiplier, Decimal::percent(150));
+            assert_eq!(resp.locks[0].fee_multiplier, Decimal::percent(125));
+
+            //Unlock
+            app.set_block(BlockInfo {
+                height: app.block_info().height,
+                time: app.block_info().time.plus_seconds(86_400u64 * (MAX_LOCK_DAYS / 2)),
+                chain_id: app.block_info().chain_id,
+            });
+            let resp: UserLocksResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    staking_contract.addr(),
+                    &QueryMsg::UserLocks {
+                        user: String::from("user_1"),
+                    }
+                )
+                .unwrap();
+            assert_eq!(resp.total_locked, Uint128::zero());
+
+            //Anyone can refresh the expired lock so it stops earning boosted fees
+            let msg = ExecuteMsg::RefreshLocks { stakers: vec![String::from("user_1")] };
+            let cosmos_msg = staking_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked("keeper"), cosmos_msg).unwrap();
+
+            //DepositFees: 1M staked + 200k vesting
+            let msg = ExecuteMsg::DepositFee {  };
+            let cosmos_msg = staking_contract.call(msg, vec![coin(1200, "credit_fulldenom")]).unwrap();
+            app.execute(Addr::unchecked("contract1"), cosmos_msg).unwrap();
+
+            //Boost fees from before the refresh are kept
+            let resp: RewardsResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    staking_contract.addr(),
+                    &QueryMsg::UserRewards {
+                        user: String::from("user_1"),
+                    }
+                )
+                .unwrap();
+            assert_eq!(resp.claimables, vec![
+                Asset {
+                    amount: Uint128::new(2500),
+                    info: AssetInfo::NativeToken { denom: String::from("credit_fulldenom") },
+                },
+            ]);
+
+            //Successful Unstake
+            let msg = ExecuteMsg::Unstake {
+                tema_amount: None,
+            };
+            let cosmos_msg = staking_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked("user_1"), cosmos_msg).unwrap();
+        }
+
+        #[test]
+        fn instant_unstake() {
+            let (mut app, staking_contract, _auction_contract) = proper_instantiate();
+
+            //Stake TEMA as users
+            let msg = ExecuteMsg::Stake { user: None, lock_duration: None };
+            let cosmos_msg = staking_contract.call(msg, vec![coin(1_000_000, "tema_denom")]).unwrap();
+            app.execute(Addr::unchecked("user_1"), cosmos_msg).unwrap();
+            let msg = ExecuteMsg::Stake { user: None, lock_duration: None };
+            let cosmos_msg = staking_contract.call(msg, vec![coin(1_000_000, "tema_denom")]).unwrap();
+            app.execute(Addr::unchecked("coin_God"), cosmos_msg).unwrap();
+
+            //Start unstaking
+            let msg = ExecuteMsg::Unstake { tema_amount: None };
+            let cosmos_msg = staking_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked("user_1"), cosmos_msg).unwrap();
+
+            //Error: Instant unstaking is disabled
+            let msg = ExecuteMsg::InstantUnstake {};
+            let cosmos_msg = staking_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked("user_1"), cosmos_msg).unwrap_err();
+
+            //Set penalty
+            let msg = ExecuteMsg::UpdateConfig { 
+                owner: None,
+                unstaking_period: None,
+                osmosis_proxy: None,
+                positions_contract: None,
+                auction_contract: None,
+                governance_contract: None,
+                tema_denom: None,
+                vesting_contract: None,
+                incentive_schedule: None,
+                max_commission_rate: None,
+                keep_raw_cdt: None,
+                vesting_rev_multiplier: None,
+                participation_requirements: None,
+                dex_router: None,
+                keeper_tip: None,
+                instant_unstake_penalty: Some(InstantUnstakePenalty {
+                    max_penalty: Decimal::percent(10),
+                    recipient: PenaltyRecipient::Stakers,
+                }),
+                stability_pool: None,
+                oracle_contract: None,
+                max_slippage: None,
+            };
+            let cosmos_msg = staking_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            //Query unbonding entries
+            let unstake_start_time = app.block_info().time.seconds();
+            let resp: Vec<UnbondingResponse> = app
+                .wrap()
+                .query_wasm_smart(
+                    staking_contract.addr(),
+                    &QueryMsg::Unbonding {
+                        limit: None,
+                        start_after: None,
+                        user: None,
+                    },
+                )
+                .unwrap();
+            assert_eq!(resp, vec![UnbondingResponse {
+                user: String::from("user_1"),
+                entries: vec![UnbondingEntry {
+                    amount: Uint128::new(1_000_000),
+                    unstake_start_time,
+                    maturity_time: unstake_start_time + 86_400u64 * 4,
+                    instant_unstake_penalty: Uint128::new(100_000),
+                }],
+            }]);
+
+            //Penalty decays halfway through the unstaking period
+            app.set_block(BlockInfo {
+                height: app.block_info().height,
+                time: app.block_info().time.plus_seconds(86_400u64 * 2),
+                chain_id: app.block_info().chain_id,
+            });
+
+            //Instant unstake
+            let msg = ExecuteMsg::InstantUnstake {};
+            let cosmos_msg = staking_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked("user_1"), cosmos_msg).unwrap();
+
+            //User received their stake minus the 5% penalty
+            assert_eq!(
+                app.wrap().query_balance("user_1", "tema_denom").unwrap().amount,
+                Uint128::new(9_950_000)
+            );
+
+            //Penalty is distributed to stakers: 1M staked + 200k vesting
+            let resp: RewardsResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    staking_contract.addr(),
+                    &QueryMsg::UserRewards {
+                        user: String::from("coin_God"),
+                    },
+                )
+                .unwrap();
+            assert_eq!(resp.claimables, vec![
+                Asset {
+                    amount: Uint128::new(41_666),
+                    info: AssetInfo::NativeToken { denom: String::from("tema_denom") },
+                },
+            ]);
+
+            //Error: Nothing left unstaking
+            let msg = ExecuteMsg::InstantUnstake {};
+            let cosmos_msg = staking_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked("user_1"), cosmos_msg).unwrap_err();
+
+            //Error: Penalties to stakers aren't batched for the Stability Pool
+            let msg = ExecuteMsg::StreamPenalties {};
+            let cosmos_msg = staking_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked("keeper"), cosmos_msg).unwrap_err();
+        }
+
+        #[test]
+        fn stake_checkpoints() {
+            let (mut app, staking_contract, _auction_contract) = proper_instantiate();
+
+            //Stake TEMA as users
+            let stake_time = app.block_info().time.seconds();
+            let msg = ExecuteMsg::Stake { user: None, lock_duration: None };
+            let cosmos_msg = staking_contract.call(msg, vec![coin(1_000_000, "tema_denom")]).unwrap();
+            app.execute(Addr::unchecked("user_1"), cosmos_msg).unwrap();
+            let msg = ExecuteMsg::Stake { user: None, lock_duration: None };
+            let cosmos_msg = staking_contract.call(msg, vec![coin(1_000_000, "tema_denom")]).unwrap();
+            app.execute(Addr::unchecked("coin_God"), cosmos_msg).unwrap();
+
+            app.set_block(BlockInfo {
+                height: app.block_info().height,
+                time: app.block_info().time.plus_seconds(100u64),
+                chain_id: app.block_info().chain_id,
+            });
 
-            //Trim fee events since all were used & claimed
-            let msg = ExecuteMsg::TrimFeeEvents { };
+            //Unstake part of user_1's stake
+            let unstake_time = app.block_info().time.seconds();
+            let msg = ExecuteMsg::Unstake { tema_amount: Some(Uint128::new(400_000)) };
+            let cosmos_msg = staking_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked("user_1"), cosmos_msg).unwrap();
+
+            app.set_block(BlockInfo {
+                height: app.block_info().height,
+                time: app.block_info().time.plus_seconds(100u64),
+                chain_id: app.block_info().chain_id,
+            });
+
+            //Query user_1's stake over time
+            let stake_at = |time: Option<u64>| -> Uint128 {
+                app.wrap()
+                    .query_wasm_smart(
+                        staking_contract.addr(),
+                        &QueryMsg::StakeAt {
+                            user: String::from("user_1"),
+                            time,
+                        },
+                    )
+                    .unwrap()
+            };
+            //Checkpoints hold the stake before changes made at the queried time
+            assert_eq!(stake_at(Some(stake_time)), Uint128::zero());
+            assert_eq!(stake_at(Some(stake_time + 1)), Uint128::new(1_000_000));
+            assert_eq!(stake_at(Some(unstake_time)), Uint128::new(1_000_000));
+            assert_eq!(stake_at(Some(unstake_time + 1)), Uint128::new(600_000));
+            assert_eq!(stake_at(None), Uint128::new(600_000));
+
+            //Query total stake over time
+            let total_at = |time: Option<u64>, quadratic: bool| -> Uint128 {
+                app.wrap()
+                    .query_wasm_smart(
+                        staking_contract.addr(),
+                        &QueryMsg::TotalStakeAt { time, quadratic: Some(quadratic) },
+                    )
+                    .unwrap()
+            };
+            assert_eq!(total_at(Some(stake_time + 1), false), Uint128::new(2_000_000));
+            assert_eq!(total_at(None, false), Uint128::new(1_600_000));
+            //1000 + 1000
+            assert_eq!(total_at(Some(stake_time + 1), true), Uint128::new(2_000));
+            //775 + 1000
+            assert_eq!(total_at(None, true), Uint128::new(1_775));
+
+            //Query all checkpoints before the unstake
+            let resp: Vec<StakeCheckpointResponse> = app
+                .wrap()
+                .query_wasm_smart(
+                    staking_contract.addr(),
+                    &QueryMsg::StakeCheckpoints {
+                        time: Some(unstake_time),
+                        limit: None,
+                        start_after: None,
+                    },
+                )
+                .unwrap();
+            assert_eq!(resp, vec![
+                StakeCheckpointResponse {
+                    user: String::from("coin_God"),
+                    stake: Uint128::new(1_000_000),
+                },
+                StakeCheckpointResponse {
+                    user: String::from("user_1"),
+                    stake: Uint128::new(1_000_000),
+                },
+            ]);
+
+            //Locks add their voting boost to the checkpoint
+            let lock_time = app.block_info().time.seconds();
+            let msg = ExecuteMsg::Stake { user: None, lock_duration: Some(MAX_LOCK_DAYS) };
+            let cosmos_msg = staking_contract.call(msg, vec![coin(1_000_000, "tema_denom")]).unwrap();
+            app.execute(Addr::unchecked("coin_God"), cosmos_msg).unwrap();
+
+            let god_stake_at = |app: &App, time: Option<u64>| -> Uint128 {
+                app.wrap()
+                    .query_wasm_smart(
+                        staking_contract.addr(),
+                        &QueryMsg::StakeAt {
+                            user: String::from("coin_God"),
+                            time,
+                        },
+                    )
+                    .unwrap()
+            };
+            //2M staked + 1M boost
+            assert_eq!(god_stake_at(&app, None), Uint128::new(3_000_000));
+
+            //The decayed boost is checkpointed on refresh, earlier checkpoints don't change
+            app.set_block(BlockInfo {
+                height: app.block_info().height,
+                time: app.block_info().time.plus_seconds(86_400u64 * (MAX_LOCK_DAYS / 2)),
+                chain_id: app.block_info().chain_id,
+            });
+            assert_eq!(god_stake_at(&app, None), Uint128::new(3_000_000));
+
+            let msg = ExecuteMsg::RefreshLocks { stakers: vec![String::from("coin_God")] };
+            let cosmos_msg = staking_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked("keeper"), cosmos_msg).unwrap();
+
+            assert_eq!(god_stake_at(&app, None), Uint128::new(2_500_000));
+            assert_eq!(god_stake_at(&app, Some(lock_time + 1)), Uint128::new(3_000_000));
+        }
+
+        #[test]
+        fn auto_compound() {
+            let (mut app, staking_contract, _auction_contract) = proper_instantiate();
+
+            //Instantiate & fund Router
+            let router_id = app.store_code(router_contract());
+            let router_contract_addr = app
+                .instantiate_contract(
+                    router_id,
+                    Addr::unchecked(ADMIN),
+                    &Router_MockInstantiateMsg {},
+                    &[],
+                    "test",
+                    None,
+                )
+                .unwrap();
+            app.send_tokens(Addr::unchecked("coin_God"), router_contract_addr.clone(), &[coin(1_000_000, "tema_denom")]).unwrap();
+
+            //Instantiate Oracle
+            let oracle_id = app.store_code(oracle_contract());
+            let oracle_contract_addr = app
+                .instantiate_contract(
+                    oracle_id,
+                    Addr::unchecked(ADMIN),
+                    &Oracle_MockInstantiateMsg {},
+                    &[],
+                    "test",
+                    None,
+                )
+                .unwrap();
+
+            //Error: Keeper tip above the max
+            let msg = ExecuteMsg::UpdateConfig { 
+                owner: None,
+                unstaking_period: None,
+                osmosis_proxy: None,
+                positions_contract: None,
+                auction_contract: None,
+                governance_contract: None,
+                tema_denom: None,
+                vesting_contract: None,
+                incentive_schedule: None,
+                max_commission_rate: None,
+                keep_raw_cdt: None,
+                vesting_rev_multiplier: None,
+                participation_requirements: None,
+                dex_router: Some(router_contract_addr.to_string()),
+                keeper_tip: Some(Decimal::percent(6)),
+                instant_unstake_penalty: None,
+                stability_pool: None,
+                oracle_contract: None,
+                max_slippage: None,
+            };
+            let cosmos_msg = staking_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap_err();
+
+            //Set router & tip
+            let msg = ExecuteMsg::UpdateConfig { 
+                owner: None,
+                unstaking_period: None,
+                osmosis_proxy: None,
+                positions_contract: None,
+                auction_contract: None,
+                governance_contract: None,
+                tema_denom: None,
+                vesting_contract: None,
+                incentive_schedule: None,
+                max_commission_rate: None,
+                keep_raw_cdt: None,
+                vesting_rev_multiplier: None,
+                participation_requirements: None,
+                dex_router: Some(router_contract_addr.to_string()),
+                keeper_tip: Some(Decimal::percent(2)),
+                instant_unstake_penalty: None,
+                stability_pool: None,
+                oracle_contract: Some(oracle_contract_addr.to_string()),
+                max_slippage: Some(Decimal::percent(1)),
+            };
             let cosmos_msg = staking_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
-            //Query FeeEvents
-            let resp: FeeEventsResponse = app
+
+            //Stake TEMA as user
+            let msg = ExecuteMsg::Stake { user: None, lock_duration: None };
+            let cosmos_msg = staking_contract.call(msg, vec![coin(1_000_000, "tema_denom")]).unwrap();
+            app.execute(Addr::unchecked("user_1"), cosmos_msg).unwrap();
+
+            //DepositFees: 1M staked + 200k vesting
+            let msg = ExecuteMsg::DepositFee {  };
+            let cosmos_msg = staking_contract.call(msg, vec![coin(1000, "credit_fulldenom")]).unwrap();
+            app.execute(Addr::unchecked("contract1"), cosmos_msg).unwrap();
+
+            //Error: User hasn't opted in
+            let msg = ExecuteMsg::AutoCompound { user: String::from("user_1") };
+            let cosmos_msg = staking_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked("keeper"), cosmos_msg).unwrap_err();
+
+            //Opt in
+            let msg = ExecuteMsg::SetAutoCompound { enabled: true };
+            let cosmos_msg = staking_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked("user_1"), cosmos_msg).unwrap();
+
+            //Auto-compound
+            let msg = ExecuteMsg::AutoCompound { user: String::from("user_1") };
+            let cosmos_msg = staking_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked("keeper"), cosmos_msg).unwrap();
+
+            //Keeper is tipped 2% of the 833 swapped TEMA
+            assert_eq!(
+                app.wrap().query_balance("keeper", "tema_denom").unwrap().amount,
+                Uint128::new(16)
+            );
+
+            //Assert the rest is restaked
+            let resp: StakerResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    staking_contract.addr(),
+                    &QueryMsg::UserStake {
+                        staker: String::from("user_1"),
+                    },
+                )
+                .unwrap();
+            assert_eq!(resp.total_staked, Uint128::new(1_000_817));
+
+            //Error: Compounded too recently
+            let msg = ExecuteMsg::AutoCompound { user: String::from("user_1") };
+            let cosmos_msg = staking_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked("keeper"), cosmos_msg).unwrap_err();
+
+            //Query auto-compounders
+            let resp: Vec<AutoCompoundResponse> = app
+                .wrap()
+                .query_wasm_smart(
+                    staking_contract.addr(),
+                    &QueryMsg::AutoCompounders {
+                        limit: None,
+                        start_after: None,
+                    },
+                )
+                .unwrap();
+            assert_eq!(resp, vec![AutoCompoundResponse {
+                user: String::from("user_1"),
+                last_compound: app.block_info().time.seconds(),
+            }]);
+
+            //Liquid stake
+            let msg = ExecuteMsg::CreateReceiptDenom { subdenom: String::from("stema") };
+            let cosmos_msg = staking_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+            let msg = ExecuteMsg::LiquidStake {};
+            let cosmos_msg = staking_contract.call(msg, vec![coin(1_000_000, "tema_denom")]).unwrap();
+            app.execute(Addr::unchecked("user_1"), cosmos_msg).unwrap();
+
+            //DepositFees & accrue TEMA incentives
+            let msg = ExecuteMsg::DepositFee {  };
+            let cosmos_msg = staking_contract.call(msg, vec![coin(1000, "credit_fulldenom")]).unwrap();
+            app.execute(Addr::unchecked("contract1"), cosmos_msg).unwrap();
+            app.update_block(|block| block.time = block.time.plus_seconds(86_400 * 7));
+            //Fund the proxy so incentives are minted
+            app.send_tokens(Addr::unchecked("coin_God"), Addr::unchecked("contract0"), &[coin(1_000_000, "tema_denom")]).unwrap();
+
+            let rewards: RewardsResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    staking_contract.addr(),
+                    &QueryMsg::UserRewards {
+                        user: staking_contract.addr().to_string(),
+                    },
+                )
+                .unwrap();
+            assert!(!rewards.accrued_interest.is_zero());
+            assert_eq!(rewards.claimables.len(), 1);
+            let swapped = rewards.claimables[0].amount;
+            let pool_before: LiquidStakeResponse = app
+                .wrap()
+                .query_wasm_smart(staking_contract.addr(), &QueryMsg::LiquidStake {})
+                .unwrap();
+
+            //Auto-compound the liquid pool
+            let msg = ExecuteMsg::AutoCompound { user: staking_contract.addr().to_string() };
+            let cosmos_msg = staking_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked("keeper"), cosmos_msg).unwrap();
+
+            //Pool's stake grows by exactly its incentives & swapped fees, less the tip
+            let tip = swapped * Decimal::percent(2);
+            let pool_after: LiquidStakeResponse = app
+                .wrap()
+                .query_wasm_smart(staking_contract.addr(), &QueryMsg::LiquidStake {})
+                .unwrap();
+            assert_eq!(pool_after.total_staked, pool_before.total_staked + rewards.accrued_interest + swapped - tip);
+            assert_eq!(
+                app.wrap().query_balance("keeper", "tema_denom").unwrap().amount,
+                Uint128::new(16) + tip
+            );
+
+            //Held fees are priced into new receipts before they're swapped & restaked
+            let msg = ExecuteMsg::DepositFee {  };
+            let cosmos_msg = staking_contract.call(msg, vec![coin(1000, "credit_fulldenom")]).unwrap();
+            app.execute(Addr::unchecked("contract1"), cosmos_msg).unwrap();
+            let rewards: RewardsResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    staking_contract.addr(),
+                    &QueryMsg::UserRewards {
+                        user: staking_contract.addr().to_string(),
+                    },
+                )
+                .unwrap();
+            let held_fees = rewards.claimables[0].amount;
+            assert!(!held_fees.is_zero());
+
+            let msg = ExecuteMsg::LiquidStake {};
+            let cosmos_msg = staking_contract.call(msg, vec![coin(1_000_000, "tema_denom")]).unwrap();
+            app.execute(Addr::unchecked("coin_God"), cosmos_msg).unwrap();
+
+            //Oracle & router are 1:1
+            let pool: LiquidStakeResponse = app
+                .wrap()
+                .query_wasm_smart(staking_contract.addr(), &QueryMsg::LiquidStake {})
+                .unwrap();
+            assert_eq!(
+                pool.receipt_supply - pool_after.receipt_supply,
+                Uint128::new(1_000_000).multiply_ratio(
+                    pool_after.receipt_supply,
+                    pool_after.total_staked + rewards.accrued_interest + held_fees
+                )
+            );
+        }
+
+        #[test]
+        fn liquid_stake() {
+            let (mut app, staking_contract, _auction_contract) = proper_instantiate();
+
+            //Error: Receipt denom isn't created
+            let msg = ExecuteMsg::LiquidStake {};
+            let cosmos_msg = staking_contract.call(msg, vec![coin(1_000_000, "tema_denom")]).unwrap();
+            app.execute(Addr::unchecked("user_1"), cosmos_msg).unwrap_err();
+
+            //Error: Unauthorized
+            let msg = ExecuteMsg::CreateReceiptDenom { subdenom: String::from("stema") };
+            let cosmos_msg = staking_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked("user_1"), cosmos_msg).unwrap_err();
+
+            //Create receipt denom
+            let msg = ExecuteMsg::CreateReceiptDenom { subdenom: String::from("stema") };
+            let cosmos_msg = staking_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            //Liquid stake
+            let msg = ExecuteMsg::LiquidStake {};
+            let cosmos_msg = staking_contract.call(msg, vec![coin(1_000_000, "tema_denom")]).unwrap();
+            app.execute(Addr::unchecked("user_1"), cosmos_msg).unwrap();
+
+            //Query pool
+            let resp: LiquidStakeResponse = app
                 .wrap()
                 .query_wasm_smart(
                     staking_contract.addr(),
-                    &QueryMsg::FeeEvents {
+                    &QueryMsg::LiquidStake {},
+                )
+                .unwrap();
+            assert_eq!(resp.receipt_denom, String::from("factory/contract0/stema"));
+            assert_eq!(resp.receipt_supply, Uint128::new(1_000_000));
+            assert_eq!(resp.total_staked, Uint128::new(1_000_000));
+            assert_eq!(resp.exchange_rate, Decimal::one());
+
+            //Pool's stake doesn't vote
+            let resp: Uint128 = app
+                .wrap()
+                .query_wasm_smart(
+                    staking_contract.addr(),
+                    &QueryMsg::StakeAt {
+                        user: staking_contract.addr().to_string(),
+                        time: None,
+                    },
+                )
+                .unwrap();
+            assert_eq!(resp, Uint128::zero());
+            let resp: Uint128 = app
+                .wrap()
+                .query_wasm_smart(
+                    staking_contract.addr(),
+                    &QueryMsg::TotalStakeAt { time: None, quadratic: None },
+                )
+                .unwrap();
+            assert_eq!(resp, Uint128::zero());
+
+            //Pool's stake isn't in the Staked list
+            let resp: StakedResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    staking_contract.addr(),
+                    &QueryMsg::Staked {
                         limit: None,
                         start_after: None,
+                        end_before: None,
+                        unstaking: false,
                     },
                 )
                 .unwrap();
-            assert_eq!(resp.fee_events.len(), 0 as usize);
+            assert_eq!(resp.stakers.len(), 0);
+
+            //Liquid unstake half
+            let msg = ExecuteMsg::LiquidUnstake {};
+            let cosmos_msg = staking_contract.call(msg, vec![coin(500_000, "factory/contract0/stema")]).unwrap();
+            app.execute(Addr::unchecked("user_1"), cosmos_msg).unwrap();
+
+            //Assert unstaking deposit
+            let resp: StakerResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    staking_contract.addr(),
+                    &QueryMsg::UserStake {
+                        staker: String::from("user_1"),
+                    },
+                )
+                .unwrap();
+            assert_eq!(resp.deposit_list.len(), 1);
+            assert_eq!(resp.deposit_list[0].amount, Uint128::new(500_000));
+            assert!(resp.deposit_list[0].unstake_start_time.is_some());
+
+            //Query pool
+            let resp: LiquidStakeResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    staking_contract.addr(),
+                    &QueryMsg::LiquidStake {},
+                )
+                .unwrap();
+            assert_eq!(resp.receipt_supply, Uint128::new(500_000));
+            assert_eq!(resp.total_staked, Uint128::new(500_000));
         }
 
         #[test]
@@ -762,7 +1604,7 @@ mod tests {
             let (mut app, staking_contract, auction_contract) = proper_instantiate();
 
             //Stake TEMA as user
-            let msg = ExecuteMsg::Stake { user: None };
+            let msg = ExecuteMsg::Stake { user: None, lock_duration: None };
             let cosmos_msg = staking_contract.call(msg, vec![coin(1_000_000, "tema_denom")]).unwrap();
             app.execute(Addr::unchecked("user_1"), cosmos_msg).unwrap();
             
@@ -792,6 +1634,13 @@ mod tests {
                 max_commission_rate: None,
                 keep_raw_cdt: None,
                 vesting_rev_multiplier: Some(Decimal::percent(50)),
+                participation_requirements: None,
+                dex_router: None,
+                keeper_tip: None,
+                instant_unstake_penalty: None,
+                stability_pool: None,
+                oracle_contract: None,
+                max_slippage: None,
             };
             let cosmos_msg = staking_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -867,6 +1716,13 @@ mod tests {
                 max_commission_rate: None,
                 keep_raw_cdt: None,
                 vesting_rev_multiplier: Some(Decimal::zero()),
+                participation_requirements: None,
+                dex_router: None,
+                keeper_tip: None,
+                instant_unstake_penalty: None,
+                stability_pool: None,
+                oracle_contract: None,
+                max_slippage: None,
             };
             let cosmos_msg = staking_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -987,12 +1843,12 @@ mod tests {
             let (mut app, staking_contract, auction_contract) = proper_instantiate();
 
             //Stake TEMA as user
-            let msg = ExecuteMsg::Stake { user: None };
+            let msg = ExecuteMsg::Stake { user: None, lock_duration: None };
             let cosmos_msg = staking_contract.call(msg, vec![coin(1_000_000, "tema_denom")]).unwrap();
             app.execute(Addr::unchecked("coin_God"), cosmos_msg).unwrap();
             
             //Stake TEMA as user
-            let msg = ExecuteMsg::Stake { user: None };
+            let msg = ExecuteMsg::Stake { user: None, lock_duration: None };
             let cosmos_msg = staking_contract.call(msg, vec![coin(10_000_000, "tema_denom")]).unwrap();
             app.execute(Addr::unchecked("user_1"), cosmos_msg).unwrap();
 
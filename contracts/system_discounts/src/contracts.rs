@@ -6,6 +6,7 @@ use cosmwasm_std::{
 };
 use cw2::set_contract_version;
 
+use membrane::pause::{assert_not_paused, execute_pause, PauseActions};
 use membrane::helpers::query_basket;
 use osmosis_std::shim::Duration;
 use osmosis_std::types::osmosis::lockup::{LockupQuerier, AccountLockedLongerDurationDenomResponse};
@@ -26,6 +27,12 @@ use crate::state::{CONFIG, OWNERSHIP_TRANSFER};
 const CONTRACT_NAME: &str = "system_discounts";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+//Execute msgs handled by the pause guard
+const PAUSE_ACTIONS: PauseActions = PauseActions {
+    pausable: &["update_config"],
+    unpausable: &[],
+};
+
 //Constants
 const SECONDS_PER_DAY: u64 = 86_400u64;
 
@@ -84,12 +91,19 @@ pub fn instantiate(
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
+    //Check the pause guard
+    assert_not_paused(deps.storage, &env, &msg, &PAUSE_ACTIONS)?;
+
     match msg {
         ExecuteMsg::UpdateConfig(update) => update_config(deps, info, update),
+        ExecuteMsg::Pause(pause_msg) => {
+            let config = CONFIG.load(deps.storage)?;
+            Ok(execute_pause(deps.storage, deps.api, &env, &info, info.sender == config.owner, &PAUSE_ACTIONS, pause_msg)?)
+        }
     }
 }
 
@@ -201,6 +201,13 @@ mod tests {
                             vesting_contract: None,
                             governance_contract: None,
                             osmosis_proxy: None,
+                            participation_requirements: None,
+                            dex_router: None,
+                            keeper_tip: Decimal::zero(),
+                            instant_unstake_penalty: None,
+                            stability_pool: None,
+                            oracle_contract: None,
+                            max_slippage: Decimal::zero(),
                         })?)
                     },
                     Staking_MockQueryMsg::UserRewards { user } => {
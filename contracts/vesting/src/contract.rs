@@ -6,6 +6,7 @@ use cosmwasm_std::{
 };
 use cw2::set_contract_version;
 
+use membrane::pause::{assert_not_paused, execute_pause, PauseActions};
 use membrane::vesting::{Config, ExecuteMsg, InstantiateMsg, QueryMsg, MigrateMsg};
 use membrane::governance::{ExecuteMsg as GovExecuteMsg, ProposalMessage, ProposalVoteOption};
 use membrane::math::decimal_division;
@@ -24,6 +25,12 @@ use crate::state::{CONFIG, RECIPIENTS, OWNERSHIP_TRANSFER};
 const CONTRACT_NAME: &str = "crates.io:vesting";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+//Execute msgs handled by the pause guard
+const PAUSE_ACTIONS: PauseActions = PauseActions {
+    pausable: &["add_recipient", "remove_recipient", "add_allocation", "withdraw_unlocked", "claim_feesfor_contract", "claim_feesfor_recipient", "submit_proposal", "cast_vote", "update_config"],
+    unpausable: &[],
+};
+
 //Constants
 const SECONDS_IN_A_DAY: u64 = 86400u64;
 
@@ -118,6 +125,9 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
+    //Check the pause guard
+    assert_not_paused(deps.storage, &env, &msg, &PAUSE_ACTIONS)?;
+
     match msg {
         ExecuteMsg::AddRecipient { recipient } => add_recipient(deps, info, recipient),
         ExecuteMsg::RemoveRecipient { recipient } => remove_recipient(deps, info, recipient),
@@ -152,6 +162,10 @@ pub fn execute(
             staking_contract,
             additional_allocation
         ),
+        ExecuteMsg::Pause(pause_msg) => {
+            let config = CONFIG.load(deps.storage)?;
+            Ok(execute_pause(deps.storage, deps.api, &env, &info, info.sender == config.owner, &PAUSE_ACTIONS, pause_msg)?)
+        }
     }
 }
 
@@ -180,10 +194,12 @@ fn submit_proposal(
                     description,
                     link,
                     messages,
+                    options: None,
                     recipient: Some(recipient.recipient.to_string()),
                     expedited,
                 })?,
-                funds: vec![],
+                //Forward the proposal deposit
+                funds: info.funds,
             });
 
             Ok(Response::new()
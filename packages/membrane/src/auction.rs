@@ -1,6 +1,7 @@
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{Decimal, Addr};
 
+use crate::pause::PauseMsg;
 use crate::types::{Asset, AssetInfo, UserInfo};
 
 #[cw_serde]
@@ -50,6 +51,8 @@ pub enum ExecuteMsg {
     RemoveAuction {},
     /// Update config
     UpdateConfig(UpdateConfig),
+    /// Pause or unpause execute msgs
+    Pause(PauseMsg),
 }
 
 #[cw_serde]
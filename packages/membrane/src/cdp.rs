@@ -1,6 +1,7 @@
 use cosmwasm_std::{Addr, Decimal, Uint128, StdResult, Api, StdError};
 use cosmwasm_schema::cw_serde;
 
+use crate::pause::PauseMsg;
 use crate::types::{
     cAsset, Asset, AssetInfo, InsolventPosition,
     SupplyCap, MultiAssetSupplyCap, TWAPPoolInfo, UserInfo, PoolType, Basket, equal, PremiumInfo,
@@ -146,6 +147,8 @@ pub enum ExecuteMsg {
     },
     //Callbacks; Only callable by the contract
     Callback(CallbackMsg),
+    /// Pause or unpause execute msgs
+    Pause(PauseMsg),
 }
 
 
@@ -1,6 +1,7 @@
 use cosmwasm_std::{Addr, Uint128};
 use cosmwasm_schema::cw_serde;
 
+use crate::pause::PauseMsg;
 use crate::types::{Asset, LPPoolInfo, VaultedLP};
 
 #[cw_serde]
@@ -39,6 +40,8 @@ pub enum ExecuteMsg {
     },
     /// Enable or disable deposits
     ToggleDeposits { enable: bool },
+    /// Pause or unpause execute msgs
+    Pause(PauseMsg),
 }
 
 #[cw_serde]
@@ -1,4 +1,4 @@
-use cosmwasm_std::{Addr, CosmosMsg, Decimal, StdError, StdResult, Uint128, Uint64};
+use cosmwasm_std::{Addr, Binary, Coin, CosmosMsg, Decimal, StdError, StdResult, Uint128, Uint64};
 use osmosis_std::types::osmosis::incentives::{MsgCreateGauge, MsgAddToGauge};
 use cosmwasm_schema::cw_serde;
 use std::fmt::{Display, Formatter, Result};
@@ -17,6 +17,7 @@ pub const VOTING_PERIOD_INTERVAL: RangeInclusive<u64> = BLOCKS_PER_DAY..=14 * BL
 pub const DELAY_INTERVAL: RangeInclusive<u64> = 0..=BLOCKS_PER_DAY; // from 0 to 1 day in blocks (6 seconds per block)
 pub const EXPIRATION_PERIOD_INTERVAL: RangeInclusive<u64> = BLOCKS_PER_DAY..=14 * BLOCKS_PER_DAY; //1 to 14 days in blocks (6 seconds per block)
 pub const STAKE_INTERVAL: RangeInclusive<u128> = 1000000000..=5000000000; // from 1000 to 5000 $TEMA
+pub const MAX_TIMELOCK_DELAY: u64 = 30 * 86_400; //30 days in seconds
 
 /// Proposal validation attributes
 const MIN_TITLE_LENGTH: usize = 4;
@@ -25,6 +26,8 @@ const MIN_DESC_LENGTH: usize = 4;
 const MAX_DESC_LENGTH: usize = 1024;
 const MIN_LINK_LENGTH: usize = 12;
 const MAX_LINK_LENGTH: usize = 128;
+const MIN_PROPOSAL_OPTIONS: usize = 2;
+const MAX_PROPOSAL_OPTIONS: usize = 10;
 
 /// Special characters that are allowed in proposal text
 const SAFE_TEXT_CHARS: &str = "!&?#()*+'-./\"";
@@ -65,6 +68,9 @@ pub enum ExecuteMsg {
         description: String,
         link: Option<String>,
         messages: Option<Vec<ProposalMessage>>,
+        //Options of a multiple-choice proposal, each with its own messages.
+        //Can't be used with messages.
+        options: Option<Vec<ProposalOption>>,
         //If from the vesting contract
         recipient: Option<String>,
         //Expedited toggle
@@ -79,6 +85,25 @@ pub enum ExecuteMsg {
         //If from the vesting contract
         recipient: Option<String>,
     },
+    /// Split voting power across the options of a multiple-choice proposal
+    CastOptionVote {
+        /// Proposal identifier
+        proposal_id: u64,
+        /// Ratio of voting power per option, must sum to 1
+        weights: Vec<Decimal>,
+        //If from the vesting contract
+        recipient: Option<String>,
+    },
+    /// Rank the options of a multiple-choice proposal.
+    /// Rankings are split as Borda weights, i.e. 1st of 3 ranked options gets 3/6 of the voting power, 2nd gets 2/6 & 3rd gets 1/6.
+    CastRankedVote {
+        /// Proposal identifier
+        proposal_id: u64,
+        /// Option indices from most to least preferred, unranked options get no power
+        ranking: Vec<u64>,
+        //If from the vesting contract
+        recipient: Option<String>,
+    },
     /// Set the status of a proposal that expired
     EndProposal {
         /// Proposal identifier
@@ -88,21 +113,81 @@ pub enum ExecuteMsg {
     CheckMessages {
         /// messages
         messages: Vec<ProposalMessage>,
-        /// Additional contract check messages: 0 - CDP, 1 - Staking, 2 - LQ
-        msg_switch: Option<u64>,
+        /// Names of the registered health checks to run, None runs all
+        checks: Option<Vec<String>>,
     },
     /// The last endpoint which is executed only if all proposal messages have been passed
     CheckMessagesPassed { error: Option<bool> },
-    /// Execute a successful proposal
+    /// Queue a passed proposal in the timelock
+    QueueProposal {
+        /// Proposal identifier
+        proposal_id: u64,
+    },
+    /// Execute a queued proposal once its timelock ends
     ExecuteProposal {
         /// Proposal identifier
         proposal_id: u64,
     },
+    /// Cancel a passed or queued proposal
+    /// ## Executor
+    /// Only the guardian or the Governance contract can cancel
+    CancelProposal {
+        /// Proposal identifier
+        proposal_id: u64,
+    },
     /// Remove a proposal that was already executed (or failed/expired)
     RemoveCompletedProposal {
         /// Proposal identifier
         proposal_id: u64,
     },
+    /// Add or remove post-proposal health checks
+    /// ## Executor
+    /// Only the Governance contract is allowed to edit its health checks
+    EditHealthChecks {
+        /// Checks to add, overwrites checks with the same name
+        add: Option<Vec<HealthCheck>>,
+        /// Names of checks to remove
+        remove: Option<Vec<String>>,
+    },
+    /// Set parameter guardrails for proposal messages
+    /// ## Executor
+    /// Only the Governance contract is allowed to edit its guardrails
+    EditGuardrails {
+        /// Guardrails to set, overwrites the target msg's guardrail & removes it if it has no fields
+        guardrails: Vec<Guardrail>,
+    },
+    /// Set treasury budgets, overwriting the remaining amount of each category & denom
+    /// ## Executor
+    /// Only the Governance contract is allowed to edit its budgets
+    EditBudgets { budgets: Vec<BudgetUpdate> },
+    /// Stream a payment from a treasury budget to a contributor
+    /// ## Executor
+    /// Only the Governance contract is allowed to create streams
+    CreateStream {
+        /// Budget category paying the stream
+        category: String,
+        /// Contributor receiving the stream
+        recipient: String,
+        /// Streamed asset
+        denom: String,
+        /// Total amount streamed
+        amount: Uint128,
+        /// Time the stream starts vesting, in seconds
+        start_time: u64,
+        /// Time before which nothing can be claimed, in seconds
+        cliff_time: u64,
+        /// Time the stream is fully vested, in seconds
+        end_time: u64,
+    },
+    /// Cancel a stream, returning its unvested amount to its budget.
+    /// The vested amount stays claimable.
+    /// ## Executor
+    /// Only the Governance contract is allowed to cancel streams
+    CancelStream { stream_id: u64 },
+    /// Claim the vested amount of a stream
+    /// ## Executor
+    /// Only the stream recipient
+    ClaimStream { stream_id: u64 },
     /// Update parameters in the Governance contract
     /// ## Executor
     /// Only the Governance contract is allowed to update its own parameters
@@ -138,14 +223,14 @@ pub enum QueryMsg {
         /// The amount of proposals to return
         limit: Option<u32>,
     },
-    /// Return proposal voters of specified proposal
+    /// Return proposal voters of specified proposal & their voting power breakdown
     ProposalVoters {
         /// Proposal unique id
         proposal_id: u64,
         /// Proposal vote option
         vote_option: ProposalVoteOption,
-        /// Index from which to start querying
-        start: Option<u64>,
+        /// Voter to start after
+        start_after: Option<String>,
         /// The amount of voters to return
         limit: Option<u32>,
         /// Specific user to query for
@@ -155,6 +240,17 @@ pub enum QueryMsg {
     Proposal { proposal_id: u64 },
     /// Return information about the votes cast on a specific proposal
     ProposalVotes { proposal_id: u64 },
+    /// Return the deposit escrowed for a specific proposal
+    ProposalDeposit { proposal_id: u64 },
+    /// Return option votes of a multiple-choice proposal
+    OptionVoters {
+        /// Proposal unique id
+        proposal_id: u64,
+        /// Voter to start after
+        start_after: Option<String>,
+        /// The amount of voters to return
+        limit: Option<u32>,
+    },
     /// Return user voting power for a specific proposal
     UserVotingPower {
         user: String,
@@ -163,6 +259,163 @@ pub enum QueryMsg {
     },
     /// Return total voting power for a specific proposal
     TotalVotingPower { proposal_id: u64 },
+    /// Return the registered post-proposal health checks
+    HealthChecks {},
+    /// Return the parameter guardrails for proposal messages
+    Guardrails {},
+    /// Return the treasury budgets
+    Budgets {},
+    /// Return active payment streams
+    Streams {
+        /// Only return streams to this recipient
+        recipient: Option<String>,
+        /// Stream id to start after
+        start_after: Option<u64>,
+        /// The amount of streams to return
+        limit: Option<u32>,
+    },
+    /// Return proposals queued in the timelock
+    TimelockQueue {
+        /// Id from which to start querying
+        start: Option<u64>,
+        /// The amount of proposals to return
+        limit: Option<u32>,
+    },
+    /// Return the timelock delays of message targets
+    TimelockDelays {},
+}
+
+/// Query checked after simulating a proposal's messages in CheckMessages
+#[cw_serde]
+pub struct HealthCheck {
+    /// Unique name of the check
+    pub name: String,
+    /// Contract to query
+    pub contract: String,
+    /// JSON encoded query msg
+    pub query: Binary,
+    /// Condition the query must meet after the messages
+    pub condition: HealthCheckCondition,
+}
+
+#[cw_serde]
+pub enum HealthCheckCondition {
+    /// The query succeeds
+    Queryable,
+    /// The query response is the same as before the messages
+    Unchanged,
+}
+
+/// This structure describes the guardrails of a target contract's execute msg.
+#[cw_serde]
+pub struct Guardrail {
+    /// Target contract
+    pub target: String,
+    /// Execute msg variant, e.g. "edit_basket"
+    pub msg: String,
+    /// Bounds of the msg's fields
+    pub fields: Vec<FieldGuardrail>,
+}
+
+/// This structure describes the bounds of an execute msg field.
+/// Fields that aren't set in the msg aren't checked.
+#[cw_serde]
+pub struct FieldGuardrail {
+    /// Dot separated path of the field in the msg variant, array elements are indexed by number, e.g. `assets.0.max_LTV`
+    pub field: String,
+    /// Minimum value
+    pub min: Option<Decimal>,
+    /// Maximum value
+    pub max: Option<Decimal>,
+    /// Maximum change from the current value per proposal
+    pub max_change: Option<Decimal>,
+    /// Query for the current value, required for max_change
+    pub current_value: Option<CurrentValueQuery>,
+}
+
+/// This structure describes a query for a field's current value.
+#[cw_serde]
+pub struct CurrentValueQuery {
+    /// Query msg sent to the target contract
+    pub query: Binary,
+    /// Dot separated path of the value in the query response
+    pub field: String,
+}
+
+/// This structure describes a treasury budget update.
+#[cw_serde]
+pub struct BudgetUpdate {
+    /// Budget category, e.g. "contributors"
+    pub category: String,
+    /// Budgeted asset
+    pub denom: String,
+    /// Amount left to allocate to streams
+    pub amount: Uint128,
+}
+
+/// This structure describes a treasury budget.
+#[cw_serde]
+pub struct Budget {
+    /// Budget category, e.g. "contributors"
+    pub category: String,
+    /// Budgeted asset
+    pub denom: String,
+    /// Amount left to allocate to streams
+    pub remaining: Uint128,
+    /// Amount allocated to streams
+    pub allocated: Uint128,
+}
+
+/// This structure describes a payment stream from a treasury budget.
+#[cw_serde]
+pub struct PaymentStream {
+    /// Stream identifier
+    pub stream_id: u64,
+    /// Budget category paying the stream
+    pub category: String,
+    /// Contributor receiving the stream
+    pub recipient: Addr,
+    /// Streamed asset
+    pub denom: String,
+    /// Total amount streamed
+    pub amount: Uint128,
+    /// Amount already claimed
+    pub claimed: Uint128,
+    /// Time the stream starts vesting, in seconds
+    pub start_time: u64,
+    /// Time before which nothing can be claimed, in seconds
+    pub cliff_time: u64,
+    /// Time the stream is fully vested, in seconds
+    pub end_time: u64,
+}
+
+impl PaymentStream {
+    /// Amount vested at the given time
+    pub fn vested(&self, time: u64) -> Uint128 {
+        if time < self.cliff_time {
+            Uint128::zero()
+        } else if time >= self.end_time {
+            self.amount
+        } else {
+            self.amount.multiply_ratio(
+                time.saturating_sub(self.start_time),
+                self.end_time - self.start_time,
+            )
+        }
+    }
+
+    /// Vested amount that hasn't been claimed at the given time
+    pub fn claimable(&self, time: u64) -> Uint128 {
+        self.vested(time).saturating_sub(self.claimed)
+    }
+}
+
+/// This structure describes a payment stream response.
+#[cw_serde]
+pub struct StreamResponse {
+    pub stream: PaymentStream,
+    /// Amount the recipient can currently claim
+    pub claimable: Uint128,
 }
 
 /// This structure stores general parameters for the Governance contract.
@@ -196,6 +449,18 @@ pub struct Config {
     pub whitelisted_links: Vec<String>,
     /// Toggle quadratic voting
     pub quadratic_voting: bool,
+    /// Can cancel passed or queued proposals but not execute them
+    pub guardian: Option<Addr>,
+    /// Minimum time queued proposals wait before execution, in seconds.
+    /// Defaults to 0 for configs saved before this field existed.
+    #[serde(default)]
+    pub timelock_delay: u64,
+    /// Deposit escrowed from submitters, refunded once the proposal reaches quorum & burned otherwise
+    #[serde(default)]
+    pub proposal_deposit: Option<Coin>,
+    /// Send deposits of proposals removed by the Remove vote to stakers instead of burning them
+    #[serde(default)]
+    pub removed_deposit_to_stakers: bool,
 }
 
 impl Config {
@@ -247,6 +512,13 @@ impl Config {
             )));
         }
 
+        if self.timelock_delay > MAX_TIMELOCK_DELAY {
+            return Err(StdError::generic_err(format!(
+                "The timelock delay cannot be higher than {} seconds",
+                MAX_TIMELOCK_DELAY
+            )));
+        }
+
         if !STAKE_INTERVAL.contains(&self.proposal_required_stake.u128()) {
             return Err(StdError::generic_err(format!(
                 "The required deposit for a proposal cannot be lower than {}",
@@ -291,11 +563,91 @@ pub struct UpdateConfig {
     pub whitelist_add: Option<Vec<String>>,
     /// Toggle quadratic voting
     pub quadratic_voting: Option<bool>,
+    /// Guardian address, an empty address removes it
+    pub guardian: Option<String>,
+    /// Minimum timelock delay, in seconds
+    pub timelock_delay: Option<u64>,
+    /// Timelock delays to set for message targets, a delay of 0 removes it
+    pub timelock_target_delays: Option<Vec<TimelockDelay>>,
+    /// Proposal deposit, a zero amount removes it
+    pub proposal_deposit: Option<Coin>,
+    /// Send deposits of removed proposals to stakers instead of burning them
+    pub removed_deposit_to_stakers: Option<bool>,
+}
+
+/// Minimum timelock delay for proposal messages to a target contract
+#[cw_serde]
+pub struct TimelockDelay {
+    /// Target contract
+    pub target: String,
+    /// Execute msg name the delay applies to (e.g. "update_config"), None applies to all.
+    /// Migrate & admin msgs to the target use its longest delay.
+    pub msg: Option<String>,
+    /// Delay in seconds
+    pub delay: u64,
+}
+
+/// Proposal waiting in the timelock
+#[cw_serde]
+pub struct QueuedProposal {
+    /// Proposal identifier
+    pub proposal_id: u64,
+    /// Time the proposal was queued, in seconds
+    pub queued_time: u64,
+    /// Time the proposal becomes executable, in seconds
+    pub eta: u64,
 }
 
 /// This structure stores data for a proposal.
 #[cw_serde]
 pub struct Proposal {
+    /// Unique proposal ID
+    pub proposal_id: Uint64,
+    /// The address of the proposal submitter
+    pub submitter: Addr,
+    /// Status of the proposal
+    pub status: ProposalStatus,
+    /// Aligned power of proposal
+    pub aligned_power: Uint128,
+    /// `For` power of proposal
+    pub for_power: Uint128,
+    /// `Against` power of proposal
+    pub against_power: Uint128,
+    /// `Amend` power of proposal
+    pub amendment_power: Uint128,
+    /// `Remove` power of proposal
+    pub removal_power: Uint128,
+    /// Start block of proposal
+    pub start_block: u64,
+    /// Start time of proposal
+    pub start_time: u64,
+    /// End block of proposal
+    pub end_block: u64,
+    /// Delayed end block of proposal
+    pub delayed_end_block: u64,
+    /// Expiration block of proposal
+    pub expiration_block: u64,
+    /// Proposal title
+    pub title: String,
+    /// Proposal description
+    pub description: String,
+    /// Proposal link
+    pub link: Option<String>,
+    /// Proposal messages, set to the winning option's messages for multiple-choice proposals
+    pub messages: Option<Vec<ProposalMessage>>,
+    /// Options of a multiple-choice proposal
+    pub options: Option<Vec<ProposalOption>>,
+    /// Voting power per option
+    #[serde(default)]
+    pub option_power: Vec<Uint128>,
+    /// Index of the winning option, options tied for the most power have no winner
+    pub winning_option: Option<u64>,
+}
+
+/// This structure describes a proposal with its voters stored inline.
+/// Used to migrate proposals to per-vote storage.
+#[cw_serde]
+pub struct OldProposal {
     /// Voting power per user for this proposal
     pub voting_power: Vec<(Addr, Uint128)>,
     /// Unique proposal ID
@@ -340,15 +692,20 @@ pub struct Proposal {
     pub description: String,
     /// Proposal link
     pub link: Option<String>,
-    /// Proposal messages
+    /// Proposal messages, set to the winning option's messages for multiple-choice proposals
     pub messages: Option<Vec<ProposalMessage>>,
+    /// Options of a multiple-choice proposal
+    pub options: Option<Vec<ProposalOption>>,
+    /// Voting power per option
+    #[serde(default)]
+    pub option_power: Vec<Uint128>,
+    /// Index of the winning option
+    pub winning_option: Option<u64>,
 }
 
 /// This structure describes a proposal response.
 #[cw_serde]
 pub struct ProposalResponse {
-    /// Voting power per user for this proposal
-    pub voting_power: Vec<(Addr, Uint128)>,
     /// Unique proposal ID
     pub proposal_id: Uint64,
     /// The address of the proposal submitter
@@ -383,6 +740,12 @@ pub struct ProposalResponse {
     pub messages: Option<Vec<ProposalMessage>>,
     /// Proposal link
     pub link: Option<String>,
+    /// Options of a multiple-choice proposal
+    pub options: Option<Vec<ProposalOption>>,
+    /// Voting power per option
+    pub option_power: Vec<Uint128>,
+    /// Index of the winning option
+    pub winning_option: Option<u64>,
 }
 
 impl Proposal {
@@ -435,6 +798,31 @@ impl Proposal {
             }
         }
 
+        // Options validation
+        if let Some(options) = &self.options {
+            if self.messages.is_some() {
+                return Err(StdError::generic_err("Multiple-choice proposals put messages in their options!"));
+            }
+            if options.len() < MIN_PROPOSAL_OPTIONS || options.len() > MAX_PROPOSAL_OPTIONS {
+                return Err(StdError::generic_err(format!(
+                    "Proposals must have {} to {} options!",
+                    MIN_PROPOSAL_OPTIONS, MAX_PROPOSAL_OPTIONS
+                )));
+            }
+            for option in options {
+                if option.title.is_empty() || option.title.len() > MAX_TITLE_LENGTH {
+                    return Err(StdError::generic_err("Option title is empty or too long!"));
+                }
+                if !option.title.chars().all(|c| {
+                    c.is_ascii_alphanumeric() || c.is_ascii_whitespace() || SAFE_TEXT_CHARS.contains(c)
+                }) {
+                    return Err(StdError::generic_err(
+                        "Option title is not in alphanumeric format!",
+                    ));
+                }
+            }
+        }
+
         Ok(())
     }
 }
@@ -448,6 +836,8 @@ pub enum ProposalStatus {
     Rejected,
     Executed,
     Expired,
+    Queued,
+    Cancelled,
 }
 
 impl Display for ProposalStatus {
@@ -459,6 +849,8 @@ impl Display for ProposalStatus {
             ProposalStatus::Rejected {} => fmt.write_str("rejected"),
             ProposalStatus::Executed {} => fmt.write_str("executed"),
             ProposalStatus::Expired {} => fmt.write_str("expired"),
+            ProposalStatus::Queued {} => fmt.write_str("queued"),
+            ProposalStatus::Cancelled {} => fmt.write_str("cancelled"),
         }
     }
 }
@@ -472,6 +864,78 @@ pub struct ProposalMessage {
     pub msg: CosmosMsg,
 }
 
+/// This structure describes an option of a multiple-choice proposal.
+#[cw_serde]
+pub struct ProposalOption {
+    /// Option title
+    pub title: String,
+    /// Messages executed if the option wins
+    pub messages: Option<Vec<ProposalMessage>>,
+}
+
+/// This structure describes a vote on a proposal.
+#[cw_serde]
+pub struct Vote {
+    /// Vote option, None for option votes on multiple-choice proposals
+    pub option: Option<ProposalVoteOption>,
+    /// Ratio of voting power per option of a multiple-choice proposal
+    pub weights: Option<Vec<Decimal>>,
+    /// Option ranking the weights were derived from, if ranked
+    #[serde(default)]
+    pub ranking: Option<Vec<u64>>,
+    /// Voting power counted for the vote
+    pub power: Uint128,
+    /// Voting power breakdown, None for alignments from the submitter & migrated votes
+    pub voter_power: Option<VoterPower>,
+}
+
+/// This structure describes a vote on a multiple-choice proposal.
+#[cw_serde]
+pub struct OptionVote {
+    /// Voting power at the time of the vote
+    pub power: Uint128,
+    /// Ratio of voting power per option
+    pub weights: Vec<Decimal>,
+    /// Option ranking the weights were derived from, if ranked
+    pub ranking: Option<Vec<u64>>,
+}
+
+/// This structure describes an option vote response.
+#[cw_serde]
+pub struct OptionVoterResponse {
+    /// Voter address
+    pub voter: Addr,
+    /// Vote
+    pub vote: OptionVote,
+}
+
+/// This structure describes a voter's voting power on a proposal.
+#[cw_serde]
+pub struct VoterPower {
+    /// Voting power from the voter's own stake
+    pub own_power: Uint128,
+    /// Voting power delegated to the voter
+    pub delegated_power: Uint128,
+    /// Delegated power reclaimed by delegators who voted directly
+    pub overridden_power: Uint128,
+}
+
+impl VoterPower {
+    /// Voting power counted for the voter
+    pub fn total(&self) -> Uint128 {
+        (self.own_power + self.delegated_power).saturating_sub(self.overridden_power)
+    }
+}
+
+/// This structure describes a proposal voter response.
+#[cw_serde]
+pub struct ProposalVoterResponse {
+    /// Voter address
+    pub voter: Addr,
+    /// Voting power breakdown, None for votes cast before breakdowns were saved
+    pub power: Option<VoterPower>,
+}
+
 /// This structure describes a proposal vote.
 #[cw_serde]
 pub struct ProposalVote {
@@ -503,6 +967,30 @@ impl Display for ProposalVoteOption {
     }
 }
 
+/// This structure describes a proposal's deposit.
+#[cw_serde]
+pub struct ProposalDeposit {
+    /// Refunded to the proposal submitter
+    pub depositor: Addr,
+    /// Escrowed deposit
+    pub amount: Coin,
+    /// Deposit status
+    pub status: DepositStatus,
+}
+
+/// This enum describes the status of a proposal deposit.
+#[cw_serde]
+pub enum DepositStatus {
+    /// Held until the proposal ends
+    Escrowed,
+    /// Returned after the proposal reached quorum
+    Refunded,
+    /// Burned after the proposal was removed by the Remove vote or failed to reach quorum
+    Burned,
+    /// Sent to stakers after the proposal was removed by the Remove vote
+    SentToStakers,
+}
+
 /// This structure describes a proposal vote response.
 #[cw_serde]
 pub struct ProposalVotesResponse {
@@ -518,6 +1006,8 @@ pub struct ProposalVotesResponse {
     pub removal_power: Uint128,
     /// Total amount of `align` votes for a proposal.
     pub aligned_power: Uint128,
+    /// Total amount of votes per option of a multiple-choice proposal.
+    pub option_power: Vec<Uint128>,
 }
 
 /// This structure describes a proposal list response.
@@ -530,7 +1020,11 @@ pub struct ProposalListResponse {
 }
 
 pub mod helpers {
-    use cosmwasm_std::{StdError, StdResult};
+    use std::fmt;
+    use std::str::FromStr;
+
+    use cosmwasm_std::{from_slice, Decimal, StdError, StdResult};
+    use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
 
     const SAFE_LINK_CHARS: &str = "-_:/?#@!$&()*+,;=.~[]'%";
 
@@ -553,7 +1047,124 @@ pub mod helpers {
 
         Ok(())
     }
+
+    /// JSON value of a msg, numbers are kept as text
+    enum JsonValue {
+        Null,
+        Bool(bool),
+        Number(String),
+        String(String),
+        Array(Vec<JsonValue>),
+        Object(Vec<(String, JsonValue)>),
+    }
+
+    impl<'de> Deserialize<'de> for JsonValue {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserializer.deserialize_any(JsonValueVisitor)
+        }
+    }
+
+    struct JsonValueVisitor;
+
+    impl<'de> Visitor<'de> for JsonValueVisitor {
+        type Value = JsonValue;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a JSON value")
+        }
+
+        fn visit_unit<E: de::Error>(self) -> Result<JsonValue, E> {
+            Ok(JsonValue::Null)
+        }
+
+        fn visit_none<E: de::Error>(self) -> Result<JsonValue, E> {
+            Ok(JsonValue::Null)
+        }
+
+        fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<JsonValue, D::Error> {
+            deserializer.deserialize_any(JsonValueVisitor)
+        }
+
+        fn visit_bool<E: de::Error>(self, value: bool) -> Result<JsonValue, E> {
+            Ok(JsonValue::Bool(value))
+        }
+
+        fn visit_u64<E: de::Error>(self, value: u64) -> Result<JsonValue, E> {
+            Ok(JsonValue::Number(value.to_string()))
+        }
+
+        fn visit_i64<E: de::Error>(self, value: i64) -> Result<JsonValue, E> {
+            Ok(JsonValue::Number(value.to_string()))
+        }
+
+        fn visit_f64<E: de::Error>(self, value: f64) -> Result<JsonValue, E> {
+            Ok(JsonValue::Number(value.to_string()))
+        }
+
+        fn visit_str<E: de::Error>(self, value: &str) -> Result<JsonValue, E> {
+            Ok(JsonValue::String(value.to_string()))
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<JsonValue, A::Error> {
+            let mut values = vec![];
+            while let Some(value) = seq.next_element()? {
+                values.push(value);
+            }
+            Ok(JsonValue::Array(values))
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<JsonValue, A::Error> {
+            let mut entries = vec![];
+            while let Some(entry) = map.next_entry::<String, JsonValue>()? {
+                entries.push(entry);
+            }
+            Ok(JsonValue::Object(entries))
+        }
+    }
+
+    /// Returns the name of a JSON msg, i.e. its first object key.
+    /// Returns None if the msg isn't a JSON object.
+    pub fn get_json_msg_name(json: &[u8]) -> Option<String> {
+        match from_slice::<JsonValue>(json) {
+            Ok(JsonValue::Object(entries)) => entries.into_iter().next().map(|(key, _)| key),
+            _ => None,
+        }
+    }
+
+    /// Returns the decimal value of a dot separated field path in a JSON object, array elements are indexed by number.
+    /// Numbers & numeric strings are accepted, returns None if the field isn't set.
+    /// Errors if the path can't be resolved, i.e. it indexes into a value that isn't an object or array.
+    pub fn get_json_decimal(json: &[u8], path: &str) -> StdResult<Option<Decimal>> {
+        let mut value: JsonValue = from_slice(json)?;
+
+        for key in path.split('.') {
+            value = match value {
+                JsonValue::Object(entries) => match entries.into_iter().find(|(entry_key, _)| entry_key == key) {
+                    Some((_, entry)) => entry,
+                    None => return Ok(None),
+                },
+                JsonValue::Array(values) => match key.parse::<usize>() {
+                    Ok(index) => match values.into_iter().nth(index) {
+                        Some(entry) => entry,
+                        None => return Ok(None),
+                    },
+                    Err(_) => return Err(StdError::generic_err(format!("{} isn't an array index in {}", key, path))),
+                },
+                JsonValue::Null => return Ok(None),
+                _ => return Err(StdError::generic_err(format!("{} can't be resolved at {}", path, key))),
+            };
+        }
+
+        match value {
+            JsonValue::Null => Ok(None),
+            JsonValue::Number(number) | JsonValue::String(number) => Ok(Some(Decimal::from_str(&number)?)),
+            _ => Err(StdError::generic_err(format!("{} isn't a number", path))),
+        }
+    }
 }
 
 #[cw_serde]
-pub struct MigrateMsg {}
\ No newline at end of file
+pub struct MigrateMsg {
+    /// Max proposals to move to per-vote storage, rerun until no proposals are migrated
+    pub limit: Option<u32>,
+}
\ No newline at end of file
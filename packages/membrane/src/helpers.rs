@@ -6,14 +6,15 @@ use apollo_cw_asset::AssetInfoUnchecked;
 use crate::apollo_router::ExecuteMsg as RouterExecuteMsg;
 use crate::oracle::PriceResponse;
 use crate::discount_vault::Config as DV_Config;
-use crate::staking::Totals;
-use crate::types::{AssetInfo, Asset, PoolStateResponse, AssetPool, Basket}; 
+use crate::staking::{Totals, MAX_LOCK_DAYS};
+use crate::types::{AssetInfo, Asset, PoolStateResponse, AssetPool, Basket, StakeLock}; 
 use crate::osmosis_proxy::{QueryMsg as OsmoQueryMsg, OwnerResponse};
 use crate::liquidity_check::{QueryMsg as LiquidityQueryMsg, LiquidityResponse};
 use crate::cdp::{ExecuteMsg as CDPExecuteMsg, QueryMsg as CDPQueryMsg, PositionResponse, BasketPositionsResponse};
 
 //Constants
 pub const SECONDS_PER_YEAR: u64 = 31_536_000u64;
+pub const SECONDS_PER_DAY: u64 = 86_400u64;
 
 /// Returns asset price from the oracle contract 
 /// with the assumption that its the shared oracle contract.
@@ -111,6 +112,7 @@ pub fn router_native_to_native(
     asset_to_buy: AssetInfo,
     recipient: Option<String>,
     amount_to_sell: u128,
+    minimum_receive: Option<Uint128>,
 ) -> StdResult<CosmosMsg>{
     if let AssetInfo::NativeToken { denom } = asset_to_sell {
         if let AssetInfo::NativeToken { denom:_ } = asset_to_buy {
@@ -121,7 +123,7 @@ pub fn router_native_to_native(
                     amount: Uint128::new(amount_to_sell),
                 }].into(),
                 receive_asset: asset_to_buy.into_apollo_cw_asset(), 
-                minimum_receive: None, 
+                minimum_receive, 
                 to: recipient 
             };
     
@@ -383,4 +385,19 @@ pub fn accrue_user_positions(
     });
 
     Ok(accrual_msg)
-}
\ No newline at end of file
+}
+/// Returns a lock's multiplier at a given time.
+/// Decays linearly from 1 + max_boost at a max length lock to 1 at unlock.
+pub fn get_lock_multiplier(
+    lock: &StakeLock,
+    time: u64,
+    max_boost: Decimal,
+) -> Decimal {
+    if time < lock.start_time || time >= lock.end_time {
+        return Decimal::one();
+    }
+    let remaining = lock.end_time - time;
+    let max_duration = MAX_LOCK_DAYS * SECONDS_PER_DAY;
+
+    Decimal::one() + max_boost * Decimal::from_ratio(remaining.min(max_duration), max_duration)
+}
@@ -1,6 +1,8 @@
 use cosmwasm_std::{Addr, Uint128};
 use cosmwasm_schema::cw_serde;
 
+use crate::pause::PauseMsg;
+
 
 #[cw_serde]
 pub struct InstantiateMsg {
@@ -73,6 +75,8 @@ pub enum ExecuteMsg {
         /// New Governance contract address
         new_governance_contract: String,
     },
+    /// Pause or unpause execute msgs
+    Pause(PauseMsg),
 }
 
 #[cw_serde]
@@ -18,5 +18,6 @@ pub mod system_discounts;
 pub mod launch;
 pub mod discount_vault;
 pub mod helpers;
+pub mod pause;
 pub mod types;
 pub mod math;
\ No newline at end of file
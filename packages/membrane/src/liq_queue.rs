@@ -1,7 +1,8 @@
 use cosmwasm_schema::cw_serde;
 
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, Decimal, Uint128};
 
+use crate::pause::PauseMsg;
 use crate::math::{Decimal256, Uint256};
 use crate::types::{AssetInfo, Bid, BidInput, Asset};
 use crate::oracle::PriceResponse;
@@ -51,11 +52,26 @@ pub enum ExecuteMsg {
         /// Collateral asset info to bid_for
         bid_for: AssetInfo,
     },
+    /// Repay with sp_fallback bids once the Stability Pool is exhausted. Called by Positions
+    FallbackLiquidate {
+        /// Credit amount to repay
+        liq_amount: Decimal,
+    },
+    /// Distribute collateral to the sp_fallback bids that repaid. Called by Positions
+    DistributeFallback {
+        /// Collateral assets to distribute
+        distribution_assets: Vec<Asset>,
+        /// Credit amount the distribution is for
+        distribute_for: Uint128,
+    },
+    /// Claim collateral from fallback liquidations
+    ClaimFallback {},
     /// Claim liquidated assets
     ClaimLiquidations {
         /// Collateral asset info the bid was for
         bid_for: AssetInfo,
-        /// Bid ids to claim, none = all bids in the collateral's queue
+        /// Bid ids to claim, none = all bids in the collateral's queue.
+        /// Auto-recycled collateral is swapped for at least its value at the last liquidation prices, less each bid's max slippage.
         bid_ids: Option<Vec<Uint128>>,
     },
     /// Add a new queue
@@ -91,7 +107,11 @@ pub enum ExecuteMsg {
         minimum_bid: Option<Uint128>,
         /// Maximum waiting bids
         maximum_waiting_bids: Option<u64>,
+        /// DEX router contract address, used to recycle claimed collateral
+        dex_router: Option<String>,
     },
+    /// Pause or unpause execute msgs
+    Pause(PauseMsg),
 }
 
 
@@ -142,6 +162,8 @@ pub enum QueryMsg {
         /// Credit price
         credit_price: PriceResponse,
     },
+    /// Returns FallbackCapitalResponse, the capital available to the Stability Pool fallback
+    FallbackCapital {},
     /// Returns User's claimable assetss
     UserClaims {
         /// User address
@@ -153,8 +175,14 @@ pub enum QueryMsg {
         bid_for: AssetInfo,
         /// Premium slot. Taken as % 50 = 50%.
         premium: u64, 
+        /// Start after bid ID for active bids
+        start_after: Option<Uint128>,
+        /// Start after bid ID for waiting bids
+        waiting_start_after: Option<Uint128>,
+        /// Bid response limit
+        limit: Option<u32>,
     },
-    /// Returns multiple SlotResponse
+    /// Returns multiple SlotResponse without their bids
     PremiumSlots {
         /// Bid for asset
         bid_for: AssetInfo,
@@ -185,13 +213,16 @@ pub struct Config {
     pub minimum_bid: Uint128,
     /// Maximum total bids
     pub maximum_waiting_bids: u64,
+    /// DEX router contract address, used to recycle claimed collateral.
+    /// Auto-recycled collateral is sent to the bidder if unset.
+    pub dex_router: Option<Addr>,
 }
 
 #[cw_serde]
 pub struct SlotResponse {
-    /// List of bids
+    /// Page of bids, empty for PremiumSlots queries
     pub bids: Vec<Bid>,
-    /// List of waiting bids
+    /// Page of waiting bids, empty for PremiumSlots queries
     pub waiting_bids: Vec<Bid>,
     /// Liquidation premium 
     pub liq_premium: String,
@@ -233,6 +264,18 @@ pub struct BidResponse {
     pub epoch_snapshot: Uint128,
     /// Scale snapshot
     pub scale_snapshot: Uint128,
+    /// Recycle claimed collateral into new bids
+    pub auto_recycle: bool,
+    /// Used as Stability Pool fallback capital
+    pub sp_fallback: bool,
+    /// Max slippage of auto-recycle swaps
+    pub max_slippage: Decimal,
+}
+
+#[cw_serde]
+pub struct FallbackCapitalResponse {
+    /// Total active sp_fallback bid amount
+    pub capital: Uint256,
 }
 
 #[cw_serde]
@@ -262,3 +305,9 @@ pub struct QueueResponse {
     /// Minimum bid amount
     pub bid_threshold: Uint256,
 }
+
+#[cw_serde]
+pub struct MigrateMsg {
+    /// Max inline bids to move to their own Maps, rerun until no bids are migrated
+    pub limit: Option<u32>,
+}
@@ -1,6 +1,7 @@
 use cosmwasm_std::{Addr, Decimal, Uint128};
 use cosmwasm_schema::cw_serde;
 
+use crate::pause::PauseMsg;
 use crate::types::{AssetInfo, LiquidityInfo};
 
 #[cw_serde]
@@ -41,6 +42,8 @@ pub enum ExecuteMsg {
         /// Stableswap liquidity multiplier
         stableswap_multiplier: Option<Decimal>,
     },
+    /// Pause or unpause execute msgs
+    Pause(PauseMsg),
 }
 
 #[cw_serde]
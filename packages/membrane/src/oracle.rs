@@ -4,7 +4,10 @@ use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{Decimal, Uint128, Addr, StdResult};
 
 use pyth_sdk_cw::PriceIdentifier;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
+use crate::pause::PauseMsg;
 use crate::{types::{AssetInfo, AssetOracleInfo, PriceInfo, TWAPPoolInfo}, math::{decimal_multiplication, decimal_division, Decimal256, Uint256}};
 
 #[cw_serde]
@@ -53,6 +56,8 @@ pub enum ExecuteMsg {
         /// Toggle to remove
         remove: bool,
     },
+    /// Pause or unpause execute msgs
+    Pause(PauseMsg),
 }
 
 #[cw_serde]
@@ -188,6 +193,18 @@ impl PriceResponse256 {
     }
 }
 
+/// Price response expected from an external oracle contract.
+/// Unknown fields are ignored so any feed that returns a scaled `price` or `rate` & its `last_updated` time can be used.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ExternalPriceResponse {
+    /// Price scaled by the oracle's response decimals
+    #[serde(alias = "rate")]
+    pub price: Uint128,
+    /// Time of the price's last update, in seconds
+    #[serde(alias = "last_updated_base")]
+    pub last_updated: u64,
+}
+
 #[cw_serde]
 pub struct AssetResponse {
     /// Asset info
@@ -3,6 +3,7 @@ use cosmwasm_std::{Uint128, Addr, Decimal};
 
 use osmosis_std::types::osmosis::incentives::MsgCreateGauge;
 
+use crate::pause::PauseMsg;
 use crate::types::Owner;
 
 #[cw_serde]
@@ -51,6 +52,19 @@ pub enum ExecuteMsg {
         /// New max supply
         max_supply: Uint128,
     },
+    /// Set a rolling-window mint limit for a denom or one of its minters.
+    /// Minted amounts decay out of the window linearly.
+    /// Mints over a limit error.
+    EditMintLimit {
+        /// Native token denom
+        denom: String,
+        /// Owner to limit, limits every owner's mints combined if None
+        owner: Option<String>,
+        /// Max amount minted per window, 0 removes the limit
+        limit: Uint128,
+        /// Window length, in seconds
+        window: u64,
+    },
     /// Update contract config
     UpdateConfig {
         /// List of owners
@@ -77,6 +91,8 @@ pub enum ExecuteMsg {
         /// Toggle authority over non-token contract state
         non_token_contract_auth: Option<bool>,
     },
+    /// Pause or unpause execute msgs
+    Pause(PauseMsg),
 }
 
 #[cw_serde]
@@ -155,6 +171,20 @@ pub struct TokenInfoResponse {
     pub max_supply: Uint128,
     /// Burned supply
     pub burned_supply: Uint128,
+    /// Rolling-window mint limits
+    pub mint_limits: Vec<MintLimitResponse>,
+}
+
+#[cw_serde]
+pub struct MintLimitResponse {
+    /// Limited owner, None for the denom-wide limit
+    pub owner: Option<Addr>,
+    /// Max amount minted per window
+    pub limit: Uint128,
+    /// Window length, in seconds
+    pub window: u64,
+    /// Amount that can currently be minted
+    pub available: Uint128,
 }
 
 #[cw_serde]
@@ -0,0 +1,205 @@
+//Pause guard shared by the core contracts.
+//The guardian can pause execute msgs for a limited time & then has to wait out a cooldown,
+//only the contract owner (Governance) can pause for longer, extend a pause or lift its own pauses.
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{attr, to_vec, Addr, Api, Env, MessageInfo, Response, StdError, StdResult, Storage};
+use cw_storage_plus::{Item, Map};
+use serde::Serialize;
+
+/// Longest pause the guardian can set, in seconds
+pub const MAX_GUARDIAN_PAUSE: u64 = 3 * 86_400; //3 days
+/// Time after a guardian pause expires before the guardian can pause again, in seconds
+pub const GUARDIAN_PAUSE_COOLDOWN: u64 = 7 * 86_400; //7 days
+/// Action that pauses every pausable execute msg
+pub const ALL_ACTIONS: &str = "all";
+/// Execute msg name of PauseMsgs, which are never paused
+const PAUSE_ACTION: &str = "pause";
+
+/// ## Description
+/// Address allowed to pause the contract besides its owner
+pub const PAUSE_GUARDIAN: Item<Addr> = Item::new("pause_guardian");
+
+/// ## Description
+/// Time the guardian can pause again, in seconds
+pub const GUARDIAN_COOLDOWN: Item<u64> = Item::new("guardian_pause_cooldown");
+
+/// ## Description
+/// Paused actions
+/// <Execute msg name or ALL_ACTIONS, PausedAction>
+pub const PAUSED_ACTIONS: Map<String, PausedAction> = Map::new("paused_actions");
+
+/// This structure describes a paused action.
+#[cw_serde]
+pub struct PausedAction {
+    /// Time the pause expires, in seconds
+    pub expiry: u64,
+    /// Paused by the owner, so only the owner can lift it
+    pub owner_pause: bool,
+}
+
+/// This structure describes the execute msgs a contract handles for the pause guard.
+pub struct PauseActions {
+    /// Snake case names of the execute msgs that can be paused
+    pub pausable: &'static [&'static str],
+    /// Snake case names of the execute msgs that are never paused, e.g. ones other contracts rely on to settle
+    pub unpausable: &'static [&'static str],
+}
+
+/// This enum describes the pause msgs shared by the core contracts.
+#[cw_serde]
+pub enum PauseMsg {
+    /// Pause execute msgs, e.g. "deposit" or "all"
+    /// ## Executor
+    /// Guardian for up to MAX_GUARDIAN_PAUSE, if the actions aren't already paused & its cooldown has passed, or the owner
+    Pause {
+        /// Snake case execute msg names
+        actions: Vec<String>,
+        /// Time until the pause expires, in seconds
+        duration: u64,
+    },
+    /// Unpause execute msgs
+    /// ## Executor
+    /// Owner, or the guardian for pauses the owner didn't set
+    Unpause { actions: Vec<String> },
+    /// Set or remove the guardian
+    /// ## Executor
+    /// Owner
+    SetGuardian { guardian: Option<String> },
+}
+
+/// Return the snake case name of an execute msg
+pub fn msg_action<T: Serialize>(msg: &T) -> StdResult<String> {
+    //Msgs serialize as {"msg_name":{..}} or "msg_name"
+    let json = String::from_utf8(to_vec(msg)?)?;
+
+    json.split('"')
+        .nth(1)
+        .map(String::from)
+        .ok_or_else(|| StdError::generic_err("Execute msg has no name"))
+}
+
+/// Error if the execute msg or all actions are paused
+pub fn assert_not_paused<T: Serialize>(storage: &dyn Storage, env: &Env, msg: &T, pause_actions: &PauseActions) -> StdResult<()> {
+    let action = msg_action(msg)?;
+    if action == PAUSE_ACTION || pause_actions.unpausable.contains(&action.as_str()) {
+        return Ok(());
+    }
+
+    for key in [ALL_ACTIONS.to_string(), action.clone()] {
+        if let Some(paused) = PAUSED_ACTIONS.may_load(storage, key)? {
+            if env.block.time.seconds() < paused.expiry {
+                return Err(StdError::generic_err(format!("{} is paused until {}", action, paused.expiry)));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Error if an action isn't a pausable execute msg of the contract
+fn validate_actions(actions: &[String], pause_actions: &PauseActions) -> StdResult<()> {
+    for action in actions {
+        if action != ALL_ACTIONS && !pause_actions.pausable.contains(&action.as_str()) {
+            return Err(StdError::generic_err(format!("{} isn't a pausable action", action)));
+        }
+    }
+
+    Ok(())
+}
+
+/// Handle a PauseMsg.
+/// is_owner is whether the sender is the contract's owner, i.e. Governance.
+pub fn execute_pause(
+    storage: &mut dyn Storage,
+    api: &dyn Api,
+    env: &Env,
+    info: &MessageInfo,
+    is_owner: bool,
+    pause_actions: &PauseActions,
+    msg: PauseMsg,
+) -> StdResult<Response> {
+    let is_guardian = PAUSE_GUARDIAN.may_load(storage)? == Some(info.sender.clone());
+    let current_time = env.block.time.seconds();
+
+    match msg {
+        PauseMsg::Pause { actions, duration } => {
+            if !is_owner && !is_guardian {
+                return Err(StdError::generic_err("Unauthorized"));
+            }
+            validate_actions(&actions, pause_actions)?;
+
+            //Guardian pauses are short, can't be extended & can't be chained
+            if !is_owner {
+                if duration > MAX_GUARDIAN_PAUSE {
+                    return Err(StdError::generic_err(format!("The guardian can't pause for more than {} seconds", MAX_GUARDIAN_PAUSE)));
+                }
+                let cooldown = GUARDIAN_COOLDOWN.may_load(storage)?.unwrap_or_default();
+                if current_time < cooldown {
+                    return Err(StdError::generic_err(format!("The guardian can't pause again until {}", cooldown)));
+                }
+                GUARDIAN_COOLDOWN.save(storage, &(current_time + duration + GUARDIAN_PAUSE_COOLDOWN))?;
+            }
+
+            for action in actions.iter() {
+                if !is_owner {
+                    if let Some(paused) = PAUSED_ACTIONS.may_load(storage, action.clone())? {
+                        if current_time < paused.expiry {
+                            return Err(StdError::generic_err(format!("{} is already paused, only the owner can extend it", action)));
+                        }
+                    }
+                }
+
+                PAUSED_ACTIONS.save(storage, action.clone(), &PausedAction {
+                    expiry: current_time + duration,
+                    owner_pause: is_owner,
+                })?;
+            }
+
+            Ok(Response::new().add_attributes(vec![
+                attr("method", "pause"),
+                attr("actions", format!("{:?}", actions)),
+                attr("expiry", (current_time + duration).to_string()),
+            ]))
+        }
+        PauseMsg::Unpause { actions } => {
+            if !is_owner && !is_guardian {
+                return Err(StdError::generic_err("Unauthorized"));
+            }
+            validate_actions(&actions, pause_actions)?;
+
+            for action in actions.iter() {
+                //Only the owner can lift its own pauses
+                if !is_owner {
+                    if let Some(paused) = PAUSED_ACTIONS.may_load(storage, action.clone())? {
+                        if paused.owner_pause && current_time < paused.expiry {
+                            return Err(StdError::generic_err(format!("{} was paused by the owner, only the owner can unpause it", action)));
+                        }
+                    }
+                }
+
+                PAUSED_ACTIONS.remove(storage, action.clone());
+            }
+
+            Ok(Response::new().add_attributes(vec![
+                attr("method", "unpause"),
+                attr("actions", format!("{:?}", actions)),
+            ]))
+        }
+        PauseMsg::SetGuardian { guardian } => {
+            if !is_owner {
+                return Err(StdError::generic_err("Unauthorized"));
+            }
+
+            match guardian.clone() {
+                Some(guardian) => PAUSE_GUARDIAN.save(storage, &api.addr_validate(&guardian)?)?,
+                None => PAUSE_GUARDIAN.remove(storage),
+            }
+
+            Ok(Response::new().add_attributes(vec![
+                attr("method", "set_pause_guardian"),
+                attr("guardian", guardian.unwrap_or_else(|| String::from("None"))),
+            ]))
+        }
+    }
+}
@@ -2,7 +2,8 @@ use cosmwasm_schema::cw_serde;
 
 use cosmwasm_std::{Decimal, Uint128, Addr, Coin};
 
-use crate::types::{Asset, AssetPool, Deposit, UserInfo};
+use crate::pause::PauseMsg;
+use crate::types::{Asset, AssetInfo, AssetPool, Deposit, UserInfo};
 
 #[cw_serde]
 pub struct InstantiateMsg {
@@ -30,7 +31,7 @@ pub struct InstantiateMsg {
 pub enum ExecuteMsg {
     /// Update contract config
     UpdateConfig(UpdateConfig),
-    /// Deposit the debt token into the pool
+    /// Deposit the debt token or an accepted deposit asset into the pool
     Deposit {
         /// User address, defaults to info.sender
         user: Option<String>,
@@ -61,6 +62,28 @@ pub enum ExecuteMsg {
         /// Amount to distribute for
         distribute_for: Uint128,
     },
+    /// Fund a reward stream for depositors with the sent native token.
+    /// Tokens are emitted per second between the start & end time.
+    AddIncentiveStream {
+        /// Stream start time in seconds, can't be in the past
+        start_time: u64,
+        /// Stream end time in seconds
+        end_time: u64,
+    },
+    /// Add, edit or remove an accepted incentive stream denom (Owner)
+    EditStreamDenom {
+        /// Native token to accept for streams
+        denom: String,
+        /// Minimum stream amount, None removes the denom
+        minimum_amount: Option<Uint128>,
+    },
+    /// Add or edit an accepted deposit asset (Owner)
+    EditDepositAsset {
+        /// Native token to accept
+        asset: AssetInfo,
+        /// Discount on the asset's value in credit asset terms
+        haircut: Decimal,
+    },
     /// Allow the Positions contract to use user funds to repay for themselves
     Repay {
         /// User position info
@@ -68,18 +91,21 @@ pub enum ExecuteMsg {
         /// Repayment asset
         repayment: Asset,
     },
+    /// Pause or unpause execute msgs
+    Pause(PauseMsg),
 }
 
 #[cw_serde]
 pub enum QueryMsg {
     /// Returns contract config
     Config {},
-    /// Returns amount of unclaimed incentives for a user
+    /// Returns amount of unclaimed TEMA & stream incentives for a user
     UnclaimedIncentives { 
         /// User address
         user: String 
     },
-    /// Returns capital ahead of frontmost user deposit
+    /// Returns capital ahead of frontmost user deposit.
+    /// Always 0 in pro-rata mode.
     CapitalAheadOfDeposit { 
         /// User address
         user: String 
@@ -94,6 +120,13 @@ pub enum QueryMsg {
         /// User address
         user: String 
     },
+    /// Returns active incentive streams
+    IncentiveStreams {
+        /// Stream id to start after
+        start_after: Option<u64>,
+        /// Response limit
+        limit: Option<u32>,
+    },
     /// Returns AssetPool
     AssetPool { 
         /// User address
@@ -125,6 +158,45 @@ pub struct Config {
     pub positions_contract: Addr,
     /// Oracle contract address
     pub oracle_contract: Addr,
+    /// Share liquidations across all Tranches by weight instead of FIFO.
+    /// Defaults to false for configs saved before this field existed.
+    #[serde(default)]
+    pub pro_rata: bool,
+    /// DEX router contract address, used to swap deposit assets to the credit asset
+    pub dex_router: Option<Addr>,
+    /// Accepted deposits other than the credit asset
+    #[serde(default)]
+    pub deposit_assets: Vec<DepositAsset>,
+    /// Accepted incentive stream denoms & their minimum stream amount
+    #[serde(default)]
+    pub stream_denoms: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct IncentiveStream {
+    /// Stream id
+    pub id: u64,
+    /// Address that funded the stream
+    pub funder: Addr,
+    /// Reward token denom
+    pub denom: String,
+    /// Total amount funded
+    pub amount: Uint128,
+    /// Start time in seconds
+    pub start_time: u64,
+    /// End time in seconds
+    pub end_time: u64,
+    /// Time the stream has been emitted up to
+    pub last_accrued: u64,
+}
+
+#[cw_serde]
+pub struct DepositAsset {
+    /// Native token accepted for deposits
+    pub info: AssetInfo,
+    /// Discount on the asset's value in credit asset terms.
+    /// Deposits are credited amount * (1 - haircut).
+    pub haircut: Decimal,
 }
 
 #[cw_serde]
@@ -147,6 +219,10 @@ pub struct UpdateConfig {
     pub oracle_contract: Option<String>,
     /// TEMA denom
     pub tema_denom: Option<String>,
+    /// Toggle pro-rata liquidations
+    pub pro_rata: Option<bool>,
+    /// DEX router contract address, used to swap deposit assets to the credit asset
+    pub dex_router: Option<String>,
 }
 
 #[cw_serde]
@@ -173,7 +249,12 @@ pub struct DepositPositionResponse {
 pub struct UserIncentivesResponse {
     /// User's unclaimed incentives
     pub incentives: Uint128,
+    /// User's unclaimed incentive stream rewards
+    pub stream_incentives: Vec<Coin>,
 }
 
 #[cw_serde]
-pub struct MigrateMsg {}
\ No newline at end of file
+pub struct MigrateMsg {
+    /// Switch existing deposits to pro-rata liquidations
+    pub pro_rata: Option<bool>,
+}
\ No newline at end of file
@@ -1,7 +1,25 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Uint128, Addr, Decimal};
+use cosmwasm_std::{Uint128, Addr, Coin, Decimal};
 
-use crate::types::{Asset, FeeEvent, OldStakeDeposit, StakeDistribution, OldDelegationInfo, Delegate};
+use crate::pause::PauseMsg;
+use crate::types::{Asset, LiqAsset, OldStakeDeposit, StakeDistribution, OldDelegationInfo, Delegate, StakeLock, ParticipationRequirements, ProposalParticipation, InstantUnstakePenalty, UnbondingEntry};
+
+//Constants
+/// Max lock duration in days (4 years)
+pub const MAX_LOCK_DAYS: u64 = 1_460u64;
+/// Extra voting power of a max length lock, i.e. 2x
+pub const LOCK_VOTING_BOOST: u64 = 100u64;
+/// Extra fee revenue share of a max length lock, i.e. 1.5x
+pub const LOCK_FEE_BOOST: u64 = 50u64;
+/// Max keeper tip for auto-compounding, in percent of the compounded TEMA
+pub const MAX_KEEPER_TIP: u64 = 5u64;
+/// Max slippage from oracle prices for auto-compound swaps, in percent
+pub const MAX_SLIPPAGE: u64 = 10u64;
+/// Min time between a user's auto-compounds, in seconds
+pub const MIN_COMPOUND_INTERVAL: u64 = 86_400u64;
+/// Duration of Stability Pool incentive streams funded by instant unstake penalties, in seconds.
+/// Penalties are batched into one stream per duration.
+pub const PENALTY_STREAM_DURATION: u64 = 604_800u64;
 
 #[cw_serde]
 pub struct InstantiateMsg {
@@ -55,11 +73,28 @@ pub enum ExecuteMsg {
         /// Transforms the total stake in the revenue calculations, not the revenue directly
         /// WARNING: SETTING TO 0 IS PERMANENT
         vesting_rev_multiplier: Option<Decimal>,
+        /// Delegate participation requirements
+        participation_requirements: Option<ParticipationRequirements>,
+        /// DEX router address, used to auto-compound fee assets
+        dex_router: Option<String>,
+        /// Keeper tip for auto-compounding, capped at MAX_KEEPER_TIP
+        keeper_tip: Option<Decimal>,
+        /// Instant unstake penalty
+        instant_unstake_penalty: Option<InstantUnstakePenalty>,
+        /// Stability Pool address
+        stability_pool: Option<String>,
+        /// Oracle address, used to set the minimum receive of auto-compound swaps
+        oracle_contract: Option<String>,
+        /// Max slippage from oracle prices for auto-compound swaps, capped at MAX_SLIPPAGE
+        max_slippage: Option<Decimal>,
     },
     /// Stake TEMA tokens
     Stake {
         /// User address
         user: Option<String>,
+        /// Lock the stake for this many days to boost voting power & fee revenue.
+        /// Locked stake can't be unstaked until the lock ends.
+        lock_duration: Option<u64>,
     },
     /// Unstake/Withdraw TEMA tokens & claim claimables
     Unstake {
@@ -114,9 +149,50 @@ pub enum ExecuteMsg {
     },
     /// Position's contract deposits protocol revenue
     DepositFee {},
-    /// Clear FeeEvent state object
-    TrimFeeEvents {},
-
+    /// Create the liquid staking receipt denom through the Osmosis Proxy
+    CreateReceiptDenom {
+        /// Receipt token subdenom
+        subdenom: String,
+    },
+    /// Stake TEMA into the liquid pool & receive receipt tokens
+    LiquidStake {},
+    /// Redeem receipt tokens for TEMA through the unstaking queue
+    LiquidUnstake {},
+    /// Claim the liquid pool's rewards & restake its TEMA incentives
+    CompoundLiquidStake {},
+    /// Withdraw all unstaking deposits now for a penalty that decays over the unstaking period
+    InstantUnstake {},
+    /// Stream batched instant unstake penalties to the Stability Pool.
+    /// Callable by anyone once per PENALTY_STREAM_DURATION.
+    StreamPenalties {},
+    /// Opt in/out of auto-compounding fee revenue into TEMA stake
+    SetAutoCompound {
+        /// Toggle
+        enabled: bool,
+    },
+    /// Swap a user's fee revenue to TEMA & restake it.
+    /// Callable by anyone, the caller is tipped a portion of the swapped TEMA.
+    /// Pass the contract address to compound the liquid pool's held fees.
+    AutoCompound {
+        /// User address
+        user: String,
+    },
+    /// Settle stakers' lock boost fees, remove their expired locks & decay their fee boost.
+    /// Callable by anyone so expired locks stop diluting other stakers' fee revenue.
+    RefreshLocks {
+        /// Staker addresses
+        stakers: Vec<String>,
+    },
+    /// Governance reports which addresses voted on an ended proposal.
+    /// Declared delegates below the participation requirements lose commission & fluid delegations.
+    ReportParticipation {
+        /// Proposal ID
+        proposal_id: u64,
+        /// Addresses that voted
+        voters: Vec<String>,
+    },
+    /// Pause or unpause execute msgs
+    Pause(PauseMsg),
 }
 
 #[cw_serde]
@@ -166,13 +242,71 @@ pub enum QueryMsg {
         /// Query a specific user
         user: Option<String>,
     },
-    /// Returns list of FeeEvents
-    FeeEvents {
+    /// Returns declared delegates' participation history
+    DelegateParticipation {
         /// Response limit
         limit: Option<u32>,
-        /// Start after timestamp in seconds
-        start_after: Option<u64>,
+        /// Start after delegate address
+        start_after: Option<String>,
+        /// Query a specific delegate
+        user: Option<String>,
+    },
+    /// Returns liquid staking pool info
+    LiquidStake {},
+    /// Returns users' unstaking deposits & their maturity times
+    Unbonding {
+        /// Response limit
+        limit: Option<u32>,
+        /// Start after user address
+        start_after: Option<String>,
+        /// Query a specific user
+        user: Option<String>,
+    },
+    /// Returns list of AutoCompoundResponse
+    AutoCompounders {
+        /// Response limit
+        limit: Option<u32>,
+        /// Start after user address
+        start_after: Option<String>,
+    },
+    /// Returns the user's lock schedule
+    UserLocks {
+        /// User address
+        user: String,
+    },
+    /// Returns list of UserLocksResponse
+    Locks {
+        /// Response limit
+        limit: Option<u32>,
+        /// Start after user address
+        start_after: Option<String>,
     },
+    /// Returns the user's voting power at a time, i.e. stake plus the lock voting boost.
+    /// Excludes stake that was unstaking at the time.
+    StakeAt {
+        /// User address
+        user: String,
+        /// Time in seconds, defaults to now
+        time: Option<u64>,
+    },
+    /// Returns the total voting power at a time
+    TotalStakeAt {
+        /// Time in seconds, defaults to now
+        time: Option<u64>,
+        /// Sum the square root of each user's voting power, defaults to false
+        quadratic: Option<bool>,
+    },
+    /// Returns list of StakeCheckpointResponse with stake at a time
+    StakeCheckpoints {
+        /// Time in seconds, defaults to now
+        time: Option<u64>,
+        /// Response limit
+        limit: Option<u32>,
+        /// Start after user address
+        start_after: Option<String>,
+    },
+    /// Returns cumulative fee rewards per staked TEMA
+    RewardIndices {},
     /// Returns total TEMA staked
     TotalStaked {},
     /// Returns progress of current incentive schedule
@@ -208,6 +342,25 @@ pub struct Config {
     pub governance_contract: Option<Addr>,
     /// Osmosis Proxy contract address
     pub osmosis_proxy: Option<Addr>,
+    /// Delegate participation requirements, None disables
+    pub participation_requirements: Option<ParticipationRequirements>,
+    /// DEX router address
+    pub dex_router: Option<Addr>,
+    /// Ratio of auto-compounded TEMA tipped to the keeper.
+    /// Defaults to 0 for configs saved before this field existed.
+    #[serde(default)]
+    pub keeper_tip: Decimal,
+    /// Instant unstake penalty, None disables instant unstaking
+    pub instant_unstake_penalty: Option<InstantUnstakePenalty>,
+    /// Stability Pool address
+    pub stability_pool: Option<Addr>,
+    /// Oracle contract address
+    #[serde(default)]
+    pub oracle_contract: Option<Addr>,
+    /// Max slippage from oracle prices for auto-compound swaps.
+    /// Defaults to 0 for configs saved before this field existed.
+    #[serde(default)]
+    pub max_slippage: Decimal,
 }
 
 #[cw_serde]
@@ -220,6 +373,50 @@ pub struct StakerResponse {
     pub deposit_list: Vec<OldStakeDeposit>,
 }
 
+#[cw_serde]
+pub struct UnbondingResponse {
+    /// User address
+    pub user: String,
+    /// Unstaking deposits
+    pub entries: Vec<UnbondingEntry>,
+}
+
+#[cw_serde]
+pub struct StakeCheckpointResponse {
+    /// User address
+    pub user: String,
+    /// Voting stake at the queried time
+    pub stake: Uint128,
+}
+
+#[cw_serde]
+pub struct AutoCompoundResponse {
+    /// User address
+    pub user: String,
+    /// Time of last auto-compound in seconds
+    pub last_compound: u64,
+}
+
+#[cw_serde]
+pub struct LockResponse {
+    /// Lock info
+    pub lock: StakeLock,
+    /// Current voting power multiplier
+    pub voting_multiplier: Decimal,
+    /// Current fee revenue multiplier
+    pub fee_multiplier: Decimal,
+}
+
+#[cw_serde]
+pub struct UserLocksResponse {
+    /// User address
+    pub user: String,
+    /// Total TEMA locked
+    pub total_locked: Uint128,
+    /// Active locks
+    pub locks: Vec<LockResponse>,
+}
+
 #[cw_serde]
 pub struct RewardsResponse {
     /// Claimable rewards
@@ -249,9 +446,35 @@ pub struct TotalStakedResponse {
 }
 
 #[cw_serde]
-pub struct FeeEventsResponse {
-    /// List of FeeEvents
-    pub fee_events: Vec<FeeEvent>,
+pub struct RewardIndicesResponse {
+    /// Cumulative reward per staked TEMA for each fee asset
+    pub reward_indices: Vec<LiqAsset>,
+}
+
+#[cw_serde]
+pub struct LiquidStakeResponse {
+    /// Receipt token denom
+    pub receipt_denom: String,
+    /// Receipt tokens outstanding
+    pub receipt_supply: Uint128,
+    /// TEMA staked by the pool
+    pub total_staked: Uint128,
+    /// TEMA per receipt token
+    pub exchange_rate: Decimal,
+    /// Fee revenue held by the pool
+    pub fee_claims: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct DelegateParticipationResponse {
+    /// Delegate address
+    pub delegate: Addr,
+    /// Participation in the most recent proposals
+    pub participation: Vec<ProposalParticipation>,
+    /// Ratio of recent proposals voted on
+    pub participation_rate: Decimal,
+    /// True if below the participation requirements
+    pub penalized: bool,
 }
 
 #[cw_serde]
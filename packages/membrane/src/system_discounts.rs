@@ -1,5 +1,6 @@
 use cosmwasm_std::{Addr, Decimal};
 use cosmwasm_schema::cw_serde;
+use crate::pause::PauseMsg;
 
 
 #[cw_serde]
@@ -26,6 +27,8 @@ pub struct InstantiateMsg {
 pub enum ExecuteMsg {
     //Updates Config
     UpdateConfig(UpdateConfig),
+    /// Pause or unpause execute msgs
+    Pause(PauseMsg),
 }
 
 #[cw_serde]
@@ -5,7 +5,7 @@ use std::{str::FromStr, convert::TryFrom};
 use crate::{math::{Decimal256, Uint256}, liq_queue::QueueResponse, oracle::PriceResponse};
 
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Decimal, Uint128, StdError};
+use cosmwasm_std::{Addr, Binary, Decimal, Uint128, StdError};
 use cw_coins::Coins;
 
 use osmosis_std::types::cosmos::base::v1beta1::Coin;
@@ -81,7 +81,7 @@ pub struct AssetPool {
     pub credit_asset: Asset,
     /// Liquidation premium
     pub liq_premium: Decimal,
-    /// Asset deposits
+    /// Asset deposits, only populated in query responses
     pub deposits: Vec<Deposit>,
 }
 
@@ -124,14 +124,39 @@ pub struct BidInput {
     pub bid_for: AssetInfo,
     /// Liquidation premium within range of Queue's max_premium
     pub liq_premium: u8,
+    /// Spread the bid across a range of premiums, liq_premium is ignored if set
+    pub premium_range: Option<PremiumRange>,
+    /// Swap claimed collateral back to the bid asset & re-bid in the same slots
+    #[serde(default)]
+    pub auto_recycle: bool,
+    /// Let the bid repay liquidations at the Stability Pool's premium once the SP is exhausted
+    #[serde(default)]
+    pub sp_fallback: bool,
+    /// Max slippage of auto-recycle swaps from the collateral's last liquidation prices
+    #[serde(default)]
+    pub max_slippage: Decimal,
 }
 
 impl fmt::Display for BidInput {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{} {}", self.bid_for, self.liq_premium)
+        match &self.premium_range {
+            Some(range) => write!(f, "{} {}-{}", self.bid_for, range.min_premium, range.max_premium),
+            None => write!(f, "{} {}", self.bid_for, self.liq_premium),
+        }
     }
 }
 
+#[cw_serde]
+pub struct PremiumRange {
+    /// Lowest premium in the range
+    pub min_premium: u8,
+    /// Highest premium in the range
+    pub max_premium: u8,
+    /// Weight for each premium in the range, must sum to 1.
+    /// None = spread evenly.
+    pub weights: Option<Vec<Decimal>>,
+}
+
 #[cw_serde]
 pub struct Bid {
     /// Bidder address
@@ -154,6 +179,13 @@ pub struct Bid {
     pub epoch_snapshot: Uint128,
     /// Scale snapshot
     pub scale_snapshot: Uint128,
+    /// Recycle claimed collateral into new bids
+    pub auto_recycle: bool,
+    /// Used as Stability Pool fallback capital
+    pub sp_fallback: bool,
+    /// Max slippage of auto-recycle swaps
+    #[serde(default)]
+    pub max_slippage: Decimal,
 }
 
 impl fmt::Display for Bid {
@@ -183,10 +215,6 @@ pub struct User {
 
 #[cw_serde]
 pub struct PremiumSlot {
-    /// Bids in the slot
-    pub bids: Vec<Bid>,    
-    /// Waiting bids in the slot
-    pub waiting_bids: Vec<Bid>,
     /// Liquidation premium
     pub liq_premium: Decimal256,
     /// Sum snapshot
@@ -195,6 +223,8 @@ pub struct PremiumSlot {
     pub product_snapshot: Decimal256,
     /// Total bid amount
     pub total_bid_amount: Uint256,
+    /// Number of waiting bids
+    pub waiting_bid_count: u64,
     /// Last time the bids have been totaled, in seconds
     pub last_total: u64, 
     /// Current epoch
@@ -228,6 +258,26 @@ impl fmt::Display for StakeDeposit {
     }
 }
 
+/// Pooled stake represented by a transferable receipt token
+#[cw_serde]
+pub struct LiquidStakePool {
+    /// Receipt token denom
+    pub receipt_denom: String,
+    /// Receipt tokens outstanding
+    pub receipt_supply: Uint128,
+}
+
+/// Stake that can't be unstaked until end_time
+#[cw_serde]
+pub struct StakeLock {
+    /// Amount of locked stake
+    pub amount: Uint128,
+    /// Time of lock in seconds
+    pub start_time: u64,
+    /// Time of unlock in seconds
+    pub end_time: u64,
+}
+
 #[cw_serde]
 pub struct OldStakeDeposit {
     /// Staker address
@@ -305,6 +355,51 @@ pub struct Delegate {
     pub url: Option<String>,
 }
 
+#[cw_serde]
+pub struct ProposalParticipation {
+    /// Proposal ID
+    pub proposal_id: u64,
+    /// Whether the delegate voted
+    pub voted: bool,
+}
+
+#[cw_serde]
+pub struct ParticipationRequirements {
+    /// Min. ratio of proposals a declared delegate must vote on
+    pub threshold: Decimal,
+    /// Number of most recent proposals used to calc participation
+    pub window: u64,
+}
+
+#[cw_serde]
+pub enum PenaltyRecipient {
+    /// Distributed to remaining stakers as fee revenue
+    Stakers,
+    /// Swapped to the credit asset & streamed to Stability Pool depositors
+    StabilityPool,
+}
+
+#[cw_serde]
+pub struct InstantUnstakePenalty {
+    /// Penalty for skipping the full unstaking period.
+    /// Decays linearly to 0 as the unstaking period ends.
+    pub max_penalty: Decimal,
+    /// Recipient of penalties
+    pub recipient: PenaltyRecipient,
+}
+
+#[cw_serde]
+pub struct UnbondingEntry {
+    /// Amount unstaking
+    pub amount: Uint128,
+    /// Time of unstake in seconds
+    pub unstake_start_time: u64,
+    /// Time the entry is withdrawable in seconds
+    pub maturity_time: u64,
+    /// Current penalty to withdraw the entry instantly
+    pub instant_unstake_penalty: Uint128,
+}
+
 #[cw_serde]
 pub struct FeeEvent {
     /// Time of event in seconds
@@ -344,11 +439,37 @@ pub struct AssetOracleInfo {
     pub lp_pool_info: Option<PoolInfo>,
     /// Asset decimals
     pub decimals: u64,
+    /// External pull-oracle price feed
+    pub external_oracle: Option<ExternalOracleInfo>,
 }
 
 impl fmt::Display for AssetOracleInfo {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "osmo_pools: {:?}, is_usd_par: {:?}", self.pools_for_osmo_twap, self.is_usd_par)
+        write!(f, "osmo_pools: {:?}, is_usd_par: {:?}, external_oracle: {:?}", self.pools_for_osmo_twap, self.is_usd_par, self.external_oracle)
+    }
+}
+
+#[cw_serde]
+pub struct ExternalOracleInfo {
+    /// Oracle contract address
+    pub contract_addr: String,
+    /// Query msg sent to the oracle contract as is.
+    /// Its response must contain a `price` (or `rate`) field.
+    pub query_msg: Binary,
+    /// Decimals of the queried price, used to scale it to a Decimal
+    pub response_decimals: u64,
+    /// Max deviation of the external price from the Osmosis TWAP price.
+    /// Prices that deviate further error.
+    pub max_deviation: Decimal,
+}
+
+impl fmt::Display for ExternalOracleInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "contract_addr: {}, query_msg: {}, response_decimals: {}, max_deviation: {}",
+            self.contract_addr, self.query_msg.to_base64(), self.response_decimals, self.max_deviation
+        )
     }
 }
 
@@ -1,6 +1,7 @@
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{Uint128, Addr};
 
+use crate::pause::PauseMsg;
 use crate::{
     governance::{ProposalMessage, ProposalVoteOption},
     types::{Allocation, Asset, VestingPeriod},
@@ -89,6 +90,8 @@ pub enum ExecuteMsg {
         /// Additional allocation for the contract to distribute
         additional_allocation: Option<Uint128>,
     },
+    /// Pause or unpause execute msgs
+    Pause(PauseMsg),
 }
 
 #[cw_serde]